@@ -0,0 +1,28 @@
+//! Mines 100 random games for tactic puzzles and writes them in the
+//! puzzle file format, to the given path or to standard output.
+//!
+//! ```text
+//! cargo run --release --example generate_puzzles -- puzzles.txt
+//! ```
+
+use baghchal::puzzle::{generate, random_games, GeneratorOptions};
+use std::fs;
+
+fn main() {
+    let games = random_games(100, 1);
+    let puzzles = generate(&games, &GeneratorOptions::default());
+
+    let mut text = format!("# {} puzzles mined from 100 random games\n", puzzles.len());
+    for puzzle in &puzzles {
+        text.push('\n');
+        text.push_str(&puzzle.to_string());
+    }
+
+    match std::env::args().nth(1) {
+        Some(path) => {
+            fs::write(&path, text).expect("failed to write the puzzle file");
+            eprintln!("Wrote {} puzzles to {path}", puzzles.len());
+        }
+        None => print!("{text}"),
+    }
+}
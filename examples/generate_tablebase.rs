@@ -0,0 +1,31 @@
+//! Builds the endgame tablebase for the standard rules with up to the given
+//! number of goats on the board and writes it to the given path. Even two
+//! goats take a while; load the result with `Tablebase::load`.
+//!
+//! ```text
+//! cargo run --release --example generate_tablebase -- 2 endgame.tb
+//! ```
+
+use baghchal::{RuleSet, Tablebase};
+use std::time::Instant;
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let max_goats = args
+        .next()
+        .and_then(|arg| arg.parse().ok())
+        .expect("usage: generate_tablebase <max goats> <path>");
+    let path = args
+        .next()
+        .expect("usage: generate_tablebase <max goats> <path>");
+
+    let start = Instant::now();
+    let table = Tablebase::generate(&RuleSet::standard(), max_goats)
+        .expect("the standard rules fit a tablebase");
+    table.save(&path).expect("failed to write the tablebase");
+    eprintln!(
+        "Wrote {} positions to {path} in {:.1?}",
+        table.len(),
+        start.elapsed()
+    );
+}
@@ -0,0 +1,54 @@
+//! Plays an AI-vs-AI game with a custom evaluation that counts captured
+//! goats and nothing else, to show how to plug in an [`Evaluator`].
+//!
+//! ```text
+//! cargo run --release --example material_evaluator
+//! ```
+
+use baghchal::notation::format_move;
+use baghchal::{AiConfig, Board, Evaluator, Extensions, MinimaxEngine};
+use std::sync::Arc;
+
+/// 100 points per captured goat.
+#[derive(Debug)]
+struct Material;
+
+impl Evaluator for Material {
+    fn evaluate(&self, board: &Board) -> i32 {
+        board.captured_goats as i32 * 100
+    }
+}
+
+fn main() {
+    let mut board = Board::new();
+    board.set_evaluator(Arc::new(Material));
+    board.set_use_opening_book(false);
+    board.set_ai_config(AiConfig {
+        time_limit: None,
+        max_depth: Some(3),
+        max_nodes: None,
+        ponder: false,
+        tie_margin: 0,
+        seed: None,
+        blunders: None,
+        contempt: 0,
+        null_move: true,
+        extensions: Extensions::default(),
+        safe_goats: false,
+        threads: 1,
+    });
+
+    let mut engine = MinimaxEngine::new();
+    while board.result().is_none() && board.record().moves.len() < 200 {
+        let Some(played) = board.play_engine_move(&mut engine) else {
+            break;
+        };
+        println!("{}", format_move(board.topology(), &played.mv));
+    }
+
+    match board.result() {
+        Some(result) => println!("Result: {:?}, {}", result.winner, result.reason),
+        None => println!("Stopped after 200 moves"),
+    }
+    println!("{board}");
+}
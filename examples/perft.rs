@@ -0,0 +1,56 @@
+//! Counts the lines of play from a position to a depth, split by first
+//! move, for tracking down move generation bugs. Also times the count, and
+//! with the `parallel` feature times it again on every core.
+//!
+//! ```text
+//! cargo run --release --example perft -- "T3T/5/5/5/T3T g 20 0 1" 5
+//! cargo run --release --features parallel --example perft -- "T3T/5/5/5/T3T g 20 0 1" 7
+//! ```
+
+use baghchal::notation::format_move;
+use baghchal::{perft_divide, Board};
+use std::time::{Duration, Instant};
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let (Some(fen), Some(Ok(depth))) = (args.get(1), args.get(2).map(|depth| depth.parse())) else {
+        eprintln!("usage: perft <fen> <depth>");
+        std::process::exit(2);
+    };
+    let board = match Board::from_fen(fen) {
+        Ok(board) => board,
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(2);
+        }
+    };
+    let started = Instant::now();
+    let divide = perft_divide(&board, depth);
+    let elapsed = started.elapsed();
+    for (mv, nodes) in &divide {
+        println!("{:<12} {nodes}", format_move(board.topology(), mv));
+    }
+    let total: u64 = divide.iter().map(|(_, nodes)| nodes).sum();
+    println!("\n{} moves, {total} lines", divide.len());
+    report("sequential", total, elapsed);
+
+    #[cfg(feature = "parallel")]
+    {
+        let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+        let started = Instant::now();
+        let parallel = baghchal::perft_parallel(&board, depth, threads);
+        let label = format!("parallel, {threads} threads");
+        report(&label, parallel, started.elapsed());
+        if parallel != total {
+            eprintln!("parallel count {parallel} differs from {total}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Prints how long counting `nodes` lines took, and how many that is a
+/// second.
+fn report(label: &str, nodes: u64, elapsed: Duration) {
+    let per_second = nodes as f64 / elapsed.as_secs_f64().max(1e-9);
+    println!("{label}: {elapsed:.2?}, {per_second:.0} nodes/s");
+}
@@ -0,0 +1,43 @@
+//! Writes training data from games of the minimax engine against itself,
+//! 2,000 nodes a move, drawing the first 12 moves of each game at a
+//! temperature of 40 points for variety. Run it again with the same file
+//! to add to it.
+//!
+//! ```text
+//! cargo run --release --example selfplay -- selfplay.jsonl 500
+//! ```
+//!
+//! Writes CSV instead if the file name ends in `.csv`.
+
+use baghchal::selfplay::Format;
+use baghchal::{AiConfig, MinimaxEngine, SelfPlay};
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let path = args.next().unwrap_or_else(|| "selfplay.jsonl".to_string());
+    let games = args.next().map_or(100, |games| {
+        games.parse().expect("the number of games is a number")
+    });
+    let selfplay = SelfPlay {
+        games,
+        limits: AiConfig::nodes(2_000),
+        temperature: 40.0,
+        temperature_plies: 12,
+        format: if path.ends_with(".csv") {
+            Format::Csv
+        } else {
+            Format::JsonLines
+        },
+        ..SelfPlay::default()
+    };
+    match selfplay.run(&path, MinimaxEngine::new, MinimaxEngine::new) {
+        Ok(summary) => println!(
+            "{}: {} games, {} positions ({} games were already there)",
+            path, summary.games, summary.positions, summary.resumed_games
+        ),
+        Err(err) => {
+            eprintln!("{path}: {err}");
+            std::process::exit(1);
+        }
+    }
+}
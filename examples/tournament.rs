@@ -0,0 +1,44 @@
+//! Plays the minimax engine against Monte Carlo tree search, 1,000 nodes
+//! a move each, over 10 pairs of games from four openings, and prints the
+//! result as a table, or as JSON with `--json`.
+//!
+//! ```text
+//! cargo run --release --example tournament -- --json
+//! ```
+
+use baghchal::notation::parse_move;
+use baghchal::{AiConfig, Board, Mcts, MinimaxEngine, Move, Tournament};
+
+/// Goat placements to start from, with the tigers' replies.
+const OPENINGS: [&[&str]; 4] = [&[], &["C3", "A1-B1"], &["B2", "E5-D5"], &["C2", "A5-A4"]];
+
+fn opening(moves: &[&str]) -> Vec<Move> {
+    let mut board = Board::new();
+    moves
+        .iter()
+        .map(|text| {
+            let mv = parse_move(&board, text).expect("opening moves are legal");
+            board.make_move(&mv);
+            mv
+        })
+        .collect()
+}
+
+fn main() {
+    let tournament = Tournament {
+        pairs: 10,
+        limits: AiConfig::nodes(1_000),
+        openings: OPENINGS.iter().map(|moves| opening(moves)).collect(),
+        ..Tournament::default()
+    };
+    let result = tournament.run(MinimaxEngine::new, || Mcts {
+        seed: Some(1),
+        ..Mcts::default()
+    });
+    if std::env::args().any(|arg| arg == "--json") {
+        println!("{}", result.to_json());
+    } else {
+        println!("Engine A: minimax, engine B: Monte Carlo tree search\n");
+        println!("{result}");
+    }
+}
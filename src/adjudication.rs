@@ -0,0 +1,170 @@
+//! Assigning results to games that are cut off before they end, such as
+//! long AI-vs-AI games in a batch.
+//!
+//! An [`AdjudicationPolicy`] lists the criteria to apply, and
+//! [`Board::adjudicate`] stores the policy with the game so the result can
+//! be reproduced from the record.
+
+use crate::{
+    AiConfig, Board, Extensions, GameResult, SearchContext, Side, TerminationReason, Winner,
+};
+
+/// What [`adjudicate`] looks at, in the order listed. A game none of them
+/// decides is a draw.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdjudicationPolicy {
+    /// Call the game a draw once the current position has come up this
+    /// many times.
+    pub draw_on_repetition: Option<usize>,
+    /// Give the goats the game once the tigers can no longer reach the
+    /// capture threshold.
+    pub goats_win_when_hopeless: bool,
+    /// Give the tigers the game once they've captured this many goats.
+    pub tigers_win_at_captures: Option<u32>,
+    /// Give the game to a side the search has favoured by a clear margin
+    /// for several plies in a row.
+    pub search: Option<SearchThreshold>,
+}
+
+impl Default for AdjudicationPolicy {
+    fn default() -> Self {
+        AdjudicationPolicy {
+            draw_on_repetition: Some(3),
+            goats_win_when_hopeless: true,
+            tigers_win_at_captures: None,
+            search: None,
+        }
+    }
+}
+
+/// A search score one side has to sustain to be given the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchThreshold {
+    /// Plies to search each position, without a time limit.
+    pub depth: u32,
+    /// Score, from the tigers' side, the tigers need to reach or the goats
+    /// need to get below the negative of.
+    pub score: i32,
+    /// Consecutive positions, ending with the current one, that need to
+    /// pass the threshold.
+    pub plies: usize,
+}
+
+/// The result `policy` assigns to the game on `board`. A game that is
+/// already over keeps its result.
+pub fn adjudicate(board: &Board, policy: &AdjudicationPolicy) -> GameResult {
+    settled(board, policy).unwrap_or(GameResult {
+        winner: Winner::Draw,
+        reason: TerminationReason::Adjudicated,
+    })
+}
+
+/// The result one of `policy`'s criteria already gives the game on
+/// `board`, if any, for deciding when to stop a game early. Unlike
+/// [`adjudicate`] a game none of them decides has no result. A game that
+/// is already over keeps its result.
+pub fn settled(board: &Board, policy: &AdjudicationPolicy) -> Option<GameResult> {
+    if let Some(result) = board.result() {
+        return Some(result);
+    }
+    let adjudicated = |winner| {
+        Some(GameResult {
+            winner,
+            reason: TerminationReason::Adjudicated,
+        })
+    };
+
+    if let Some(times) = policy.draw_on_repetition {
+        if repetitions(board) >= times {
+            return adjudicated(Winner::Draw);
+        }
+    }
+    if policy.goats_win_when_hopeless && !board.tigers_can_still_win() {
+        return adjudicated(Winner::Goats);
+    }
+    if let Some(captures) = policy.tigers_win_at_captures {
+        if board.captured_goats >= captures {
+            return adjudicated(Winner::Tigers);
+        }
+    }
+    if let Some(threshold) = &policy.search {
+        if let Some(winner) = sustained_advantage(board, threshold) {
+            return adjudicated(winner);
+        }
+    }
+    None
+}
+
+/// Times the current position has come up in the game, counting itself.
+fn repetitions(board: &Board) -> usize {
+    let current = position_key(board);
+    let mut earlier = board.clone();
+    let mut count = 1;
+    while earlier.undo() {
+        if position_key(&earlier) == current {
+            count += 1;
+        }
+    }
+    count
+}
+
+fn position_key(board: &Board) -> (Vec<crate::Piece>, u32, u32, Side) {
+    (
+        board.cells.clone(),
+        board.goats_in_hand,
+        board.captured_goats,
+        board.side_to_move(),
+    )
+}
+
+/// The side the search favours beyond the threshold in each of the last
+/// `threshold.plies` positions, if there is one.
+fn sustained_advantage(board: &Board, threshold: &SearchThreshold) -> Option<Winner> {
+    let mut position = board.clone();
+    // The search asks at every position whether the game is over, and
+    // none of its lines can run out of time
+    position.clock = None;
+    let mut leader = None;
+    for i in 0..threshold.plies {
+        if i > 0 && !position.undo() {
+            return None; // The game is too short to tell
+        }
+        let tigers_to_move = position.side_to_move() == Side::Tigers;
+        let mut search = SearchContext::new(
+            AiConfig {
+                time_limit: None,
+                max_depth: None,
+                max_nodes: None,
+                ponder: false,
+                tie_margin: 0,
+                seed: None,
+                blunders: None,
+                contempt: 0,
+                null_move: true,
+                extensions: Extensions::default(),
+                safe_goats: false,
+                threads: 1,
+            },
+            position.cells.len(),
+        );
+        let score = position.clone().minimax(
+            threshold.depth as i32,
+            i32::MIN,
+            i32::MAX,
+            tigers_to_move,
+            &mut search,
+        );
+        let favoured = if score >= threshold.score {
+            Winner::Tigers
+        } else if score <= -threshold.score {
+            Winner::Goats
+        } else {
+            return None;
+        };
+        if leader.is_some_and(|leader| leader != favoured) {
+            return None;
+        }
+        leader = Some(favoured);
+    }
+    leader
+}
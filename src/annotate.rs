@@ -0,0 +1,203 @@
+//! Plain-language notes on what a move does, for explaining hints.
+//!
+//! The notes are facts read off the position before and after the move,
+//! not the AI's reasoning: which goats it captures, saves or leaves
+//! hanging, which tigers it shuts in, and so on.
+
+use crate::{Board, Move, Piece, Side, Topology, Winner};
+
+/// Something a move does.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveReason {
+    /// Ends the game in the mover's favour.
+    Wins,
+    /// Captures the goats on these points.
+    Captures(Vec<usize>),
+    /// Leaves the tiger on this point with no move.
+    Traps(usize),
+    /// Fills the point a tiger would land on to capture the goat on this
+    /// point.
+    Blocks(usize),
+    /// Moves the goat on this point out of reach of a capture.
+    Escapes(usize),
+    /// Lines up captures of the goats on these points at once.
+    DoubleThreat(Vec<usize>),
+    /// Lines up a capture of the goat on this point.
+    Threatens(usize),
+    /// Leaves the goat on this point open to capture.
+    Exposes(usize),
+    /// Walks the tiger into a pocket on this point, where one goat can
+    /// seal it in, see [`Board::is_self_trapping`].
+    WalksIntoPocket(usize),
+    /// Brings a piece in from the edge of the board, or places a goat
+    /// inside it.
+    Centralises,
+}
+
+impl MoveReason {
+    /// The reason in words, e.g. `blocks the capture of the goat on B2`.
+    pub fn describe(&self, topology: &Topology) -> String {
+        let name = |pos: &usize| topology.point_name(*pos);
+        let names = |points: &[usize]| {
+            let names: Vec<String> = points.iter().map(name).collect();
+            match names.split_last() {
+                Some((last, [])) => last.clone(),
+                Some((last, rest)) => format!("{} and {last}", rest.join(", ")),
+                None => String::new(),
+            }
+        };
+        match self {
+            MoveReason::Wins => "wins the game".to_string(),
+            MoveReason::Captures(goats) if goats.len() == 1 => {
+                format!("captures the goat on {}", names(goats))
+            }
+            MoveReason::Captures(goats) => format!("captures the goats on {}", names(goats)),
+            MoveReason::Traps(tiger) => {
+                format!("leaves the tiger on {} with no moves", name(tiger))
+            }
+            MoveReason::Blocks(goat) => format!("blocks the capture of the goat on {}", name(goat)),
+            MoveReason::Escapes(goat) => format!("moves the goat on {} out of danger", name(goat)),
+            MoveReason::DoubleThreat(goats) => {
+                format!("threatens the goats on {} at once", names(goats))
+            }
+            MoveReason::Threatens(goat) => format!("threatens the goat on {}", name(goat)),
+            MoveReason::Exposes(goat) => {
+                format!("leaves the goat on {} open to capture", name(goat))
+            }
+            MoveReason::WalksIntoPocket(tiger) => {
+                format!("walks the tiger into a pocket on {}", name(tiger))
+            }
+            MoveReason::Centralises => "develops toward the centre".to_string(),
+        }
+    }
+}
+
+/// What a move does, see [`annotate_move`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveAnnotation {
+    pub mv: Move,
+    /// Most telling first. Empty for a quiet move.
+    pub reasons: Vec<MoveReason>,
+}
+
+impl MoveAnnotation {
+    /// The move in move notation with what it does, e.g. `C3-B3: blocks
+    /// the capture of the goat on B2`.
+    pub fn describe(&self, topology: &Topology) -> String {
+        let mv = crate::notation::format_move(topology, &self.mv);
+        if self.reasons.is_empty() {
+            return format!("{mv}: a quiet move");
+        }
+        let reasons: Vec<String> = self
+            .reasons
+            .iter()
+            .map(|reason| reason.describe(topology))
+            .collect();
+        format!("{mv}: {}", reasons.join("; "))
+    }
+}
+
+/// Notes what `mv` does on `board`, for the side to move. A move that
+/// isn't legal there gets no reasons.
+pub fn annotate_move(board: &Board, mv: Move) -> MoveAnnotation {
+    let mut after = board.clone();
+    if !after.make_move(&mv) {
+        return MoveAnnotation {
+            mv,
+            reasons: Vec::new(),
+        };
+    }
+    let mut reasons = Vec::new();
+    let mover = board.side_to_move();
+    let winner = match mover {
+        Side::Tigers => Winner::Tigers,
+        Side::Goats => Winner::Goats,
+    };
+    if after.get_winner() == winner {
+        reasons.push(MoveReason::Wins);
+    }
+
+    let topology = board.topology();
+    let before_threats = threats(board);
+    let (before, now) = (board.threatened_goats(), after.threatened_goats());
+
+    match &mv {
+        Move::PlaceGoat { .. } | Move::MoveGoat { .. } => {
+            let (from, to) = match &mv {
+                Move::PlaceGoat { position } => (None, *position),
+                Move::MoveGoat { from, to } => (Some(*from), *to),
+                _ => unreachable!(),
+            };
+            for tiger in board.tigers().map(|tiger| tiger.0) {
+                if !board.get_valid_tiger_moves(tiger).is_empty()
+                    && after.get_valid_tiger_moves(tiger).is_empty()
+                {
+                    reasons.push(MoveReason::Traps(tiger));
+                }
+            }
+            for &goat in before.iter().filter(|goat| !now.contains(goat)) {
+                if Some(goat) == from {
+                    reasons.push(MoveReason::Escapes(goat));
+                } else if before_threats.contains(&(goat, to)) {
+                    reasons.push(MoveReason::Blocks(goat));
+                }
+            }
+            for &goat in now.iter().filter(|goat| !before.contains(goat)) {
+                reasons.push(MoveReason::Exposes(goat));
+            }
+            let inward = from.is_none_or(|from| !topology.is_interior(from));
+            if inward && topology.is_interior(to) {
+                reasons.push(MoveReason::Centralises);
+            }
+        }
+        Move::MoveTiger { .. } | Move::MultiJump(_) => {
+            let captured: Vec<usize> = match &mv {
+                Move::MoveTiger {
+                    captured_position: Some(over),
+                    ..
+                } => vec![*over],
+                Move::MultiJump(jumps) => jumps.iter().map(|jump| jump.over).collect(),
+                _ => Vec::new(),
+            };
+            if !captured.is_empty() {
+                reasons.push(MoveReason::Captures(captured));
+            }
+            let new: Vec<usize> = now
+                .iter()
+                .copied()
+                .filter(|goat| !before.contains(goat))
+                .collect();
+            match (new.as_slice(), now.len()) {
+                ([], _) => {}
+                ([goat], 1) => reasons.push(MoveReason::Threatens(*goat)),
+                _ => reasons.push(MoveReason::DoubleThreat(now.clone())),
+            }
+            let (from, to) = match &mv {
+                Move::MoveTiger { from, to, .. } => (*from, *to),
+                Move::MultiJump(jumps) => (jumps[0].from, jumps[jumps.len() - 1].to),
+                _ => unreachable!(),
+            };
+            if board.is_self_trapping(&mv) {
+                reasons.push(MoveReason::WalksIntoPocket(to));
+            }
+            if !topology.is_interior(from) && topology.is_interior(to) {
+                reasons.push(MoveReason::Centralises);
+            }
+        }
+    }
+    MoveAnnotation { mv, reasons }
+}
+
+/// Every capture a tiger could make on `board` if it were the tigers'
+/// turn, as the goat captured and the point the tiger lands on.
+fn threats(board: &Board) -> Vec<(usize, usize)> {
+    let topology = board.topology();
+    board
+        .tigers()
+        .flat_map(|tiger| topology.jumps_from(tiger.0))
+        .filter(|jump| {
+            board.cells[jump.over] == Piece::Goat && board.cells[jump.to] == Piece::Empty
+        })
+        .map(|jump| (jump.over, jump.to))
+        .collect()
+}
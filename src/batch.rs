@@ -0,0 +1,298 @@
+//! Many games between engines with nobody watching, for trying out
+//! changes to an engine or its settings.
+//!
+//! A [`Batch`] plays its games from the initial position, each side
+//! searching within limits of its own, and stops games that are decided
+//! or going nowhere by adjudicating them. The [`BatchResult`] sums up
+//! who won, how long the games went, how many goats were captured and
+//! how long each side thought, and prints as a table or as JSON.
+//!
+//! Game `n` of a batch is seeded with `seed + n`, so a batch searching a
+//! fixed number of nodes or plies per move plays the same games every
+//! time it is run.
+
+use crate::tournament::{play_out, winner_name};
+use crate::{
+    save, AdjudicationPolicy, AiConfig, Board, Engine, GameResult, Side, TerminationReason, Winner,
+};
+use std::fmt::{self, Display};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// How a batch of games is played.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Batch {
+    pub games: usize,
+    /// Limits on the tigers' search per move. The seed is replaced by the
+    /// game's.
+    pub tiger_limits: AiConfig,
+    /// Limits on the goats' search per move. The seed is replaced by the
+    /// game's.
+    pub goat_limits: AiConfig,
+    /// Seed of the first game; each game after it adds one.
+    pub seed: u64,
+    /// Stops a game once it is decided or drawn in all but name, see
+    /// [`settled`](crate::adjudication::settled).
+    pub adjudication: AdjudicationPolicy,
+    /// Plies after which a game still going is adjudicated, with
+    /// [`Board::adjudicate`].
+    pub max_plies: usize,
+    /// A directory to save every game to, as `game-0001.txt` and so on in
+    /// the format of [`save`]. It is made if it doesn't exist.
+    pub records: Option<PathBuf>,
+}
+
+impl Default for Batch {
+    fn default() -> Self {
+        Batch {
+            games: 100,
+            tiger_limits: AiConfig::nodes(2_000),
+            goat_limits: AiConfig::nodes(2_000),
+            seed: 0,
+            adjudication: AdjudicationPolicy::default(),
+            max_plies: 300,
+            records: None,
+        }
+    }
+}
+
+impl Batch {
+    /// Plays the batch between engines made by `tigers` and `goats`, which
+    /// are given each game's seed. Every game gets new engines, and is
+    /// played without the opening book, which picks its moves at random.
+    /// `on_game` is called as each game ends, to report progress.
+    pub fn run<T: Engine, G: Engine>(
+        &self,
+        mut tigers: impl FnMut(u64) -> T,
+        mut goats: impl FnMut(u64) -> G,
+        mut on_game: impl FnMut(&BatchGame),
+    ) -> io::Result<BatchResult> {
+        if let Some(dir) = &self.records {
+            fs::create_dir_all(dir)?;
+        }
+        let mut games = Vec::with_capacity(self.games);
+        for game in 0..self.games {
+            let seed = self.seed.wrapping_add(game as u64);
+            let (board, played) = self.play_game(game, seed, &mut tigers(seed), &mut goats(seed));
+            if let Some(dir) = &self.records {
+                save::save(&board, dir.join(format!("game-{:04}.txt", game + 1)))?;
+            }
+            on_game(&played);
+            games.push(played);
+        }
+        Ok(BatchResult { games })
+    }
+
+    /// Plays game number `game`, returning the finished board and what
+    /// came of it.
+    fn play_game(
+        &self,
+        game: usize,
+        seed: u64,
+        tigers: &mut impl Engine,
+        goats: &mut impl Engine,
+    ) -> (Board, BatchGame) {
+        let mut board = Board::new();
+        board.set_use_opening_book(false);
+        let (mut tiger_time, mut goat_time) = (Duration::ZERO, Duration::ZERO);
+        let limits = (
+            AiConfig {
+                seed: Some(seed),
+                ..self.tiger_limits
+            },
+            AiConfig {
+                seed: Some(seed),
+                ..self.goat_limits
+            },
+        );
+        let result = play_out(
+            &mut board,
+            tigers,
+            goats,
+            &self.adjudication,
+            self.max_plies,
+            Some(limits),
+            |side, time| match side {
+                Side::Tigers => tiger_time += time,
+                Side::Goats => goat_time += time,
+            },
+        );
+        let played = BatchGame {
+            game,
+            seed,
+            result,
+            plies: board.ply(),
+            captures: board.captured_goats,
+            tiger_time,
+            goat_time,
+        };
+        (board, played)
+    }
+}
+
+/// One game of a batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchGame {
+    /// The game's number, counting from 0.
+    pub game: usize,
+    pub seed: u64,
+    pub result: GameResult,
+    pub plies: usize,
+    /// Goats the tigers captured.
+    pub captures: u32,
+    /// Time the tigers' engine spent on its moves.
+    pub tiger_time: Duration,
+    /// Time the goats' engine spent on its moves.
+    pub goat_time: Duration,
+}
+
+impl BatchGame {
+    /// Whether the game ended early, by adjudication rather than by the
+    /// rules.
+    pub fn adjudicated(&self) -> bool {
+        self.result.reason == TerminationReason::Adjudicated
+    }
+}
+
+/// The games of a batch, in the order they were played.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchResult {
+    pub games: Vec<BatchGame>,
+}
+
+impl BatchResult {
+    /// The tigers' wins, the draws and the goats' wins.
+    pub fn record(&self) -> (usize, usize, usize) {
+        let mut record = (0, 0, 0);
+        for game in &self.games {
+            match game.result.winner {
+                Winner::Tigers => record.0 += 1,
+                Winner::Goats => record.2 += 1,
+                Winner::Draw | Winner::None => record.1 += 1,
+            }
+        }
+        record
+    }
+
+    /// Games that ended by adjudication.
+    pub fn adjudicated(&self) -> usize {
+        self.games.iter().filter(|game| game.adjudicated()).count()
+    }
+
+    /// The average length of a game in plies.
+    pub fn average_plies(&self) -> f64 {
+        self.average(|game| game.plies as f64)
+    }
+
+    /// The average number of goats captured in a game.
+    pub fn average_captures(&self) -> f64 {
+        self.average(|game| game.captures as f64)
+    }
+
+    /// Time `side`'s engine spent over the whole batch.
+    pub fn total_time(&self, side: Side) -> Duration {
+        self.games
+            .iter()
+            .map(|game| match side {
+                Side::Tigers => game.tiger_time,
+                Side::Goats => game.goat_time,
+            })
+            .sum()
+    }
+
+    /// Time `side`'s engine spent on an average move. The goats move first,
+    /// so they have the extra move of a game of odd length.
+    pub fn time_per_move(&self, side: Side) -> Duration {
+        let moves: usize = self
+            .games
+            .iter()
+            .map(|game| match side {
+                Side::Goats => game.plies.div_ceil(2),
+                Side::Tigers => game.plies / 2,
+            })
+            .sum();
+        match moves {
+            0 => Duration::ZERO,
+            moves => self.total_time(side) / moves as u32,
+        }
+    }
+
+    fn average(&self, value: impl Fn(&BatchGame) -> f64) -> f64 {
+        if self.games.is_empty() {
+            return 0.0;
+        }
+        self.games.iter().map(value).sum::<f64>() / self.games.len() as f64
+    }
+
+    /// The batch as a JSON object: the totals, then every game.
+    pub fn to_json(&self) -> String {
+        let (tiger_wins, draws, goat_wins) = self.record();
+        let games: Vec<String> = self
+            .games
+            .iter()
+            .map(|game| {
+                format!(
+                    "{{\"game\":{},\"seed\":{},\"winner\":\"{}\",\"reason\":\"{:?}\",\"plies\":{},\"captures\":{},\"tiger_ms\":{},\"goat_ms\":{}}}",
+                    game.game,
+                    game.seed,
+                    winner_name(game.result.winner),
+                    game.result.reason,
+                    game.plies,
+                    game.captures,
+                    game.tiger_time.as_millis(),
+                    game.goat_time.as_millis(),
+                )
+            })
+            .collect();
+        format!(
+            "{{\"games\":{},\"tiger_wins\":{tiger_wins},\"draws\":{draws},\"goat_wins\":{goat_wins},\"adjudicated\":{},\"average_plies\":{:.2},\"average_captures\":{:.2},\"tiger_ms\":{},\"goat_ms\":{},\"tiger_ms_per_move\":{:.1},\"goat_ms_per_move\":{:.1},\"results\":[{}]}}",
+            self.games.len(),
+            self.adjudicated(),
+            self.average_plies(),
+            self.average_captures(),
+            self.total_time(Side::Tigers).as_millis(),
+            self.total_time(Side::Goats).as_millis(),
+            self.time_per_move(Side::Tigers).as_secs_f64() * 1000.0,
+            self.time_per_move(Side::Goats).as_secs_f64() * 1000.0,
+            games.join(","),
+        )
+    }
+}
+
+impl Display for BatchResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (tiger_wins, draws, goat_wins) = self.record();
+        let games = self.games.len();
+        let share = |count: usize| match games {
+            0 => 0.0,
+            games => count as f64 * 100.0 / games as f64,
+        };
+        writeln!(f, "{:<16} {:>6} {:>7}", "result", "games", "share")?;
+        for (label, count) in [
+            ("tigers won", tiger_wins),
+            ("drawn", draws),
+            ("goats won", goat_wins),
+        ] {
+            writeln!(f, "{label:<16} {count:>6} {:>6.1}%", share(count))?;
+        }
+        writeln!(f, "{:<16} {games:>6}", "total")?;
+        writeln!(f, "Adjudicated: {} of {games}", self.adjudicated())?;
+        writeln!(
+            f,
+            "Average length: {:.1} plies, {:.2} goats captured",
+            self.average_plies(),
+            self.average_captures()
+        )?;
+        let thinking = |side| {
+            format!(
+                "{:.1}s, {:.1}ms a move",
+                self.total_time(side).as_secs_f64(),
+                self.time_per_move(side).as_secs_f64() * 1000.0
+            )
+        };
+        writeln!(f, "Tigers thought for {}", thinking(Side::Tigers))?;
+        write!(f, "Goats thought for {}", thinking(Side::Goats))
+    }
+}
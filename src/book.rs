@@ -0,0 +1,244 @@
+//! Opening books: moves worth playing in well-known early positions, so the
+//! AI doesn't spend its time working them out again every game.
+//!
+//! A book is a text file with one position per line, written as
+//! `fen: move weight, move weight, ...` with moves in [move
+//! notation](crate::notation), and `#` starting a comment line:
+//!
+//! ```text
+//! # Goats open in the centre or next to it
+//! T3T/5/5/5/T3T g 20 0 1: C3 2, B2 1
+//! ```
+//!
+//! A missing weight counts as 1. Positions are stored up to the symmetries
+//! of the board, so a line covers its mirrored and rotated copies too, and
+//! the move numbers in the FENs are ignored.
+
+use crate::notation::{format_move, parse_move};
+use crate::{Board, Jump, Move, ParseError, Piece, Side};
+use rand::seq::SliceRandom;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+const BUILTIN: &str = include_str!("../data/book.txt");
+
+/// Positions with the moves to play in them, up to symmetry.
+#[derive(Debug, Clone)]
+pub struct Book {
+    /// Name of the board the positions are on.
+    topology: String,
+    symmetries: Vec<Vec<usize>>,
+    /// Moves by canonical position, as seen in the canonical orientation.
+    entries: HashMap<String, Vec<(Move, u32)>>,
+}
+
+impl Book {
+    /// Reads a book in the format described in the [module
+    /// documentation](self) from a file.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Book> {
+        let text = fs::read_to_string(path)?;
+        Book::parse(&text).map_err(|err| io::Error::new(ErrorKind::InvalidData, err))
+    }
+
+    /// Reads a book in the format described in the [module
+    /// documentation](self). Positions are on the standard board.
+    pub fn parse(text: &str) -> Result<Book, ParseError> {
+        let start = Board::new();
+        let mut book = Book {
+            topology: start.topology().name().to_string(),
+            symmetries: start.topology().symmetries(),
+            entries: HashMap::new(),
+        };
+
+        for (index, line) in text.lines().enumerate() {
+            let number = index + 1;
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+            let Some((fen, moves)) = line.split_once(':') else {
+                return Err(ParseError::new(number, 1, "expected 'fen: moves'"));
+            };
+            let board = Board::from_fen(fen.trim())
+                .map_err(|err| ParseError::new(number, err.column, err.message))?;
+
+            let (key, symmetry) = book.canonical(&board);
+            let mut column = fen.len() + 2;
+            for entry in moves.split(',') {
+                let entry_column = column + entry.len() - entry.trim_start().len();
+                column += entry.len() + 1;
+                let mut words = entry.split_whitespace();
+                let Some(text) = words.next() else {
+                    return Err(ParseError::new(number, entry_column, "expected a move"));
+                };
+                let Some(mv) = parse_move(&board, text) else {
+                    return Err(ParseError::new(
+                        number,
+                        entry_column,
+                        format!("illegal move '{text}'"),
+                    ));
+                };
+                let weight = match words.next() {
+                    None => 1,
+                    Some(word) => word.parse().map_err(|_| {
+                        ParseError::new(number, entry_column, format!("invalid weight '{word}'"))
+                    })?,
+                };
+                let mv = map_move(&mv, &book.symmetries[symmetry]);
+                let moves = book.entries.entry(key.clone()).or_default();
+                match moves.iter_mut().find(|(known, _)| *known == mv) {
+                    Some((_, total)) => *total += weight,
+                    None => moves.push((mv, weight)),
+                }
+            }
+        }
+        Ok(book)
+    }
+
+    /// Number of positions in the book, counting symmetric ones once.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The book moves for the side to move on `board` with their weights,
+    /// turned to match the board. Moves that aren't legal under the
+    /// board's rules are left out.
+    pub fn moves(&self, board: &Board) -> Vec<(Move, u32)> {
+        if board.topology().name() != self.topology {
+            return Vec::new();
+        }
+        let (key, symmetry) = self.canonical(board);
+        let Some(moves) = self.entries.get(&key) else {
+            return Vec::new();
+        };
+
+        let mut inverse = vec![0; board.cells.len()];
+        for (pos, &image) in self.symmetries[symmetry].iter().enumerate() {
+            inverse[image] = pos;
+        }
+        let legal = board.legal_moves();
+        moves
+            .iter()
+            .map(|(mv, weight)| (map_move(mv, &inverse), *weight))
+            .filter(|(mv, weight)| *weight > 0 && legal.contains(mv))
+            .collect()
+    }
+
+    /// Picks one of the book moves for `board` at random, in proportion to
+    /// their weights.
+    pub fn probe(&self, board: &Board) -> Option<Move> {
+        let moves = self.moves(board);
+        let (mv, _) = moves
+            .choose_weighted(&mut rand::thread_rng(), |(_, weight)| *weight)
+            .ok()?;
+        Some(mv.clone())
+    }
+
+    /// The key of `board` up to symmetry, with the index of the symmetry
+    /// that turns the board into the orientation the key describes.
+    fn canonical(&self, board: &Board) -> (String, usize) {
+        let (cells, symmetry) = self
+            .symmetries
+            .iter()
+            .enumerate()
+            .map(|(i, symmetry)| {
+                let mut cells = vec!['.'; board.cells.len()];
+                for (pos, &piece) in board.cells.iter().enumerate() {
+                    cells[symmetry[pos]] = match piece {
+                        Piece::Empty => '.',
+                        Piece::Goat => 'G',
+                        Piece::Tiger => 'T',
+                    };
+                }
+                (cells.into_iter().collect::<String>(), i)
+            })
+            .min()
+            .expect("the identity is always a symmetry");
+        let side = match board.side_to_move() {
+            Side::Goats => 'g',
+            Side::Tigers => 't',
+        };
+        let key = format!(
+            "{cells} {side} {} {}",
+            board.goats_in_hand, board.captured_goats
+        );
+        (key, symmetry)
+    }
+}
+
+impl std::fmt::Display for Book {
+    /// Writes the book in the text format, one canonical position per line.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let start = Board::new();
+        let mut keys: Vec<&String> = self.entries.keys().collect();
+        keys.sort();
+        for key in keys {
+            let mut fields = key.split(' ');
+            let cells = fields.next().unwrap_or_default();
+            let mut board = start.clone();
+            for (pos, piece) in cells.chars().enumerate() {
+                board.cells[pos] = match piece {
+                    'G' => Piece::Goat,
+                    'T' => Piece::Tiger,
+                    _ => Piece::Empty,
+                };
+            }
+            let side = fields.next().unwrap_or_default();
+            board.goats_in_hand = fields.next().and_then(|n| n.parse().ok()).unwrap_or(0);
+            board.captured_goats = fields.next().and_then(|n| n.parse().ok()).unwrap_or(0);
+            board.ply = (side == "t") as usize;
+
+            let moves: Vec<String> = self.entries[key]
+                .iter()
+                .map(|(mv, weight)| format!("{} {weight}", format_move(board.topology(), mv)))
+                .collect();
+            writeln!(f, "{}: {}", board.to_fen(), moves.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// The book that ships with the crate, covering the first few placements.
+pub fn builtin() -> Arc<Book> {
+    static BOOK: OnceLock<Arc<Book>> = OnceLock::new();
+    BOOK.get_or_init(|| Arc::new(Book::parse(BUILTIN).expect("built-in book is valid")))
+        .clone()
+}
+
+/// `mv` with every point sent through `map`.
+fn map_move(mv: &Move, map: &[usize]) -> Move {
+    match mv {
+        Move::PlaceGoat { position } => Move::PlaceGoat {
+            position: map[*position],
+        },
+        Move::MoveGoat { from, to } => Move::MoveGoat {
+            from: map[*from],
+            to: map[*to],
+        },
+        Move::MoveTiger {
+            from,
+            to,
+            captured_position,
+        } => Move::MoveTiger {
+            from: map[*from],
+            to: map[*to],
+            captured_position: captured_position.map(|pos| map[pos]),
+        },
+        Move::MultiJump(jumps) => Move::MultiJump(
+            jumps
+                .iter()
+                .map(|jump| Jump {
+                    from: map[jump.from],
+                    over: map[jump.over],
+                    to: map[jump.to],
+                })
+                .collect(),
+        ),
+    }
+}
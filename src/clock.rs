@@ -0,0 +1,244 @@
+//! Game clocks: each side has a time budget that runs down while it is to
+//! move.
+//!
+//! Clocks read the time from a [`TimeSource`], so tests and replays can
+//! drive them with [`ManualTime`] instead of waiting for real time to pass.
+//! The AI thinks on the same time, within a [`TimeBudget`] it plans from
+//! what is left on its clock.
+
+use crate::{Phase, Side};
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Where a clock gets the time from. Only differences between readings
+/// matter.
+pub trait TimeSource: Debug + Send + Sync {
+    fn now(&self) -> Duration;
+}
+
+/// Real time, measured from when the source was created.
+#[derive(Debug)]
+pub struct SystemTime {
+    start: Instant,
+}
+
+impl SystemTime {
+    pub fn new() -> Self {
+        SystemTime {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemTime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimeSource for SystemTime {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// Time that only moves when told to, for tests.
+#[derive(Debug, Default)]
+pub struct ManualTime {
+    now: Mutex<Duration>,
+}
+
+impl ManualTime {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Moves the time forward by `by`.
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap() += by;
+    }
+}
+
+impl TimeSource for ManualTime {
+    fn now(&self) -> Duration {
+        *self.now.lock().unwrap()
+    }
+}
+
+/// A pair of game clocks, one per side. At most one of them runs at a time.
+#[derive(Debug, Clone)]
+pub struct Clock {
+    goats: Duration,
+    tigers: Duration,
+    /// The side whose clock is running and when it started, if any.
+    running: Option<(Side, Duration)>,
+    /// Time used so far in the current turn, before the latest start.
+    turn: Duration,
+    /// Time added to a side's budget for each move it makes.
+    increment: Duration,
+    source: Arc<dyn TimeSource>,
+}
+
+impl Clock {
+    /// Gives both sides `budget` on a clock that follows real time.
+    pub fn new(budget: Duration) -> Self {
+        Clock::with_source(budget, Arc::new(SystemTime::new()))
+    }
+
+    /// Gives both sides `budget` on a clock that reads the time from
+    /// `source`.
+    pub fn with_source(budget: Duration, source: Arc<dyn TimeSource>) -> Self {
+        Clock {
+            goats: budget,
+            tigers: budget,
+            running: None,
+            turn: Duration::ZERO,
+            increment: Duration::ZERO,
+            source,
+        }
+    }
+
+    /// Adds `increment` to a side's time for every move it makes.
+    pub fn with_increment(mut self, increment: Duration) -> Self {
+        self.increment = increment;
+        self
+    }
+
+    /// Sets the time each side has left, for a game carried on from a
+    /// save.
+    pub(crate) fn with_remaining(mut self, goats: Duration, tigers: Duration) -> Self {
+        self.goats = goats;
+        self.tigers = tigers;
+        self
+    }
+
+    /// Time added to a side's budget for each move it makes.
+    pub fn increment(&self) -> Duration {
+        self.increment
+    }
+
+    /// Where the clock gets the time from.
+    pub(crate) fn source(&self) -> &Arc<dyn TimeSource> {
+        &self.source
+    }
+
+    /// Time `side` has left, counting the turn in progress.
+    pub fn remaining(&self, side: Side) -> Duration {
+        let spent = match self.running {
+            Some((running, started)) if running == side => self.source.now() - started,
+            _ => Duration::ZERO,
+        };
+        self.budget(side).saturating_sub(spent)
+    }
+
+    /// Whether `side` has used up all its time.
+    pub fn is_flagged(&self, side: Side) -> bool {
+        self.remaining(side).is_zero()
+    }
+
+    /// Whether a clock is running, as opposed to both being stopped or
+    /// paused.
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+
+    /// Starts `side`'s clock, or resumes it after [`Clock::stop`]. The
+    /// other clock is stopped first.
+    pub fn start(&mut self, side: Side) {
+        self.stop();
+        self.running = Some((side, self.source.now()));
+    }
+
+    /// Stops the running clock, e.g. to pause the game. The time it ran for
+    /// still counts towards the current turn.
+    pub fn stop(&mut self) {
+        if let Some((side, started)) = self.running.take() {
+            let spent = self.source.now() - started;
+            let budget = self.budget_mut(side);
+            *budget = budget.saturating_sub(spent);
+            self.turn += spent;
+        }
+    }
+
+    /// Ends the current turn, adding the increment to the side that moved,
+    /// and starts `next`'s clock. Returns the time the turn took, leaving
+    /// out pauses.
+    pub fn end_turn(&mut self, next: Side) -> Duration {
+        if let Some((mover, _)) = self.running {
+            let increment = self.increment;
+            *self.budget_mut(mover) += increment;
+        }
+        self.stop();
+        let spent = std::mem::take(&mut self.turn);
+        self.start(next);
+        spent
+    }
+
+    /// Gives `side` back time it spent, e.g. on a move that was undone.
+    pub fn refund(&mut self, side: Side, time: Duration) {
+        *self.budget_mut(side) += time;
+    }
+
+    /// Takes back `side`'s last move, which took `time`: the side gets the
+    /// time back, less the increment the move earned, and is on the clock
+    /// again.
+    pub fn take_back(&mut self, side: Side, time: Duration) {
+        self.stop();
+        self.turn = Duration::ZERO;
+        let increment = self.increment;
+        let budget = self.budget_mut(side);
+        *budget = (*budget + time).saturating_sub(increment);
+        self.start(side);
+    }
+
+    fn budget(&self, side: Side) -> Duration {
+        match side {
+            Side::Goats => self.goats,
+            Side::Tigers => self.tigers,
+        }
+    }
+
+    fn budget_mut(&mut self, side: Side) -> &mut Duration {
+        match side {
+            Side::Goats => &mut self.goats,
+            Side::Tigers => &mut self.tigers,
+        }
+    }
+}
+
+/// How long the AI may think about one move on a clock. It starts no new
+/// depth after `soft` and stops searching at `hard`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeBudget {
+    pub soft: Duration,
+    pub hard: Duration,
+}
+
+impl TimeBudget {
+    /// Shares out `remaining` time, plus the `increment` each move earns,
+    /// over the moves still to come, which are fewer the later in the
+    /// game `move_number` is. Moves in the movement phase, where a slip
+    /// costs a goat, get more than placements. A tenth of the time is
+    /// never touched, and no move may take more than a third of the rest,
+    /// so the AI doesn't run out of time.
+    pub fn plan(
+        remaining: Duration,
+        increment: Duration,
+        phase: Phase,
+        move_number: usize,
+    ) -> Self {
+        let usable = remaining - (remaining / 10).min(Duration::from_secs(2));
+        let moves_to_go = 35u32.saturating_sub(move_number as u32 / 2).max(25);
+        let share = usable / moves_to_go + increment * 3 / 4;
+        let soft = match phase {
+            Phase::Placement => share * 3 / 4,
+            Phase::Movement => share * 5 / 4,
+        };
+        let hard = (soft * 3).min(usable / 3);
+        TimeBudget {
+            soft: soft.min(hard),
+            hard,
+        }
+    }
+}
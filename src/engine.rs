@@ -0,0 +1,352 @@
+//! Interchangeable AIs.
+//!
+//! An [`Engine`] picks a move for whichever side is to move, within the
+//! limits of an [`AiConfig`]. [`MinimaxEngine`] is the AI's alpha-beta
+//! search, [`Mcts`] a Monte Carlo tree search; either can be handed to
+//! [`Board::play_engine_move`], and a game can pit one against the other.
+//! [`Engine::start_search`] runs a search on a worker thread instead, so
+//! that the caller can go on with other things while it thinks.
+
+use crate::{
+    AiConfig, Board, Mcts, Move, PositionView, SearchInfo, SearchProgress, SearchStats,
+    SearchTables, Side,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+/// Something that picks moves.
+pub trait Engine {
+    /// The move to play for the side to move on `board`, searched within
+    /// `limits`, or `None` if there is no legal move.
+    fn best_move(&mut self, board: &Board, limits: &AiConfig) -> Option<EngineResult>;
+
+    /// [`Engine::best_move`] for a position on its own, searched as a new
+    /// game from there: with no history, repetitions and the shuttle rule
+    /// don't come into it.
+    fn best_move_in(&mut self, position: &PositionView, limits: &AiConfig) -> Option<EngineResult> {
+        self.best_move(&Board::from_position(position.clone()), limits)
+    }
+
+    /// Searches `board` like [`Engine::best_move`], but on a worker
+    /// thread, returning at once. The engine goes with the search, and
+    /// [`SearchHandle::await_result`] hands it back.
+    fn start_search(self, board: Board, limits: AiConfig) -> SearchHandle<Self>
+    where
+        Self: Sized + Send + 'static,
+    {
+        SearchHandle::start(self, board, limits)
+    }
+
+    /// Forgets what earlier searches learned, such as the scores of the
+    /// positions they searched, so the next search starts cold. For
+    /// timing searches on their own.
+    fn clear_hash(&mut self) {}
+}
+
+impl<E: Engine + ?Sized> Engine for Box<E> {
+    fn best_move(&mut self, board: &Board, limits: &AiConfig) -> Option<EngineResult> {
+        (**self).best_move(board, limits)
+    }
+
+    fn clear_hash(&mut self) {
+        (**self).clear_hash();
+    }
+}
+
+/// A move an [`Engine`] picked, with what it found on the way.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EngineResult {
+    pub mv: Move,
+    /// Score of the move from the tigers' side.
+    pub score: i32,
+    /// Counters from the search, all zero for a move taken from a book.
+    pub stats: SearchStats,
+}
+
+/// The AI's alpha-beta search, with the board's opening book, evaluation
+/// and tablebase.
+///
+/// The engine remembers the scores and move ordering it learned from one
+/// search to the next, and ponders on the opponent's time when
+/// [`AiConfig::ponder`] is set, so keep one engine for the whole game.
+#[derive(Debug, Clone, Default)]
+pub struct MinimaxEngine {
+    /// Left by the last search for the next one to start from.
+    tables: Option<SearchTables>,
+    ponder: Ponder,
+    last_search: Option<SearchInfo>,
+}
+
+impl MinimaxEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How deep the last search went, the line it expected and how many
+    /// positions it visited. `None` before the first search and after a
+    /// move from the opening book.
+    pub fn last_search(&self) -> Option<&SearchInfo> {
+        self.last_search.as_ref()
+    }
+
+    /// Whether a search is running in the background on the position
+    /// after the reply the engine expects to its last move.
+    pub fn is_pondering(&self) -> bool {
+        self.ponder.0.is_some()
+    }
+
+    /// Stops the pondering search, if one is running. Its result is
+    /// thrown away.
+    pub fn stop_pondering(&mut self) {
+        self.ponder = Ponder(None);
+    }
+
+    /// Starts searching on the position after `mv` and the reply the last
+    /// search expected to it, if the search found one.
+    fn start_pondering(&mut self, board: &Board, mv: &Move, limits: &AiConfig) {
+        let Some(expected) = self.last_search.as_ref().and_then(|s| s.pv.get(1)) else {
+            return;
+        };
+        let expected = expected.clone();
+        let mut position = board.clone();
+        if !position.make_move(mv) || position.is_game_over() || !position.make_move(&expected) {
+            return;
+        }
+        let side = position.side_to_move();
+        let moves = position.move_history.len();
+        let stop = Arc::new(AtomicBool::new(false));
+        position.search_stop = Some(stop.clone());
+        position.search_progress = None;
+        position.search_tables = self.tables.clone();
+        let config = *limits;
+        let handle = std::thread::spawn(move || {
+            let chosen = position.choose_move(side, config);
+            PonderResult {
+                chosen,
+                search: position.last_search,
+                stats: position.last_search_stats,
+                tables: position.search_tables,
+            }
+        });
+        self.ponder = Ponder(Some(Pondering {
+            side,
+            line: [mv.clone(), expected],
+            moves,
+            stop,
+            handle: Some(handle),
+        }));
+    }
+
+    /// What the pondering search found if it searched the position on
+    /// `board` for `side`, waiting for it to finish. Otherwise the search is stopped
+    /// and `None` returned.
+    fn ponder_hit(&mut self, board: &Board, side: Side) -> Option<PonderResult> {
+        let mut pondering = self.ponder.0.take()?;
+        let history = &board.move_history;
+        let hit = pondering.side == side
+            && history.len() == pondering.moves
+            && history.ends_with(&pondering.line);
+        if !hit {
+            return None;
+        }
+        pondering.handle.take()?.join().ok()
+    }
+}
+
+impl MinimaxEngine {
+    /// Searches `board` for `side`, whether or not it is that side's turn.
+    pub(crate) fn search(
+        &mut self,
+        board: &Board,
+        side: Side,
+        limits: &AiConfig,
+    ) -> Option<EngineResult> {
+        let found = match self.ponder_hit(board, side) {
+            Some(found) => found,
+            None => {
+                let mut position = board.clone();
+                position.search_tables = self.tables.take();
+                let chosen = position.choose_move(side, *limits);
+                PonderResult {
+                    chosen,
+                    search: position.last_search,
+                    stats: position.last_search_stats,
+                    tables: position.search_tables,
+                }
+            }
+        };
+        if found.tables.is_some() {
+            self.tables = found.tables;
+        }
+        self.last_search = found.search;
+        let (mv, score) = found.chosen?;
+        if limits.ponder {
+            self.start_pondering(board, &mv, limits);
+        }
+        Some(EngineResult {
+            mv,
+            score,
+            stats: found.stats.unwrap_or_default(),
+        })
+    }
+}
+
+impl Engine for MinimaxEngine {
+    fn best_move(&mut self, board: &Board, limits: &AiConfig) -> Option<EngineResult> {
+        self.search(board, board.side_to_move(), limits)
+    }
+
+    fn clear_hash(&mut self) {
+        self.tables = None;
+    }
+}
+
+impl Engine for Mcts {
+    fn best_move(&mut self, board: &Board, limits: &AiConfig) -> Option<EngineResult> {
+        self.search(board, limits)
+    }
+}
+
+/// A search running on a worker thread, see [`Engine::start_search`].
+/// Dropping the handle stops the search and lets the worker finish on its
+/// own.
+pub struct SearchHandle<E> {
+    stop: Arc<AtomicBool>,
+    /// The last depth the search completed.
+    latest: Arc<Mutex<Option<SearchInfo>>>,
+    /// What the search found, once it is done.
+    result: Arc<Mutex<Option<Option<EngineResult>>>>,
+    worker: Option<JoinHandle<E>>,
+}
+
+/// How a search started with [`Engine::start_search`] is getting on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchStatus {
+    /// Still searching, with the last depth completed if there is one.
+    Running(Option<SearchInfo>),
+    /// Done, with the move found, or `None` if there was no legal move.
+    Finished(Option<EngineResult>),
+}
+
+impl<E: Engine + Send + 'static> SearchHandle<E> {
+    fn start(mut engine: E, mut board: Board, limits: AiConfig) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let latest = Arc::new(Mutex::new(None));
+        let result = Arc::new(Mutex::new(None));
+        board.search_stop = Some(stop.clone());
+        // Whoever watched the board's searches goes on watching this one
+        let watcher = board.search_progress.take();
+        let progress = latest.clone();
+        board.search_progress = Some(SearchProgress(Arc::new(move |info: &SearchInfo| {
+            *progress.lock().expect("progress lock poisoned") = Some(info.clone());
+            if let Some(watcher) = &watcher {
+                (watcher.0)(info);
+            }
+        })));
+        let found = result.clone();
+        let worker = std::thread::spawn(move || {
+            let best = engine.best_move(&board, &limits);
+            *found.lock().expect("result lock poisoned") = Some(best);
+            engine
+        });
+        SearchHandle {
+            stop,
+            latest,
+            result,
+            worker: Some(worker),
+        }
+    }
+}
+
+impl<E> SearchHandle<E> {
+    /// Whether the search is done, and what it has found so far.
+    pub fn poll(&self) -> SearchStatus {
+        if let Some(result) = self.result.lock().expect("result lock poisoned").clone() {
+            return SearchStatus::Finished(result);
+        }
+        SearchStatus::Running(self.latest.lock().expect("progress lock poisoned").clone())
+    }
+
+    /// Asks the search to stop. It soon finishes with the best move it
+    /// has found, as if it had run out of time.
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    /// Waits for the search to finish, returning what it found with the
+    /// engine, to search with again.
+    ///
+    /// # Panics
+    ///
+    /// If the engine panicked during the search.
+    pub fn await_result(mut self) -> (Option<EngineResult>, E) {
+        let worker = self.worker.take().expect("the worker is only taken here");
+        let engine = match worker.join() {
+            Ok(engine) => engine,
+            Err(panic) => std::panic::resume_unwind(panic),
+        };
+        let result = self.result.lock().expect("result lock poisoned").take();
+        (result.flatten(), engine)
+    }
+}
+
+impl<E> Drop for SearchHandle<E> {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl<E> std::fmt::Debug for SearchHandle<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SearchHandle")
+            .field("status", &self.poll())
+            .finish()
+    }
+}
+
+/// What a search hands back: the move it chose with its score, how the
+/// search went, and the tables it ended with.
+struct PonderResult {
+    chosen: Option<(Move, i32)>,
+    search: Option<SearchInfo>,
+    stats: Option<SearchStats>,
+    tables: Option<SearchTables>,
+}
+
+/// A search for `side` running in the background on the position after
+/// the engine's move and the reply it expects, `line`, which leaves
+/// `moves` moves in the history. Dropping it stops the search.
+struct Pondering {
+    side: Side,
+    line: [Move; 2],
+    moves: usize,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<PonderResult>>,
+}
+
+impl Drop for Pondering {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// The engine's pondering search, if any. A clone of the engine starts
+/// without one.
+#[derive(Default)]
+struct Ponder(Option<Pondering>);
+
+impl Clone for Ponder {
+    fn clone(&self) -> Self {
+        Ponder(None)
+    }
+}
+
+impl std::fmt::Debug for Ponder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            Some(pondering) => write!(f, "Ponder({:?})", pondering.line[1]),
+            None => f.write_str("Ponder(None)"),
+        }
+    }
+}
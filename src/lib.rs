@@ -1,25 +1,182 @@
-use colored::Colorize;
+use colored::{ColoredString, Colorize};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
 use std::fmt::Display;
-use std::time::{Duration, Instant};
+use std::ops::{Index, IndexMut};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+pub mod adjudication;
+pub mod annotate;
+pub mod batch;
+pub mod book;
+pub mod clock;
+pub mod engine;
+pub mod mcts;
+pub mod notation;
+#[cfg(feature = "onnx")]
+pub mod onnx;
+pub mod perft;
+pub mod position;
+pub mod puzzle;
+pub mod review;
+pub mod save;
+pub mod selfplay;
+#[cfg(feature = "parallel")]
+mod smp;
+pub mod solver;
+pub mod tablebase;
+pub mod theme;
+pub mod topology;
+pub mod tournament;
+mod transposition;
+
+pub use adjudication::{adjudicate, AdjudicationPolicy};
+pub use annotate::{annotate_move, MoveAnnotation};
+pub use batch::{Batch, BatchResult};
+pub use book::Book;
+pub use clock::{Clock, ManualTime, SystemTime, TimeBudget, TimeSource};
+pub use engine::{Engine, EngineResult, MinimaxEngine, SearchHandle, SearchStatus};
+pub use mcts::Mcts;
+#[cfg(feature = "onnx")]
+pub use onnx::OnnxEvaluator;
+#[cfg(feature = "parallel")]
+pub use perft::perft_parallel;
+pub use perft::{perft, perft_divide};
+use position::Pieces;
+pub use position::PositionView;
+pub use puzzle::{Puzzle, PuzzleResult};
+pub use review::{GameReview, Review};
+pub use selfplay::{SelfPlay, SelfPlaySummary};
+pub use solver::{solve, SolveLimits, SolveResult};
+use std::time::Duration;
+pub use tablebase::{Outcome, Tablebase};
+pub use theme::Theme;
+pub use topology::{Jump, Layout, Topology};
+pub use tournament::{Tournament, TournamentResult};
+use transposition::{TranspositionTable, TABLE_ENTRIES};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Piece {
     Tiger,
     Goat,
     Empty,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Position(pub usize);
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Winner {
     Tigers,
     Goats,
+    /// The game ended without a winner.
+    Draw,
     None,
 }
 
-#[derive(Debug, Clone, Copy)]
+/// Why a game came to an end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerminationReason {
+    /// The tigers captured enough goats to win.
+    GoatsCaptured,
+    /// No tiger has a legal move left.
+    TigersTrapped,
+    /// A tiger kept shuttling back and forth between two points, see
+    /// [`ShuttleRule`].
+    TigerShuttling,
+    /// Too few goats are left for the tigers to ever reach the capture
+    /// threshold, see [`RuleSet::adjudicate_hopeless`].
+    CaptureThresholdUnreachable,
+    /// A side ran out of time on its [`Clock`].
+    Timeout,
+    /// The game was cut off and given a result, see
+    /// [`Board::adjudicate`].
+    Adjudicated,
+    /// The players agreed to a draw, see [`Board::offer_draw`].
+    Agreement,
+}
+
+impl Display for TerminationReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TerminationReason::GoatsCaptured => write!(f, "enough goats were captured"),
+            TerminationReason::TigersTrapped => write!(f, "all tigers are trapped"),
+            TerminationReason::TigerShuttling => {
+                write!(f, "a tiger kept shuttling between two points")
+            }
+            TerminationReason::CaptureThresholdUnreachable => {
+                write!(f, "too few goats are left to capture")
+            }
+            TerminationReason::Timeout => write!(f, "ran out of time"),
+            TerminationReason::Adjudicated => write!(f, "the game was adjudicated"),
+            TerminationReason::Agreement => write!(f, "the players agreed to a draw"),
+        }
+    }
+}
+
+/// The outcome of a finished game: who won and why.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameResult {
+    pub winner: Winner,
+    pub reason: TerminationReason,
+}
+
+/// A complete record of a game: every move played and, once the game is
+/// over, how it ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameRecord {
+    pub rules: RuleSet,
+    /// The position the recorded moves start from, as [`Board::to_fen`]
+    /// writes it.
+    pub start: String,
+    /// Ply counter of the position the recorded moves start from.
+    pub start_ply: usize,
+    pub moves: Vec<Move>,
+    /// Time each move in `moves` took, for moves played on a clock.
+    pub move_times: Vec<Option<Duration>>,
+    /// Ply at which the players swapped sides under
+    /// [`RuleSet::swap_after`], if they did.
+    pub swapped_at: Option<usize>,
+    pub result: Option<GameResult>,
+    /// Policy the result was assigned under, if the game was adjudicated.
+    pub adjudication: Option<AdjudicationPolicy>,
+}
+
+/// A single goat capture, as recorded in the board's capture log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaptureEvent {
+    /// Index of the capturing move in the game's move history.
+    pub ply: usize,
+    /// Square the captured goat was standing on.
+    pub square: usize,
+    pub by_tiger_from: usize,
+    pub by_tiger_to: usize,
+}
+
+/// Something that happened on the board, queued for observers such as a
+/// GUI. Collect them with [`Board::drain_events`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameEvent {
+    /// A move was played.
+    Moved(Move),
+    /// A goat was captured on the given square.
+    Captured { square: usize },
+    /// The last move ended the game.
+    GameOver(GameResult),
+    /// A move was taken back with [`Board::undo`].
+    MoveUndone(Move),
+    /// Undoing a capture put the goat back on the given square.
+    CaptureUndone { square: usize },
+    /// The players swapped sides, see [`Board::swap_sides`].
+    SidesSwapped,
+    /// Undoing took the game back to before the swap, so the players are
+    /// on their original sides again.
+    SwapUndone,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Move {
     PlaceGoat {
         position: usize,
@@ -33,299 +190,2678 @@ pub enum Move {
         to: usize,
         captured_position: Option<usize>,
     },
+    /// Two or more captures chained in one turn, allowed by
+    /// [`RuleSet::multi_capture`]. Each jump starts where the last landed.
+    MultiJump(Vec<Jump>),
+}
+
+impl Move {
+    /// Whether the move captures at least one goat.
+    pub fn is_capture(&self) -> bool {
+        matches!(
+            self,
+            Move::MoveTiger {
+                captured_position: Some(_),
+                ..
+            } | Move::MultiJump(_)
+        )
+    }
+}
+
+/// Whether a tiger may chain several captures into one turn, like a piece
+/// in checkers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MultiCapture {
+    /// One capture per turn, as in the standard game.
+    #[default]
+    Off,
+    /// After a capture the tiger may keep jumping, or stop.
+    Optional,
+    /// After a capture the tiger must keep jumping while it can.
+    Mandatory,
+}
+
+/// How a game is decided when a house rule ends it early.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Adjudication {
+    GoatsWin,
+    Draw,
+}
+
+impl Adjudication {
+    fn winner(self) -> Winner {
+        match self {
+            Adjudication::GoatsWin => Winner::Goats,
+            Adjudication::Draw => Winner::Draw,
+        }
+    }
+}
+
+/// House rule against tigers that dodge a trap by stepping back and forth
+/// between two points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShuttleRule {
+    /// Consecutive back-and-forth moves by one tiger that end the game;
+    /// A→B, B→A, A→B counts as three. Goat moves in between don't
+    /// interrupt the pattern, but any other tiger move or a capture does.
+    pub moves: usize,
+    pub outcome: Adjudication,
+}
+
+/// Optional rules that vary between places and players.
+///
+/// The default is the standard game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleSet {
+    /// Let goats move on the board before every goat has been placed.
+    pub goats_may_move_during_placement: bool,
+    /// Number of captured goats that wins the game for the tigers (1-20).
+    pub capture_threshold: u32,
+    /// Goats in hand at the start of the game. Fewer than 20 is a common
+    /// handicap for the goat player.
+    pub starting_goats: u32,
+    /// Points the tigers start on. Their number is the number of tigers.
+    pub tiger_squares: Vec<usize>,
+    /// The board the game is played on.
+    pub topology: Arc<Topology>,
+    /// Whether captures can be chained within one turn.
+    pub multi_capture: MultiCapture,
+    /// A tiger that can capture must capture. Chained captures must then
+    /// be played out in full as well.
+    pub forced_capture: bool,
+    /// Ends the game when a tiger shuttles between two points, if set.
+    pub shuttle_rule: Option<ShuttleRule>,
+    /// Lets the tiger player swap sides once, after this many plies, as a
+    /// pie rule against the tigers' head start.
+    pub swap_after: Option<usize>,
+    /// Ends the game as soon as the tigers can no longer reach the capture
+    /// threshold, if set. Saves playing out dead games.
+    pub adjudicate_hopeless: Option<Adjudication>,
+}
+
+impl RuleSet {
+    /// Most points a board can have, so the search can copy a position
+    /// as two 128-bit masks. An 11×11 grid still fits.
+    pub const MAX_POINTS: usize = 128;
+
+    /// The standard rules: four tigers in the corners, twenty goats.
+    pub fn standard() -> Self {
+        RuleSet {
+            goats_may_move_during_placement: false,
+            capture_threshold: 5,
+            starting_goats: 20,
+            tiger_squares: vec![0, 4, 20, 24],
+            topology: Arc::new(Topology::standard()),
+            multi_capture: MultiCapture::Off,
+            forced_capture: false,
+            shuttle_rule: None,
+            swap_after: None,
+            adjudicate_hopeless: None,
+        }
+    }
+
+    /// Bagh-Chal on a larger N×N Alquerque grid, as some families play it
+    /// with 6×6 or 7×7 boards. The four tigers start in the corners.
+    pub fn alquerque(size: usize, starting_goats: u32) -> Self {
+        let last = size.saturating_sub(1);
+        RuleSet {
+            starting_goats,
+            tiger_squares: vec![0, last, last * size, size * size - 1],
+            topology: Arc::new(Topology::alquerque(size)),
+            ..RuleSet::standard()
+        }
+    }
+
+    /// Aadu Puli Attam, the South Indian lambs-and-tigers game: three tigers
+    /// start on the apex of a 23-point triangular board and fifteen goats
+    /// try to trap them.
+    pub fn aadu_puli_attam() -> Self {
+        RuleSet {
+            goats_may_move_during_placement: false,
+            capture_threshold: 6,
+            starting_goats: 15,
+            tiger_squares: vec![0, 3, 4],
+            topology: Arc::new(Topology::aadu_puli_attam()),
+            multi_capture: MultiCapture::Off,
+            forced_capture: false,
+            shuttle_rule: None,
+            swap_after: None,
+            adjudicate_hopeless: None,
+        }
+    }
+
+    /// Checks that the rules describe a playable game.
+    pub fn validate(&self) -> Result<(), RuleError> {
+        if !(1..=20).contains(&self.capture_threshold) {
+            return Err(RuleError::CaptureThresholdOutOfRange(
+                self.capture_threshold,
+            ));
+        }
+        if self.topology.len() > Self::MAX_POINTS {
+            return Err(RuleError::TooManyPoints(self.topology.len()));
+        }
+        if self.tiger_squares.is_empty() {
+            return Err(RuleError::NoTigers);
+        }
+        for (i, &square) in self.tiger_squares.iter().enumerate() {
+            if square >= self.topology.len() {
+                return Err(RuleError::TigerSquareOutOfBounds(square));
+            }
+            if self.tiger_squares[..i].contains(&square) {
+                return Err(RuleError::DuplicateTigerSquare(square));
+            }
+        }
+        // Every goat must be placeable with a point left over to move into
+        if self.starting_goats == 0
+            || self.starting_goats as usize + self.tiger_squares.len() >= self.topology.len()
+        {
+            return Err(RuleError::StartingGoatsOutOfRange(self.starting_goats));
+        }
+        if let Some(rule) = self.shuttle_rule {
+            // A single move can't be shuttling
+            if rule.moves < 2 {
+                return Err(RuleError::ShuttleMovesTooFew(rule.moves));
+            }
+        }
+        if self.capture_threshold > self.starting_goats {
+            return Err(RuleError::ThresholdExceedsGoats {
+                threshold: self.capture_threshold,
+                goats: self.starting_goats,
+            });
+        }
+        Ok(())
+    }
 }
 
+impl Default for RuleSet {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+/// Why a [`RuleSet`] was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleError {
+    /// The capture threshold must be between 1 and 20.
+    CaptureThresholdOutOfRange(u32),
+    /// There must be at least one goat, and the goats and tigers together
+    /// must leave a point free.
+    StartingGoatsOutOfRange(u32),
+    /// At least one tiger is needed.
+    NoTigers,
+    /// A tiger starting square is off the board.
+    TigerSquareOutOfBounds(usize),
+    /// Two tigers were given the same starting square.
+    DuplicateTigerSquare(usize),
+    /// The tigers could never capture enough goats to win.
+    ThresholdExceedsGoats { threshold: u32, goats: u32 },
+    /// The shuttle rule needs at least two moves to spot shuttling.
+    ShuttleMovesTooFew(usize),
+    /// The board has more than [`RuleSet::MAX_POINTS`] points.
+    TooManyPoints(usize),
+}
+
+impl Display for RuleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleError::CaptureThresholdOutOfRange(threshold) => write!(
+                f,
+                "capture threshold must be between 1 and 20, got {threshold}"
+            ),
+            RuleError::StartingGoatsOutOfRange(goats) => {
+                write!(f, "{goats} goats don't fit on the board with the tigers")
+            }
+            RuleError::NoTigers => write!(f, "at least one tiger is needed"),
+            RuleError::TigerSquareOutOfBounds(square) => {
+                write!(f, "tiger square {square} is off the board")
+            }
+            RuleError::DuplicateTigerSquare(square) => {
+                write!(f, "two tigers start on square {square}")
+            }
+            RuleError::ThresholdExceedsGoats { threshold, goats } => write!(
+                f,
+                "capture threshold {threshold} is more than the {goats} goats in play"
+            ),
+            RuleError::ShuttleMovesTooFew(moves) => {
+                write!(f, "shuttling needs at least 2 moves to detect, got {moves}")
+            }
+            RuleError::TooManyPoints(points) => write!(
+                f,
+                "boards have at most {} points, got {points}",
+                RuleSet::MAX_POINTS
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RuleError {}
+
+/// Why a move was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveError {
+    /// The move breaks the rules of movement.
+    Illegal,
+    /// A capture is available, and [`RuleSet::forced_capture`] says it
+    /// must be played.
+    CaptureRequired,
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveError::Illegal => write!(f, "illegal move"),
+            MoveError::CaptureRequired => write!(f, "a capture is available and must be played"),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+/// One of the two sides in the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Goats,
+    Tigers,
+}
+
+impl Side {
+    pub fn opponent(self) -> Side {
+        match self {
+            Side::Goats => Side::Tigers,
+            Side::Tigers => Side::Goats,
+        }
+    }
+}
+
+/// The two stages of a game: goats are placed until none are left in
+/// hand, after which they move like the tigers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Placement,
+    Movement,
+}
+
+/// Error produced when text describing a position can't be parsed.
+///
+/// `line` and `column` are 1-based and point at the offending character.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        ParseError {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Player {
     Human,
     AI,
 }
 
+/// Limits on how hard the AI searches for a move.
+///
+/// The search deepens one ply at a time and stops at whichever limit it
+/// reaches first, except that the first ply is always searched in full:
+/// with `max_depth` of 1 the AI looks at every move once however small the
+/// time or node budget. With every limit `None` the search goes on until it
+/// is stopped from outside.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AiConfig {
+    /// Wall-clock time to think per move. On a clock the AI plans its
+    /// own time per move, keeping within this limit as well.
+    pub time_limit: Option<Duration>,
+    /// Deepest search to start, in plies.
+    pub max_depth: Option<u32>,
+    /// Positions to visit per move, counting every deepening pass.
+    pub max_nodes: Option<u64>,
+    /// Whether to go on searching in the background after the AI moves,
+    /// on the position after the reply it expects. If the opponent plays
+    /// that reply, the AI's next move comes from the search already under
+    /// way, see [`Board::is_pondering`].
+    pub ponder: bool,
+    /// Points within which the AI treats moves as equally good, picking
+    /// one of them at random so that games vary: any move scoring less
+    /// than `tie_margin` below the best may be played. 0 always plays the
+    /// best move found, 1 picks among moves that score exactly the same.
+    pub tie_margin: i32,
+    /// Seed for picking between equally good moves. The same seed gives
+    /// the same moves in the same positions; `None` seeds from the
+    /// operating system.
+    pub seed: Option<u64>,
+    /// Mistakes to make on purpose, for easier opponents. `None` always
+    /// plays the best move found.
+    pub blunders: Option<Blunders>,
+    /// Points the AI gives up to avoid a draw by repetition, for an AI
+    /// that expects to outplay its opponent. The search scores a line that
+    /// repeats a position as this much worse than an even game for the
+    /// side it searches for; a negative value makes it seek repetitions.
+    pub contempt: i32,
+    /// Whether to let the side to move pass, searching a little less deep,
+    /// and skip the position if it is still good enough for them. This
+    /// prunes much of the tree, but is left out where passing would tell
+    /// the search nothing: in the goats' placement phase, for a cornered
+    /// tiger side and when a capture is forced.
+    pub null_move: bool,
+    /// Moves after which the search looks further than its depth.
+    pub extensions: Extensions,
+    /// Whether the goats leave out every move after which a tiger could
+    /// capture, unless they all do. A shallow search can miss the capture
+    /// past its horizon; a deep one is better left to find the goats that
+    /// are worth giving up.
+    pub safe_goats: bool,
+    /// Threads to search on. The extra threads search the same position
+    /// alongside the main one, sharing what they find through a
+    /// transposition table, and the main thread's move is played. With
+    /// more than one thread the move can differ from run to run, whatever
+    /// the seed or node limit, and [`SearchStats`] count the main thread
+    /// alone. Needs the `parallel` feature; without it the search always
+    /// runs on one thread.
+    pub threads: usize,
+}
+
+/// How often, and how badly, the AI misplays on purpose.
+///
+/// When it blunders, the AI sets aside the move it would have played and
+/// picks at random among the others scoring at most `margin` worse, never
+/// one that lets the opponent win on the spot. If there is no such move it
+/// plays its best after all. The random pick is seeded by
+/// [`AiConfig::seed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Blunders {
+    /// Percentage of moves to misplay, from 0 to 100.
+    pub chance: u8,
+    /// Points the misplayed move may score below the best.
+    pub margin: i32,
+}
+
+/// Moves that deserve a deeper look than the search depth gives them.
+///
+/// Each kind of move adds a fraction of a ply to the line it is played
+/// in, and the line is searched a ply deeper for every whole ply that adds
+/// up to, up to four plies in all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Extensions {
+    /// Half a ply after a tiger captures.
+    pub captures: bool,
+    /// Half a ply after a goat move that leaves a tiger with a single
+    /// move.
+    pub traps: bool,
+    /// A ply wherever the side to move has a single legal move.
+    pub single_reply: bool,
+}
+
+impl Extensions {
+    /// Searches every line to the same depth.
+    pub const NONE: Extensions = Extensions {
+        captures: false,
+        traps: false,
+        single_reply: false,
+    };
+}
+
+impl Default for Extensions {
+    fn default() -> Self {
+        Extensions {
+            captures: true,
+            traps: true,
+            single_reply: true,
+        }
+    }
+}
+
+/// Ready-made AI settings, from an opponent that misplays often to one
+/// that plays the best move it can find in two seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    Beginner,
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Default for AiConfig {
+    fn default() -> Self {
+        AiConfig {
+            time_limit: Some(Duration::from_secs(2)),
+            max_depth: None,
+            max_nodes: None,
+            ponder: false,
+            tie_margin: 0,
+            seed: None,
+            blunders: None,
+            contempt: 0,
+            null_move: true,
+            extensions: Extensions::default(),
+            safe_goats: false,
+            threads: 1,
+        }
+    }
+}
+
+impl AiConfig {
+    /// Searches a fixed number of positions per move with no time limit,
+    /// so the same position always gets the same move whatever the
+    /// hardware. Turn the opening book off too if it has more than one
+    /// move for a position, as it picks between them at random.
+    pub fn nodes(max_nodes: u64) -> Self {
+        AiConfig {
+            time_limit: None,
+            max_depth: None,
+            max_nodes: Some(max_nodes),
+            ponder: false,
+            tie_margin: 0,
+            seed: None,
+            blunders: None,
+            contempt: 0,
+            null_move: true,
+            extensions: Extensions::default(),
+            safe_goats: false,
+            threads: 1,
+        }
+    }
+
+    /// The settings for `difficulty`. The easier levels search a few
+    /// plies deep and misplay some of their moves, picking between equally
+    /// good moves at random, from `seed` if given. Easy and Medium goats
+    /// never leave a goat hanging when they needn't.
+    pub fn preset(difficulty: Difficulty, seed: Option<u64>) -> Self {
+        let (depth, chance, margin) = match difficulty {
+            Difficulty::Beginner => (2, 40, 150),
+            Difficulty::Easy => (3, 20, 80),
+            Difficulty::Medium => (4, 8, 40),
+            Difficulty::Hard => {
+                return AiConfig {
+                    tie_margin: 1,
+                    seed,
+                    ..AiConfig::default()
+                }
+            }
+        };
+        AiConfig {
+            time_limit: None,
+            max_depth: Some(depth),
+            max_nodes: None,
+            ponder: false,
+            tie_margin: 1,
+            seed,
+            blunders: Some(Blunders { chance, margin }),
+            contempt: 0,
+            null_move: true,
+            extensions: Extensions::default(),
+            safe_goats: matches!(difficulty, Difficulty::Easy | Difficulty::Medium),
+            threads: 1,
+        }
+    }
+}
+
+/// The parts of a board a search move changes, small enough to copy at
+/// every node. The search saves one before making a move and copies it
+/// back afterwards, so no move needs undoing piece by piece.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SearchBoard {
+    /// One bit per point holding a tiger.
+    tigers: u128,
+    /// One bit per point holding a goat.
+    goats: u128,
+    goats_in_hand: u32,
+    captured_goats: u32,
+    /// Length of the move history, which tiger moves go on.
+    history: usize,
+}
+
+impl SearchBoard {
+    fn of(board: &Board) -> Self {
+        let mut tigers = 0;
+        let mut goats = 0;
+        for (pos, piece) in board.cells.iter().enumerate() {
+            match piece {
+                Piece::Tiger => tigers |= 1 << pos,
+                Piece::Goat => goats |= 1 << pos,
+                Piece::Empty => {}
+            }
+        }
+        SearchBoard {
+            tigers,
+            goats,
+            goats_in_hand: board.goats_in_hand,
+            captured_goats: board.captured_goats,
+            history: board.move_history.len(),
+        }
+    }
+
+    /// Puts `board` back as it was when this was taken.
+    fn restore(self, board: &mut Board) {
+        for (pos, cell) in board.cells.iter_mut().enumerate() {
+            *cell = if self.tigers >> pos & 1 == 1 {
+                Piece::Tiger
+            } else if self.goats >> pos & 1 == 1 {
+                Piece::Goat
+            } else {
+                Piece::Empty
+            };
+        }
+        board.goats_in_hand = self.goats_in_hand;
+        board.captured_goats = self.captured_goats;
+        board.move_history.truncate(self.history);
+    }
+}
+
+/// State shared by every node of one search.
+struct SearchContext {
+    /// What the search reads the time from: the game's clock when playing
+    /// on one, so that its time is the time the search is charged.
+    time: Arc<dyn TimeSource>,
+    start: Duration,
+    config: AiConfig,
+    /// Past this, no new depth is started. `config.time_limit` still
+    /// bounds the search as a whole.
+    soft_limit: Option<Duration>,
+    /// Set from outside to end the search early.
+    stop: Option<Arc<AtomicBool>>,
+    stats: SearchStats,
+    /// Plies from the root to the node being searched.
+    ply: usize,
+    /// Per ply, the last two quiet moves that caused a cutoff there, as
+    /// `(from, to)` with `from == to` for a placement.
+    killers: Vec<[Option<(usize, usize)>; 2]>,
+    /// Cutoffs each quiet move has caused anywhere in the search, weighted
+    /// by the depth left, indexed by `from * points + to`.
+    history: Vec<u32>,
+    points: usize,
+    /// Per ply, the best line found from the node being searched there.
+    pv: Vec<Vec<Move>>,
+    /// The line from the root behind the move the search has settled on.
+    best_line: Vec<Move>,
+    /// Keys of the positions from the game's last placement or capture,
+    /// before which none can come up again, to the node being searched.
+    path: Vec<u64>,
+    /// Score, from the tigers' side, of a line that repeats a position.
+    draw_score: i32,
+    /// Whether the node being searched was reached by a pass, so that
+    /// the search doesn't pass twice running.
+    passed: bool,
+    /// Extensions along the line to the node being searched.
+    extension: LineExtension,
+    /// Scores of the positions searched so far this game, shared with
+    /// any other threads searching the same position.
+    table: Option<Arc<TranspositionTable>>,
+    /// Which helper thread this search runs on, 0 for the main thread.
+    #[cfg(feature = "parallel")]
+    helper: usize,
+}
+
+/// How much a line has been extended: whole plies so far, and quarter
+/// plies toward the next.
+#[derive(Debug, Clone, Copy, Default)]
+struct LineExtension {
+    plies: u32,
+    quarters: u32,
+}
+
+/// The killers, history and scores a search ended with, kept on the
+/// board so the AI's next search starts with them.
 #[derive(Debug, Clone)]
-pub struct Board {
-    pub cells: [Piece; 25],
-    pub goats_in_hand: u32,
-    pub captured_goats: u32,
-    pub selected_position: Option<usize>,
-    move_history: Vec<Move>, // Track all moves
-    ai_time_limit: Duration, // Add time limit field
+struct SearchTables {
+    /// The rules of the game searched, which the scores only hold for.
+    rules: Arc<RuleSet>,
+    /// Half-moves played when the search started.
+    ply: usize,
+    killers: Vec<[Option<(usize, usize)>; 2]>,
+    history: Vec<u32>,
+    table: Option<Arc<TranspositionTable>>,
+}
+
+impl SearchContext {
+    fn new(config: AiConfig, points: usize) -> Self {
+        let time: Arc<dyn TimeSource> = Arc::new(SystemTime::new());
+        SearchContext {
+            start: time.now(),
+            time,
+            config,
+            soft_limit: None,
+            stop: None,
+            stats: SearchStats::default(),
+            ply: 0,
+            killers: Vec::new(),
+            history: vec![0; points * points],
+            points,
+            pv: Vec::new(),
+            best_line: Vec::new(),
+            path: Vec::new(),
+            draw_score: 0,
+            passed: false,
+            extension: LineExtension::default(),
+            table: None,
+            #[cfg(feature = "parallel")]
+            helper: 0,
+        }
+    }
+
+    /// A search for helper thread `helper` to run alongside this one, with
+    /// the same limits, tables and path, ended when `stop` is set.
+    #[cfg(feature = "parallel")]
+    fn for_helper(&self, helper: usize, stop: Arc<AtomicBool>) -> Self {
+        SearchContext {
+            time: self.time.clone(),
+            start: self.start,
+            config: self.config,
+            soft_limit: self.soft_limit,
+            stop: Some(stop),
+            stats: SearchStats::default(),
+            ply: 0,
+            killers: self.killers.clone(),
+            history: self.history.clone(),
+            points: self.points,
+            pv: Vec::new(),
+            best_line: Vec::new(),
+            path: self.path.clone(),
+            draw_score: self.draw_score,
+            passed: false,
+            extension: LineExtension::default(),
+            table: self.table.clone(),
+            helper,
+        }
+    }
+
+    /// The depth iterative deepening starts from. Every other helper
+    /// thread starts a ply deeper, so the threads spread over two depths.
+    fn first_depth(&self) -> i32 {
+        #[cfg(feature = "parallel")]
+        if self.helper % 2 == 1 {
+            return 2;
+        }
+        1
+    }
+
+    /// Starts from the tables an earlier search in the same game ended
+    /// with, its root `ply` half-moves into the game. Killers move to the
+    /// plies they now stand at, and history from before counts for half
+    /// so that what this search finds soon outweighs it.
+    fn resume(&mut self, tables: Option<SearchTables>, ply: usize) {
+        let Some(mut tables) = tables else {
+            return;
+        };
+        if tables.history.len() != self.history.len() {
+            return;
+        }
+        match ply.checked_sub(tables.ply) {
+            Some(played) if played <= tables.killers.len() => {
+                tables.killers.drain(..played);
+            }
+            _ => tables.killers.clear(),
+        }
+        self.killers = tables.killers;
+        self.history = tables.history;
+        for entry in &mut self.history {
+            *entry /= 2;
+        }
+    }
+
+    /// Hands over the tables for the next search, this one having started
+    /// `ply` half-moves into a game played by `rules`.
+    fn into_tables(self, ply: usize, rules: Arc<RuleSet>) -> SearchTables {
+        SearchTables {
+            rules,
+            ply,
+            killers: self.killers,
+            history: self.history,
+            table: self.table,
+        }
+    }
+
+    /// Takes the best line found from the root as the one to play.
+    fn settle_pv(&mut self) {
+        self.best_line = self.pv.first().cloned().unwrap_or_default();
+    }
+
+    /// Forgets the line from the current ply, before searching it.
+    fn clear_pv(&mut self) {
+        if self.pv.len() <= self.ply + 1 {
+            self.pv.resize(self.ply + 2, Vec::new());
+        }
+        self.pv[self.ply].clear();
+    }
+
+    /// Makes `mv`, followed by the line just found after it, the best line
+    /// from the current ply.
+    fn update_pv(&mut self, mv: Move) {
+        if self.pv.len() <= self.ply + 1 {
+            self.pv.resize(self.ply + 2, Vec::new());
+        }
+        let (line, rest) = self.pv.split_at_mut(self.ply + 1);
+        let line = &mut line[self.ply];
+        line.clear();
+        line.push(mv);
+        line.extend(rest[0].iter().cloned());
+    }
+
+    /// Extends the line being searched by `quarters` quarter plies,
+    /// returning the whole plies that adds to its depth. Lines already
+    /// extended by the most allowed are searched to their depth.
+    fn extend(&mut self, quarters: u32) -> i32 {
+        let line = &mut self.extension;
+        if quarters == 0 || line.plies >= MAX_EXTENSION {
+            return 0;
+        }
+        line.quarters += quarters;
+        let plies = (line.quarters / 4).min(MAX_EXTENSION - line.plies);
+        line.quarters %= 4;
+        line.plies += plies;
+        plies as i32
+    }
+
+    /// Plies to extend a node by at which the side to move has `moves`
+    /// moves.
+    fn reply_extension(&mut self, moves: usize) -> i32 {
+        if moves == 1 && self.config.extensions.single_reply {
+            self.extend(4)
+        } else {
+            0
+        }
+    }
+
+    /// Plies to extend the line by after the tigers play `mv`.
+    fn capture_extension(&mut self, mv: &Move) -> i32 {
+        if mv.is_capture() && self.config.extensions.captures {
+            self.extend(2)
+        } else {
+            0
+        }
+    }
+
+    /// The line from the current ply that starts with `mv`, the move just
+    /// searched there.
+    fn line_after(&self, mv: Move) -> Vec<Move> {
+        let mut line = vec![mv];
+        if let Some(rest) = self.pv.get(self.ply + 1) {
+            line.extend(rest.iter().cloned());
+        }
+        line
+    }
+
+    /// What the search has found so far, having completed `depth` plies.
+    fn info(&self, depth: i32, score: i32) -> SearchInfo {
+        SearchInfo {
+            depth: depth as u32,
+            score,
+            nodes: self.stats.nodes,
+            elapsed: self.elapsed(),
+            pv: self.best_line.clone(),
+        }
+    }
+
+    /// The counters so far, having completed `depth` plies.
+    fn stats(&self, depth: i32) -> SearchStats {
+        SearchStats {
+            depth: depth as u32,
+            elapsed: self.elapsed(),
+            ..self.stats
+        }
+    }
+
+    /// Remembers that the quiet move `(from, to)` caused a cutoff at the
+    /// current ply with `depth` plies left to search.
+    fn record_cutoff(&mut self, mv: (usize, usize), depth: i32) {
+        if self.killers.len() <= self.ply {
+            self.killers.resize(self.ply + 1, [None; 2]);
+        }
+        let killers = &mut self.killers[self.ply];
+        if killers[0] != Some(mv) {
+            killers[1] = killers[0];
+            killers[0] = Some(mv);
+        }
+        let entry = &mut self.history[mv.0 * self.points + mv.1];
+        *entry = entry.saturating_add((depth * depth) as u32);
+    }
+
+    /// How promising the quiet move `(from, to)` looks from earlier
+    /// cutoffs: its rank among the current ply's killers, then its
+    /// history.
+    fn quiet_priority(&self, mv: (usize, usize)) -> (u8, u32) {
+        let killer = match self.killers.get(self.ply) {
+            Some([Some(first), _]) if *first == mv => 2,
+            Some([_, Some(second)]) if *second == mv => 1,
+            _ => 0,
+        };
+        (killer, self.history[mv.0 * self.points + mv.1])
+    }
+
+    /// Whether the time or node budget has run out, or the search was
+    /// stopped.
+    fn out_of_budget(&self) -> bool {
+        self.config
+            .time_limit
+            .is_some_and(|limit| self.elapsed() >= limit)
+            || self
+                .config
+                .max_nodes
+                .is_some_and(|max| self.stats.nodes >= max)
+            || self
+                .stop
+                .as_ref()
+                .is_some_and(|stop| stop.load(Ordering::Relaxed))
+    }
+
+    /// Whether to search `depth` plies deep, given that the previous depth
+    /// was completed. The first ply is always searched.
+    fn should_deepen(&self, depth: i32) -> bool {
+        depth == 1
+            || (!self.out_of_budget()
+                && self.config.max_depth.is_none_or(|max| depth <= max as i32)
+                && self.soft_limit.is_none_or(|limit| self.elapsed() < limit))
+    }
+
+    /// Time since the search started.
+    fn elapsed(&self) -> Duration {
+        self.time.now().saturating_sub(self.start)
+    }
+
+    /// Reads the time from `time` from now on, thinking within `budget`
+    /// of it, or within the configured time limit if that is shorter.
+    fn on_clock(&mut self, time: Arc<dyn TimeSource>, budget: TimeBudget) {
+        self.start = time.now();
+        self.time = time;
+        let hard = self
+            .config
+            .time_limit
+            .map_or(budget.hard, |limit| limit.min(budget.hard));
+        self.config.time_limit = Some(hard);
+        self.soft_limit = Some(budget.soft.min(hard));
+    }
+}
+
+/// Points the AI's evaluation gives each feature of a position, from the
+/// tigers' side: positive weights favour the tigers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EvalWeights {
+    /// Per goat captured so far.
+    pub captured_goat: i32,
+    /// Once the tigers can no longer reach the capture threshold.
+    pub tigers_cannot_win: i32,
+    /// Per tiger with no legal move.
+    pub tiger_trapped: i32,
+    /// Per legal tiger move, captures included.
+    pub tiger_mobility: i32,
+    /// Per tiger with only one or two legal moves left.
+    pub tiger_nearly_trapped: i32,
+    /// Per tiger whose every move leads to a point it would have one move
+    /// or none from, on top of [`tiger_nearly_trapped`](Self::tiger_nearly_trapped).
+    pub tiger_pocketed: i32,
+    /// Per tiger that a single goat, on a point it could move to, would
+    /// leave with no move at all, for each goat the tigers still need to
+    /// capture: a tiger sealed in early costs more.
+    pub tiger_sealable: i32,
+    /// Per legal goat move, once every goat is placed.
+    pub goat_mobility: i32,
+    /// Points for where each tiger stands.
+    pub tiger_squares: PieceSquareTable,
+    /// Points for where each goat stands.
+    pub goat_squares: PieceSquareTable,
+    /// Per capture the tigers could make next.
+    pub goat_capturable: i32,
+    /// Per pair of goats on neighbouring points.
+    pub goat_adjacency: i32,
+    /// Per goat the tigers could capture however the goats reply, as
+    /// [`Board::is_square_safe_for_goat`] decides, on top of
+    /// [`goat_capturable`](Self::goat_capturable).
+    pub goat_lost: i32,
+    /// Per goat on the edge of the board while goats are still being
+    /// placed.
+    pub goat_on_edge: i32,
+    /// Per goat no tiger could ever jump as things stand: every point
+    /// behind it is taken, or it isn't in the middle of any line.
+    pub goat_backed: i32,
+}
+
+impl Default for EvalWeights {
+    fn default() -> Self {
+        EvalWeights {
+            captured_goat: 100,
+            tigers_cannot_win: -5000,
+            tiger_trapped: -50,
+            tiger_mobility: 5,
+            tiger_nearly_trapped: -20,
+            tiger_pocketed: -30,
+            tiger_sealable: -10,
+            goat_mobility: -2,
+            // Tigers want the points with the most lines through them,
+            // above all while the goats are still coming in
+            tiger_squares: PieceSquareTable {
+                placement: [
+                    0, 0, 4, 0, 0, //
+                    0, 8, 4, 8, 0, //
+                    4, 4, 10, 4, 4, //
+                    0, 8, 4, 8, 0, //
+                    0, 0, 4, 0, 0, //
+                ],
+                movement: [
+                    -5, -2, 2, -2, -5, //
+                    -2, 4, 2, 4, -2, //
+                    2, 2, 5, 2, 2, //
+                    -2, 4, 2, 4, -2, //
+                    -5, -2, 2, -2, -5, //
+                ],
+            },
+            // Goats hold the middle, and start out from the corners where
+            // they can't be jumped
+            goat_squares: PieceSquareTable {
+                placement: [
+                    -2, 0, 0, 0, -2, //
+                    0, -10, -8, -10, 0, //
+                    0, -8, -10, -8, 0, //
+                    0, -10, -8, -10, 0, //
+                    -2, 0, 0, 0, -2, //
+                ],
+                movement: [
+                    0, -2, -2, -2, 0, //
+                    -2, -10, -10, -10, -2, //
+                    -2, -10, -12, -10, -2, //
+                    -2, -10, -10, -10, -2, //
+                    0, -2, -2, -2, 0, //
+                ],
+            },
+            goat_capturable: 20,
+            goat_lost: 30,
+            goat_adjacency: -3,
+            goat_on_edge: -4,
+            goat_backed: -6,
+        }
+    }
+}
+
+/// Points for a piece standing on each point of the standard board, in
+/// [`Board::cells`] order, from the tigers' side. Other boards leave the
+/// tables out.
+///
+/// The evaluation blends the two tables by how far the placement phase
+/// has gone: `placement` alone before the first goat is placed, shading
+/// into `movement` alone once the last one is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PieceSquareTable {
+    pub placement: [i32; 25],
+    pub movement: [i32; 25],
+}
+
+impl PieceSquareTable {
+    /// The table for a game with `goats_in_hand` of `starting_goats` still
+    /// to place.
+    fn blend(&self, goats_in_hand: u32, starting_goats: u32) -> [i32; 25] {
+        let (placing, starting) = (goats_in_hand as i32, starting_goats.max(1) as i32);
+        std::array::from_fn(|pos| {
+            (self.placement[pos] * placing + self.movement[pos] * (starting - placing)) / starting
+        })
+    }
+}
+
+/// Scores positions for the AI's search, from the tigers' side: positive
+/// favours the tigers. Set one with [`Board::set_evaluator`].
+///
+/// Finished games never reach the evaluator; the search scores them
+/// itself as ±10000 for a win, less a point per ply it takes to get there,
+/// or 0 for a draw, so an evaluator should stay well inside that range.
+pub trait Evaluator: std::fmt::Debug + Send + Sync {
+    fn evaluate(&self, board: &Board) -> i32;
+
+    /// Scores several positions at once, in order. Evaluators that are
+    /// quicker on a batch than one position at a time, such as a neural
+    /// network, override this; [`Mcts`] with a `leaf_batch` above 1 calls
+    /// it.
+    fn evaluate_batch(&self, boards: &[Board]) -> Vec<i32> {
+        boards.iter().map(|board| self.evaluate(board)).collect()
+    }
+}
+
+/// The built-in evaluation, [`Board::evaluate_detailed`] with the board's
+/// [`EvalWeights`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultEvaluator;
+
+impl Evaluator for DefaultEvaluator {
+    fn evaluate(&self, board: &Board) -> i32 {
+        board.evaluate_detailed().total
+    }
+}
+
+/// One feature of a position in an [`EvalBreakdown`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EvalTerm {
+    pub name: &'static str,
+    /// How many times the feature occurs.
+    pub count: i32,
+    /// Points per occurrence, from [`EvalWeights`].
+    pub weight: i32,
+    /// `count * weight`.
+    pub contribution: i32,
+}
+
+/// The AI's score for a position with the terms that make it up, see
+/// [`Board::evaluate_detailed`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EvalBreakdown {
+    /// Score from the tigers' side, the sum of the contributions.
+    pub total: i32,
+    pub terms: Vec<EvalTerm>,
+}
+
+impl EvalBreakdown {
+    fn add(&mut self, name: &'static str, count: i32, weight: i32) {
+        let contribution = count * weight;
+        self.total += contribution;
+        self.terms.push(EvalTerm {
+            name,
+            count,
+            weight,
+            contribution,
+        });
+    }
+
+    /// The breakdown as a table: every term with its count, weight and
+    /// points, which stay from the tigers' side, then the total from
+    /// `side`'s and a word on who stands better.
+    pub fn report(&self, side: Side) -> String {
+        let mut lines = vec![format!(
+            "{:<22} {:>5} {:>6} {:>7}",
+            "term", "count", "weight", "points"
+        )];
+        for term in &self.terms {
+            lines.push(format!(
+                "{:<22} {:>5} {:>6} {:>+7}",
+                term.name, term.count, term.weight, term.contribution
+            ));
+        }
+        let (own, name) = match side {
+            Side::Tigers => (self.total, "tigers"),
+            Side::Goats => (-self.total, "goats"),
+        };
+        lines.push(format!(
+            "{:<35} {:>+7}",
+            format!("total for the {name}"),
+            own
+        ));
+        let leader = if self.total > 0 { "Tigers" } else { "Goats" };
+        lines.push(match self.total.unsigned_abs() {
+            0..=29 => "The position is about even".to_string(),
+            30..=149 => format!("{leader} are slightly better"),
+            150..=399 => format!("{leader} are clearly better"),
+            _ => format!("{leader} are winning"),
+        });
+        lines.join("\n")
+    }
+}
+
+impl std::fmt::Display for EvalBreakdown {
+    /// One line per term that adds anything, then the total.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for term in self.terms.iter().filter(|term| term.contribution != 0) {
+            writeln!(
+                f,
+                "{:<22} {:>3} x {:>5} = {:>6}",
+                term.name, term.count, term.weight, term.contribution
+            )?;
+        }
+        write!(f, "{:<22} {:>20}", "total", self.total)
+    }
+}
+
+/// How far the AI's search got, see [`Board::last_search`] and
+/// [`Board::set_search_progress`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchInfo {
+    /// Deepest search completed, in plies.
+    pub depth: u32,
+    /// Score of the best move, from the tigers' side.
+    pub score: i32,
+    /// Positions visited, counting every deepening pass and every
+    /// re-search of a move that beat the null window.
+    pub nodes: u64,
+    /// Time since the search started.
+    pub elapsed: Duration,
+    /// The line the search expects, starting with the best move. Every
+    /// move in it is legal in turn from the searched position.
+    pub pv: Vec<Move>,
 }
 
-impl Board {
-    pub fn new() -> Self {
-        let mut cells = [Piece::Empty; 25];
-        cells[0] = Piece::Tiger;
-        cells[4] = Piece::Tiger;
-        cells[20] = Piece::Tiger;
-        cells[24] = Piece::Tiger;
-
-        Board {
-            cells,
-            goats_in_hand: 20,
-            captured_goats: 0,
-            selected_position: None,
-            move_history: Vec::new(),
-            ai_time_limit: Duration::from_secs(2), // Default 2 seconds
+/// Counters from the AI's search, see [`Board::last_search_stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    /// Positions visited, as in [`SearchInfo::nodes`].
+    pub nodes: u64,
+    /// Positions visited past the depth, by quiescence search, and not
+    /// counted in `nodes`.
+    pub qnodes: u64,
+    /// Moves that refuted their position before its other moves were
+    /// searched.
+    pub cutoffs: u64,
+    /// Deepest search completed, in plies.
+    pub depth: u32,
+    /// Furthest from the root any position searched was, in plies.
+    pub max_ply: u32,
+    /// Time the whole search took.
+    pub elapsed: Duration,
+}
+
+impl Display for SearchStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let secs = self.elapsed.as_secs_f64();
+        let rate = if secs > 0.0 {
+            (self.nodes + self.qnodes) as f64 / secs
+        } else {
+            0.0
+        };
+        write!(
+            f,
+            "depth {} (max ply {}), {} nodes, {} qnodes, {} cutoffs in {:.3}s ({:.0} nodes/s)",
+            self.depth, self.max_ply, self.nodes, self.qnodes, self.cutoffs, secs, rate
+        )
+    }
+}
+
+/// How [`Board::display_with`] draws the board. The default is what
+/// [`Board::display_with_hints`] shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplayOptions {
+    /// Marks the last move: brackets around the piece that moved, a dim
+    /// `◦` on the point it left and a `✗` on each point a goat was
+    /// captured from. The valid moves of a selected piece show over the
+    /// last two.
+    pub highlight_last_move: bool,
+    /// Marks each goat a tiger could capture with its next move with a
+    /// red background and a `!`, and lists them under the board.
+    pub highlight_threatened_goats: bool,
+    /// Colours the pieces and markers. Without colour the board is plain
+    /// text, and the markers that were symbols or only a colour turn to
+    /// ASCII: `.` for an empty point, `*` for a valid move, `X` for a
+    /// capturing one, `o` for the point the last move left, `x` for a
+    /// captured goat and `g` for the goat a selected tiger would take.
+    pub color: bool,
+    /// Draws the lines with `-`, `|`, `/` and `\` rather than
+    /// box-drawing characters, for terminals without them.
+    pub ascii_lines: bool,
+    /// What the pieces are drawn as. Without colour they are always
+    /// letters.
+    pub pieces: PieceStyle,
+    /// The colours to draw with.
+    pub theme: Theme,
+    /// Turns the board round, for the player sitting across from the
+    /// other: the last row at the top and the last column on the left.
+    pub flipped: bool,
+    /// Prints the row numbers on the right as well as the left, and the
+    /// column letters under the board as well as above it.
+    pub coordinates_on_all_sides: bool,
+}
+
+/// How the pieces are drawn, see [`DisplayOptions::pieces`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PieceStyle {
+    /// `T` and `G`.
+    #[default]
+    Letters,
+    /// `▲` and `●`.
+    Symbols,
+    /// 🐯 and 🐐. Each takes two columns, the second in place of the line
+    /// or marker that would follow it, so the board keeps its width.
+    Emoji,
+}
+
+impl DisplayOptions {
+    /// What `piece` is drawn as in this style; an empty point is `·`, or
+    /// `.` without colour.
+    pub fn piece_symbol(&self, piece: Piece) -> &'static str {
+        let style = if self.color {
+            self.pieces
+        } else {
+            PieceStyle::Letters
+        };
+        match (piece, style) {
+            (Piece::Tiger, PieceStyle::Letters) => "T",
+            (Piece::Tiger, PieceStyle::Symbols) => "▲",
+            (Piece::Tiger, PieceStyle::Emoji) => "🐯",
+            (Piece::Goat, PieceStyle::Letters) => "G",
+            (Piece::Goat, PieceStyle::Symbols) => "●",
+            (Piece::Goat, PieceStyle::Emoji) => "🐐",
+            (Piece::Empty, _) => self.glyph("·", "."),
+        }
+    }
+
+    /// Whether pieces take two columns on a terminal.
+    fn wide_pieces(&self) -> bool {
+        self.color && self.pieces == PieceStyle::Emoji
+    }
+
+    /// `fancy`, or `plain` when drawing without colour.
+    fn glyph(&self, fancy: &'static str, plain: &'static str) -> &'static str {
+        if self.color {
+            fancy
+        } else {
+            plain
+        }
+    }
+
+    /// `line`, or `ascii` when drawing ASCII lines, in the colour of the
+    /// borders.
+    fn stroke(&self, line: &'static str, ascii: &'static str) -> ColoredString {
+        if self.ascii_lines { ascii } else { line }.color(self.theme.borders)
+    }
+}
+
+impl Default for DisplayOptions {
+    fn default() -> Self {
+        DisplayOptions {
+            highlight_last_move: true,
+            highlight_threatened_goats: true,
+            color: true,
+            ascii_lines: false,
+            pieces: PieceStyle::Letters,
+            theme: Theme::default(),
+            flipped: false,
+            coordinates_on_all_sides: false,
+        }
+    }
+}
+
+/// Called with each depth the AI's search completes.
+#[derive(Clone)]
+struct SearchProgress(Arc<dyn Fn(&SearchInfo) + Send + Sync>);
+
+impl std::fmt::Debug for SearchProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SearchProgress")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Board {
+    pub cells: Vec<Piece>,
+    pub goats_in_hand: u32,
+    pub captured_goats: u32,
+    pub selected_position: Option<usize>,
+    rules: Arc<RuleSet>,
+    move_history: Vec<Move>,           // Track all moves
+    captures: Vec<CaptureEvent>,       // Captures in the order they happened
+    ply: usize,                        // Half-moves played, goats move on even plies
+    swapped_at: Option<usize>,         // Ply the players swapped sides at
+    clock: Option<Clock>,              // Game clocks, if playing on time
+    move_times: Vec<Option<Duration>>, // Time each move in the history took
+    events: Vec<GameEvent>,            // Not yet drained by an observer
+    ai_config: AiConfig,               // How hard the AI searches
+    eval_weights: EvalWeights,         // How the AI values positions
+    // Evaluation the search uses, if not the built-in one
+    evaluator: Option<Arc<dyn Evaluator>>,
+    book: Option<Arc<Book>>,         // Opening book, if not the built-in one
+    use_book: bool,                  // Whether the AI plays from the book
+    last_search: Option<SearchInfo>, // How far the AI's last search got
+    last_search_stats: Option<SearchStats>, // Counters from that search
+    search_progress: Option<SearchProgress>, // Told of each completed depth
+    search_stop: Option<Arc<AtomicBool>>, // Ends the search when set
+    engine: MinimaxEngine,           // Plays the AI's moves
+    search_tables: Option<SearchTables>, // Left by the AI's last search
+    multi_pv: usize,                 // Root moves a search scores exactly, if any, for top_moves
+    root_lines: Vec<(Move, i32, Vec<Move>)>, // Those moves with their lines
+    // Result assigned to a cut-off game, with the policy that assigned it
+    adjudicated: Option<(GameResult, AdjudicationPolicy)>,
+    draw_offer: Option<Side>, // Side whose offer of a draw is open
+    agreed_draw: bool,        // Whether the players agreed to a draw
+    legal_moves: MoveCache,   // Legal moves of the position last asked about
+    taken_back: Vec<Move>,    // Moves undone, latest last, to play again
+}
+
+/// The legal moves of the last position they were asked for, so that
+/// asking again before anything moves costs a look-up. The moves are kept
+/// with the position's key rather than cleared by each change, since the
+/// board's fields can be written from outside.
+#[derive(Debug, Default)]
+struct MoveCache {
+    entry: Mutex<Option<(u64, Arc<[Move]>)>>,
+    /// Times the moves had to be generated, for the tests.
+    generated: AtomicU64,
+}
+
+impl Clone for MoveCache {
+    fn clone(&self) -> Self {
+        MoveCache {
+            entry: Mutex::new(self.entry.lock().expect("move cache poisoned").clone()),
+            generated: AtomicU64::new(self.generated.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+impl Board {
+    pub fn new() -> Self {
+        Board::with_rules(RuleSet::default()).expect("standard rules are valid")
+    }
+
+    /// Creates a board set up for a new game under the given rules.
+    pub fn with_rules(rules: RuleSet) -> Result<Self, RuleError> {
+        rules.validate()?;
+        Ok(Board::with_shared_rules(Arc::new(rules)))
+    }
+
+    /// A new game under `rules`, which have been validated already.
+    fn with_shared_rules(rules: Arc<RuleSet>) -> Self {
+        let mut board = Board {
+            cells: Vec::new(),
+            goats_in_hand: 0,
+            captured_goats: 0,
+            selected_position: None,
+            rules,
+            move_history: Vec::new(),
+            captures: Vec::new(),
+            ply: 0,
+            swapped_at: None,
+            clock: None,
+            move_times: Vec::new(),
+            events: Vec::new(),
+            ai_config: AiConfig::default(),
+            eval_weights: EvalWeights::default(),
+            evaluator: None,
+            book: None,
+            use_book: true,
+            last_search: None,
+            last_search_stats: None,
+            search_progress: None,
+            search_stop: None,
+            engine: MinimaxEngine::default(),
+            search_tables: None,
+            multi_pv: 0,
+            root_lines: Vec::new(),
+            adjudicated: None,
+            draw_offer: None,
+            agreed_draw: false,
+            legal_moves: MoveCache::default(),
+            taken_back: Vec::new(),
+        };
+        board.reset();
+        board
+    }
+
+    /// Puts the pieces back in their starting positions under the current
+    /// rules and forgets the game played so far, clock included.
+    pub fn reset(&mut self) {
+        self.cells = vec![Piece::Empty; self.rules.topology.len()];
+        for &square in &self.rules.tiger_squares {
+            self.cells[square] = Piece::Tiger;
+        }
+        self.goats_in_hand = self.rules.starting_goats;
+        self.captured_goats = 0;
+        self.selected_position = None;
+        self.move_history.clear();
+        self.taken_back.clear();
+        self.captures.clear();
+        self.ply = 0;
+        self.swapped_at = None;
+        self.clock = None;
+        self.move_times.clear();
+        self.events.clear();
+        self.adjudicated = None;
+        self.draw_offer = None;
+        self.agreed_draw = false;
+        self.search_tables = None;
+        self.engine = MinimaxEngine::default();
+    }
+
+    pub fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    fn pieces(&self) -> Pieces<'_> {
+        Pieces {
+            cells: &self.cells,
+            rules: &self.rules,
+            goats_in_hand: self.goats_in_hand,
+            captured_goats: self.captured_goats,
+            ply: self.ply,
+        }
+    }
+
+    /// The position as it stands, without the game that led to it, to hand
+    /// to other threads. The view shares the board's rules rather than
+    /// copying them.
+    pub fn position(&self) -> PositionView {
+        PositionView {
+            cells: self.cells.as_slice().into(),
+            rules: self.rules.clone(),
+            goats_in_hand: self.goats_in_hand,
+            captured_goats: self.captured_goats,
+            ply: self.ply,
+        }
+    }
+
+    /// A board set up at `position` with an empty move history, like one
+    /// read from the position's FEN.
+    pub fn from_position(position: PositionView) -> Board {
+        let mut board = Board::with_shared_rules(position.rules);
+        board.cells = position.cells.to_vec();
+        board.goats_in_hand = position.goats_in_hand;
+        board.captured_goats = position.captured_goats;
+        board.ply = position.ply;
+        board
+    }
+
+    /// Returns the piece at `pos`, or `None` if it's off the board.
+    pub fn get(&self, pos: Position) -> Option<Piece> {
+        self.cells.get(pos.0).copied()
+    }
+
+    /// Iterates over every cell of the board together with its contents.
+    pub fn iter_pieces(&self) -> impl Iterator<Item = (Position, Piece)> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(pos, &piece)| (Position(pos), piece))
+    }
+
+    /// Iterates over the positions that currently hold `piece`.
+    fn positions_of(&self, piece: Piece) -> impl Iterator<Item = Position> + '_ {
+        self.pieces().positions_of(piece)
+    }
+
+    /// Iterates over all unoccupied positions.
+    pub fn empty_cells(&self) -> impl Iterator<Item = Position> + '_ {
+        self.positions_of(Piece::Empty)
+    }
+
+    /// Iterates over the positions of all goats on the board.
+    pub fn goats(&self) -> impl Iterator<Item = Position> + '_ {
+        self.positions_of(Piece::Goat)
+    }
+
+    /// Iterates over the positions of all tigers on the board.
+    pub fn tigers(&self) -> impl Iterator<Item = Position> + '_ {
+        self.positions_of(Piece::Tiger)
+    }
+
+    /// Sets how hard the AI searches for its moves.
+    pub fn set_ai_config(&mut self, config: AiConfig) {
+        self.ai_config = config;
+    }
+
+    pub fn ai_config(&self) -> &AiConfig {
+        &self.ai_config
+    }
+
+    /// Sets how the AI values positions.
+    pub fn set_eval_weights(&mut self, weights: EvalWeights) {
+        self.eval_weights = weights;
+    }
+
+    pub fn eval_weights(&self) -> &EvalWeights {
+        &self.eval_weights
+    }
+
+    /// Sets the evaluation the AI searches with, in place of
+    /// [`DefaultEvaluator`].
+    pub fn set_evaluator(&mut self, evaluator: Arc<dyn Evaluator>) {
+        self.evaluator = Some(evaluator);
+    }
+
+    /// Sets how long the AI may think about each move, leaving its other
+    /// limits alone. The default is two seconds.
+    pub fn set_ai_time_limit(&mut self, limit: Duration) {
+        self.ai_config.time_limit = Some(limit);
+    }
+
+    pub fn ai_time_limit(&self) -> Option<Duration> {
+        self.ai_config.time_limit
+    }
+
+    /// Sets the opening book the AI plays from while the position is in
+    /// it, in place of the [built-in one](book::builtin).
+    pub fn set_opening_book(&mut self, book: Arc<Book>) {
+        self.book = Some(book);
+    }
+
+    /// Turns the opening book on or off; it's on by default.
+    pub fn set_use_opening_book(&mut self, enabled: bool) {
+        self.use_book = enabled;
+    }
+
+    pub fn uses_opening_book(&self) -> bool {
+        self.use_book
+    }
+
+    /// A move from the opening book for `side`, if it's that side's turn
+    /// and the position is in the book.
+    fn book_move(&self, side: Side) -> Option<Move> {
+        if !self.use_book || self.side_to_move() != side {
+            return None;
+        }
+        match &self.book {
+            Some(book) => book.probe(self),
+            None => book::builtin().probe(self),
+        }
+    }
+
+    pub fn display_with_hints(&self) -> String {
+        self.display_with(&DisplayOptions::default())
+    }
+
+    /// Draws the board as `options` ask, with the valid moves of the
+    /// selected piece marked.
+    pub fn display_with(&self, options: &DisplayOptions) -> String {
+        let mut output = self.display_points_with_hints(options);
+        let threatened = self.threatened_goats();
+        if options.highlight_threatened_goats && !threatened.is_empty() {
+            let names: Vec<String> = threatened
+                .iter()
+                .map(|&pos| self.rules.topology.point_name(pos))
+                .collect();
+            let warning = match threatened.len() {
+                1 => format!("1 goat can be captured: {}", names[0]),
+                count => format!("{count} goats can be captured: {}", names.join(", ")),
+            };
+            let warning = format!("{} {warning}", options.glyph("⚠", "!"));
+            if options.color {
+                output.push_str(&format!("   {}\n", warning.color(options.theme.danger)));
+            } else {
+                output.push_str(&format!("   {warning}\n"));
+            }
+        }
+        output
+    }
+
+    /// The position in words, for players using a screen reader: where
+    /// the pieces stand, the goats in hand and captured, the last move,
+    /// whose turn it is or how the game ended, and each goat standing next
+    /// to a tiger on a line, with whether the point the tiger would land on
+    /// is free.
+    pub fn describe(&self) -> String {
+        let topology = &self.rules.topology;
+        let names = |points: &[usize]| {
+            let names: Vec<String> = points.iter().map(|&pos| topology.point_name(pos)).collect();
+            names.join(", ")
+        };
+        let plural = |count: u32, what: &str| match count {
+            1 => format!("1 {what}"),
+            count => format!("{count} {what}s"),
+        };
+
+        let mut sentences = Vec::new();
+        for (piece, name) in [(Piece::Tiger, "Tigers"), (Piece::Goat, "Goats")] {
+            let points: Vec<usize> = (0..self.cells.len())
+                .filter(|&pos| self.cells[pos] == piece)
+                .collect();
+            sentences.push(match points.is_empty() {
+                true => format!("No {} on the board.", name.to_lowercase()),
+                false => format!("{name} on {}.", names(&points)),
+            });
+        }
+        sentences.push(format!(
+            "{} in hand, {} captured.",
+            plural(self.goats_in_hand, "goat"),
+            self.captured_goats
+        ));
+        if let Some(mv) = self.last_move() {
+            sentences.push(format!(
+                "Last move {}.",
+                notation::format_move(topology, mv)
+            ));
+        }
+        sentences.push(match self.result() {
+            Some(GameResult {
+                winner: Winner::Tigers,
+                reason,
+            }) => format!("Tigers won: {reason}."),
+            Some(GameResult {
+                winner: Winner::Goats,
+                reason,
+            }) => format!("Goats won: {reason}."),
+            Some(GameResult { reason, .. }) => format!("Drawn: {reason}."),
+            None => match self.side_to_move() {
+                Side::Tigers => "Tigers to move.".to_string(),
+                Side::Goats => "Goats to move.".to_string(),
+            },
+        });
+
+        let mut threats: Vec<&Jump> = (0..self.cells.len())
+            .filter(|&pos| self.cells[pos] == Piece::Tiger)
+            .flat_map(|tiger| topology.jumps_from(tiger))
+            .filter(|jump| self.cells[jump.over] == Piece::Goat)
+            .collect();
+        threats.sort_by_key(|jump| (jump.over, jump.from));
+        for jump in threats {
+            let blocked = match self.cells[jump.to] {
+                Piece::Empty => "",
+                _ => " — blocked",
+            };
+            sentences.push(format!(
+                "Goat on {} is threatened by the tiger on {} (would land on {}{blocked}).",
+                topology.point_name(jump.over),
+                topology.point_name(jump.from),
+                topology.point_name(jump.to)
+            ));
+        }
+        sentences.join(" ")
+    }
+
+    /// Symbol for a point: the piece on it, or a marker when it's empty.
+    fn hint_symbol(&self, pos: usize, options: &DisplayOptions) -> ColoredString {
+        let highlight = options.highlight_last_move;
+        let theme = &options.theme;
+        match self.cells[pos] {
+            Piece::Empty => {
+                if self.selected_position.is_some()
+                    && self.is_valid_move(self.selected_position.unwrap(), pos)
+                {
+                    // A selected tiger's captures stand out from its quiet
+                    // moves
+                    match self.selected_capture_to(pos) {
+                        Some(_) => options.glyph("‼", "X").color(theme.danger).bold(),
+                        None => options.glyph("•", "*").color(theme.hint),
+                    }
+                } else if highlight && self.last_capture().contains(&pos) {
+                    options.glyph("✗", "x").color(theme.danger)
+                } else if highlight && self.last_move_from() == Some(pos) {
+                    options.glyph("◦", "o").color(theme.borders)
+                } else {
+                    options.piece_symbol(Piece::Empty).normal()
+                }
+            }
+            // The goat a selected tiger could capture is dimmed
+            Piece::Goat if self.selected_capture_over(pos) => match options.color {
+                true => options.piece_symbol(Piece::Goat).color(theme.goat).dimmed(),
+                false => "g".normal(),
+            },
+            piece => {
+                let color = match piece {
+                    _ if self.selected_position == Some(pos) => theme.selection,
+                    Piece::Tiger => theme.tiger,
+                    _ => theme.goat,
+                };
+                let mut symbol = options.piece_symbol(piece).color(color);
+                // The piece that just moved is underlined
+                if highlight && self.last_move_to() == Some(pos) {
+                    symbol = symbol.underline();
+                }
+                if self.threat_marked(pos, options) {
+                    symbol = symbol.on_color(theme.danger);
+                }
+                symbol
+            }
+        }
+    }
+
+    /// The goat the selected tiger would capture by moving to `to`.
+    fn selected_capture_to(&self, to: usize) -> Option<usize> {
+        self.would_capture(self.selected_position?, to)
+    }
+
+    /// Whether the selected tiger has a move that captures the goat on
+    /// `pos`.
+    fn selected_capture_over(&self, pos: usize) -> bool {
+        self.selected_position.is_some_and(|tiger| {
+            self.get_valid_tiger_moves(tiger)
+                .iter()
+                .any(|&Position(to)| self.would_capture(tiger, to) == Some(pos))
+        })
+    }
+
+    /// Whether `pos` holds a goat marked as one a tiger could capture
+    /// next.
+    fn threat_marked(&self, pos: usize, options: &DisplayOptions) -> bool {
+        options.highlight_threatened_goats && self.is_goat_threatened(pos)
+    }
+
+    /// Whether `pos` gets brackets around it, as the point the last move
+    /// put its piece on.
+    fn bracketed(&self, pos: usize, options: &DisplayOptions) -> bool {
+        options.highlight_last_move && self.last_move_to() == Some(pos)
+    }
+
+    /// Where the last move put its piece.
+    fn last_move_to(&self) -> Option<usize> {
+        match self.last_move()? {
+            Move::PlaceGoat { position } => Some(*position),
+            Move::MoveGoat { to, .. } | Move::MoveTiger { to, .. } => Some(*to),
+            Move::MultiJump(jumps) => jumps.last().map(|jump| jump.to),
+        }
+    }
+
+    /// Where the last move took its piece from, or `None` for a placement.
+    fn last_move_from(&self) -> Option<usize> {
+        match self.last_move()? {
+            Move::PlaceGoat { .. } => None,
+            Move::MoveGoat { from, .. } | Move::MoveTiger { from, .. } => Some(*from),
+            Move::MultiJump(jumps) => jumps.first().map(|jump| jump.from),
+        }
+    }
+
+    /// The points the last move captured goats on, in the order it jumped
+    /// them; empty if it captured none.
+    pub fn last_capture(&self) -> Vec<usize> {
+        match self.last_move() {
+            Some(Move::MoveTiger {
+                captured_position: Some(over),
+                ..
+            }) => vec![*over],
+            Some(Move::MultiJump(jumps)) => jumps.iter().map(|jump| jump.over).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Draws the board as its points and the lines joining them, read off
+    /// the topology: a row of points to a line, with the lines between two
+    /// rows under it. Lines reaching further than the next column, like the
+    /// fan under the apex of the Aadu Puli Attam board, take three.
+    /// Turned round, the last row is drawn first and each row runs from
+    /// its last column.
+    fn display_points_with_hints(&self, options: &DisplayOptions) -> String {
+        let topology = &self.rules.topology;
+        let mut rows = topology.rows();
+        if options.flipped {
+            rows.reverse();
+        }
+        let width = rows
+            .iter()
+            .flatten()
+            .map(|&p| topology.coords(p).1)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        // The layout column drawn in place of `col`, and its character
+        // offset
+        let turned = |col: usize| match options.flipped {
+            true => width - 1 - col,
+            false => col,
+        };
+        let offset = |col: usize| 5 + col * 4;
+        let column = |pos: usize| offset(turned(topology.coords(pos).1));
+
+        let mut letters = String::from("    ");
+        for col in 0..width {
+            letters.push_str(&format!(" {}  ", (b'A' + turned(col) as u8) as char));
+        }
+        letters.truncate(letters.trim_end().len());
+        letters.push('\n');
+        let mut output = letters.clone();
+
+        for (i, row) in rows.iter().enumerate() {
+            let mut line = vec![" ".normal(); offset(width) + 2];
+            let row_number = topology.coords(row[0]).0 + 1;
+            for (cell, digit) in line.iter_mut().zip(format!("{row_number:>2}").chars()) {
+                *cell = digit.to_string().normal();
+            }
+            if options.coordinates_on_all_sides {
+                let right = &mut line[offset(width)..];
+                for (cell, digit) in right.iter_mut().zip(row_number.to_string().chars()) {
+                    *cell = digit.to_string().normal();
+                }
+            }
+            for (k, &pos) in row.iter().enumerate() {
+                line[column(pos)] = self.hint_symbol(pos, options);
+                if let Some(&next) = row.get(k + 1) {
+                    if topology.neighbors(pos).contains(&next) {
+                        let (left, right) = match column(pos) < column(next) {
+                            true => (column(pos), column(next)),
+                            false => (column(next), column(pos)),
+                        };
+                        for cell in &mut line[left + 1..right] {
+                            *cell = options.stroke("─", "-");
+                        }
+                    }
+                }
+            }
+            for &pos in row {
+                let at = column(pos);
+                // A wide piece covers the column after it, so what would
+                // go there moves one along
+                let wide = options.wide_pieces() && self.cells[pos] != Piece::Empty;
+                let after = if wide { at + 2 } else { at + 1 };
+                if wide {
+                    line[at + 1] = "".normal();
+                }
+                if self.bracketed(pos, options) {
+                    line[at - 1] = "[".normal();
+                    line[after] = "]".normal();
+                } else if self.threat_marked(pos, options) {
+                    line[after] = "!".color(options.theme.danger);
+                }
+            }
+            push_line(&mut output, &line, options.color);
+
+            let Some(below) = rows.get(i + 1) else { break };
+            let links: Vec<(usize, usize)> = row
+                .iter()
+                .flat_map(|&pos| {
+                    below
+                        .iter()
+                        .filter(move |&&other| topology.neighbors(pos).contains(&other))
+                        .map(move |&other| (column(pos), column(other)))
+                })
+                .collect();
+            let steps = if links.iter().any(|&(from, to)| from.abs_diff(to) > 4) {
+                3
+            } else {
+                1
+            };
+            for step in 1..=steps {
+                let mut line = vec![" ".normal(); offset(width)];
+                for &(from, to) in &links {
+                    let at = (from as isize + (to as isize - from as isize) * step / (steps + 1))
+                        as usize;
+                    let (stroke, ascii) = match to.cmp(&from) {
+                        std::cmp::Ordering::Less => ("╱", "/"),
+                        std::cmp::Ordering::Equal => ("│", "|"),
+                        std::cmp::Ordering::Greater => ("╲", "\\"),
+                    };
+                    // Two diagonals crossing between four points
+                    let crossed = matches!(
+                        (line[at].as_ref(), stroke),
+                        ("╱" | "/", "╲") | ("╲" | "\\", "╱")
+                    );
+                    line[at] = if crossed {
+                        options.stroke("╳", "X")
+                    } else {
+                        options.stroke(stroke, ascii)
+                    };
+                }
+                push_line(&mut output, &line, options.color);
+            }
+        }
+        if options.coordinates_on_all_sides {
+            output.push_str(&letters);
+        }
+
+        output
+    }
+
+    pub fn select_position(&mut self, pos: usize) -> bool {
+        if pos >= self.cells.len() {
+            return false;
+        }
+        self.selected_position = Some(pos);
+        true
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.selected_position = None;
+    }
+
+    pub fn place_goat(&mut self, position: usize) -> bool {
+        if self.side_to_move() != Side::Goats
+            || position >= self.cells.len()
+            || self.cells[position] != Piece::Empty
+            || self.goats_in_hand == 0
+        {
+            return false;
+        }
+
+        self.cells[position] = Piece::Goat;
+        self.goats_in_hand -= 1;
+        self.finish_move(Move::PlaceGoat { position });
+        true
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.get_winner() != Winner::None
+    }
+
+    pub fn get_winner(&self) -> Winner {
+        self.result().map_or(Winner::None, |result| result.winner)
+    }
+
+    /// Returns the result of the game, or `None` while it is still in
+    /// progress.
+    pub fn result(&self) -> Option<GameResult> {
+        if let Some((result, _)) = self.adjudicated {
+            return Some(result);
+        }
+        if self.agreed_draw {
+            return Some(GameResult {
+                winner: Winner::Draw,
+                reason: TerminationReason::Agreement,
+            });
+        }
+        if let Some(clock) = &self.clock {
+            if clock.is_flagged(Side::Goats) {
+                return Some(GameResult {
+                    winner: Winner::Tigers,
+                    reason: TerminationReason::Timeout,
+                });
+            }
+            if clock.is_flagged(Side::Tigers) {
+                return Some(GameResult {
+                    winner: Winner::Goats,
+                    reason: TerminationReason::Timeout,
+                });
+            }
+        }
+
+        // Tigers win once they've captured enough goats, before the shuttle
+        // rule is looked at; the rest of what the pieces decide comes after
+        let position = self.pieces().result();
+        if let Some(GameResult {
+            reason: TerminationReason::GoatsCaptured,
+            ..
+        }) = position
+        {
+            return position;
+        }
+
+        if let Some(rule) = self.rules.shuttle_rule {
+            if self.shuttle_count() >= rule.moves {
+                return Some(GameResult {
+                    winner: rule.outcome.winner(),
+                    reason: TerminationReason::TigerShuttling,
+                });
+            }
+        }
+
+        position
+    }
+
+    /// Whether some tiger has a legal move. Stops at the first one found
+    /// without building any move lists, as the search asks at every node.
+    pub fn any_tiger_can_move(&self) -> bool {
+        self.pieces().any_tiger_can_move()
+    }
+
+    /// Whether enough goats are left, on the board and in hand, for the
+    /// tigers to still reach the capture threshold.
+    pub fn tigers_can_still_win(&self) -> bool {
+        self.pieces().tigers_can_still_win()
+    }
+
+    /// Length of the run of back-and-forth moves by one tiger that ends with
+    /// the latest tiger move. Goat moves are skipped over.
+    fn shuttle_count(&self) -> usize {
+        let mut tiger_moves = self.move_history.iter().rev().filter_map(|mv| match mv {
+            Move::MoveTiger {
+                from,
+                to,
+                captured_position: None,
+            } => Some(Some((*from, *to))),
+            Move::MoveTiger { .. } | Move::MultiJump(_) => Some(None),
+            Move::PlaceGoat { .. } | Move::MoveGoat { .. } => None,
+        });
+
+        let Some(Some(mut last)) = tiger_moves.next() else {
+            return 0;
+        };
+        let mut count = 1;
+        for mv in tiger_moves {
+            // Each earlier move must be the reverse of the one after it
+            match mv {
+                Some((from, to)) if (to, from) == last => {
+                    count += 1;
+                    last = (from, to);
+                }
+                _ => break,
+            }
+        }
+        count
+    }
+
+    /// Whether the tiger player may swap sides now: the game has reached
+    /// the ply set by [`RuleSet::swap_after`] and nobody has swapped yet.
+    pub fn can_swap(&self) -> bool {
+        self.rules.swap_after == Some(self.ply) && self.swapped_at.is_none() && !self.is_game_over()
+    }
+
+    /// Swaps the players' sides. The pieces stay where they are; each
+    /// player takes over the other's. Returns `false` if a swap isn't
+    /// allowed now.
+    pub fn swap_sides(&mut self) -> bool {
+        if !self.can_swap() {
+            return false;
+        }
+        self.swapped_at = Some(self.ply);
+        self.events.push(GameEvent::SidesSwapped);
+        true
+    }
+
+    /// Ply at which the players swapped sides, if they have.
+    pub fn swapped_at(&self) -> Option<usize> {
+        self.swapped_at
+    }
+
+    /// Whether the AI, holding the swap option as the tigers, would rather
+    /// play the goats from here.
+    pub fn ai_should_swap(&self) -> bool {
+        self.can_swap() && self.evaluate_position() < 0
+    }
+
+    /// Plays the rest of the game on `clock`. The clock of the side to
+    /// move starts running right away.
+    pub fn set_clock(&mut self, mut clock: Clock) {
+        clock.start(self.side_to_move());
+        self.clock = Some(clock);
+    }
+
+    pub fn clock(&self) -> Option<&Clock> {
+        self.clock.as_ref()
+    }
+
+    /// Time `side` has left on the clock, or `None` if the game isn't
+    /// played on a clock.
+    pub fn remaining_time(&self, side: Side) -> Option<Duration> {
+        self.clock.as_ref().map(|clock| clock.remaining(side))
+    }
+
+    /// Stops the clock until [`Board::resume_clock`], e.g. while a hint is
+    /// being worked out.
+    pub fn pause_clock(&mut self) {
+        if let Some(clock) = &mut self.clock {
+            clock.stop();
+        }
+    }
+
+    pub fn resume_clock(&mut self) {
+        if self.is_game_over() {
+            return;
+        }
+        let side = self.side_to_move();
+        if let Some(clock) = &mut self.clock {
+            clock.start(side);
+        }
+    }
+
+    /// Returns the events that happened since the previous call, oldest
+    /// first.
+    pub fn drain_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Records a move that has just been applied, along with the game
+    /// ending it may have caused.
+    fn finish_move(&mut self, mv: Move) {
+        self.ply += 1;
+        self.events.push(GameEvent::Moved(mv.clone()));
+        match &mv {
+            Move::MoveTiger {
+                captured_position: Some(square),
+                ..
+            } => self.events.push(GameEvent::Captured { square: *square }),
+            Move::MultiJump(jumps) => {
+                for jump in jumps {
+                    self.events.push(GameEvent::Captured { square: jump.over });
+                }
+            }
+            _ => {}
+        }
+        // Playing again a move taken back keeps the ones after it
+        if self.taken_back.last() == Some(&mv) {
+            self.taken_back.pop();
+        } else {
+            self.taken_back.clear();
+        }
+        self.move_history.push(mv);
+        let next = self.side_to_move();
+        // A move by the side a draw was offered to turns it down
+        if self.draw_offer == Some(next) {
+            self.draw_offer = None;
+        }
+        self.move_times
+            .push(self.clock.as_mut().map(|clock| clock.end_turn(next)));
+        if let Some(result) = self.result() {
+            if let Some(clock) = &mut self.clock {
+                clock.stop();
+            }
+            self.events.push(GameEvent::GameOver(result));
+        }
+    }
+
+    /// Ends an unfinished game with the result `policy` assigns it, see
+    /// [`adjudicate`]. The policy is kept in the game's
+    /// [record](Board::record). A game that is already over is left as it
+    /// is.
+    pub fn adjudicate(&mut self, policy: &AdjudicationPolicy) -> GameResult {
+        if let Some(result) = self.result() {
+            return result;
+        }
+        let result = adjudicate(self, policy);
+        self.adjudicated = Some((result, *policy));
+        if let Some(clock) = &mut self.clock {
+            clock.stop();
         }
+        self.events.push(GameEvent::GameOver(result));
+        result
     }
 
-    // Add setter for AI time limit
-    pub fn set_ai_time_limit(&mut self, seconds: u64) {
-        self.ai_time_limit = Duration::from_secs(seconds);
+    /// Offers a draw on behalf of the side to move. The offer stays open
+    /// until the other side accepts or declines it, or makes a move.
+    /// Returns `false` once the game is over.
+    pub fn offer_draw(&mut self) -> bool {
+        if self.is_game_over() {
+            return false;
+        }
+        self.draw_offer = Some(self.side_to_move());
+        true
     }
 
-    // Add getter for AI time limit
-    pub fn get_ai_time_limit(&self) -> u64 {
-        self.ai_time_limit.as_secs()
+    /// The side whose offer of a draw is open, if one is.
+    pub fn draw_offer(&self) -> Option<Side> {
+        self.draw_offer
     }
 
-    pub fn display_with_hints(&self) -> String {
-        let mut output = String::new();
+    /// Accepts the open offer of a draw, ending the game as a draw by
+    /// [agreement](TerminationReason::Agreement). Returns `false` if no
+    /// offer is open.
+    pub fn accept_draw(&mut self) -> bool {
+        if self.draw_offer.take().is_none() || self.is_game_over() {
+            return false;
+        }
+        self.agreed_draw = true;
+        if let Some(clock) = &mut self.clock {
+            clock.stop();
+        }
+        let result = self.result().expect("the draw was just agreed");
+        self.events.push(GameEvent::GameOver(result));
+        true
+    }
 
-        // Add column labels (A-E)
-        output.push_str("     A   B   C   D   E\n");
-
-        // Top border
-        output.push_str("   ┌───┬───┬───┬───┬───┐\n");
-
-        for row in 0..5 {
-            // Row number
-            output.push_str(&format!(" {} │", row + 1));
-
-            for col in 0..5 {
-                let pos = row * 5 + col;
-                let piece = match self.cells[pos] {
-                    Piece::Empty => {
-                        if self.selected_position.is_some()
-                            && self.is_valid_move(self.selected_position.unwrap(), pos)
-                        {
-                            "•".bright_green()
-                        } else if self.is_diagonal_allowed(pos) {
-                            "×".bright_black()
-                        } else {
-                            " ".normal()
-                        }
-                    }
-                    Piece::Goat => "G".bright_yellow(),
-                    Piece::Tiger => "T".bright_red(),
-                };
+    /// Turns down the open offer of a draw; the game goes on.
+    pub fn decline_draw(&mut self) {
+        self.draw_offer = None;
+    }
 
-                output.push_str(&format!(" {} │", piece));
-            }
-            output.push('\n');
+    /// Whether the AI playing `side` takes a draw here: it does unless its
+    /// search, under the board's [`AiConfig`], puts `side` more than
+    /// `window` ahead.
+    pub fn ai_accepts_draw(&self, side: Side, window: i32) -> bool {
+        let Some((_, score)) = self.suggest_move(&self.ai_config) else {
+            return true;
+        };
+        let own = match side {
+            Side::Tigers => score,
+            Side::Goats => -score,
+        };
+        own <= window
+    }
 
-            // Add horizontal lines between rows, except for the last row
-            if row < 4 {
-                output.push_str("   ├───┼───┼───┼───┼───┤\n");
-            }
-        }
+    /// Returns every capture made so far, oldest first.
+    pub fn captures(&self) -> &[CaptureEvent] {
+        &self.captures
+    }
 
-        // Bottom border
-        output.push_str("   └───┴───┴───┴───┴───┘\n");
+    /// Number of half-moves (single goat or tiger actions) played so far.
+    pub fn ply(&self) -> usize {
+        self.ply
+    }
 
-        output
+    /// The current move number, starting at 1. A full move is one goat
+    /// action followed by one tiger action.
+    pub fn fullmove_number(&self) -> usize {
+        self.ply / 2 + 1
     }
 
-    pub fn select_position(&mut self, pos: usize) -> bool {
-        if pos >= self.cells.len() {
-            return false;
+    /// The side whose turn it is. Goats move on even plies.
+    pub fn side_to_move(&self) -> Side {
+        self.pieces().side_to_move()
+    }
+
+    /// Whether goats are still being placed.
+    pub fn phase(&self) -> Phase {
+        if self.goats_in_hand > 0 {
+            Phase::Placement
+        } else {
+            Phase::Movement
         }
-        self.selected_position = Some(pos);
-        true
     }
 
-    pub fn clear_selection(&mut self) {
-        self.selected_position = None;
+    /// Serializes the position as a FEN-like string: the board rows from
+    /// top to bottom separated by `/` (digits count empty points), then
+    /// the side to move (`g` or `t`), goats in hand, captured goats and
+    /// the full-move number, e.g. `T3T/5/5/5/T3T g 20 0 1`.
+    ///
+    /// Rows follow the board's layout, so on boards that aren't square
+    /// they have different lengths.
+    pub fn to_fen(&self) -> String {
+        self.pieces().to_fen()
     }
 
-    pub fn place_goat(&mut self, position: usize) -> bool {
-        if position >= self.cells.len()
-            || self.cells[position] != Piece::Empty
-            || self.goats_in_hand == 0
-        {
-            return false;
+    /// Parses a standard-board position produced by [`Board::to_fen`]. The
+    /// resulting board has an empty move history.
+    pub fn from_fen(fen: &str) -> Result<Board, ParseError> {
+        Board::from_fen_with_rules(fen, RuleSet::default())
+    }
+
+    /// Parses a position produced by [`Board::to_fen`] on the board used by
+    /// `rules`.
+    pub fn from_fen_with_rules(fen: &str, rules: RuleSet) -> Result<Board, ParseError> {
+        // Locate each whitespace-separated field along with its column
+        let mut fields = Vec::new();
+        let mut start = None;
+        for (i, c) in fen.char_indices().chain(std::iter::once((fen.len(), ' '))) {
+            match (c.is_whitespace(), start) {
+                (true, Some(s)) => {
+                    fields.push((s + 1, &fen[s..i]));
+                    start = None;
+                }
+                (false, None) => start = Some(i),
+                _ => {}
+            }
+        }
+        if fields.len() != 5 {
+            return Err(ParseError::new(
+                1,
+                fen.len() + 1,
+                format!("expected 5 fields, found {}", fields.len()),
+            ));
         }
 
-        self.cells[position] = Piece::Goat;
-        self.goats_in_hand -= 1;
-        self.move_history.push(Move::PlaceGoat { position });
-        true
+        let mut board =
+            Board::with_rules(rules).map_err(|err| ParseError::new(1, 1, err.to_string()))?;
+        board.cells.fill(Piece::Empty);
+        let layout = board.rules.topology.rows();
+
+        let (rows_col, rows) = fields[0];
+        let mut row = 0;
+        let mut col = 0;
+        let mut run = 0; // Empty points counted by the digits read so far
+        for (i, c) in rows.chars().enumerate() {
+            let column = rows_col + i;
+            let width = layout[row].len();
+            if !c.is_ascii_digit() {
+                run = 0;
+            }
+            match c {
+                '/' => {
+                    if col != width {
+                        return Err(ParseError::new(
+                            1,
+                            column,
+                            format!("row does not have {width} points"),
+                        ));
+                    }
+                    row += 1;
+                    col = 0;
+                    if row == layout.len() {
+                        return Err(ParseError::new(1, column, "too many rows"));
+                    }
+                }
+                '0'..='9' => {
+                    // Digits run together into one count, as in `10`
+                    let extended = run * 10 + c.to_digit(10).unwrap() as usize;
+                    if extended == 0 {
+                        return Err(ParseError::new(1, column, "unexpected character '0'"));
+                    }
+                    col = col - run + extended;
+                    run = extended;
+                }
+                'T' | 'G' => {
+                    if col < width {
+                        board.cells[layout[row][col]] =
+                            if c == 'T' { Piece::Tiger } else { Piece::Goat };
+                    }
+                    col += 1;
+                }
+                _ => {
+                    return Err(ParseError::new(
+                        1,
+                        column,
+                        format!("unexpected character '{c}'"),
+                    ))
+                }
+            }
+            if col > width {
+                return Err(ParseError::new(
+                    1,
+                    column,
+                    format!("row has more than {width} points"),
+                ));
+            }
+        }
+        if row != layout.len() - 1 || col != layout[row].len() {
+            return Err(ParseError::new(
+                1,
+                rows_col + rows.len(),
+                format!("expected {} rows", layout.len()),
+            ));
+        }
+
+        let (side_col, side) = fields[1];
+        let tigers_to_move = match side {
+            "g" => false,
+            "t" => true,
+            _ => {
+                return Err(ParseError::new(
+                    1,
+                    side_col,
+                    "side to move must be 'g' or 't'",
+                ))
+            }
+        };
+
+        let parse_number = |(column, text): (usize, &str), what: &str| {
+            text.parse::<u32>()
+                .map_err(|_| ParseError::new(1, column, format!("invalid {what} '{text}'")))
+        };
+        board.goats_in_hand = parse_number(fields[2], "goats in hand")?;
+        board.captured_goats = parse_number(fields[3], "captured goats")?;
+        let fullmove = parse_number(fields[4], "move number")?;
+        if fullmove == 0 {
+            return Err(ParseError::new(1, fields[4].0, "move number starts at 1"));
+        }
+        board.ply = (fullmove as usize - 1) * 2 + tigers_to_move as usize;
+
+        Ok(board)
     }
 
-    pub fn is_game_over(&self) -> bool {
-        self.get_winner() != Winner::None
+    /// Builds a standard board from a 5×5 text diagram, one row per line,
+    /// using `T` for tigers, `G` for goats and `.` for empty points:
+    ///
+    /// ```text
+    /// T . . . T
+    /// . . G . .
+    /// . . . . .
+    /// . . . . .
+    /// T . . . T
+    /// ```
+    ///
+    /// Whitespace between points and blank lines before or after the
+    /// diagram are ignored. Goats move first in the resulting position.
+    pub fn from_diagram(
+        diagram: &str,
+        goats_in_hand: u32,
+        captured_goats: u32,
+    ) -> Result<Board, ParseError> {
+        Board::from_diagram_with_rules(diagram, RuleSet::default(), goats_in_hand, captured_goats)
     }
 
-    pub fn get_winner(&self) -> Winner {
-        // Tigers win if they've captured 5 or more goats
-        if self.captured_goats >= 5 {
-            return Winner::Tigers;
-        }
+    /// Like [`Board::from_diagram`], for the board used by `rules`. Each
+    /// line lists the points of one layout row from left to right.
+    pub fn from_diagram_with_rules(
+        diagram: &str,
+        rules: RuleSet,
+        goats_in_hand: u32,
+        captured_goats: u32,
+    ) -> Result<Board, ParseError> {
+        let mut board =
+            Board::with_rules(rules).map_err(|err| ParseError::new(1, 1, err.to_string()))?;
+        board.cells.fill(Piece::Empty);
+        board.goats_in_hand = goats_in_hand;
+        board.captured_goats = captured_goats;
+        let layout = board.rules.topology.rows();
 
-        // Check if all tigers are trapped
-        let tiger_positions: Vec<usize> = self
-            .cells
-            .iter()
-            .enumerate()
-            .filter(|(_, &piece)| piece == Piece::Tiger)
-            .map(|(pos, _)| pos)
-            .collect();
+        let mut row = 0;
+        let mut last_line = 0;
+        for (line_index, line) in diagram.lines().enumerate() {
+            let line_number = line_index + 1;
+            last_line = line_number;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if row == layout.len() {
+                return Err(ParseError::new(
+                    line_number,
+                    line.len() - line.trim_start().len() + 1,
+                    format!("diagram has more than {} rows", layout.len()),
+                ));
+            }
 
-        // If any tiger can move, game is not over
-        for &pos in &tiger_positions {
-            if !self.get_valid_tiger_moves(pos).is_empty() {
-                return Winner::None;
+            let width = layout[row].len();
+            let mut col = 0;
+            for (char_index, c) in line.chars().enumerate() {
+                let piece = match c {
+                    'T' | 't' => Piece::Tiger,
+                    'G' | 'g' => Piece::Goat,
+                    '.' => Piece::Empty,
+                    c if c.is_whitespace() => continue,
+                    c => {
+                        return Err(ParseError::new(
+                            line_number,
+                            char_index + 1,
+                            format!("unexpected character '{c}'"),
+                        ))
+                    }
+                };
+                if col == width {
+                    return Err(ParseError::new(
+                        line_number,
+                        char_index + 1,
+                        format!("row has more than {width} points"),
+                    ));
+                }
+                board.cells[layout[row][col]] = piece;
+                col += 1;
+            }
+            if col < width {
+                return Err(ParseError::new(
+                    line_number,
+                    line.chars().count() + 1,
+                    format!("row has {col} points, expected {width}"),
+                ));
             }
+            row += 1;
         }
+        if row < layout.len() {
+            return Err(ParseError::new(
+                last_line.max(1),
+                1,
+                format!("diagram has {row} rows, expected {}", layout.len()),
+            ));
+        }
+
+        Ok(board)
+    }
+
+    /// Renders the board in the format accepted by
+    /// [`Board::from_diagram_with_rules`], without colors.
+    pub fn to_diagram(&self) -> String {
+        let mut output = String::new();
+        for row in self.rules.topology.rows() {
+            let symbols: Vec<&str> = row
+                .iter()
+                .map(|&pos| match self.cells[pos] {
+                    Piece::Tiger => "T",
+                    Piece::Goat => "G",
+                    Piece::Empty => ".",
+                })
+                .collect();
+            output.push_str(&symbols.join(" "));
+            output.push('\n');
+        }
+        output
+    }
 
-        // If we get here, no tiger can move
-        Winner::Goats
+    /// Builds a record of the game played so far.
+    pub fn record(&self) -> GameRecord {
+        let mut start = self.clone();
+        // Taking moves back on the clock would read its time source,
+        // which the copy shares
+        start.clock = None;
+        start.goto_ply(self.start_ply());
+        GameRecord {
+            rules: (*self.rules).clone(),
+            start: start.to_fen(),
+            start_ply: self.start_ply(),
+            moves: self.move_history.clone(),
+            move_times: self.move_times.clone(),
+            swapped_at: self.swapped_at,
+            result: self.result(),
+            adjudication: self.adjudicated.map(|(_, policy)| policy),
+        }
     }
 
     pub fn move_tiger(&mut self, from: usize, to: usize) -> bool {
+        self.try_move_tiger(from, to).is_ok()
+    }
+
+    /// Like [`Board::move_tiger`], but says why a move was rejected.
+    pub fn try_move_tiger(&mut self, from: usize, to: usize) -> Result<(), MoveError> {
+        if self.side_to_move() != Side::Tigers {
+            return Err(MoveError::Illegal);
+        }
         if from >= self.cells.len() || to >= self.cells.len() {
-            return false;
+            return Err(MoveError::Illegal);
         }
 
         // Check if there's actually a tiger at the starting position
         if self.cells[from] != Piece::Tiger {
-            return false;
+            return Err(MoveError::Illegal);
         }
 
         // Check if destination is empty
         if self.cells[to] != Piece::Empty {
-            return false;
+            return Err(MoveError::Illegal);
         }
 
         // Get valid moves for this tiger
         let valid_moves = self.get_valid_tiger_moves(from);
         if !valid_moves.contains(&Position(to)) {
-            return false;
+            return Err(MoveError::Illegal);
         }
 
         // If it's a capture move (distance > 1), remove the captured goat
         let captured_position = self.get_captured_position(from, to);
+        if (captured_position.is_some() || self.rules.forced_capture)
+            && !self.tiger_moves().contains(&Move::MoveTiger {
+                from,
+                to,
+                captured_position,
+            })
+        {
+            // Either a capture is forced or the tiger has to keep jumping
+            return Err(if captured_position.is_none() {
+                MoveError::CaptureRequired
+            } else {
+                MoveError::Illegal
+            });
+        }
         if let Some(captured_pos) = captured_position {
             self.cells[captured_pos] = Piece::Empty;
             self.captured_goats += 1;
+            self.captures.push(CaptureEvent {
+                ply: self.move_history.len(),
+                square: captured_pos,
+                by_tiger_from: from,
+                by_tiger_to: to,
+            });
         }
 
         // Make the move
         self.cells[to] = Piece::Tiger;
         self.cells[from] = Piece::Empty;
-        self.move_history.push(Move::MoveTiger {
+        self.finish_move(Move::MoveTiger {
             from,
             to,
             captured_position,
         });
+        Ok(())
+    }
+
+    /// Plays a chain of captures as one turn. Only allowed when the rules
+    /// permit multi-captures and the chain is one of the tiger's legal
+    /// moves.
+    pub fn multi_jump(&mut self, jumps: &[Jump]) -> bool {
+        let mv = Move::MultiJump(jumps.to_vec());
+        if self.side_to_move() != Side::Tigers
+            || jumps.len() < 2
+            || !self.tiger_moves().contains(&mv)
+        {
+            return false;
+        }
+
+        for jump in jumps {
+            self.cells[jump.from] = Piece::Empty;
+            self.cells[jump.over] = Piece::Empty;
+            self.cells[jump.to] = Piece::Tiger;
+            self.captured_goats += 1;
+            self.captures.push(CaptureEvent {
+                ply: self.move_history.len(),
+                square: jump.over,
+                by_tiger_from: jump.from,
+                by_tiger_to: jump.to,
+            });
+        }
+        self.finish_move(mv);
         true
     }
 
+    /// Plays a move as returned by [`Board::legal_moves`].
+    pub fn make_move(&mut self, mv: &Move) -> bool {
+        match mv {
+            Move::PlaceGoat { position } => self.place_goat(*position),
+            Move::MoveGoat { from, to } => self.move_goat(*from, *to),
+            Move::MoveTiger { from, to, .. } => self.move_tiger(*from, *to),
+            Move::MultiJump(jumps) => self.multi_jump(jumps),
+        }
+    }
+
     pub fn is_diagonal_allowed(&self, pos: usize) -> bool {
-        matches!(
-            pos,
-            0 | 2 | 4 | 6 | 8 | 10 | 12 | 14 | 16 | 18 | 20 | 22 | 24
-        )
+        self.rules.topology.has_diagonals(pos)
     }
 
-    pub fn get_valid_tiger_moves(&self, pos: usize) -> Vec<Position> {
-        let mut moves = Vec::new();
-        let row = pos / 5;
-        let col = pos % 5;
-
-        // Define possible moves (adjacent positions and potential jumps)
-        let mut possible_moves = vec![
-            // Adjacent moves
-            (row.wrapping_sub(1), col), // Up
-            (row + 1, col),             // Down
-            (row, col.wrapping_sub(1)), // Left
-            (row, col + 1),             // Right
-            // Jump moves
-            (row.wrapping_sub(2), col), // Jump Up
-            (row + 2, col),             // Jump Down
-            (row, col.wrapping_sub(2)), // Jump Left
-            (row, col + 2),             // Jump Right
-        ];
-
-        // Only add diagonal moves if the current position allows them
-        if self.is_diagonal_allowed(pos) {
-            possible_moves.extend_from_slice(&[
-                // Adjacent diagonal moves
-                (row.wrapping_sub(1), col.wrapping_sub(1)), // Up-Left
-                (row.wrapping_sub(1), col + 1),             // Up-Right
-                (row + 1, col.wrapping_sub(1)),             // Down-Left
-                (row + 1, col + 1),                         // Down-Right
-                // Jump diagonal moves
-                (row.wrapping_sub(2), col.wrapping_sub(2)), // Jump Up-Left
-                (row.wrapping_sub(2), col + 2),             // Jump Up-Right
-                (row + 2, col.wrapping_sub(2)),             // Jump Down-Left
-                (row + 2, col + 2),                         // Jump Down-Right
-            ]);
-        }
-
-        // Check each possible move
-        for (new_row, new_col) in possible_moves {
-            if new_row < 5 && new_col < 5 {
-                let new_pos = new_row * 5 + new_col;
-
-                // Calculate if this is a jump move
-                let row_diff = (new_row as i32 - row as i32).abs();
-                let col_diff = (new_col as i32 - col as i32).abs();
-                let is_jump = row_diff == 2 || col_diff == 2;
-                let is_diagonal = row_diff == col_diff;
-
-                // Skip invalid diagonal moves
-                if is_diagonal && !self.is_diagonal_allowed(new_pos) {
-                    continue;
-                }
-
-                // For jump moves, check if there's a goat to capture
-                if is_jump {
-                    let mid_row = (row + new_row) / 2;
-                    let mid_col = (col + new_col) / 2;
-                    let mid_pos = mid_row * 5 + mid_col;
-
-                    // For diagonal jumps, all positions must allow diagonals
-                    if is_diagonal && !self.is_diagonal_allowed(mid_pos) {
-                        continue;
-                    }
+    /// The shape of the board this game is played on.
+    pub fn topology(&self) -> &Topology {
+        &self.rules.topology
+    }
 
-                    // Can only jump if there's a goat in the middle and the destination is empty
-                    if self.cells[mid_pos] == Piece::Goat && self.cells[new_pos] == Piece::Empty {
-                        moves.push(Position(new_pos));
-                    }
-                } else if self.cells[new_pos] == Piece::Empty {
-                    // For non-jump moves, just check if the destination is empty
-                    moves.push(Position(new_pos));
-                }
-            }
-        }
-        moves
+    pub fn get_valid_tiger_moves(&self, pos: usize) -> Vec<Position> {
+        self.pieces().get_valid_tiger_moves(pos)
     }
 
     pub fn get_captured_position(&self, from: usize, to: usize) -> Option<usize> {
-        let from_row = from / 5;
-        let from_col = from % 5;
-        let to_row = to / 5;
-        let to_col = to % 5;
-
-        // If the move is more than one step away, it's a capture move
-        if (from_row as i32 - to_row as i32).abs() > 1
-            || (from_col as i32 - to_col as i32).abs() > 1
-        {
-            // The captured position is the middle position
-            let mid_row = (from_row + to_row) / 2;
-            let mid_col = (from_col + to_col) / 2;
-            let mid_pos = mid_row * 5 + mid_col;
+        self.pieces().get_captured_position(from, to)
+    }
 
-            if self.cells[mid_pos] == Piece::Goat {
-                return Some(mid_pos);
-            }
+    /// The goat a tiger on `from` would capture by moving to `to`, if that
+    /// is one of its moves and jumps a goat.
+    pub fn would_capture(&self, from: usize, to: usize) -> Option<usize> {
+        if self.cells.get(from) != Some(&Piece::Tiger)
+            || !self.get_valid_tiger_moves(from).contains(&Position(to))
+        {
+            return None;
         }
-        None
+        self.get_captured_position(from, to)
+    }
+
+    /// Every capture the tigers could make if it were their turn, with
+    /// capture chains expanded as the multi-capture rule allows. Read off
+    /// the jump tables, so cheap enough to call on every redraw.
+    pub fn capturing_moves(&self) -> Vec<Move> {
+        self.pieces().capturing_moves()
+    }
+
+    /// The goats a tiger could capture with its next move, in board
+    /// order, each listed once however many tigers threaten it.
+    pub fn threatened_goats(&self) -> Vec<usize> {
+        self.pieces().threatened_goats()
+    }
+
+    /// Whether a goat stands on `pos` with a tiger next to it and an empty
+    /// point beyond it on the same line.
+    pub fn is_goat_threatened(&self, pos: usize) -> bool {
+        self.pieces().is_goat_threatened(pos)
+    }
+
+    /// Whether a goat on `pos` would hold it: either no tiger could jump
+    /// it, or every tiger that could lands on the same point and the goats,
+    /// moving first, could fill that point with another goat. `pos` may be
+    /// empty, to ask before a goat goes there.
+    ///
+    /// Unlike [`Board::is_goat_threatened`] this only reports the goats
+    /// that are truly lost: two tigers landing on different points, or one
+    /// landing point no goat can reach in a move.
+    pub fn is_square_safe_for_goat(&self, pos: usize) -> bool {
+        self.pieces().is_square_safe_for_goat(pos)
     }
 
     pub fn move_goat(&mut self, from: usize, to: usize) -> bool {
+        if self.side_to_move() != Side::Goats {
+            return false;
+        }
         if from >= self.cells.len() || to >= self.cells.len() {
             return false;
         }
@@ -349,55 +2885,12 @@ impl Board {
         // Make the move
         self.cells[to] = Piece::Goat;
         self.cells[from] = Piece::Empty;
-        self.move_history.push(Move::MoveGoat { from, to });
+        self.finish_move(Move::MoveGoat { from, to });
         true
     }
 
     pub fn get_valid_goat_moves(&self, pos: usize) -> Vec<Position> {
-        let mut moves = Vec::new();
-        let row = pos / 5;
-        let col = pos % 5;
-
-        // Define possible moves (adjacent positions only)
-        let mut possible_moves = vec![
-            (row.wrapping_sub(1), col), // Up
-            (row + 1, col),             // Down
-            (row, col.wrapping_sub(1)), // Left
-            (row, col + 1),             // Right
-        ];
-
-        // Only add diagonal moves if the current position allows them
-        if self.is_diagonal_allowed(pos) {
-            possible_moves.extend_from_slice(&[
-                (row.wrapping_sub(1), col.wrapping_sub(1)), // Up-Left
-                (row.wrapping_sub(1), col + 1),             // Up-Right
-                (row + 1, col.wrapping_sub(1)),             // Down-Left
-                (row + 1, col + 1),                         // Down-Right
-            ]);
-        }
-
-        // Check each possible move
-        for (new_row, new_col) in possible_moves {
-            if new_row < 5 && new_col < 5 {
-                let new_pos = new_row * 5 + new_col;
-
-                // Check if this is a diagonal move
-                let row_diff = (new_row as i32 - row as i32).abs();
-                let col_diff = (new_col as i32 - col as i32).abs();
-                let is_diagonal = row_diff == col_diff;
-
-                // Skip invalid diagonal moves
-                if is_diagonal && !self.is_diagonal_allowed(new_pos) {
-                    continue;
-                }
-
-                // Check if the destination is empty
-                if self.cells[new_pos] == Piece::Empty {
-                    moves.push(Position(new_pos));
-                }
-            }
-        }
-        moves
+        self.pieces().get_valid_goat_moves(pos)
     }
 
     pub fn can_undo(&self) -> bool {
@@ -405,6 +2898,10 @@ impl Board {
     }
 
     pub fn undo(&mut self) -> bool {
+        // Taking a move back reopens an adjudicated or agreed game
+        self.adjudicated = None;
+        self.draw_offer = None;
+        self.agreed_draw = false;
         if let Some(last_move) = self.move_history.pop() {
             match last_move {
                 Move::PlaceGoat { position } => {
@@ -425,9 +2922,38 @@ impl Board {
                     if let Some(captured_pos) = captured_position {
                         self.cells[captured_pos] = Piece::Goat;
                         self.captured_goats -= 1;
+                        self.captures.pop();
+                        self.events.push(GameEvent::CaptureUndone {
+                            square: captured_pos,
+                        });
+                    }
+                }
+                Move::MultiJump(ref jumps) => {
+                    // Unwind the chain from its last jump
+                    for jump in jumps.iter().rev() {
+                        self.cells[jump.to] = Piece::Empty;
+                        self.cells[jump.over] = Piece::Goat;
+                        self.cells[jump.from] = Piece::Tiger;
+                        self.captured_goats -= 1;
+                        self.captures.pop();
+                        self.events
+                            .push(GameEvent::CaptureUndone { square: jump.over });
                     }
                 }
             }
+            self.taken_back.push(last_move.clone());
+            self.events.push(GameEvent::MoveUndone(last_move));
+            self.ply -= 1;
+            let time = self.move_times.pop().flatten();
+            let side = self.side_to_move();
+            if let Some(clock) = &mut self.clock {
+                // The mover gets the time back and is on the clock again
+                clock.take_back(side, time.unwrap_or_default());
+            }
+            if self.swapped_at.is_some_and(|ply| ply > self.ply) {
+                self.swapped_at = None;
+                self.events.push(GameEvent::SwapUndone);
+            }
             self.selected_position = None;
             true
         } else {
@@ -435,286 +2961,980 @@ impl Board {
         }
     }
 
-    pub fn get_all_valid_tiger_moves(&self) -> Vec<(usize, usize)> {
-        let mut all_moves = Vec::new();
-
-        // Find all tigers
-        for (pos, &piece) in self.cells.iter().enumerate() {
-            if piece == Piece::Tiger {
-                // Get valid moves for this tiger
-                for move_pos in self.get_valid_tiger_moves(pos) {
-                    all_moves.push((pos, move_pos.0));
-                }
+    /// Goes back or forward to `ply` within the game: back by taking
+    /// moves back, forward by playing again the moves taken back since the
+    /// last new move. Returns `false`, leaving the board as it is, for a
+    /// ply before the game's start or past its last move.
+    ///
+    /// Only moves are played again; a swap of sides that was taken back
+    /// stays undone.
+    pub fn goto_ply(&mut self, ply: usize) -> bool {
+        if ply < self.start_ply() || ply > self.ply + self.taken_back.len() {
+            return false;
+        }
+        while self.ply > ply {
+            self.undo();
+        }
+        while self.ply < ply {
+            let mv = self.taken_back.last().cloned().expect("checked above");
+            if !self.make_move(&mv) {
+                return false;
             }
         }
+        true
+    }
 
-        all_moves
+    /// The ply the game started from, before its first move.
+    pub fn start_ply(&self) -> usize {
+        self.ply - self.move_history.len()
     }
 
-    pub fn get_all_valid_goat_moves(&self) -> Vec<(usize, usize)> {
-        let mut all_moves = Vec::new();
+    /// The moves of the game so far, oldest first, starting from the
+    /// position at [`start_ply`](Board::start_ply).
+    pub fn move_history(&self) -> &[Move] {
+        &self.move_history
+    }
 
-        if self.goats_in_hand > 0 {
-            // Can place a new goat
-            for pos in 0..25 {
-                if self.cells[pos] == Piece::Empty {
-                    all_moves.push((pos, pos)); // From and to are same for placement
-                }
+    /// The last move of the game so far, if there is one.
+    pub fn last_move(&self) -> Option<&Move> {
+        self.move_history.last()
+    }
+
+    /// Returns every legal move for the side to move.
+    pub fn legal_moves(&self) -> Vec<Move> {
+        self.legal_move_list().to_vec()
+    }
+
+    /// Every legal move for the side to move, like [`Board::legal_moves`],
+    /// but shared rather than copied. The moves are generated once per
+    /// position: asking again before anything on the board changes hands
+    /// back the same list.
+    pub fn legal_move_list(&self) -> Arc<[Move]> {
+        let key = position_key(
+            &self.cells,
+            self.goats_in_hand,
+            self.captured_goats,
+            self.side_to_move() == Side::Tigers,
+        );
+        let mut entry = self.legal_moves.entry.lock().expect("move cache poisoned");
+        if let Some((cached, moves)) = &*entry {
+            if *cached == key {
+                return moves.clone();
             }
-            return all_moves; // Return early to avoid mixing placement and movement
         }
+        let moves: Arc<[Move]> = self.generate_legal_moves().into();
+        self.legal_moves.generated.fetch_add(1, Ordering::Relaxed);
+        *entry = Some((key, moves.clone()));
+        moves
+    }
+
+    /// How many times [`Board::legal_move_list`] has had to generate the
+    /// moves rather than hand back the ones it had, on this board and the
+    /// ones it was cloned from.
+    #[doc(hidden)]
+    pub fn legal_move_generations(&self) -> u64 {
+        self.legal_moves.generated.load(Ordering::Relaxed)
+    }
+
+    /// The legal moves for the side to move, generated afresh. For boards
+    /// played on by a search, whose positions no one asks about twice.
+    fn generate_legal_moves(&self) -> Vec<Move> {
+        self.pieces().legal_moves()
+    }
 
-        // Move existing goats
-        for (pos, &piece) in self.cells.iter().enumerate() {
-            if piece == Piece::Goat {
-                // Get valid moves for this goat
-                for move_pos in self.get_valid_goat_moves(pos) {
-                    all_moves.push((pos, move_pos.0));
+    /// Applies a tiger move to the pieces only, for look-ahead in the
+    /// search. Take it back by restoring a [`SearchBoard`] saved before.
+    ///
+    /// The move also goes on the history so the shuttle rule sees it; goat
+    /// moves don't matter to that rule and are left out.
+    fn make_tiger_move(&mut self, mv: &Move) {
+        self.move_history.push(mv.clone());
+        match mv {
+            Move::MoveTiger {
+                from,
+                to,
+                captured_position,
+            } => {
+                self.cells[*from] = Piece::Empty;
+                self.cells[*to] = Piece::Tiger;
+                if let Some(pos) = *captured_position {
+                    self.cells[pos] = Piece::Empty;
+                    self.captured_goats += 1;
+                }
+            }
+            Move::MultiJump(jumps) => {
+                for jump in jumps {
+                    self.cells[jump.from] = Piece::Empty;
+                    self.cells[jump.over] = Piece::Empty;
+                    self.cells[jump.to] = Piece::Tiger;
+                    self.captured_goats += 1;
                 }
             }
+            Move::PlaceGoat { .. } | Move::MoveGoat { .. } => unreachable!("not a tiger move"),
         }
+    }
+
+    /// Applies a goat move, given as `(from, to)` with `from == to` for a
+    /// placement, like [`Board::make_tiger_move`].
+    fn make_goat_move(&mut self, from: usize, to: usize) {
+        if from == to {
+            self.goats_in_hand -= 1;
+        } else {
+            self.cells[from] = Piece::Empty;
+        }
+        self.cells[to] = Piece::Goat;
+    }
+
+    /// Every move the tigers could make, with capture chains expanded as
+    /// the multi-capture rule allows. Only captures when they are forced
+    /// and one is available.
+    fn tiger_moves(&self) -> Vec<Move> {
+        self.pieces().tiger_moves()
+    }
+
+    pub fn get_all_valid_tiger_moves(&self) -> Vec<(usize, usize)> {
+        self.pieces().get_all_valid_tiger_moves()
+    }
+
+    pub fn get_all_valid_goat_moves(&self) -> Vec<(usize, usize)> {
+        self.pieces().get_all_valid_goat_moves()
+    }
+
+    /// How many moves a tiger stepping from `from` to `to` would have
+    /// there, counting the step back.
+    fn tiger_moves_after_step(&self, from: usize, to: usize) -> usize {
+        self.pieces().tiger_moves_after_step(from, to)
+    }
+
+    /// How many moves the tiger on `tiger` would have if a goat stood on
+    /// `entry` that it couldn't jump.
+    fn tiger_moves_with_entry_blocked(&self, tiger: usize, entry: usize) -> usize {
+        self.pieces().tiger_moves_with_entry_blocked(tiger, entry)
+    }
+
+    /// Whether `mv` walks a tiger into a pocket: a point it would have one
+    /// move or none from once a goat blocks the way it came in, by
+    /// stepping onto the point it left or the goat it jumped. A move that
+    /// wins the game is never one.
+    pub fn is_self_trapping(&self, mv: &Move) -> bool {
+        let (to, entry) = match mv {
+            Move::MoveTiger {
+                from,
+                to,
+                captured_position,
+            } => (*to, captured_position.unwrap_or(*from)),
+            Move::MultiJump(jumps) => match jumps.last() {
+                Some(jump) => (jump.to, jump.over),
+                None => return false,
+            },
+            Move::PlaceGoat { .. } | Move::MoveGoat { .. } => return false,
+        };
+        let mut after = self.clone();
+        after.make_move(mv)
+            && after.get_winner() != Winner::Tigers
+            && after.tiger_moves_with_entry_blocked(to, entry) <= 1
+    }
+
+    fn evaluate_position(&self) -> i32 {
+        match &self.evaluator {
+            Some(evaluator) if !self.is_game_over() => evaluator.evaluate(self),
+            _ => self.evaluate_detailed().total,
+        }
+    }
+
+    /// [`Board::evaluate_position`] for each of `positions`, none of them
+    /// finished, scoring them as one batch with this board's evaluator.
+    pub(crate) fn evaluate_positions(&self, positions: &[Board]) -> Vec<i32> {
+        match &self.evaluator {
+            Some(evaluator) => evaluator.evaluate_batch(positions),
+            None => positions
+                .iter()
+                .map(|position| position.evaluate_detailed().total)
+                .collect(),
+        }
+    }
+
+    /// The AI's evaluation of the position, from the tigers' side, term by
+    /// term. A finished game is scored by its result alone.
+    pub fn evaluate_detailed(&self) -> EvalBreakdown {
+        self.pieces()
+            .evaluate(&self.eval_weights, self.get_winner())
+    }
+
+    #[deprecated(note = "play a `MinimaxEngine` with `Board::play_engine_move`")]
+    pub fn ai_move_tiger(&mut self) -> bool {
+        self.ai_move(Side::Tigers)
+    }
+
+    #[deprecated(note = "play a `MinimaxEngine` with `Board::play_engine_move`")]
+    pub fn ai_move_goat(&mut self) -> bool {
+        self.ai_move(Side::Goats)
+    }
+
+    /// Plays the move the board's own engine picks for `side`.
+    fn ai_move(&mut self, side: Side) -> bool {
+        let mut engine = std::mem::take(&mut self.engine);
+        let config = self.ai_config;
+        let result = engine.search(self, side, &config);
+        self.last_search = engine.last_search().cloned();
+        self.last_search_stats = match (&self.last_search, &result) {
+            (Some(_), Some(result)) => Some(result.stats),
+            _ => None,
+        };
+        self.engine = engine;
+        match result {
+            Some(result) => self.make_move(&result.mv),
+            None => false,
+        }
+    }
+
+    /// Plays the move `engine` picks for the side to move, searched within
+    /// the board's [`AiConfig`]. Returns what the engine found, or `None`
+    /// if it had no move to play.
+    pub fn play_engine_move(&mut self, engine: &mut dyn Engine) -> Option<EngineResult> {
+        let limits = self.ai_config;
+        let result = engine.best_move(self, &limits)?;
+        self.make_move(&result.mv).then_some(result)
+    }
+
+    /// Forgets the scores and move ordering the AI has learned from its
+    /// searches so far this game, so its next search starts as if it were
+    /// the first. For timing searches on their own.
+    pub fn clear_search_tables(&mut self) {
+        self.search_tables = None;
+        self.engine.clear_hash();
+    }
+
+    /// Whether a search is running in the background on the position
+    /// after the reply the AI expects to its last move.
+    pub fn is_pondering(&self) -> bool {
+        self.engine.is_pondering()
+    }
+
+    /// Stops the pondering search, if one is running. Its result is
+    /// thrown away.
+    pub fn stop_pondering(&mut self) {
+        self.engine.stop_pondering();
+    }
+
+    /// The move the AI would play for the side to move under `config`,
+    /// with its score from the tigers' side, or `None` if there is no
+    /// legal move. The board and its history are left as they are.
+    pub fn suggest_move(&self, config: &AiConfig) -> Option<(Move, i32)> {
+        self.clone().choose_move(self.side_to_move(), *config)
+    }
+
+    /// The `n` best moves for the side to move under `config`, best first,
+    /// each with its score from the tigers' side and the line the search
+    /// expects to follow it. All the scores come from the same depth, the
+    /// deepest one searched in full, so they can be compared. Unlike
+    /// [`Board::suggest_move`] this always searches every move: it ignores
+    /// the opening book, the tie margin, blunders and
+    /// [`AiConfig::safe_goats`]. A placement that mirrors one
+    /// already listed is left out, since it scores the same.
+    pub fn top_moves(&self, n: usize, config: &AiConfig) -> Vec<(Move, i32, Vec<Move>)> {
+        if n == 0 {
+            return Vec::new();
+        }
+        let config = AiConfig {
+            tie_margin: 0,
+            blunders: None,
+            safe_goats: false,
+            ..*config
+        };
+        let mut board = self.clone();
+        board.multi_pv = n;
+        board.root_lines.clear();
+        let side = board.side_to_move();
+        let best = board.search_move(side, config);
+        let mut lines = std::mem::take(&mut board.root_lines);
+        if lines.is_empty() {
+            // Not even the first depth finished
+            return best
+                .map(|(mv, score)| (mv.clone(), score, vec![mv]))
+                .into_iter()
+                .collect();
+        }
+        match side {
+            Side::Tigers => lines.sort_by_key(|line| std::cmp::Reverse(line.1)),
+            Side::Goats => lines.sort_by_key(|line| line.1),
+        }
+        lines.truncate(n);
+        lines
+    }
+
+    /// How deep the search for the AI's last move went, the line it
+    /// expected and how many positions it visited. `None` before the AI's
+    /// first move and after a move from the opening book.
+    pub fn last_search(&self) -> Option<&SearchInfo> {
+        self.last_search.as_ref()
+    }
+
+    /// Counters from the search for the AI's last move, for profiling the
+    /// search. `None` whenever [`Board::last_search`] is.
+    pub fn last_search_stats(&self) -> Option<&SearchStats> {
+        self.last_search_stats.as_ref()
+    }
+
+    /// Sets a function the AI's search calls each time it completes a
+    /// depth, to show its progress while it thinks.
+    pub fn set_search_progress(&mut self, progress: impl Fn(&SearchInfo) + Send + Sync + 'static) {
+        self.search_progress = Some(SearchProgress(Arc::new(progress)));
+    }
+
+    /// Sets a flag that stops the board's searches once it is set, as if
+    /// they had run out of time, for stopping a search such as
+    /// [`Board::top_moves`] from another thread.
+    pub fn set_search_stop(&mut self, stop: Arc<AtomicBool>) {
+        self.search_stop = Some(stop);
+    }
+
+    /// Picks a move for `side` from the opening book, plays its only move
+    /// straight away, or searches.
+    /// Moves are tried on the board during the search and taken back.
+    fn choose_move(&mut self, side: Side, config: AiConfig) -> Option<(Move, i32)> {
+        self.last_search = None;
+        self.last_search_stats = None;
+        // Neither a book move nor a forced one needs searching
+        if let Some(mv) = self.book_move(side).or_else(|| self.only_move(side)) {
+            let mut after = self.clone();
+            after.make_move(&mv);
+            return Some((mv, after.evaluate_position()));
+        }
+        self.search_move(side, config)
+    }
 
-        all_moves
+    /// Searches for `side`'s best move. Once the time is planned the clock
+    /// is put aside: the search asks at every position whether the game is
+    /// over, which would read the clock each time, and no line it plays
+    /// can run out of time.
+    fn search_move(&mut self, side: Side, config: AiConfig) -> Option<(Move, i32)> {
+        let search = self.new_search(side, config);
+        let clock = self.clock.take();
+        #[cfg(feature = "parallel")]
+        let found = smp::search(self, side, search);
+        #[cfg(not(feature = "parallel"))]
+        let found = self.search_root(side, search);
+        self.clock = clock;
+        found
     }
 
-    fn evaluate_position(&self) -> i32 {
-        // If game is over, return a large value
-        match self.get_winner() {
-            Winner::Tigers => return 10000,
-            Winner::Goats => return -10000,
-            Winner::None => {}
+    /// Searches for `side`'s best move on this thread.
+    fn search_root(&mut self, side: Side, search: SearchContext) -> Option<(Move, i32)> {
+        match side {
+            Side::Tigers => self.search_tiger_move(search),
+            Side::Goats => self.search_goat_move(search),
         }
+    }
 
-        let mut score = 0;
+    /// A search for `side` within `config`, or within the time `side` can
+    /// spare on the clock if the game is played on one.
+    fn new_search(&self, side: Side, config: AiConfig) -> SearchContext {
+        let mut search = SearchContext::new(config, self.cells.len());
+        search.stop = self.search_stop.clone();
+        // Scores from earlier searches this game still hold, so this one
+        // adds to them
+        let table = self
+            .search_tables
+            .as_ref()
+            .filter(|tables| tables.rules == self.rules)
+            .and_then(|tables| tables.table.clone())
+            .unwrap_or_else(|| Arc::new(TranspositionTable::new(TABLE_ENTRIES)));
+        table.new_search();
+        search.table = Some(table);
+        search.path = self.repetition_path();
+        search.draw_score = match side {
+            Side::Tigers => -config.contempt,
+            Side::Goats => config.contempt,
+        };
+        if let Some(clock) = &self.clock {
+            let budget = TimeBudget::plan(
+                clock.remaining(side),
+                clock.increment(),
+                self.phase(),
+                self.fullmove_number(),
+            );
+            search.on_clock(clock.source().clone(), budget);
+        }
+        search
+    }
 
-        // Each captured goat is worth 100 points
-        score += self.captured_goats as i32 * 100;
+    /// The tables the AI's last search left, unless it searched a game
+    /// with other rules.
+    fn take_search_tables(&mut self) -> Option<SearchTables> {
+        self.search_tables
+            .take()
+            .filter(|tables| tables.rules == self.rules)
+    }
 
-        // Each trapped tiger is worth -50 points
-        let trapped_tigers = self
-            .cells
-            .iter()
-            .enumerate()
-            .filter(|(_, &piece)| piece == Piece::Tiger)
-            .filter(|&(pos, _)| self.get_valid_tiger_moves(pos).is_empty())
-            .count();
-        score -= trapped_tigers as i32 * 50;
-
-        // Each goat in a strategic position is worth -10 points
-        let strategic_positions = [
-            12, // Center
-            6, 8, 16, 18, // Diagonal positions
-            7, 11, 13, 17, // Adjacent to center
-        ];
-        let strategic_goats = strategic_positions
-            .iter()
-            .filter(|&&pos| self.cells[pos] == Piece::Goat)
-            .count();
-        score -= strategic_goats as i32 * 10;
+    /// Keys of the positions since the last placement or capture, oldest
+    /// first and ending with the current one. Only these can come up
+    /// again.
+    fn repetition_path(&self) -> Vec<u64> {
+        let mut cells = self.cells.clone();
+        let mut tigers_to_move = self.side_to_move() == Side::Tigers;
+        let key = |cells: &[Piece], tigers_to_move| {
+            position_key(
+                cells,
+                self.goats_in_hand,
+                self.captured_goats,
+                tigers_to_move,
+            )
+        };
+        let mut path = vec![key(&cells, tigers_to_move)];
+        for mv in self.move_history.iter().rev() {
+            match *mv {
+                Move::MoveGoat { from, to } => {
+                    cells[to] = Piece::Empty;
+                    cells[from] = Piece::Goat;
+                }
+                Move::MoveTiger {
+                    from,
+                    to,
+                    captured_position: None,
+                } => {
+                    cells[to] = Piece::Empty;
+                    cells[from] = Piece::Tiger;
+                }
+                _ => break,
+            }
+            tigers_to_move = !tigers_to_move;
+            path.push(key(&cells, tigers_to_move));
+        }
+        path.reverse();
+        path
+    }
 
-        // Each goat that can be captured is worth 20 points
-        let capturable_goats = self
-            .cells
+    /// Leaves out of `moves` every goat move after which a tiger could
+    /// capture, unless that is all of them.
+    fn drop_hanging_goat_moves(&mut self, moves: &mut Vec<(usize, usize)>) {
+        let safe: Vec<(usize, usize)> = moves
             .iter()
-            .enumerate()
-            .filter(|(_, &piece)| piece == Piece::Tiger)
-            .flat_map(|(pos, _)| self.get_valid_tiger_moves(pos))
-            .filter(|move_pos| {
-                let from = self
-                    .cells
-                    .iter()
-                    .position(|&piece| piece == Piece::Tiger)
-                    .unwrap_or(0);
-                self.get_captured_position(from, move_pos.0).is_some()
+            .copied()
+            .filter(|&(from, to)| {
+                let (original_from, original_to) = (self.cells[from], self.cells[to]);
+                self.cells[from] = Piece::Empty;
+                self.cells[to] = Piece::Goat;
+                let safe = self.threatened_goats().is_empty();
+                self.cells[from] = original_from;
+                self.cells[to] = original_to;
+                safe
             })
-            .count();
-        score += capturable_goats as i32 * 20;
+            .collect();
+        if !safe.is_empty() {
+            *moves = safe;
+        }
+    }
 
-        score
+    /// The one move `side` has, if it has only one.
+    fn only_move(&self, side: Side) -> Option<Move> {
+        match side {
+            Side::Tigers => match self.tiger_moves().as_slice() {
+                [mv] => Some(mv.clone()),
+                _ => None,
+            },
+            Side::Goats => match self.get_all_valid_goat_moves().as_slice() {
+                &[(from, to)] => Some(goat_move(from, to)),
+                _ => None,
+            },
+        }
     }
 
-    pub fn ai_move_tiger(&mut self) -> bool {
-        let moves = self.get_all_valid_tiger_moves();
+    /// Searches for the tigers' best move, returning it with its score.
+    fn search_tiger_move(&mut self, mut search: SearchContext) -> Option<(Move, i32)> {
+        // Capture chains are single moves, so the search sees them as one ply
+        let mut moves = self.tiger_moves();
         if moves.is_empty() {
-            return false;
+            return None;
         }
+        let config = search.config;
+        search.resume(self.take_search_tables(), self.ply);
+        self.order_tiger_moves(&mut moves, &search);
+        // Moves into a pocket a goat can seal are rarely worth it, so they
+        // wait until the rest have set a score to beat
+        moves.sort_by_key(|mv| self.is_self_trapping(mv));
+        let progress = self.search_progress.clone();
 
         let mut best_move = None;
         let mut best_score = i32::MIN;
-        let start_time = Instant::now();
-        let mut current_depth = 1;
+        let mut current_depth = search.first_depth();
+        // Moves of the last completed depth with their scores, exact
+        // within the tie margin, or the blunder margin, of the best
+        let margin = config.tie_margin.max(0);
+        let window = search_window(&config);
+        let mut candidates = Vec::new();
 
         // Iterative deepening
-        while start_time.elapsed() < self.ai_time_limit {
+        while search.should_deepen(current_depth) {
             let mut depth_best_move = None;
             let mut depth_best_score = i32::MIN;
+            let mut depth_candidates = Vec::new();
+            let mut depth_lines = Vec::new();
             let mut search_complete = true;
 
-            for (from, to) in moves.iter() {
-                // Check if we've run out of time
-                if start_time.elapsed() >= self.ai_time_limit {
+            for mv in moves.iter() {
+                // Check if we've run out of time or nodes
+                if current_depth > 1 && search.out_of_budget() {
                     search_complete = false;
                     break;
                 }
 
                 // Make move
-                let captured_pos = self.get_captured_position(*from, *to);
-                let original_from = self.cells[*from];
-                let original_to = self.cells[*to];
-                let mut original_captured = None;
-                if let Some(pos) = captured_pos {
-                    original_captured = Some((pos, self.cells[pos]));
-                    self.cells[pos] = Piece::Empty;
-                    self.captured_goats += 1;
-                }
-                self.cells[*from] = Piece::Empty;
-                self.cells[*to] = Piece::Tiger;
+                let saved = SearchBoard::of(self);
+                self.make_tiger_move(mv);
 
-                // Evaluate position
-                let score = self.minimax(
-                    current_depth - 1,
-                    i32::MIN,
+                // Evaluate position; only a better move, or one within the
+                // window or among the lines asked for, needs an exact score
+                let mut alpha = depth_best_score.saturating_sub(window);
+                if self.multi_pv > 0 {
+                    alpha = alpha.min(multi_pv_bound(&depth_lines, self.multi_pv, true));
+                }
+                let depth = current_depth + search.capture_extension(mv);
+                let score = self.search_reply(
+                    depth - 1,
+                    alpha,
                     i32::MAX,
-                    false,
-                    start_time,
-                    self.ai_time_limit,
+                    depth_best_move.is_none(),
+                    true,
+                    &mut search,
                 );
+                search.extension = LineExtension::default();
 
                 // Undo move
-                self.cells[*from] = original_from;
-                self.cells[*to] = original_to;
-                if let Some((pos, piece)) = original_captured {
-                    self.cells[pos] = piece;
-                    self.captured_goats -= 1;
+                saved.restore(self);
+
+                // A move whose search was cut short has no reliable score
+                if current_depth > 1 && search.out_of_budget() {
+                    search_complete = false;
+                    break;
                 }
 
-                // Update best move for current depth
-                if score > depth_best_score {
+                if window > 0 {
+                    depth_candidates.push((mv.clone(), score));
+                }
+                if self.multi_pv > 0 {
+                    depth_lines.push((mv.clone(), score, search.line_after(mv.clone())));
+                }
+
+                // Update best move for current depth. Every move may score
+                // as a loss, but one still has to be played.
+                if score > depth_best_score || depth_best_move.is_none() {
                     depth_best_score = score;
-                    depth_best_move = Some((*from, *to));
+                    depth_best_move = Some(mv);
+                    search.update_pv(mv.clone());
                 }
             }
 
-            // Only update the overall best move if we completed the search at this depth
             if search_complete {
-                best_move = depth_best_move;
+                best_move = depth_best_move.cloned();
                 best_score = depth_best_score;
+                candidates = depth_candidates;
+                self.root_lines = depth_lines;
+                search.settle_pv();
+                if let Some(progress) = &progress {
+                    (progress.0)(&search.info(current_depth, best_score));
+                }
                 current_depth += 1;
             } else {
+                // The previous best was searched first, so a move that does
+                // at least as well at this depth is the better informed pick
+                if depth_best_move.is_some() && depth_best_score >= best_score {
+                    best_move = depth_best_move.cloned();
+                    best_score = depth_best_score;
+                    candidates.clear();
+                    search.settle_pv();
+                }
                 break;
             }
+
+            // The best move so far is the likeliest best at the next depth
+            if let Some(best) = &best_move {
+                if let Some(index) = moves.iter().position(|mv| mv == best) {
+                    moves[..=index].rotate_right(1);
+                }
+            }
         }
 
-        // Make the best move found
-        if let Some((from, to)) = best_move {
-            return self.move_tiger(from, to);
+        let mut rng = root_rng(config.seed, self.ply);
+        if let Some((mv, score)) = near_best(&candidates, best_score, true, margin, &mut rng) {
+            if best_move.as_ref() != Some(&mv) {
+                best_move = Some(mv.clone());
+                best_score = score;
+                search.best_line = vec![mv];
+            }
+        }
+        if let Some(top) = &best_move {
+            if let Some((mv, score)) = blunder(
+                &candidates,
+                top,
+                best_score,
+                true,
+                config.blunders,
+                &mut rng,
+                |mv| !self.lets_opponent_win(Side::Tigers, mv),
+            ) {
+                best_move = Some(mv.clone());
+                best_score = score;
+                search.best_line = vec![mv];
+            }
         }
 
-        false
+        self.last_search = Some(search.info(current_depth - 1, best_score));
+        self.last_search_stats = Some(search.stats(current_depth - 1));
+        self.search_tables = Some(search.into_tables(self.ply, self.rules.clone()));
+        best_move.map(|mv| (mv, best_score))
     }
 
-    pub fn ai_move_goat(&mut self) -> bool {
-        let start_time = Instant::now();
-        let mut current_depth = 1;
+    /// Searches for the goats' best move, returning it with its score.
+    fn search_goat_move(&mut self, mut search: SearchContext) -> Option<(Move, i32)> {
+        // Placements and, once allowed, moves of existing goats
+        let mut moves = self.get_all_valid_goat_moves();
+        if moves.is_empty() {
+            return None;
+        }
+        if search.config.safe_goats {
+            self.drop_hanging_goat_moves(&mut moves);
+        }
+        // Placements that mirror one already listed lead to mirror images
+        // of its positions, so only the first is searched
+        let twins = if self.goats_in_hand > 0 {
+            self.drop_symmetric_goat_moves(&mut moves)
+        } else {
+            Vec::new()
+        };
+        let config = search.config;
+        search.resume(self.take_search_tables(), self.ply);
+        self.order_goat_moves(&mut moves, &search);
+        let mobility = self.mobility_for_traps(&search);
+        let progress = self.search_progress.clone();
+        let mut current_depth = search.first_depth();
         let mut best_move = None;
         let mut best_score = i32::MAX;
+        let margin = config.tie_margin.max(0);
+        let window = search_window(&config);
+        let mut candidates = Vec::new();
 
-        while start_time.elapsed() < self.ai_time_limit {
+        while search.should_deepen(current_depth) {
             let mut depth_best_move = None;
             let mut depth_best_score = i32::MAX;
+            let mut depth_candidates = Vec::new();
+            let mut depth_lines = Vec::new();
             let mut search_complete = true;
 
-            if self.goats_in_hand > 0 {
-                // Try each empty position for placement
-                for pos in 0..25 {
-                    if start_time.elapsed() >= self.ai_time_limit {
-                        search_complete = false;
-                        break;
-                    }
+            for &(from, to) in &moves {
+                if current_depth > 1 && search.out_of_budget() {
+                    search_complete = false;
+                    break;
+                }
 
-                    if self.cells[pos] == Piece::Empty {
-                        // Make move
-                        self.cells[pos] = Piece::Goat;
-                        self.goats_in_hand -= 1;
-
-                        // Evaluate position
-                        let score = self.minimax(
-                            current_depth - 1,
-                            i32::MIN,
-                            i32::MAX,
-                            true,
-                            start_time,
-                            self.ai_time_limit,
-                        );
-
-                        // Undo move
-                        self.cells[pos] = Piece::Empty;
-                        self.goats_in_hand += 1;
-
-                        // Update best move for current depth
-                        if score < depth_best_score {
-                            depth_best_score = score;
-                            depth_best_move = Some((pos, pos));
-                        }
-                    }
+                // Make move
+                let saved = SearchBoard::of(self);
+                self.make_goat_move(from, to);
+
+                // Evaluate position; only a better move, or one within the
+                // window or among the lines asked for, needs an exact score
+                let mut beta = depth_best_score.saturating_add(window);
+                if self.multi_pv > 0 {
+                    beta = beta.max(multi_pv_bound(&depth_lines, self.multi_pv, false));
                 }
-            } else {
-                // Move existing goats
-                let moves = self.get_all_valid_goat_moves();
-                for (from, to) in moves {
-                    if start_time.elapsed() >= self.ai_time_limit {
-                        search_complete = false;
-                        break;
-                    }
+                let depth = current_depth + self.trap_extension(&mobility, &mut search);
+                let score = self.search_reply(
+                    depth - 1,
+                    i32::MIN,
+                    beta,
+                    depth_best_move.is_none(),
+                    false,
+                    &mut search,
+                );
+                search.extension = LineExtension::default();
 
-                    // Make move
-                    let original_from = self.cells[from];
-                    let original_to = self.cells[to];
-                    self.cells[from] = Piece::Empty;
-                    self.cells[to] = Piece::Goat;
-
-                    // Evaluate position
-                    let score = self.minimax(
-                        current_depth - 1,
-                        i32::MIN,
-                        i32::MAX,
-                        true,
-                        start_time,
-                        self.ai_time_limit,
-                    );
+                // Undo move
+                saved.restore(self);
 
-                    // Undo move
-                    self.cells[from] = original_from;
-                    self.cells[to] = original_to;
+                // A move whose search was cut short has no reliable score
+                if current_depth > 1 && search.out_of_budget() {
+                    search_complete = false;
+                    break;
+                }
 
-                    // Update best move for current depth
-                    if score < depth_best_score {
-                        depth_best_score = score;
-                        depth_best_move = Some((from, to));
-                    }
+                if window > 0 {
+                    depth_candidates.push(((from, to), score));
+                }
+                if self.multi_pv > 0 {
+                    let mv = goat_move(from, to);
+                    depth_lines.push((mv.clone(), score, search.line_after(mv)));
+                }
+
+                // Update best move for current depth. Every move may score
+                // as a loss, but one still has to be played.
+                if score < depth_best_score || depth_best_move.is_none() {
+                    depth_best_score = score;
+                    depth_best_move = Some((from, to));
+                    search.update_pv(goat_move(from, to));
                 }
             }
 
-            // Only update the overall best move if we completed the search at this depth
             if search_complete {
                 best_move = depth_best_move;
                 best_score = depth_best_score;
+                // A mirrored move scores what the move it mirrors does
+                for &(twin, original) in &twins {
+                    if let Some(&(_, score)) =
+                        depth_candidates.iter().find(|(mv, _)| *mv == original)
+                    {
+                        depth_candidates.push((twin, score));
+                    }
+                }
+                candidates = depth_candidates;
+                self.root_lines = depth_lines;
+                search.settle_pv();
+                if let Some(progress) = &progress {
+                    (progress.0)(&search.info(current_depth, best_score));
+                }
                 current_depth += 1;
             } else {
+                // The previous best was searched first, so a move that does
+                // at least as well at this depth is the better informed pick
+                if depth_best_move.is_some() && depth_best_score <= best_score {
+                    best_move = depth_best_move;
+                    best_score = depth_best_score;
+                    candidates.clear();
+                    search.settle_pv();
+                }
                 break;
             }
+
+            // The best move so far is the likeliest best at the next depth
+            if let Some(index) = moves.iter().position(|&mv| Some(mv) == best_move) {
+                moves[..=index].rotate_right(1);
+            }
         }
 
-        // Make the best move found
-        if let Some((from, to)) = best_move {
-            if from == to {
-                return self.place_goat(from);
-            } else {
-                return self.move_goat(from, to);
+        let mut rng = root_rng(config.seed, self.ply);
+        if let Some(((from, to), score)) =
+            near_best(&candidates, best_score, false, margin, &mut rng)
+        {
+            if best_move != Some((from, to)) {
+                best_move = Some((from, to));
+                best_score = score;
+                search.best_line = vec![goat_move(from, to)];
+            }
+        }
+        if let Some(top) = best_move {
+            if let Some(((from, to), score)) = blunder(
+                &candidates,
+                &top,
+                best_score,
+                false,
+                config.blunders,
+                &mut rng,
+                |&(from, to)| !self.lets_opponent_win(Side::Goats, &goat_move(from, to)),
+            ) {
+                best_move = Some((from, to));
+                best_score = score;
+                search.best_line = vec![goat_move(from, to)];
+            }
+        }
+
+        self.last_search = Some(search.info(current_depth - 1, best_score));
+        self.last_search_stats = Some(search.stats(current_depth - 1));
+        self.search_tables = Some(search.into_tables(self.ply, self.rules.clone()));
+        let (from, to) = best_move?;
+        Some((goat_move(from, to), best_score))
+    }
+
+    /// Whether `side` playing `mv` ends the game in the opponent's favour,
+    /// or lets the opponent win with their reply.
+    fn lets_opponent_win(&self, side: Side, mv: &Move) -> bool {
+        let opponent = match side {
+            Side::Tigers => Winner::Goats,
+            Side::Goats => Winner::Tigers,
+        };
+        let mut after = self.clone();
+        if !after.make_move(mv) {
+            return false;
+        }
+        if after.is_game_over() {
+            return after.get_winner() == opponent;
+        }
+        after.legal_moves().iter().any(|reply| {
+            if !after.make_move(reply) {
+                return false;
+            }
+            let won = after.get_winner() == opponent;
+            after.undo();
+            won
+        })
+    }
+
+    /// Removes the goat moves that a symmetry of the position maps onto a
+    /// move earlier in `moves`, returning each with the move it mirrors.
+    fn drop_symmetric_goat_moves(
+        &self,
+        moves: &mut Vec<(usize, usize)>,
+    ) -> Vec<((usize, usize), (usize, usize))> {
+        let symmetries: Vec<Vec<usize>> = self
+            .rules
+            .topology
+            .symmetries()
+            .into_iter()
+            .skip(1)
+            .filter(|symmetry| {
+                (0..self.cells.len()).all(|pos| self.cells[symmetry[pos]] == self.cells[pos])
+            })
+            .collect();
+        let mut kept: Vec<(usize, usize)> = Vec::new();
+        let mut twins = Vec::new();
+        for &(from, to) in moves.iter() {
+            let original = symmetries
+                .iter()
+                .map(|symmetry| (symmetry[from], symmetry[to]))
+                .find(|image| kept.contains(image));
+            match original {
+                Some(original) => twins.push(((from, to), original)),
+                None => kept.push((from, to)),
             }
         }
+        *moves = kept;
+        twins
+    }
+
+    /// Exact score of the position from the installed tablebase, if it
+    /// covers it. Faster wins score higher, as do slower losses.
+    fn tablebase_score(&self, tigers_to_move: bool) -> Option<i32> {
+        let side = if tigers_to_move {
+            Side::Tigers
+        } else {
+            Side::Goats
+        };
+        let score = match tablebase::probe_for(self, side)? {
+            Outcome::Win { plies } => 10000 - plies as i32,
+            Outcome::Loss { plies } => plies as i32 - 10000,
+            Outcome::Draw => 0,
+        };
+        Some(if tigers_to_move { score } else { -score })
+    }
+
+    /// Sorts tiger moves so the search tries the likeliest best ones first:
+    /// captures before quiet moves, longer chains first, and among equal
+    /// captures the ones that leave the tiger threatening another. Quiet
+    /// moves go by the killers and history in `search`.
+    fn order_tiger_moves(&self, moves: &mut [Move], search: &SearchContext) {
+        moves.sort_by_cached_key(|mv| {
+            let key = match self.capture_priority(mv) {
+                0 => search.quiet_priority(move_squares(mv)),
+                priority => (3, priority as u32),
+            };
+            std::cmp::Reverse(key)
+        });
+    }
+
+    /// How good a capture looks before searching it, or 0 for a quiet
+    /// move.
+    fn capture_priority(&self, mv: &Move) -> usize {
+        let (from, landing, captured) = match mv {
+            Move::MoveTiger {
+                captured_position: None,
+                ..
+            }
+            | Move::PlaceGoat { .. }
+            | Move::MoveGoat { .. } => return 0,
+            Move::MoveTiger {
+                from,
+                to,
+                captured_position: Some(over),
+            } => (*from, *to, vec![*over]),
+            Move::MultiJump(jumps) => (
+                jumps[0].from,
+                jumps[jumps.len() - 1].to,
+                jumps.iter().map(|jump| jump.over).collect(),
+            ),
+        };
+        let free =
+            |pos: usize| pos == from || captured.contains(&pos) || self.cells[pos] == Piece::Empty;
+        let threats = self
+            .rules
+            .topology
+            .jumps_from(landing)
+            .iter()
+            .filter(|jump| {
+                self.cells[jump.over] == Piece::Goat
+                    && !captured.contains(&jump.over)
+                    && free(jump.to)
+            })
+            .count();
+        captured.len() * 10 + threats.min(9)
+    }
+
+    /// Sorts goat moves, given as `(from, to)` with `from == to` for a
+    /// placement. Moves that fill a tiger's landing point come first and
+    /// moves that leave the goat open to a jump last, with the killers and
+    /// history in `search` deciding between the rest.
+    fn order_goat_moves(&self, moves: &mut [(usize, usize)], search: &SearchContext) {
+        let topology = &self.rules.topology;
+        moves.sort_by_cached_key(|&(from, to)| {
+            let blocks = topology.jumps_from(to).iter().any(|jump| {
+                self.cells[jump.to] == Piece::Tiger
+                    && self.cells[jump.over] == Piece::Goat
+                    && jump.over != from
+            });
+            // The tigers move next, so any landing point at all loses it
+            let exposed = !self.pieces().goat_landings(to, Some(from)).is_empty();
+            let (killer, history) = search.quiet_priority((from, to));
+            std::cmp::Reverse((2 * blocks as u8 + !exposed as u8, killer, history))
+        });
+    }
 
-        false
+    /// Searches the position after a move by the side `maximizing` says.
+    /// The first move at a node gets the full window; the rest, as in
+    /// principal variation search, only a null window to show they are no
+    /// better, and the full window again if one turns out to be.
+    fn search_reply(
+        &mut self,
+        depth: i32,
+        alpha: i32,
+        beta: i32,
+        first: bool,
+        maximizing: bool,
+        search: &mut SearchContext,
+    ) -> i32 {
+        search.ply += 1;
+        // Coming back to a position on the path, a line can go round
+        // forever, so it counts as a draw unless the rules have ended the
+        // game there
+        let key = position_key(
+            &self.cells,
+            self.goats_in_hand,
+            self.captured_goats,
+            !maximizing,
+        );
+        if search.path.contains(&key) && !self.is_game_over() {
+            search.clear_pv();
+            search.ply -= 1;
+            return search.draw_score;
+        }
+        search.path.push(key);
+        search.passed = false;
+        let score = if first {
+            self.minimax(depth, alpha, beta, !maximizing, search)
+        } else if maximizing {
+            let score = self.minimax(depth, alpha, alpha.saturating_add(1), false, search);
+            if score > alpha && score < beta {
+                self.minimax(depth, alpha, beta, false, search)
+            } else {
+                score
+            }
+        } else {
+            let score = self.minimax(depth, beta.saturating_sub(1), beta, true, search);
+            if score < beta && score > alpha {
+                self.minimax(depth, alpha, beta, true, search)
+            } else {
+                score
+            }
+        };
+        search.path.pop();
+        search.ply -= 1;
+        score
     }
 
     fn minimax(
@@ -723,51 +3943,111 @@ impl Board {
         mut alpha: i32,
         mut beta: i32,
         is_maximizing: bool,
-        start_time: Instant,
-        time_limit: Duration,
+        search: &mut SearchContext,
     ) -> i32 {
-        // Check if we've run out of time
-        if start_time.elapsed() >= time_limit {
-            return self.evaluate_position();
+        search.stats.nodes += 1;
+        search.stats.max_ply = search.stats.max_ply.max(search.ply as u32);
+        search.clear_pv();
+        let game_over = self.is_game_over();
+        if depth == 0 || game_over {
+            // Wins count for more the sooner they come, losses the later
+            let decided = |score: i32| score.signum() * (score.abs() - search.ply as i32);
+            if let Some(score) = self.tablebase_score(is_maximizing) {
+                return decided(score);
+            }
+            if game_over {
+                return decided(self.evaluate_position());
+            }
+            return self.quiesce(alpha, beta, is_maximizing, QUIESCENCE_PLIES, search);
         }
 
-        if depth == 0 || self.is_game_over() {
+        // Check if we've run out of time or nodes
+        if search.out_of_budget() {
             return self.evaluate_position();
         }
 
-        if is_maximizing {
+        // An earlier search, or another thread, may have searched this
+        // position already
+        let (key, window) = (
+            position_key(
+                &self.cells,
+                self.goats_in_hand,
+                self.captured_goats,
+                is_maximizing,
+            ),
+            (alpha, beta),
+        );
+        if let Some(table) = &search.table {
+            if let Some(score) = table.probe(key, depth, alpha, beta, search.ply) {
+                return score;
+            }
+        }
+
+        // If the side to move could pass and still get past the window,
+        // one of its moves would too. Only worth trying away from the
+        // principal variation, where the window is null.
+        if search.config.null_move
+            && depth > NULL_MOVE_REDUCTION
+            && alpha.saturating_add(1) == beta
+            && !search.passed
+            && self.may_pass(is_maximizing)
+        {
+            search.passed = true;
+            search.ply += 1;
+            let reduced = depth - 1 - NULL_MOVE_REDUCTION;
+            let score = self.minimax(reduced, alpha, beta, !is_maximizing, search);
+            search.ply -= 1;
+            search.passed = false;
+            if !search.out_of_budget() {
+                if is_maximizing && score >= beta {
+                    return beta;
+                }
+                // Goats with no more to place often have only moves that
+                // open a jump, so a pass flatters them: check the cutoff
+                // with a shallower search of their real moves first
+                if !is_maximizing && score <= alpha {
+                    search.passed = true;
+                    let verified =
+                        self.minimax(depth - NULL_MOVE_REDUCTION, alpha, beta, false, search);
+                    search.passed = false;
+                    if verified <= alpha {
+                        return alpha;
+                    }
+                }
+            }
+        }
+
+        let score = if is_maximizing {
             // Tiger's turn (maximizing)
             let mut max_eval = i32::MIN;
-            let moves = self.get_all_valid_tiger_moves();
+            let mut moves = self.tiger_moves();
+            self.order_tiger_moves(&mut moves, search);
+            let depth = depth + search.reply_extension(moves.len());
+            let line = search.extension;
 
-            for (from, to) in moves {
+            for (i, mv) in moves.iter().enumerate() {
                 // Make move
-                let captured_pos = self.get_captured_position(from, to);
-                let original_from = self.cells[from];
-                let original_to = self.cells[to];
-                let mut original_captured = None;
-                if let Some(pos) = captured_pos {
-                    original_captured = Some((pos, self.cells[pos]));
-                    self.cells[pos] = Piece::Empty;
-                    self.captured_goats += 1;
-                }
-                self.cells[from] = Piece::Empty;
-                self.cells[to] = Piece::Tiger;
+                let saved = SearchBoard::of(self);
+                self.make_tiger_move(mv);
 
                 // Recursive evaluation
-                let eval = self.minimax(depth - 1, alpha, beta, false, start_time, time_limit);
+                let depth = depth + search.capture_extension(mv);
+                let eval = self.search_reply(depth - 1, alpha, beta, i == 0, true, search);
+                search.extension = line;
 
                 // Undo move
-                self.cells[from] = original_from;
-                self.cells[to] = original_to;
-                if let Some((pos, piece)) = original_captured {
-                    self.cells[pos] = piece;
-                    self.captured_goats -= 1;
-                }
+                saved.restore(self);
 
+                if eval > max_eval {
+                    search.update_pv(mv.clone());
+                }
                 max_eval = max_eval.max(eval);
                 alpha = alpha.max(eval);
                 if beta <= alpha {
+                    search.stats.cutoffs += 1;
+                    if !mv.is_capture() {
+                        search.record_cutoff(move_squares(mv), depth);
+                    }
                     break; // Beta cutoff
                 }
             }
@@ -775,42 +4055,174 @@ impl Board {
         } else {
             // Goat's turn (minimizing)
             let mut min_eval = i32::MAX;
-            let moves = self.get_all_valid_goat_moves();
+            let mut moves = self.get_all_valid_goat_moves();
+            self.order_goat_moves(&mut moves, search);
+            let depth = depth + search.reply_extension(moves.len());
+            let line = search.extension;
+            let mobility = self.mobility_for_traps(search);
 
-            for (from, to) in moves {
+            for (i, (from, to)) in moves.into_iter().enumerate() {
                 // Make move
-                let original_from = self.cells[from];
-                let original_to = self.cells[to];
-                if from == to {
-                    // Placing a new goat
-                    self.cells[to] = Piece::Goat;
-                    self.goats_in_hand -= 1;
-                } else {
-                    // Moving an existing goat
-                    self.cells[from] = Piece::Empty;
-                    self.cells[to] = Piece::Goat;
-                }
+                let saved = SearchBoard::of(self);
+                self.make_goat_move(from, to);
 
                 // Recursive evaluation
-                let eval = self.minimax(depth - 1, alpha, beta, true, start_time, time_limit);
+                let depth = depth + self.trap_extension(&mobility, search);
+                let eval = self.search_reply(depth - 1, alpha, beta, i == 0, false, search);
+                search.extension = line;
 
                 // Undo move
-                if from == to {
-                    self.cells[to] = Piece::Empty;
-                    self.goats_in_hand += 1;
-                } else {
-                    self.cells[from] = original_from;
-                    self.cells[to] = original_to;
-                }
+                saved.restore(self);
 
+                if eval < min_eval {
+                    search.update_pv(goat_move(from, to));
+                }
                 min_eval = min_eval.min(eval);
                 beta = beta.min(eval);
                 if beta <= alpha {
+                    search.stats.cutoffs += 1;
+                    search.record_cutoff((from, to), depth);
                     break; // Alpha cutoff
                 }
             }
             min_eval
+        };
+
+        // A search cut short has no reliable score to keep
+        if let Some(table) = &search.table {
+            if !search.out_of_budget() {
+                table.store(key, depth, score, window.0, window.1, search.ply);
+            }
+        }
+        score
+    }
+
+    /// Scores a position the search has reached the end of once it is
+    /// quiet, so a goat left hanging one ply past the depth isn't missed.
+    /// Either side may stand on the evaluation, which already counts the
+    /// goats open to a jump; the tigers try their captures instead, and
+    /// the goats the moves that take a capture away, or after a capture
+    /// any move. Stops after `plies` more.
+    fn quiesce(
+        &mut self,
+        mut alpha: i32,
+        mut beta: i32,
+        is_maximizing: bool,
+        plies: i32,
+        search: &mut SearchContext,
+    ) -> i32 {
+        search.stats.qnodes += 1;
+        search.stats.max_ply = search.stats.max_ply.max(search.ply as u32);
+        let stand_pat = self.evaluate_position();
+        if self.is_game_over() {
+            return stand_pat.signum() * (stand_pat.abs() - search.ply as i32);
+        }
+        if plies == 0 {
+            return stand_pat;
+        }
+        // The goats get to answer a capture made here as they would one
+        // made before the depth ran out, so it isn't worth a free move
+        let threats = self.capture_count();
+        if threats == 0 && (is_maximizing || plies == QUIESCENCE_PLIES) {
+            return stand_pat;
+        }
+
+        search.ply += 1;
+        let score = if is_maximizing {
+            let captures: Vec<Move> = self
+                .tiger_moves()
+                .into_iter()
+                .filter(Move::is_capture)
+                .collect();
+            // Unless the rules make them capture, the tigers can always
+            // do something quieter instead
+            let mut best = if self.rules.forced_capture {
+                i32::MIN
+            } else {
+                stand_pat
+            };
+            alpha = alpha.max(best);
+            for mv in &captures {
+                if beta <= alpha {
+                    break;
+                }
+                let saved = SearchBoard::of(self);
+                self.make_tiger_move(mv);
+                let eval = self.quiesce(alpha, beta, false, plies - 1, search);
+                saved.restore(self);
+                best = best.max(eval);
+                alpha = alpha.max(eval);
+            }
+            best
+        } else {
+            let mut best = stand_pat;
+            beta = beta.min(best);
+            for (from, to) in self.get_all_valid_goat_moves() {
+                if beta <= alpha {
+                    break;
+                }
+                let saved = SearchBoard::of(self);
+                self.make_goat_move(from, to);
+                if threats == 0 || self.capture_count() < threats {
+                    let eval = self.quiesce(alpha, beta, true, plies - 1, search);
+                    best = best.min(eval);
+                    beta = beta.min(eval);
+                }
+                saved.restore(self);
+            }
+            best
+        };
+        search.ply -= 1;
+        score
+    }
+
+    /// Captures the tigers could make if it were their move.
+    fn capture_count(&self) -> usize {
+        self.get_all_valid_tiger_moves()
+            .into_iter()
+            .filter(|&(from, to)| self.get_captured_position(from, to).is_some())
+            .count()
+    }
+
+    /// Each tiger with the moves it has, for telling which goat moves
+    /// corner one, or nothing if `search` doesn't extend those moves.
+    fn mobility_for_traps(&self, search: &SearchContext) -> Vec<(usize, usize)> {
+        if !search.config.extensions.traps {
+            return Vec::new();
+        }
+        self.tigers()
+            .map(|tiger| (tiger.0, self.tiger_moves_after_step(tiger.0, tiger.0)))
+            .collect()
+    }
+
+    /// Plies to extend the line by after the goat move just made, if it
+    /// leaves a tiger that had moves to spare, by `mobility`, a single one.
+    fn trap_extension(&self, mobility: &[(usize, usize)], search: &mut SearchContext) -> i32 {
+        let traps = mobility
+            .iter()
+            .any(|&(tiger, moves)| moves > 1 && self.tiger_moves_after_step(tiger, tiger) == 1);
+        if traps {
+            search.extend(2)
+        } else {
+            0
+        }
+    }
+
+    /// Whether a null-move search may let the side to move pass. Goats
+    /// still placing always have a useful move, so passing tells nothing;
+    /// tigers short of moves may lose only because they have to move; and
+    /// a forced capture is no time to pass.
+    fn may_pass(&self, tigers_to_move: bool) -> bool {
+        if !tigers_to_move {
+            return self.goats_in_hand == 0;
         }
+        let moves = self.get_all_valid_tiger_moves();
+        let capture = || {
+            moves
+                .iter()
+                .any(|&(from, to)| self.get_captured_position(from, to).is_some())
+        };
+        moves.len() >= NULL_MOVE_MIN_TIGER_MOVES && !(self.rules.forced_capture && capture())
     }
 
     fn is_valid_move(&self, _from: usize, to: usize) -> bool {
@@ -832,33 +4244,192 @@ impl Board {
     }
 
     fn get_all_valid_goat_placements(&self) -> Vec<Position> {
-        (0..25)
-            .filter(|&pos| self.cells[pos] == Piece::Empty)
-            .map(Position)
-            .collect()
+        self.empty_cells().collect()
     }
 }
 
-impl Display for Board {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for (i, cell) in self.cells.iter().enumerate() {
-            if i % 5 == 0 {
-                write!(f, "   ")?; // Initial spacing
-            }
+impl Index<Position> for Board {
+    type Output = Piece;
 
-            let piece = match cell {
-                Piece::Tiger => "T".red().bold().to_string(),
-                Piece::Goat => "G".yellow().bold().to_string(),
-                Piece::Empty => "·".to_string(),
-            };
-            write!(f, "{piece}")?;
+    fn index(&self, pos: Position) -> &Piece {
+        &self.cells[pos.0]
+    }
+}
 
-            if (i + 1) % 5 == 0 {
-                writeln!(f)?;
-            } else {
-                write!(f, " ")?; // Add space between pieces for better readability
-            }
+/// Direct write access, for setting up positions in tests and sandboxes.
+/// This bypasses move validation and the move history.
+impl IndexMut<Position> for Board {
+    fn index_mut(&mut self, pos: Position) -> &mut Piece {
+        &mut self.cells[pos.0]
+    }
+}
+
+/// Most plies quiescence search adds to the end of a line.
+const QUIESCENCE_PLIES: i32 = 4;
+
+/// How far below the best a root move may score and still need an exact
+/// score: within the tie margin, or within the blunder margin when the AI
+/// may blunder.
+fn search_window(config: &AiConfig) -> i32 {
+    let blunder_margin = config
+        .blunders
+        .filter(|blunders| blunders.chance > 0)
+        .map_or(0, |blunders| blunders.margin.max(0).saturating_add(1));
+    config.tie_margin.max(0).max(blunder_margin)
+}
+
+/// The score a root move has to beat to be among the `n` best of `lines`,
+/// those searched so far: from below for the tigers, from above for the
+/// goats. Any score will do until `n` moves have been searched.
+fn multi_pv_bound(lines: &[(Move, i32, Vec<Move>)], n: usize, tigers: bool) -> i32 {
+    let mut scores: Vec<i32> = lines.iter().map(|&(_, score, _)| score).collect();
+    if scores.len() < n {
+        return if tigers { i32::MIN } else { i32::MAX };
+    }
+    if tigers {
+        scores.sort_unstable_by(|a, b| b.cmp(a));
+    } else {
+        scores.sort_unstable();
+    }
+    scores[n - 1]
+}
+
+/// Plies a null-move search leaves out, besides the pass itself.
+const NULL_MOVE_REDUCTION: i32 = 2;
+
+/// Fewest moves the tigers need between them to be allowed to pass.
+const NULL_MOVE_MIN_TIGER_MOVES: usize = 4;
+
+/// Most plies any line is extended by, see [`Extensions`].
+const MAX_EXTENSION: u32 = 4;
+
+/// A Zobrist key for the position with `cells`, the goats counted and
+/// the side to move: the contents of each point, and the rest, each stand
+/// for a fixed random-looking number, and the key combines them.
+fn position_key(cells: &[Piece], goats_in_hand: u32, captured: u32, tigers_to_move: bool) -> u64 {
+    let counts = (u64::from(goats_in_hand) << 16 | u64::from(captured)) << 1;
+    let mut key = splitmix64(counts | u64::from(tigers_to_move));
+    for (pos, piece) in cells.iter().enumerate() {
+        let piece = match piece {
+            Piece::Empty => continue,
+            Piece::Goat => 1,
+            Piece::Tiger => 2,
+        };
+        key ^= splitmix64((1 << 40) + pos as u64 * 3 + piece);
+    }
+    key
+}
+
+/// Scrambles `x` into a number that looks random, the same every time.
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// The random numbers for picking between root moves, seeded from `seed`
+/// and `ply` when there is a seed.
+fn root_rng(seed: Option<u64>, ply: usize) -> StdRng {
+    match seed {
+        Some(seed) => {
+            StdRng::seed_from_u64(seed ^ (ply as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15))
         }
-        Ok(())
+        None => StdRng::from_entropy(),
+    }
+}
+
+/// One of the root `candidates` scoring less than `margin` worse than
+/// `best` for the side to move, picked at random, or `None` if `margin` is
+/// 0.
+fn near_best<T: Clone>(
+    candidates: &[(T, i32)],
+    best: i32,
+    tigers: bool,
+    margin: i32,
+    rng: &mut StdRng,
+) -> Option<(T, i32)> {
+    if margin <= 0 {
+        return None;
+    }
+    let near: Vec<&(T, i32)> = candidates
+        .iter()
+        .filter(|(_, score)| {
+            let behind = if tigers { best - score } else { score - best };
+            behind < margin
+        })
+        .collect();
+    near.choose(rng).map(|&candidate| candidate.clone())
+}
+
+/// A root move other than `top` to play instead of it, if `blunders` says
+/// to misplay this move: one of the `candidates` scoring at most the
+/// blunder margin worse than `best` that passes `safe`, picked at random.
+/// `None` if the AI plays `top` after all.
+fn blunder<T: Clone + PartialEq>(
+    candidates: &[(T, i32)],
+    top: &T,
+    best: i32,
+    tigers: bool,
+    blunders: Option<Blunders>,
+    rng: &mut StdRng,
+    safe: impl Fn(&T) -> bool,
+) -> Option<(T, i32)> {
+    let blunders = blunders?;
+    if rng.gen_range(0..100) >= u32::from(blunders.chance) {
+        return None;
     }
+    let worse: Vec<&(T, i32)> = candidates
+        .iter()
+        .filter(|(mv, score)| {
+            let behind = if tigers { best - score } else { score - best };
+            mv != top && behind <= blunders.margin && safe(mv)
+        })
+        .collect();
+    worse.choose(rng).map(|&candidate| candidate.clone())
+}
+
+/// The point a move starts from and the one it ends on, the same point
+/// for a placement.
+fn move_squares(mv: &Move) -> (usize, usize) {
+    match mv {
+        Move::PlaceGoat { position } => (*position, *position),
+        Move::MoveGoat { from, to } | Move::MoveTiger { from, to, .. } => (*from, *to),
+        Move::MultiJump(jumps) => (jumps[0].from, jumps[jumps.len() - 1].to),
+    }
+}
+
+/// The goat move from `from` to `to`, a placement if the two are the same.
+fn goat_move(from: usize, to: usize) -> Move {
+    if from == to {
+        Move::PlaceGoat { position: to }
+    } else {
+        Move::MoveGoat { from, to }
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.pieces().render(f)
+    }
+}
+
+/// Appends one line of rendered symbols, without trailing spaces, and
+/// without their colours unless `color` is set.
+fn push_line(output: &mut String, line: &[ColoredString], color: bool) {
+    let text: String = line
+        .iter()
+        .map(|symbol| match color {
+            true => symbol.to_string(),
+            false => symbol.input.clone(),
+        })
+        .collect();
+    output.push_str(text.trim_end());
+    output.push('\n');
 }
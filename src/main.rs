@@ -1,302 +1,2457 @@
-use baghchal::{Board, Piece, Player, Winner};
+use baghchal::batch::BatchGame;
+use baghchal::notation::{self, format_move};
+use baghchal::review::{MoveQuality, MoveReview, Thresholds};
+use baghchal::save;
+use baghchal::{
+    annotate_move, AiConfig, Batch, Board, Clock, Difficulty, DisplayOptions, Engine, Extensions,
+    GameEvent, GameResult, Jump, Mcts, MinimaxEngine, Move, MoveError, MultiCapture, Piece,
+    PieceStyle, Player, Review, RuleSet, SearchStatus, Side, TerminationReason, Theme, Topology,
+    Winner,
+};
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
 use colored::Colorize;
+use std::fs::OpenOptions;
 use std::io::{self, Write};
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
-use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Lines of input, read on a thread of their own so that a prompt can stop
+/// waiting when a player's clock runs out.
+fn input_lines() -> &'static Mutex<Receiver<String>> {
+    static LINES: OnceLock<Mutex<Receiver<String>>> = OnceLock::new();
+    LINES.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in io::stdin().lines() {
+                let Ok(line) = line else { break };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Mutex::new(receiver)
+    })
+}
+
+/// What came of waiting for a line of input.
+enum Input {
+    Line(String),
+    /// Input has run out, e.g. at the end of a piped script.
+    Closed,
+    /// The deadline passed first.
+    TimedOut,
+}
+
+/// Reads a line of input, trimmed, waiting until `deadline` at most.
+fn read_line_until(deadline: Option<Instant>) -> Input {
+    let lines = input_lines().lock().unwrap();
+    let line = match deadline {
+        Some(deadline) => {
+            match lines.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(line) => Some(line),
+                Err(RecvTimeoutError::Timeout) => return Input::TimedOut,
+                Err(RecvTimeoutError::Disconnected) => None,
+            }
+        }
+        None => lines.recv().ok(),
+    };
+    match line {
+        Some(line) => Input::Line(line.trim().to_string()),
+        None => Input::Closed,
+    }
+}
+
+/// Reads a line of input, trimmed, or `None` once input has run out.
+fn read_line() -> Option<String> {
+    match read_line_until(None) {
+        Input::Line(line) => Some(line),
+        Input::Closed | Input::TimedOut => None,
+    }
+}
 
 fn get_user_input(prompt: &str) -> Option<String> {
+    match get_user_input_until(prompt, None) {
+        Input::Line(input) => Some(input),
+        Input::Closed | Input::TimedOut => None,
+    }
+}
+
+/// Asks for a command until one is given, `q` is entered, input runs out
+/// or `deadline` passes; `q` counts as input running out.
+fn get_user_input_until(prompt: &str, deadline: Option<Instant>) -> Input {
     loop {
         print!("{prompt}");
         io::stdout().flush().unwrap();
 
-        let mut input = String::new();
-        io::stdin().read_line(&mut input).unwrap();
+        let input = match read_line_until(deadline) {
+            Input::Line(input) => input,
+            other => return other,
+        };
+        if input.is_empty() {
+            println!("Please enter a command");
+            continue;
+        }
+        if input.eq_ignore_ascii_case("q") || input.eq_ignore_ascii_case("quit") {
+            return Input::Closed;
+        }
+        return Input::Line(input);
+    }
+}
+
+fn parse_position(topology: &Topology, input: &str) -> Option<usize> {
+    // Only accept coordinate format (e.g. A1-E5 on the standard board)
+    topology.parse_point(input.trim())
+}
+
+fn parse_move(topology: &Topology, input: &str) -> Option<(usize, usize)> {
+    let parts: Vec<&str> = input.split_whitespace().collect();
+    if parts.len() == 2 {
+        if let (Some(from), Some(to)) = (
+            parse_position(topology, parts[0]),
+            parse_position(topology, parts[1]),
+        ) {
+            return Some((from, to));
+        }
+    }
+    None
+}
+
+/// Parses three or more positions, the squares a tiger lands on during a
+/// chain of captures, e.g. 'A1 C1 E1'.
+fn parse_path(topology: &Topology, input: &str) -> Option<Vec<usize>> {
+    let path: Option<Vec<usize>> = input
+        .split_whitespace()
+        .map(|part| parse_position(topology, part))
+        .collect();
+    path.filter(|path| path.len() >= 3)
+}
+
+/// Asks for a point until one is given, or `None` if the player quits or
+/// `deadline` passes.
+fn get_position(topology: &Topology, prompt: &str, deadline: Option<Instant>) -> Option<usize> {
+    loop {
+        if let Input::Line(input) = get_user_input_until(prompt, deadline) {
+            match parse_position(topology, &input) {
+                Some(pos) => return Some(pos),
+                None => println!("Please enter a valid position (A1-E5)"),
+            }
+        } else {
+            return None;
+        }
+    }
+}
+
+fn print_instructions() {
+    println!("\n=== BAGHCHAL ===");
+    println!("A traditional board game from Nepal");
+    println!("\nPositions are specified using grid coordinates (A1-E5)");
+    let options = display_options();
+    println!(
+        "{} = Tiger, {} = Goat, {} = Empty",
+        options.piece_symbol(Piece::Tiger),
+        options.piece_symbol(Piece::Goat),
+        options.piece_symbol(Piece::Empty)
+    );
+    println!("Commands:");
+    println!("  - To move a piece:");
+    println!("    • Enter both positions at once (e.g., 'A1 A2')");
+    println!("    • Or enter one position to see valid moves, then enter destination");
+    println!("  - Enter a single position (e.g., 'A1') to place a goat");
+    println!("  - Chain captures by listing every landing point (e.g., 'A1 C1 E1')");
+    println!("  - Type 'h' or 'hint' to get a suggested move");
+    println!("  - Type 'e' or 'eval' to see how the AI judges the position");
+    println!("  - Type 'analyze [seconds]' to list the three best moves");
+    println!("  - Type 'u' or 'undo' to take back the last move");
+    println!("  - Type 'moves' or 'history' to list the moves played so far");
+    println!("  - Type 'flip' to turn the board round");
+    println!("  - Type 'describe' to describe the position in words");
+    println!("  - Type 'draw' to offer your opponent a draw");
+    println!("  - Type 'save <file>' to save the game, 'load <file>' to carry one on");
+    println!("  - Type 'q' or 'quit' to exit the game");
+    println!("  - Press Ctrl+C during AI's turn to interrupt, or at your move to quit");
+    println!("  - Press Enter while the AIs play each other to pause the game");
+    println!("===============\n");
+}
+
+/// How the AI was told to search, as picked before the game.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AiLimit {
+    Default,
+    Level(Difficulty),
+    Time(u64),
+    Depth(u32),
+}
+
+/// Which AI plays a side in AI vs AI games.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EngineChoice {
+    Minimax,
+    MonteCarlo,
+}
+
+/// The choices made before a game, kept with its autosave so that a
+/// resumed game is played the way it was set up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Settings {
+    tiger_player: Player,
+    goat_player: Player,
+    ai_limit: AiLimit,
+    opening_book: bool,
+    ponder: bool,
+    tiger_engine: EngineChoice,
+    goat_engine: EngineChoice,
+}
+
+impl Settings {
+    /// The settings as `key: value` lines to go after a save, which the
+    /// save format leaves to programs to add.
+    fn to_lines(self) -> String {
+        let player = |player| match player {
+            Player::Human => "human",
+            Player::AI => "ai",
+        };
+        let engine = |engine| match engine {
+            EngineChoice::Minimax => "minimax",
+            EngineChoice::MonteCarlo => "monte-carlo",
+        };
+        let ai = match self.ai_limit {
+            AiLimit::Default => "default".to_string(),
+            AiLimit::Level(difficulty) => format!("level {difficulty:?}"),
+            AiLimit::Time(seconds) => format!("time {seconds}"),
+            AiLimit::Depth(depth) => format!("depth {depth}"),
+        };
+        let yes_no = |value| if value { "yes" } else { "no" };
+        format!(
+            "# Settings of the game in the terminal\n\
+             players: {} {}\n\
+             ai: {ai}\n\
+             opening book: {}\n\
+             ponder: {}\n\
+             engines: {} {}\n",
+            player(self.tiger_player),
+            player(self.goat_player),
+            yes_no(self.opening_book),
+            yes_no(self.ponder),
+            engine(self.tiger_engine),
+            engine(self.goat_engine),
+        )
+    }
+
+    /// Reads the settings back from a save written with them.
+    fn parse(text: &str) -> Option<Settings> {
+        let field = |name: &str| {
+            text.lines()
+                .filter_map(|line| line.split_once(':'))
+                .find(|(key, _)| key.trim() == name)
+                .map(|(_, value)| value.trim())
+        };
+        let pair = |name: &str| field(name)?.split_once(' ');
+        let player = |text: &str| match text {
+            "human" => Some(Player::Human),
+            "ai" => Some(Player::AI),
+            _ => None,
+        };
+        let engine = |text: &str| match text {
+            "minimax" => Some(EngineChoice::Minimax),
+            "monte-carlo" => Some(EngineChoice::MonteCarlo),
+            _ => None,
+        };
+        let yes_no = |name: &str| match field(name)? {
+            "yes" => Some(true),
+            "no" => Some(false),
+            _ => None,
+        };
+        let ai_limit = match field("ai")?.split_once(' ') {
+            None if field("ai")? == "default" => AiLimit::Default,
+            Some(("level", level)) => AiLimit::Level(
+                [
+                    Difficulty::Beginner,
+                    Difficulty::Easy,
+                    Difficulty::Medium,
+                    Difficulty::Hard,
+                ]
+                .into_iter()
+                .find(|difficulty| format!("{difficulty:?}") == level)?,
+            ),
+            Some(("time", seconds)) => AiLimit::Time(seconds.parse().ok()?),
+            Some(("depth", depth)) => AiLimit::Depth(depth.parse().ok()?),
+            _ => return None,
+        };
+        let (tigers, goats) = pair("players")?;
+        let (tiger_engine, goat_engine) = pair("engines")?;
+        Some(Settings {
+            tiger_player: player(tigers)?,
+            goat_player: player(goats)?,
+            ai_limit,
+            opening_book: yes_no("opening book")?,
+            ponder: yes_no("ponder")?,
+            tiger_engine: engine(tiger_engine)?,
+            goat_engine: engine(goat_engine)?,
+        })
+    }
+
+    /// Swaps the players' sides, engines and all.
+    fn swap_sides(&mut self) {
+        std::mem::swap(&mut self.tiger_player, &mut self.goat_player);
+        std::mem::swap(&mut self.tiger_engine, &mut self.goat_engine);
+    }
+
+    fn playing_against_ai(self) -> bool {
+        self.tiger_player != self.goat_player
+    }
+
+    /// Sets the AI up on `board` as chosen, breaking ties between equal
+    /// moves from `seed`.
+    fn apply(self, board: &mut Board, seed: Option<u64>) {
+        if self.tiger_player == Player::Human && self.goat_player == Player::Human {
+            return;
+        }
+        match self.ai_limit {
+            AiLimit::Default => {}
+            AiLimit::Level(difficulty) => board.set_ai_config(AiConfig::preset(difficulty, None)),
+            AiLimit::Time(seconds) => board.set_ai_time_limit(Duration::from_secs(seconds)),
+            AiLimit::Depth(depth) => board.set_ai_config(AiConfig {
+                time_limit: None,
+                max_depth: Some(depth),
+                max_nodes: None,
+                ponder: false,
+                tie_margin: 0,
+                seed: None,
+                blunders: None,
+                contempt: 0,
+                null_move: true,
+                extensions: Extensions::default(),
+                safe_goats: false,
+                threads: 1,
+            }),
+        }
+        board.set_use_opening_book(self.opening_book);
+        configure_tie_breaking(board, seed);
+        board.set_ai_config(AiConfig {
+            ponder: self.ponder,
+            ..*board.ai_config()
+        });
+    }
+}
+
+fn configure_ai_limits() -> AiLimit {
+    loop {
+        let Some(input) = get_user_input("Pick a (l)evel, or limit the AI by (t)ime or (d)epth? ")
+        else {
+            return AiLimit::Default;
+        };
+        match input.to_lowercase().as_str() {
+            "l" | "level" => return configure_ai_level(),
+            "t" | "time" => return configure_ai_time_limit(),
+            "d" | "depth" => return configure_ai_depth(),
+            _ => println!("Please enter 'l', 't' or 'd'"),
+        }
+    }
+}
+
+fn configure_ai_level() -> AiLimit {
+    loop {
+        let Some(input) = get_user_input("Level: 1. Beginner  2. Easy  3. Medium  4. Hard (1-4): ")
+        else {
+            return AiLimit::Default;
+        };
+        let difficulty = match input.as_str() {
+            "1" => Difficulty::Beginner,
+            "2" => Difficulty::Easy,
+            "3" => Difficulty::Medium,
+            "4" => Difficulty::Hard,
+            _ => {
+                println!("Please enter a number between 1 and 4");
+                continue;
+            }
+        };
+        println!("AI level set to {difficulty:?}");
+        return AiLimit::Level(difficulty);
+    }
+}
+
+fn configure_ai_time_limit() -> AiLimit {
+    loop {
+        let Some(input) = get_user_input("Enter AI thinking time in seconds (1-10): ") else {
+            return AiLimit::Default;
+        };
+        if let Ok(seconds) = input.parse::<u64>() {
+            if (1..=10).contains(&seconds) {
+                println!("AI thinking time set to {} seconds", seconds);
+                return AiLimit::Time(seconds);
+            }
+        }
+        println!("Please enter a number between 1 and 10");
+    }
+}
+
+fn configure_ai_depth() -> AiLimit {
+    loop {
+        let Some(input) = get_user_input("Enter AI search depth in plies (1-8): ") else {
+            return AiLimit::Default;
+        };
+        if let Ok(depth) = input.parse::<u32>() {
+            if (1..=8).contains(&depth) {
+                println!("AI search depth set to {depth} plies");
+                return AiLimit::Depth(depth);
+            }
+        }
+        println!("Please enter a number between 1 and 8");
+    }
+}
+
+fn configure_opening_book() -> bool {
+    match get_user_input("Let the AI play from its opening book? (y/n): ") {
+        Some(input) if input.eq_ignore_ascii_case("n") => {
+            println!("The AI will search every move");
+            false
+        }
+        _ => true,
+    }
+}
+
+fn configure_pondering() -> bool {
+    match get_user_input("Let the AI think on your time? (y/n): ") {
+        Some(input) if input.eq_ignore_ascii_case("y") => {
+            println!("The AI will keep thinking while you choose your moves");
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Asks which AI plays `side`: the minimax search or Monte Carlo tree
+/// search.
+fn choose_engine(side: &str) -> EngineChoice {
+    loop {
+        print!("Engine for the {side}: 1. Minimax  2. Monte Carlo (press Enter for minimax): ");
+        io::stdout().flush().unwrap();
+
+        let input = read_line().unwrap_or_default();
+        match input.as_str() {
+            "" | "1" => return EngineChoice::Minimax,
+            "2" => return EngineChoice::MonteCarlo,
+            _ => println!("Please enter 1 or 2"),
+        }
+    }
+}
+
+fn create_engine(choice: EngineChoice, seed: Option<u64>) -> Box<dyn Engine + Send> {
+    match choice {
+        EngineChoice::Minimax => Box::new(MinimaxEngine::new()),
+        EngineChoice::MonteCarlo => Box::new(Mcts {
+            seed,
+            ..Mcts::default()
+        }),
+    }
+}
+
+/// Bagh-Chal, the game of tigers and goats, in the terminal.
+///
+/// Anything not given on the command line is asked for when the game
+/// starts.
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<CliCommand>,
+
+    /// Game mode: 1 human vs human, 2 human tigers vs AI, 3 human goats
+    /// vs AI, 4 AI vs AI
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=4), conflicts_with_all = ["tigers", "goats"])]
+    mode: Option<u8>,
+
+    /// Who plays the tigers
+    #[arg(long, value_enum, requires = "goats")]
+    tigers: Option<PlayerArg>,
+
+    /// Who plays the goats
+    #[arg(long, value_enum, requires = "tigers")]
+    goats: Option<PlayerArg>,
+
+    /// Seconds the AI may think about each move
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    time_limit: Option<u64>,
+
+    /// Plies the AI searches ahead, and the most `analyze` searches
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..), conflicts_with = "time_limit")]
+    depth: Option<u32>,
+
+    /// Seconds a hint may think, rather than the AI opponent's limits
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    hint_time: Option<u64>,
+
+    /// Minutes on each side's clock
+    #[arg(long, value_name = "MINUTES", value_parser = clap::value_parser!(u64).range(1..))]
+    clock: Option<u64>,
+
+    /// Seconds added to a side's clock for each move it makes
+    #[arg(long, value_name = "SECS", requires = "clock")]
+    increment: Option<u64>,
+
+    /// Keep the clock running while a hint is worked out or a draw offer
+    /// is answered
+    #[arg(long)]
+    keep_clock_running: bool,
+
+    /// How far ahead, in evaluation points, the AI may think it is and
+    /// still accept a draw
+    #[arg(long, value_name = "POINTS", default_value_t = 50)]
+    draw_window: i32,
+
+    /// Give hints without the score and the line the AI expects
+    #[arg(long)]
+    plain_hints: bool,
+
+    /// Seed for the AI's choice between equally good moves
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Print without colors, drawing the board in plain text; setting
+    /// NO_COLOR does the same
+    #[arg(long)]
+    no_color: bool,
+
+    /// Draw the board's lines and the status panels with plain ASCII
+    /// characters, for terminals without box-drawing ones
+    #[arg(long)]
+    ascii: bool,
+
+    /// What to draw the pieces as
+    #[arg(long, value_enum, default_value_t = PiecesArg::Letters)]
+    pieces: PiecesArg,
+
+    /// Don't mark the goats a tiger could capture next
+    #[arg(long)]
+    hide_threats: bool,
+
+    /// Start with the board turned round, the last row at the top
+    #[arg(long)]
+    flip: bool,
+
+    /// In human vs human games, turn the board round for each player's
+    /// turn, so it reads the right way up to whoever is to move
+    #[arg(long)]
+    auto_flip: bool,
+
+    /// Print the coordinates on all four sides of the board
+    #[arg(long)]
+    coordinates: bool,
+
+    /// Describe the position in words in place of drawing the board, for
+    /// screen readers
+    #[arg(long)]
+    screen_reader: bool,
+
+    /// The colours to draw with, in place of the theme in theme.txt in
+    /// the data directory
+    #[arg(long, value_enum, conflicts_with = "theme_file")]
+    theme: Option<ThemeArg>,
+
+    /// Draw with the colours in this theme file
+    #[arg(long, value_name = "FILE")]
+    theme_file: Option<PathBuf>,
+
+    /// Start from this position on the standard board
+    #[arg(long, value_name = "FEN", conflicts_with = "load")]
+    fen: Option<String>,
+
+    /// Start from a saved game
+    #[arg(long, value_name = "FILE")]
+    load: Option<PathBuf>,
+
+    /// Play the moves in this file before the game starts
+    #[arg(long, value_name = "FILE")]
+    moves: Option<PathBuf>,
+
+    /// Keep the record of each game played in this directory, in place of
+    /// the games directory in the data directory
+    #[arg(long, value_name = "DIR", conflicts_with = "no_game_log")]
+    games_dir: Option<PathBuf>,
+
+    /// Don't keep a record of the games played
+    #[arg(long)]
+    no_game_log: bool,
+
+    /// Review every game once it ends, without being asked
+    #[arg(long)]
+    review: bool,
+
+    /// How deep the review of a game searches each position
+    #[arg(long, value_name = "PLIES", default_value_t = 4, value_parser = clap::value_parser!(u32).range(1..))]
+    review_depth: u32,
+
+    /// Points a move may lose before the review calls it an inaccuracy, a
+    /// mistake and a blunder
+    #[arg(long, value_name = "I,M,B", default_value = "50,150,300", value_parser = parse_thresholds)]
+    review_thresholds: Thresholds,
+}
+
+#[derive(Debug, Subcommand)]
+enum CliCommand {
+    /// Step through a saved game move by move
+    Replay { file: PathBuf },
+    /// Play AI vs AI games without drawing them, and sum up the results
+    Match(MatchArgs),
+}
+
+/// How `baghchal match` plays its games.
+#[derive(Debug, clap::Args)]
+struct MatchArgs {
+    /// Games to play
+    #[arg(long, default_value_t = 10)]
+    games: usize,
+
+    /// The tigers' engine
+    #[arg(long, value_enum, default_value_t = EngineArg::Minimax)]
+    tiger_engine: EngineArg,
+
+    /// The goats' engine
+    #[arg(long, value_enum, default_value_t = EngineArg::Minimax)]
+    goat_engine: EngineArg,
+
+    /// The tigers' difficulty level
+    #[arg(long, value_enum)]
+    tiger_level: Option<LevelArg>,
+
+    /// The goats' difficulty level
+    #[arg(long, value_enum)]
+    goat_level: Option<LevelArg>,
+
+    /// Time the tigers may think about each move, e.g. 100ms or 2s; 100ms
+    /// if no level or depth is given either
+    #[arg(long, value_name = "TIME", value_parser = parse_duration)]
+    tiger_time: Option<Duration>,
+
+    /// Time the goats may think about each move, e.g. 100ms or 2s; 100ms
+    /// if no level or depth is given either
+    #[arg(long, value_name = "TIME", value_parser = parse_duration)]
+    goat_time: Option<Duration>,
+
+    /// Plies the tigers search ahead, with no time limit unless
+    /// --tiger-time is given too
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    tiger_depth: Option<u32>,
+
+    /// Plies the goats search ahead, with no time limit unless
+    /// --goat-time is given too
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    goat_depth: Option<u32>,
+
+    /// Seed of the first game, each game after it adding one, so that a
+    /// match can be played again
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Plies after which a game still going is adjudicated
+    #[arg(long, default_value_t = 300)]
+    max_plies: usize,
+
+    /// Write the results to this file as JSON
+    #[arg(long, value_name = "FILE")]
+    out: Option<PathBuf>,
+
+    /// Save every game to this directory
+    #[arg(long, value_name = "DIR")]
+    records: Option<PathBuf>,
+}
+
+/// How long each side thinks per move in `baghchal match` when not told.
+const DEFAULT_MATCH_TIME: Duration = Duration::from_millis(100);
+
+/// Reads a time such as `100ms`, `2s` or `1.5`, in seconds without a unit.
+fn parse_duration(text: &str) -> Result<Duration, String> {
+    let (number, scale) = match text.strip_suffix("ms") {
+        Some(number) => (number, 0.001),
+        None => (text.strip_suffix('s').unwrap_or(text), 1.0),
+    };
+    match number.trim().parse::<f64>() {
+        Ok(value) if value > 0.0 && value.is_finite() => Ok(Duration::from_secs_f64(value * scale)),
+        _ => Err(format!("expected a time such as 100ms or 2s, not '{text}'")),
+    }
+}
+
+/// Reads the thresholds of `--review-thresholds`, three numbers of points
+/// from smallest to largest, e.g. `50,150,300`.
+fn parse_thresholds(text: &str) -> Result<Thresholds, String> {
+    let points: Vec<i32> = text
+        .split(',')
+        .map(|points| points.trim().parse())
+        .collect::<Result<_, _>>()
+        .map_err(|_| {
+            format!("expected three numbers of points such as 50,150,300, not '{text}'")
+        })?;
+    match points[..] {
+        [inaccuracy, mistake, blunder]
+            if 0 < inaccuracy && inaccuracy <= mistake && mistake <= blunder =>
+        {
+            Ok(Thresholds {
+                inaccuracy,
+                mistake,
+                blunder,
+            })
+        }
+        _ => Err(format!(
+            "expected three numbers of points above 0, from smallest to largest, not '{text}'"
+        )),
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum EngineArg {
+    Minimax,
+    /// Monte Carlo tree search
+    Mcts,
+}
+
+impl From<EngineArg> for EngineChoice {
+    fn from(engine: EngineArg) -> Self {
+        match engine {
+            EngineArg::Minimax => EngineChoice::Minimax,
+            EngineArg::Mcts => EngineChoice::MonteCarlo,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum LevelArg {
+    Beginner,
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl From<LevelArg> for Difficulty {
+    fn from(level: LevelArg) -> Self {
+        match level {
+            LevelArg::Beginner => Difficulty::Beginner,
+            LevelArg::Easy => Difficulty::Easy,
+            LevelArg::Medium => Difficulty::Medium,
+            LevelArg::Hard => Difficulty::Hard,
+        }
+    }
+}
+
+/// The limits on one side's search in `baghchal match`.
+fn match_limits(level: Option<LevelArg>, time: Option<Duration>, depth: Option<u32>) -> AiConfig {
+    let mut limits = match level {
+        Some(level) => AiConfig::preset(level.into(), None),
+        None => AiConfig {
+            time_limit: Some(DEFAULT_MATCH_TIME),
+            ..AiConfig::default()
+        },
+    };
+    if let Some(depth) = depth {
+        limits.max_depth = Some(depth);
+        limits.time_limit = None;
+    }
+    if let Some(time) = time {
+        limits.time_limit = Some(time);
+    }
+    limits
+}
+
+/// Plays `baghchal match`: the games one after another with a line for
+/// each, then the summary, and the results as JSON if asked for.
+fn play_match(args: &MatchArgs) {
+    let batch = Batch {
+        games: args.games,
+        tiger_limits: match_limits(args.tiger_level, args.tiger_time, args.tiger_depth),
+        goat_limits: match_limits(args.goat_level, args.goat_time, args.goat_depth),
+        seed: args.seed,
+        max_plies: args.max_plies,
+        records: args.records.clone(),
+        ..Batch::default()
+    };
+    let report = |game: &BatchGame| {
+        println!(
+            "Game {}: {} in {} plies, {} captured",
+            game.game + 1,
+            describe_result(&game.result),
+            game.plies,
+            game.captures
+        );
+    };
+    let result = batch.run(
+        |seed| create_engine(args.tiger_engine.into(), Some(seed)),
+        |seed| create_engine(args.goat_engine.into(), Some(seed)),
+        report,
+    );
+    let result = match result {
+        Ok(result) => result,
+        Err(err) => {
+            eprintln!("Could not save the games: {err}");
+            std::process::exit(1);
+        }
+    };
+    println!("\n{result}");
+    if let Some(path) = &args.out {
+        match std::fs::write(path, result.to_json() + "\n") {
+            Ok(()) => println!("Results written to {}", path.display()),
+            Err(err) => {
+                eprintln!("Could not write {}: {err}", path.display());
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum PlayerArg {
+    Human,
+    Ai,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum PiecesArg {
+    /// T and G
+    Letters,
+    /// ▲ and ●
+    Symbols,
+    /// 🐯 and 🐐
+    Emoji,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ThemeArg {
+    /// Red tigers and yellow goats
+    Default,
+    /// Bright colours on the terminal's own
+    HighContrast,
+    /// Blue tigers and yellow goats, with no red against green
+    Colorblind,
+}
+
+impl From<ThemeArg> for Theme {
+    fn from(theme: ThemeArg) -> Self {
+        match theme {
+            ThemeArg::Default => Theme::default(),
+            ThemeArg::HighContrast => Theme::high_contrast(),
+            ThemeArg::Colorblind => Theme::colorblind(),
+        }
+    }
+}
+
+impl From<PiecesArg> for PieceStyle {
+    fn from(pieces: PiecesArg) -> Self {
+        match pieces {
+            PiecesArg::Letters => PieceStyle::Letters,
+            PiecesArg::Symbols => PieceStyle::Symbols,
+            PiecesArg::Emoji => PieceStyle::Emoji,
+        }
+    }
+}
+
+impl From<PlayerArg> for Player {
+    fn from(player: PlayerArg) -> Self {
+        match player {
+            PlayerArg::Human => Player::Human,
+            PlayerArg::Ai => Player::AI,
+        }
+    }
+}
+
+impl Cli {
+    /// The players chosen with `--mode` or `--tigers` and `--goats`.
+    fn players(&self) -> Option<(Player, Player)> {
+        match (self.mode, self.tigers, self.goats) {
+            (Some(1), _, _) => Some((Player::Human, Player::Human)),
+            (Some(2), _, _) => Some((Player::Human, Player::AI)),
+            (Some(3), _, _) => Some((Player::AI, Player::Human)),
+            (Some(4), _, _) => Some((Player::AI, Player::AI)),
+            (_, Some(tigers), Some(goats)) => Some((tigers.into(), goats.into())),
+            _ => None,
+        }
+    }
+
+    /// Whether to print in colour: not with `--no-color`, nor when the
+    /// NO_COLOR environment variable is set to anything.
+    fn color(&self) -> bool {
+        !self.no_color && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+    }
+
+    /// The colours to draw with: `--theme`, the theme file given by
+    /// `--theme-file`, or theme.txt in the data directory if there is one.
+    fn theme(&self) -> Theme {
+        if let Some(theme) = self.theme {
+            return theme.into();
+        }
+        let path = match &self.theme_file {
+            Some(path) => path.clone(),
+            None => match data_dir().map(|dir| dir.join("theme.txt")) {
+                Some(path) if path.exists() => path,
+                _ => return Theme::default(),
+            },
+        };
+        Theme::load(&path).unwrap_or_else(|err| {
+            Cli::command()
+                .error(
+                    ErrorKind::InvalidValue,
+                    format!("couldn't load theme '{}': {err}", path.display()),
+                )
+                .exit()
+        })
+    }
+
+    /// Where to keep the record of each game played: `--games-dir`, or
+    /// the games directory in the data directory, or nowhere with
+    /// `--no-game-log`.
+    fn games_dir(&self) -> Option<PathBuf> {
+        if self.no_game_log {
+            return None;
+        }
+        self.games_dir
+            .clone()
+            .or_else(|| Some(data_dir()?.join("games")))
+    }
+
+    /// How the AI is limited, if `--time-limit` or `--depth` says.
+    fn ai_limit(&self) -> Option<AiLimit> {
+        match (self.time_limit, self.depth) {
+            (Some(seconds), _) => Some(AiLimit::Time(seconds)),
+            (_, Some(depth)) => Some(AiLimit::Depth(depth)),
+            _ => None,
+        }
+    }
+
+    /// The board every game starts from when `--fen`, `--load` or
+    /// `--moves` sets it, exiting with a usage error when one can't be
+    /// read.
+    fn start(&self) -> Option<Board> {
+        let fail = |message: String| -> ! {
+            Cli::command()
+                .error(ErrorKind::InvalidValue, message)
+                .exit()
+        };
+        let mut board = match (&self.fen, &self.load) {
+            (Some(fen), _) => Board::from_fen(fen)
+                .unwrap_or_else(|err| fail(format!("invalid --fen '{fen}': {err}"))),
+            (_, Some(path)) => save::load(path)
+                .unwrap_or_else(|err| fail(format!("couldn't load '{}': {err}", path.display()))),
+            _ if self.moves.is_some() => Board::new(),
+            _ => return None,
+        };
+        if let Some(path) = &self.moves {
+            let text = std::fs::read_to_string(path)
+                .unwrap_or_else(|err| fail(format!("couldn't read '{}': {err}", path.display())));
+            // Move numbers such as "3." may come between the moves
+            for text in text.split_whitespace().filter(|word| !word.ends_with('.')) {
+                let played =
+                    notation::parse_move(&board, text).is_some_and(|mv| board.make_move(&mv));
+                if !played {
+                    fail(format!("illegal move '{text}' in '{}'", path.display()));
+                }
+            }
+            board.drain_events();
+        }
+        Some(board)
+    }
+}
+
+/// Lets the AI pick at random between moves it scores the same, so that
+/// games vary, from `seed` if given.
+fn configure_tie_breaking(board: &mut Board, seed: Option<u64>) {
+    board.set_ai_config(AiConfig {
+        tie_margin: 1,
+        seed,
+        ..*board.ai_config()
+    });
+}
+
+/// Puts the game on a clock as `--clock` and `--increment` say, or as
+/// the player picks. A saved game keeps the clock it has.
+fn configure_clock(board: &mut Board, cli: &Cli) {
+    if board.clock().is_some() {
+        return;
+    }
+    let minutes = match cli.clock {
+        Some(minutes) => minutes,
+        None => loop {
+            print!("Minutes on each side's clock (press Enter for no clock): ");
+            io::stdout().flush().unwrap();
+
+            let input = read_line().unwrap_or_default();
+            if input.is_empty() {
+                return;
+            }
+            match input.parse::<u64>() {
+                Ok(minutes) if minutes > 0 => break minutes,
+                _ => println!("Please enter a whole number of minutes"),
+            }
+        },
+    };
+    let increment = match (cli.increment, cli.clock) {
+        (Some(seconds), _) => seconds,
+        (None, Some(_)) => 0,
+        (None, None) => loop {
+            print!("Seconds added after each move (press Enter for none): ");
+            io::stdout().flush().unwrap();
+
+            let input = read_line().unwrap_or_default();
+            if input.is_empty() {
+                break 0;
+            }
+            match input.parse::<u64>() {
+                Ok(seconds) => break seconds,
+                _ => println!("Please enter a whole number of seconds"),
+            }
+        },
+    };
+    board.set_clock(
+        Clock::new(Duration::from_secs(minutes * 60))
+            .with_increment(Duration::from_secs(increment)),
+    );
+    match increment {
+        0 => println!("Each side has {minutes} minutes"),
+        _ => println!("Each side has {minutes} minutes, plus {increment} seconds a move"),
+    }
+}
+
+fn format_clock(time: Duration) -> String {
+    let seconds = time.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+fn get_board_variant() -> Option<RuleSet> {
+    loop {
+        println!("\nSelect board:");
+        println!("1. Bagh-Chal (5×5 grid, 4 tigers, 20 goats)");
+        println!("2. Aadu Puli Attam (23-point triangle, 3 tigers, 15 goats)");
+
+        let input = get_user_input("Enter board (1-2): ")?;
+        match input.as_str() {
+            "1" => return Some(RuleSet::standard()),
+            "2" => return Some(RuleSet::aadu_puli_attam()),
+            _ => println!("Invalid choice. Please enter 1 or 2."),
+        }
+    }
+}
+
+fn configure_multi_capture(rules: RuleSet) -> RuleSet {
+    loop {
+        print!("Chain tiger captures? (n = no, o = optional, m = mandatory, press Enter for no): ");
+        io::stdout().flush().unwrap();
+
+        let input = read_line().unwrap_or_default();
+        let multi_capture = match input.to_ascii_lowercase().as_str() {
+            "" | "n" | "no" => MultiCapture::Off,
+            "o" | "optional" => MultiCapture::Optional,
+            "m" | "mandatory" => MultiCapture::Mandatory,
+            _ => {
+                println!("Please enter n, o or m");
+                continue;
+            }
+        };
+        return RuleSet {
+            multi_capture,
+            ..rules
+        };
+    }
+}
+
+fn configure_forced_capture(rules: RuleSet) -> RuleSet {
+    loop {
+        print!("Must a tiger capture when it can? (y/n, press Enter for no): ");
+        io::stdout().flush().unwrap();
+
+        let input = read_line().unwrap_or_default();
+        let forced_capture = match input.to_ascii_lowercase().as_str() {
+            "" | "n" | "no" => false,
+            "y" | "yes" => true,
+            _ => {
+                println!("Please enter y or n");
+                continue;
+            }
+        };
+        return RuleSet {
+            forced_capture,
+            ..rules
+        };
+    }
+}
+
+fn configure_swap(rules: RuleSet) -> RuleSet {
+    loop {
+        print!("Let the tigers swap sides after how many plies? (press Enter for no swap): ");
+        io::stdout().flush().unwrap();
+
+        let input = read_line().unwrap_or_default();
+        let input = input.as_str();
+        if input.is_empty() {
+            return rules;
+        }
+        match input.parse::<usize>() {
+            Ok(plies) => {
+                println!("The tigers may swap sides after {plies} plies");
+                return RuleSet {
+                    swap_after: Some(plies),
+                    ..rules
+                };
+            }
+            Err(_) => println!("Please enter a number of plies"),
+        }
+    }
+}
+
+fn configure_rules(variant: RuleSet) -> RuleSet {
+    let mut rules = variant;
+    loop {
+        print!(
+            "Goats to start with (handicap, 1-{}, press Enter for {}): ",
+            rules.starting_goats, rules.starting_goats
+        );
+        io::stdout().flush().unwrap();
+
+        let input = read_line().unwrap_or_default();
+        let input = input.as_str();
+        if input.is_empty() {
+            return rules;
+        }
+
+        match input.parse::<u32>() {
+            Ok(goats) => {
+                let candidate = RuleSet {
+                    starting_goats: goats,
+                    // Keep handicap games winnable for the tigers
+                    capture_threshold: rules.capture_threshold.min(goats),
+                    ..rules.clone()
+                };
+                match candidate.validate() {
+                    Ok(()) => {
+                        rules = candidate;
+                        println!("Goats start with {} pieces", rules.starting_goats);
+                        return rules;
+                    }
+                    Err(err) => println!("Invalid handicap: {err}"),
+                }
+            }
+            Err(_) => println!(
+                "Please enter a number between 1 and {}",
+                rules.starting_goats
+            ),
+        }
+    }
+}
+
+fn get_game_mode() -> Option<(Player, Player)> {
+    loop {
+        println!("\nSelect game mode:");
+        println!("1. Human vs Human");
+        println!("2. Human vs AI (Human plays Tigers)");
+        println!("3. Human vs AI (Human plays Goats)");
+        println!("4. AI vs AI");
+
+        let input = get_user_input("Enter mode (1-4): ")?;
+        match input.as_str() {
+            "1" => return Some((Player::Human, Player::Human)),
+            "2" => return Some((Player::Human, Player::AI)),
+            "3" => return Some((Player::AI, Player::Human)),
+            "4" => return Some((Player::AI, Player::AI)),
+            _ => println!("Invalid choice. Please enter 1, 2, 3, or 4."),
+        }
+    }
+}
+
+fn get_game_mode_string(tiger_player: Player, goat_player: Player) -> String {
+    match (tiger_player, goat_player) {
+        (Player::Human, Player::Human) => "Human vs Human".to_string(),
+        (Player::Human, Player::AI) => "Human (Tigers) vs AI (Goats)".to_string(),
+        (Player::AI, Player::Human) => "AI (Tigers) vs Human (Goats)".to_string(),
+        (Player::AI, Player::AI) => "AI vs AI".to_string(),
+    }
+}
+
+/// A status panel, `width` columns inside its border, drawn with
+/// box-drawing characters or with `--ascii` in plain ones.
+struct Panel {
+    width: usize,
+    ascii: bool,
+}
+
+impl Panel {
+    fn new(width: usize) -> Self {
+        Panel {
+            width,
+            ascii: display_options().ascii_lines,
+        }
+    }
+
+    fn top(&self) {
+        self.border('╔', '═', '╗');
+    }
+
+    fn rule(&self) {
+        self.border('╟', '─', '╢');
+    }
+
+    fn bottom(&self) {
+        self.border('╚', '═', '╝');
+    }
+
+    fn border(&self, left: char, fill: char, right: char) {
+        let (left, fill, right) = match self.ascii {
+            true => ('+', '-', '+'),
+            false => (left, fill, right),
+        };
+        let line = format!("{left}{}{right}", fill.to_string().repeat(self.width + 2));
+        println!("{}", line.color(display_options().theme.borders));
+    }
+
+    /// A line of text, padded out to the border.
+    fn row(&self, text: &str) {
+        let side = if self.ascii { "|" } else { "║" };
+        let side = side.color(display_options().theme.borders);
+        let padding = self.width.saturating_sub(visible_width(text));
+        println!("{side} {text}{} {side}", " ".repeat(padding));
+    }
+
+    /// A line of text in the middle of the panel, between two `emoji`
+    /// unless printing without colour.
+    fn title(&self, emoji: &str, text: &str) {
+        let text = match display_options().color {
+            true => format!("{emoji} {text} {emoji}"),
+            false => text.to_string(),
+        };
+        let indent = self.width.saturating_sub(visible_width(&text)) / 2;
+        self.row(&format!("{}{text}", " ".repeat(indent)));
+    }
+}
+
+/// How many columns `text` takes up on a terminal: colour codes take
+/// none, and emoji two.
+fn visible_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => {
+                // Skip to the end of the colour code
+                for c in chars.by_ref() {
+                    if c == 'm' {
+                        break;
+                    }
+                }
+            }
+            '\u{1F000}'.. | '⭐' => width += 2,
+            _ => width += 1,
+        }
+    }
+    width
+}
+
+fn print_game_status(board: &Board, game_mode: &str) {
+    let panel = Panel::new(41);
+    println!();
+    panel.top();
+    panel.row(game_mode);
+    panel.rule();
+
+    let options = display_options();
+    let turn_text = match board.side_to_move() {
+        Side::Tigers => "Tigers".color(options.theme.tiger).bold(),
+        Side::Goats => "Goats".color(options.theme.goat).bold(),
+    };
+    let piece = match board.side_to_move() {
+        Side::Tigers => Piece::Tiger,
+        Side::Goats => Piece::Goat,
+    };
+    let turn_text = match options.pieces {
+        PieceStyle::Symbols | PieceStyle::Emoji if options.color => {
+            format!("{} {turn_text}", options.piece_symbol(piece))
+        }
+        _ => turn_text.to_string(),
+    };
+    panel.row(&format!("Current Turn: {turn_text}"));
+    panel.row(&format!("Move: {}", board.fullmove_number()));
+    panel.row(&format!("Goats in hand: {}", board.goats_in_hand));
+    panel.row(&format!(
+        "Captured goats: {} of {}",
+        board.captured_goats,
+        board.rules().capture_threshold
+    ));
+    if !board.captures().is_empty() && options.color {
+        // One goat per capture, as an emoji unless they're drawn as symbols
+        let goat = match options.pieces {
+            PieceStyle::Symbols => options.piece_symbol(Piece::Goat),
+            PieceStyle::Letters | PieceStyle::Emoji => "🐐",
+        };
+        panel.row(&goat.repeat(board.captures().len()));
+    }
+    if let (Some(goats), Some(tigers)) = (
+        board.remaining_time(Side::Goats),
+        board.remaining_time(Side::Tigers),
+    ) {
+        let separator = if options.color { "·" } else { "/" };
+        panel.row(&format!(
+            "Clock: Goats {} {separator} Tigers {}",
+            format_clock(goats),
+            format_clock(tigers)
+        ));
+    }
+    panel.bottom();
+    println!();
+}
+
+/// Shows each depth the AI completes while it thinks.
+fn show_search_progress(board: &mut Board) {
+    let topology = board.topology().clone();
+    board.set_search_progress(move |info| {
+        let pv: Vec<String> = info
+            .pv
+            .iter()
+            .map(|mv| format_move(&topology, mv))
+            .collect();
+        println!(
+            "  depth {:>2}  score {:+6}  nodes {:>9}  pv {}",
+            info.depth,
+            info.score,
+            info.nodes,
+            pv.join(" ")
+        );
+    });
+}
+
+/// Reads a saved game to carry on with the AI settings of `board`, the
+/// game being played now.
+fn load_game(board: &Board, path: &str) -> Result<Board, String> {
+    let mut loaded = save::load(path).map_err(|err| err.to_string())?;
+    loaded.set_ai_config(*board.ai_config());
+    loaded.set_use_opening_book(board.uses_opening_book());
+    show_search_progress(&mut loaded);
+    Ok(loaded)
+}
+
+/// Steps through the game saved in `path` one move at a time, from the
+/// position it started from.
+fn replay(path: &Path) {
+    let mut board = match save::load(path) {
+        Ok(board) => board,
+        Err(err) => {
+            eprintln!("Couldn't load {}: {err}", path.display());
+            std::process::exit(1);
+        }
+    };
+    let first = board.start_ply();
+    let last = board.ply();
+    board.goto_ply(first);
+    println!(
+        "\nReplaying {}: 'n' next move, 'p' previous, 'g <ply>' go to a ply, 'q' quit",
+        path.display()
+    );
+    loop {
+        println!("\nPly {} of {}", board.ply() - first, last - first);
+        if let Some(mv) = board.last_move() {
+            let side = match board.side_to_move() {
+                Side::Goats => "Tigers",
+                Side::Tigers => "Goats",
+            };
+            println!("Last move: {} ({side})", format_move(board.topology(), mv));
+        }
+        println!("{}", render_board(&board));
+        if board.ply() == last {
+            match board.result() {
+                Some(result) => println!("Result: {:?} ({})", result.winner, result.reason),
+                None => println!("End of the saved game, which was unfinished"),
+            }
+        }
+
+        loop {
+            let Some(input) = get_user_input("Replay (n/p/g <ply>/e/q): ") else {
+                return;
+            };
+            let target = match input.split_once(' ') {
+                None if input.eq_ignore_ascii_case("n") => board.ply() + 1,
+                None if input.eq_ignore_ascii_case("p") => match board.ply() {
+                    ply if ply == first => {
+                        println!("Already at the start");
+                        continue;
+                    }
+                    ply => ply - 1,
+                },
+                None if input.eq_ignore_ascii_case("e") => {
+                    print_eval(&board);
+                    continue;
+                }
+                Some((command, ply)) if command.eq_ignore_ascii_case("g") => {
+                    match ply.trim().parse::<usize>() {
+                        Ok(ply) if ply <= last - first => first + ply,
+                        _ => {
+                            println!("Enter a ply from 0 to {}", last - first);
+                            continue;
+                        }
+                    }
+                }
+                _ => {
+                    println!("Use 'n', 'p', 'g <ply>', 'e' or 'q'");
+                    continue;
+                }
+            };
+            if target > last {
+                println!("That was the last move");
+                continue;
+            }
+            board.goto_ply(target);
+            board.drain_events();
+            break;
+        }
+    }
+}
+
+/// How long `analyze` thinks when not told, unless `--hint-time` is given.
+const ANALYSIS_TIME: u64 = 5;
 
-        let input = input.trim();
-        if input.is_empty() {
-            println!("Please enter a command");
-            continue;
+/// How boards are drawn, set once from the command line.
+static DISPLAY: OnceLock<DisplayOptions> = OnceLock::new();
+
+/// How the command line asked for boards to be drawn.
+fn display_options() -> &'static DisplayOptions {
+    DISPLAY.get_or_init(DisplayOptions::default)
+}
+
+/// Set by the `flip` command, turning the board round from the way the
+/// command line asked for.
+static FLIPPED: AtomicBool = AtomicBool::new(false);
+
+/// Set in human vs human games played with `--auto-flip`, where the board
+/// turns round while the tigers are to move.
+static AUTO_FLIP: AtomicBool = AtomicBool::new(false);
+
+/// Set with `--screen-reader`, where the board is described in words
+/// rather than drawn.
+static SCREEN_READER: AtomicBool = AtomicBool::new(false);
+
+/// `board` as the command line asked for it to be drawn.
+fn render_board(board: &Board) -> String {
+    if SCREEN_READER.load(Ordering::SeqCst) {
+        return board.describe();
+    }
+    let mut options = *display_options();
+    let tigers_turn = board.side_to_move() == Side::Tigers;
+    options.flipped ^=
+        FLIPPED.load(Ordering::SeqCst) ^ (AUTO_FLIP.load(Ordering::SeqCst) && tigers_turn);
+    board.display_with(&options)
+}
+
+/// Set while a game is being played rather than set up.
+static IN_GAME: AtomicBool = AtomicBool::new(false);
+
+/// Set while the game waits for a human's move, when Ctrl+C asks whether
+/// to save before quitting.
+static AWAITING_MOVE: AtomicBool = AtomicBool::new(false);
+
+/// How often an AI vs AI game is autosaved at most, as its moves come
+/// too fast to write each one.
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The latest autosave not yet written to disk and where it goes, for the
+/// panic hook to write out.
+static PENDING_AUTOSAVE: Mutex<Option<(PathBuf, String)>> = Mutex::new(None);
+
+/// Where the game keeps its files: `BAGHCHAL_DATA_DIR` if set, or the
+/// platform's directory for application data.
+fn data_dir() -> Option<PathBuf> {
+    let dir = match std::env::var_os("BAGHCHAL_DATA_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None if cfg!(windows) => PathBuf::from(std::env::var_os("APPDATA")?).join("baghchal"),
+        None if cfg!(target_os = "macos") => {
+            PathBuf::from(std::env::var_os("HOME")?).join("Library/Application Support/baghchal")
         }
-        if input.eq_ignore_ascii_case("q") || input.eq_ignore_ascii_case("quit") {
-            return None;
+        None => match std::env::var_os("XDG_DATA_HOME") {
+            Some(dir) => PathBuf::from(dir).join("baghchal"),
+            None => PathBuf::from(std::env::var_os("HOME")?).join(".local/share/baghchal"),
+        },
+    };
+    Some(dir)
+}
+
+/// Where the game in progress is kept, so it can be resumed after the
+/// terminal is closed.
+fn autosave_path() -> Option<PathBuf> {
+    Some(data_dir()?.join("autosave.txt"))
+}
+
+/// Keeps the game in progress on disk, in the format of a manual save
+/// with the game's settings added.
+struct Autosave {
+    path: Option<PathBuf>,
+    /// The game as last noted, so an unchanged game isn't written again.
+    noted: Option<String>,
+    /// When the autosave was last written, to throttle AI vs AI games.
+    written: Option<Instant>,
+}
+
+impl Autosave {
+    fn new() -> Self {
+        Autosave {
+            path: autosave_path(),
+            noted: None,
+            written: None,
         }
-        return Some(input.to_string());
     }
-}
 
-fn parse_position(input: &str) -> Option<usize> {
-    // Only accept coordinate format (A1-E5)
-    if input.len() == 2 {
-        let chars: Vec<char> = input.chars().collect();
-        let col = chars[0].to_ascii_uppercase();
-        let row = chars[1].to_digit(10);
+    /// Notes the game as it stands, writing it out unless `throttle` is
+    /// set and it was written a moment ago.
+    fn update(&mut self, board: &Board, settings: Settings, throttle: bool) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let text = save::to_string(board) + &settings.to_lines();
+        if self.noted.as_ref() == Some(&text) {
+            return;
+        }
+        self.noted = Some(text.clone());
+        *PENDING_AUTOSAVE.lock().unwrap() = Some((path.clone(), text));
+        if !throttle
+            || self
+                .written
+                .is_none_or(|time| time.elapsed() >= AUTOSAVE_INTERVAL)
+        {
+            self.flush();
+        }
+    }
 
-        if let Some(row_num) = row {
-            if row_num >= 1 && row_num <= 5 {
-                let col_num = match col {
-                    'A' => 0,
-                    'B' => 1,
-                    'C' => 2,
-                    'D' => 3,
-                    'E' => 4,
-                    _ => return None,
-                };
-                return Some((row_num as usize - 1) * 5 + col_num);
+    fn saved_text(&self) -> Option<String> {
+        std::fs::read_to_string(self.path.as_ref()?).ok()
+    }
+
+    /// Writes out the game if the last update was held back.
+    fn flush(&mut self) {
+        if write_pending_autosave() {
+            self.written = Some(Instant::now());
+        }
+    }
+
+    /// Forgets the game, once it's over.
+    fn remove(&mut self) {
+        PENDING_AUTOSAVE.lock().unwrap().take();
+        if let Some(path) = &self.path {
+            let _ = std::fs::remove_file(path);
+        }
+        self.noted = None;
+        self.written = None;
+    }
+
+    /// The game left by a run that didn't finish it, with its settings,
+    /// if the player wants to carry on with it.
+    fn offer_resume(&mut self) -> Option<(Board, Settings)> {
+        let text = self.saved_text()?;
+        let input = get_user_input("Resume last game? (y/n): ")?;
+        if !input.eq_ignore_ascii_case("y") {
+            self.remove();
+            return None;
+        }
+        let game = save::parse(&text)
+            .map_err(|err| err.to_string())
+            .and_then(|board| {
+                Settings::parse(&text)
+                    .map(|settings| (board, settings))
+                    .ok_or_else(|| "the game's settings are missing".to_string())
+            });
+        match game {
+            Ok(game) => Some(game),
+            Err(err) => {
+                println!("Could not resume the last game: {err}");
+                self.remove();
+                None
             }
         }
     }
+}
 
-    None
+/// Who won a game and why, e.g. `goats won (all tigers are trapped)`, as
+/// the match report and the game log write it.
+fn describe_result(result: &GameResult) -> String {
+    let winner = match result.winner {
+        Winner::Tigers => "tigers won",
+        Winner::Goats => "goats won",
+        Winner::Draw => "drawn",
+        Winner::None => "ended",
+    };
+    format!("{winner} ({})", result.reason)
 }
 
-fn parse_move(input: &str) -> Option<(usize, usize)> {
-    let parts: Vec<&str> = input.split_whitespace().collect();
-    if parts.len() == 2 {
-        if let (Some(from), Some(to)) = (parse_position(parts[0]), parse_position(parts[1])) {
-            return Some((from, to));
-        }
+/// Writes the record of a game that has ended, or was abandoned, to a
+/// file of its own in `dir`, returning its path, or `None` if no move was
+/// played. The record is a save, so the game can be loaded or replayed,
+/// with the settings, the mode and the result added; a game that didn't
+/// end has `result: unfinished`.
+fn log_game(
+    dir: &Path,
+    board: &Board,
+    settings: Settings,
+    game_mode: &str,
+) -> Option<io::Result<PathBuf>> {
+    if board.record().moves.is_empty() {
+        return None;
     }
-    None
+    let result = match board.result() {
+        Some(result) => describe_result(&result),
+        None => "unfinished".to_string(),
+    };
+    let text = format!(
+        "{}{}mode: {game_mode}\nresult: {result}\n",
+        save::to_string(board),
+        settings.to_lines()
+    );
+    Some(write_game_log(dir, &text))
 }
 
-fn get_position(prompt: &str) -> Option<usize> {
+/// Writes `text` to a new file in `dir` named after the time, adding `-2`,
+/// `-3` and so on to the name if a game ended in the same second.
+fn write_game_log(dir: &Path, text: &str) -> io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let stamp = timestamp(SystemTime::now());
+    let mut copy = 1;
     loop {
-        if let Some(input) = get_user_input(prompt) {
-            match parse_position(&input) {
-                Some(pos) => return Some(pos),
-                None => println!("Please enter a valid position (A1-E5)"),
+        let path = match copy {
+            1 => dir.join(format!("{stamp}.txt")),
+            copy => dir.join(format!("{stamp}-{copy}.txt")),
+        };
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                file.write_all(text.as_bytes())?;
+                return Ok(path);
             }
-        } else {
-            return None;
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => copy += 1,
+            Err(err) => return Err(err),
         }
     }
 }
 
-fn print_instructions() {
-    println!("\n=== BAGHCHAL ===");
-    println!("A traditional board game from Nepal");
-    println!("\nPositions are specified using grid coordinates (A1-E5)");
-    println!("T = Tiger, G = Goat, · = Empty");
-    println!("Commands:");
-    println!("  - To move a piece:");
-    println!("    • Enter both positions at once (e.g., 'A1 A2')");
-    println!("    • Or enter one position to see valid moves, then enter destination");
-    println!("  - Enter a single position (e.g., 'A1') to place a goat");
-    println!("  - Type 'h' or 'hint' to get a suggested move");
-    println!("  - Type 'u' or 'undo' to take back the last move");
-    println!("  - Type 'q' or 'quit' to exit the game");
-    println!("  - Press Ctrl+C during AI's turn to interrupt");
-    println!("===============\n");
+/// `time` in UTC as `YYYY-MM-DD_HH-MM-SS`, which sorts by date and is a
+/// valid file name everywhere.
+fn timestamp(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+    let (days, seconds) = (seconds / 86_400, seconds % 86_400);
+    // The date from the days since 1970, counting in 400-year eras that
+    // start on 1 March 0000 so leap days fall at the end of a year
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = era * 400 + year_of_era + u64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}_{:02}-{:02}-{:02}",
+        seconds / 3_600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
 }
 
-fn configure_ai_time_limit(board: &mut Board) {
-    loop {
-        if let Some(input) = get_user_input("Enter AI thinking time in seconds (1-10): ") {
-            if let Ok(seconds) = input.parse::<u64>() {
-                if seconds >= 1 && seconds <= 10 {
-                    board.set_ai_time_limit(seconds);
-                    println!("AI thinking time set to {} seconds", seconds);
-                    break;
+/// Says where a game was logged to, or why it couldn't be.
+fn print_game_logged(logged: Option<io::Result<PathBuf>>) {
+    match logged {
+        Some(Ok(path)) => println!("📜 Game logged to {}", path.display()),
+        Some(Err(err)) => println!("Could not log the game: {err}"),
+        None => {}
+    }
+}
+
+/// Writes the pending autosave to disk, returning whether there was one
+/// and it was written.
+fn write_pending_autosave() -> bool {
+    // A panic while the lock was held still leaves a save worth writing
+    let mut pending = PENDING_AUTOSAVE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let Some((path, text)) = pending.take() else {
+        return false;
+    };
+    let written = path
+        .parent()
+        .is_none_or(|dir| std::fs::create_dir_all(dir).is_ok())
+        && std::fs::write(&path, text).is_ok();
+    if !written {
+        eprintln!("Could not autosave the game to {}", path.display());
+    }
+    written
+}
+
+fn get_coordinate_string(topology: &Topology, pos: usize) -> String {
+    topology.point_name(pos)
+}
+
+/// Explains why a tiger move was rejected, pointing out a capture to play
+/// when one is forced.
+fn print_tiger_move_error(board: &Board, err: MoveError) {
+    match err {
+        MoveError::Illegal => println!("Invalid tiger move! Try again."),
+        MoveError::CaptureRequired => {
+            let example = board.legal_move_list().iter().find_map(|mv| match mv {
+                Move::MoveTiger {
+                    from,
+                    to,
+                    captured_position: Some(over),
+                } => Some((*from, *over, *to)),
+                Move::MultiJump(jumps) => jumps.first().map(|jump| (jump.from, jump.over, jump.to)),
+                _ => None,
+            });
+            match example {
+                Some((from, over, to)) => {
+                    let topology = board.topology();
+                    println!(
+                        "A capture is available and must be played, e.g. {}x{}-{}",
+                        get_coordinate_string(topology, from),
+                        get_coordinate_string(topology, over),
+                        get_coordinate_string(topology, to)
+                    );
                 }
+                None => println!("A capture is available and must be played!"),
             }
-            println!("Please enter a number between 1 and 10");
         }
     }
 }
 
-fn get_game_mode() -> (Player, Player) {
-    loop {
-        println!("\nSelect game mode:");
-        println!("1. Human vs Human");
-        println!("2. Human vs AI (Human plays Tigers)");
-        println!("3. Human vs AI (Human plays Goats)");
-        println!("4. AI vs AI");
+/// Puts `score`, from the tigers' side, into words for `side`, e.g.
+/// `+120, tigers slightly better`.
+fn describe_score(score: i32, side: Side) -> String {
+    let own = match side {
+        Side::Tigers => score,
+        Side::Goats => -score,
+    };
+    let leader = if score > 0 { "tigers" } else { "goats" };
+    // Won positions score 10000, less a point per ply to the win
+    let verdict = match score.unsigned_abs() {
+        9000.. => format!("{leader} win in {} plies", 10000 - score.unsigned_abs()),
+        0..=29 => "about even".to_string(),
+        30..=149 => format!("{leader} slightly better"),
+        150..=399 => format!("{leader} better"),
+        _ => format!("{leader} winning"),
+    };
+    format!("{own:+}, {verdict}")
+}
 
-        if let Some(input) = get_user_input("Enter mode (1-4): ") {
-            let players = match input.as_str() {
-                "1" => Some((Player::Human, Player::Human)),
-                "2" => Some((Player::Human, Player::AI)),
-                "3" => Some((Player::AI, Player::Human)),
-                "4" => Some((Player::AI, Player::AI)),
-                _ => {
-                    println!("Invalid choice. Please enter 1, 2, 3, or 4.");
-                    None
-                }
-            };
+/// Shows how the AI's evaluation judges the position, term by term, or
+/// the result once the game is over.
+fn print_eval(board: &Board) {
+    match board.result() {
+        Some(result) => println!(
+            "\n📊 The game is over: {} ({})",
+            match result.winner {
+                Winner::Tigers => "the tigers won",
+                Winner::Goats => "the goats won",
+                _ => "a draw",
+            },
+            result.reason
+        ),
+        None => {
+            println!("\n📊 Evaluation, with points from the tigers' side:");
+            println!("{}", board.evaluate_detailed().report(board.side_to_move()));
+        }
+    }
+}
+
+/// Suggests a move for the side to move with what it does and, unless
+/// `--plain-hints` was given, the score and the line the AI expects.
+fn print_hint(board: &Board, cli: &Cli) {
+    let mut config = AiConfig {
+        ponder: false,
+        ..*board.ai_config()
+    };
+    if let Some(seconds) = cli.hint_time {
+        config.time_limit = Some(Duration::from_secs(seconds));
+    }
+    let mut engine = MinimaxEngine::new();
+    let Some(result) = engine.best_move(board, &config) else {
+        println!("\n😕 No good moves available!");
+        return;
+    };
+    println!(
+        "\n💡 Suggested move: {}",
+        annotate_move(board, result.mv.clone()).describe(board.topology())
+    );
+    if cli.plain_hints {
+        return;
+    }
+    let side = board.side_to_move();
+    match engine.last_search() {
+        Some(search) => {
+            println!(
+                "   Score {} (depth {})",
+                describe_score(result.score, side),
+                search.depth
+            );
+            let line: Vec<String> = search
+                .pv
+                .iter()
+                .take(6)
+                .map(|mv| format_move(board.topology(), mv))
+                .collect();
+            println!("   Line: {}", line.join(" "));
+        }
+        // From the opening book, or the only move
+        None => println!("   Score {}", describe_score(result.score, side)),
+    }
+}
+
+/// Searches the position for `seconds`, or to `max_depth` plies if that
+/// comes first, or until Ctrl+C clears `running`, and lists the three
+/// best moves for the side to move with their scores and the lines the
+/// AI expects. No move is played.
+fn analyze(board: &Board, seconds: u64, max_depth: Option<u32>, running: &AtomicBool) {
+    let side = board.side_to_move();
+    let (name, own) = match side {
+        Side::Tigers => ("tigers", 1),
+        Side::Goats => ("goats", -1),
+    };
+    let config = AiConfig {
+        time_limit: Some(Duration::from_secs(seconds)),
+        max_depth,
+        max_nodes: None,
+        ponder: false,
+        ..*board.ai_config()
+    };
+    let mut position = board.clone();
+    let stop = Arc::new(AtomicBool::new(false));
+    position.set_search_stop(stop.clone());
+    let depth = Arc::new(AtomicU32::new(0));
+    let reached = depth.clone();
+    let topology = board.topology().clone();
+    position.set_search_progress(move |info| {
+        reached.store(info.depth, Ordering::SeqCst);
+        if let Some(mv) = info.pv.first() {
+            println!(
+                "  depth {:>2}  best {:<8} score {:+}",
+                info.depth,
+                format_move(&topology, mv),
+                own * info.score
+            );
+        }
+    });
 
-            if let Some(players) = players {
-                return players;
+    println!("\n🔎 Analyzing for up to {seconds}s (press Ctrl+C to stop early)...");
+    let lines = std::thread::scope(|scope| {
+        let search = scope.spawn(|| position.top_moves(3, &config));
+        while !search.is_finished() {
+            if !running.load(Ordering::SeqCst) {
+                stop.store(true, Ordering::SeqCst);
             }
+            std::thread::sleep(Duration::from_millis(20));
         }
+        search.join().expect("the analysis panicked")
+    });
+    if !running.swap(true, Ordering::SeqCst) {
+        println!("\nAnalysis stopped");
+    }
+    if lines.is_empty() {
+        println!("\n😕 No legal moves to analyze");
+        return;
+    }
+
+    let depth = depth.load(Ordering::SeqCst);
+    println!(
+        "\nBest moves for the {name}, scored from the {name}' side (positive is good for them):"
+    );
+    for (rank, (mv, score, pv)) in lines.iter().enumerate() {
+        let line: Vec<String> = pv
+            .iter()
+            .take(6)
+            .map(|mv| format_move(board.topology(), mv))
+            .collect();
+        println!(
+            "  {}. {:<8} {:>+6}  depth {:>2}  {}",
+            rank + 1,
+            format_move(board.topology(), mv),
+            own * score,
+            depth,
+            line.join(" ")
+        );
     }
 }
 
-fn get_game_mode_string(tiger_player: Player, goat_player: Player) -> String {
-    match (tiger_player, goat_player) {
-        (Player::Human, Player::Human) => "Human vs Human".to_string(),
-        (Player::Human, Player::AI) => "Human (Tigers) vs AI (Goats)".to_string(),
-        (Player::AI, Player::Human) => "AI (Tigers) vs Human (Goats)".to_string(),
-        (Player::AI, Player::AI) => "AI vs AI".to_string(),
+/// Offers a draw on behalf of the player to move and asks their opponent,
+/// human or AI, whether to take it. The AI takes it unless it thinks it
+/// is more than `window` ahead.
+fn offer_draw(board: &mut Board, settings: Settings, window: i32) {
+    let (offering, other, other_player) = match board.side_to_move() {
+        Side::Tigers => ("tigers", Side::Goats, settings.goat_player),
+        Side::Goats => ("goats", Side::Tigers, settings.tiger_player),
+    };
+    board.offer_draw();
+    let accepted = match other_player {
+        Player::Human => {
+            println!("{}", render_board(board));
+            get_user_input(&format!("The {offering} offer a draw — accept? (y/n): "))
+                .is_some_and(|answer| answer.eq_ignore_ascii_case("y"))
+        }
+        Player::AI => {
+            println!("\n🤔 The AI is considering the offer...");
+            board.ai_accepts_draw(other, window)
+        }
+    };
+    if accepted && board.accept_draw() {
+        board.drain_events();
+        println!("\n🤝 Draw agreed");
+    } else {
+        board.decline_draw();
+        println!("\nThe draw was declined; the game goes on");
     }
 }
 
-fn print_game_status(board: &Board, tigers_turn: bool, game_mode: &str) {
-    println!("\n╔═══════════════════════════════════════════╗");
-    println!("║ {:<41} ║", game_mode);
-    println!("╟───────────────────────────────────────────╢");
+/// Lists the moves played so far, for the `moves` command.
+fn print_history(board: &Board) {
+    if board.move_history().is_empty() {
+        println!("\nNo moves played yet");
+    } else {
+        println!("\n📜 Moves so far:");
+        println!("{}", notation::format_history(board, 72));
+    }
+}
 
-    // Current turn with fixed spacing
-    let turn_text = if tigers_turn {
-        "Tigers".red().bold().to_string()
+/// Runs the commands that only look at the game, which a spectator can
+/// use too: `eval`, `moves`, `describe` and `flip`. Returns whether
+/// `input` was one of them.
+fn look_at_game(board: &Board, input: &str) -> bool {
+    if input.eq_ignore_ascii_case("e") || input.eq_ignore_ascii_case("eval") {
+        print_eval(board);
+    } else if input.eq_ignore_ascii_case("moves") || input.eq_ignore_ascii_case("history") {
+        print_history(board);
+    } else if input.eq_ignore_ascii_case("describe") {
+        println!("{}", board.describe());
+    } else if input.eq_ignore_ascii_case("flip") {
+        FLIPPED.fetch_xor(true, Ordering::SeqCst);
     } else {
-        "Goats".yellow().bold().to_string()
-    };
-    println!("║ Current Turn: {:<38} ║", turn_text);
-    println!("║ Goats in hand: {:<26} ║", board.goats_in_hand);
-    println!("║ Captured goats: {:<25} ║", board.captured_goats);
-    println!("╚═══════════════════════════════════════════╝\n");
+        return false;
+    }
+    true
+}
+
+/// Holds an AI vs AI game between moves while the spectator looks at it,
+/// until Enter is pressed or input runs out. Returns false if they quit
+/// the game instead.
+fn pause_game(board: &Board) -> bool {
+    loop {
+        print!("Game paused. Enter command (eval, moves, describe, flip, or quit), or press Enter to carry on: ");
+        io::stdout().flush().unwrap();
+
+        let Some(input) = read_line().filter(|input| !input.is_empty()) else {
+            return true;
+        };
+        if input.eq_ignore_ascii_case("q") || input.eq_ignore_ascii_case("quit") {
+            return false;
+        }
+        if !look_at_game(board, &input) {
+            println!(
+                "Please enter eval, moves, describe, flip or quit, or press Enter to carry on"
+            );
+        } else if input.eq_ignore_ascii_case("flip") {
+            println!("{}", render_board(board));
+        }
+    }
 }
 
-fn get_coordinate_string(pos: usize) -> String {
-    let row = pos / 5 + 1;
-    let col = (pos % 5) as u8 + b'A';
-    format!("{}{}", col as char, row)
+fn print_events(board: &mut Board) {
+    for event in board.drain_events() {
+        match event {
+            GameEvent::Moved(Move::PlaceGoat { .. }) => {
+                println!("Goat placed! Remaining to place: {}", board.goats_in_hand)
+            }
+            GameEvent::Moved(Move::MoveGoat { .. }) => println!("Goat moved!"),
+            GameEvent::Moved(Move::MoveTiger { .. } | Move::MultiJump(_)) => {
+                println!(
+                    "Tiger moved! Captured goats: {}/{}",
+                    board.captured_goats,
+                    board.rules().capture_threshold
+                )
+            }
+            GameEvent::Captured { square } => {
+                println!(
+                    "Goat captured on {}!",
+                    get_coordinate_string(board.topology(), square)
+                )
+            }
+            GameEvent::GameOver(_)
+            | GameEvent::MoveUndone(_)
+            | GameEvent::CaptureUndone { .. }
+            | GameEvent::SidesSwapped
+            | GameEvent::SwapUndone => {}
+        }
+    }
 }
 
-fn print_game_end_screen(board: &Board, winner: Winner, interrupted: bool, game_mode: &str) {
-    println!("\n╔═════════════════════════════════════════════════╗");
-    println!("║               🎮 GAME OVER! 🎮                  ║");
-    println!("╟─────────────────────────────────────────────────╢");
-    println!("║ Mode: {:<41} ║", game_mode);
-    println!("╟─────────────────────────────────────────────────╢");
+fn print_game_end_screen(
+    board: &Board,
+    result: Option<GameResult>,
+    interrupted: bool,
+    game_mode: &str,
+    logged: Option<io::Result<PathBuf>>,
+) {
+    let panel = Panel::new(47);
+    println!();
+    panel.top();
+    panel.title("🎮", "GAME OVER!");
+    panel.rule();
+    panel.row(&format!("Mode: {game_mode}"));
+    panel.rule();
 
     if interrupted {
-        println!("║           🛑 Game was interrupted! 🛑            ║");
+        panel.title("🛑", "Game was interrupted!");
     } else {
-        match winner {
-            Winner::Tigers => {
-                println!("║          🐯 The Tigers are victorious! 🐯         ║");
-                println!("╟─────────────────────────────────────────────────╢");
-                println!("║ Goats captured: {:<33} ║", board.captured_goats);
-            }
-            Winner::Goats => {
-                println!("║           🐐 The Goats have won! 🐐             ║");
-                println!("╟─────────────────────────────────────────────────╢");
-                println!("║ Tigers trapped: All                             ║");
+        match result.map(|result| result.winner) {
+            Some(Winner::Tigers) => {
+                panel.title("🐯", "The Tigers are victorious!");
+                panel.rule();
+                panel.row(&format!(
+                    "Goats captured: {} (needed {})",
+                    board.captured_goats,
+                    board.rules().capture_threshold
+                ));
             }
-            Winner::None => {
-                println!("║              ⭐ Game ended! ⭐                   ║");
+            Some(Winner::Goats) => {
+                panel.title("🐐", "The Goats have won!");
+                if result.map(|result| result.reason) == Some(TerminationReason::TigersTrapped) {
+                    panel.rule();
+                    panel.row("Tigers trapped: All");
+                }
             }
+            Some(Winner::Draw) => panel.title("🤝", "The game is a draw!"),
+            Some(Winner::None) | None => panel.title("⭐", "Game ended!"),
+        }
+        if let Some(result) = result {
+            panel.row(&format!("Reason: {}", result.reason));
         }
     }
 
-    println!("╟─────────────────────────────────────────────────╢");
-    println!("║ Final board state:                              ║");
-    println!("╚═════════════════════════════════════════════════╝\n");
+    panel.rule();
+    panel.row("Final board state:");
+    panel.bottom();
+    println!();
+
+    println!("{}", render_board(board));
+    print_game_logged(logged);
+}
+
+/// Reviews the game just played: how many points each side's moves lost
+/// against the AI's best move at `--review-depth`, and the costliest
+/// mistakes of the players at the board, or of both sides if the AI
+/// played both.
+fn review_game(board: &Board, settings: Settings, cli: &Cli) {
+    let record = board.record();
+    if record.moves.is_empty() {
+        println!("\nNo moves to review");
+        return;
+    }
+    let review = Review {
+        limits: AiConfig {
+            time_limit: None,
+            max_depth: Some(cli.review_depth),
+            max_nodes: None,
+            ponder: false,
+            ..*board.ai_config()
+        },
+        thresholds: cli.review_thresholds,
+    };
+    println!();
+    let review = review.run(&record, |done, total| {
+        print!("\r🔎 Reviewing the game... {done}/{total}");
+        let _ = io::stdout().flush();
+    });
+    println!();
+
+    println!(
+        "\n📋 Game review, searching {} plies deep:",
+        cli.review_depth
+    );
+    println!(
+        "   {:<8}{:>6}{:>6}{:>12}{:>9}{:>9}{:>14}",
+        "", "best", "good", "inaccuracy", "mistake", "blunder", "average loss"
+    );
+    for (name, side) in [("Goats", Side::Goats), ("Tigers", Side::Tigers)] {
+        let count = |quality| review.count(side, quality);
+        println!(
+            "   {name:<8}{:>6}{:>6}{:>12}{:>9}{:>9}{:>14.1}",
+            count(MoveQuality::Best),
+            count(MoveQuality::Good),
+            count(MoveQuality::Inaccuracy),
+            count(MoveQuality::Mistake),
+            count(MoveQuality::Blunder),
+            review.average_loss(side)
+        );
+    }
+
+    // The side a player had when they moved, who swapped with the other
+    // player if the game went past the swap
+    let player = |review: &MoveReview| {
+        let side = match record.swapped_at {
+            Some(at) if review.ply < at => review.side.opponent(),
+            _ => review.side,
+        };
+        match side {
+            Side::Tigers => settings.tiger_player,
+            Side::Goats => settings.goat_player,
+        }
+    };
+    let ai_vs_ai = settings.tiger_player == Player::AI && settings.goat_player == Player::AI;
+    let mistakes: Vec<&MoveReview> = review
+        .mistakes()
+        .into_iter()
+        .filter(|review| ai_vs_ai || player(review) == Player::Human)
+        .take(3)
+        .collect();
+    if mistakes.is_empty() {
+        println!(
+            "\nNo mistakes: every move kept within {} points of the best",
+            cli.review_thresholds.inaccuracy
+        );
+        return;
+    }
+    println!("\nBiggest mistakes:");
+    for review in mistakes {
+        let side = match review.side {
+            Side::Tigers => "tigers",
+            Side::Goats => "goats",
+        };
+        println!(
+            "   {} ({}, {side})",
+            review.describe(board.topology()),
+            review.quality
+        );
+    }
+}
 
-    println!("{}", board.display_with_hints());
+/// Sets up a new game from the command line and the menus, with the
+/// choices made for it, or `None` if the player quits instead.
+fn new_game(cli: &Cli, start: Option<&Board>) -> Option<(Board, Settings)> {
+    let (tiger_player, goat_player) = match cli.players() {
+        Some(players) => players,
+        None => get_game_mode()?,
+    };
+    let playing_against_ai = tiger_player != goat_player;
+    let mut board = match start {
+        // The position brings its own rules
+        Some(board) => board.clone(),
+        None => {
+            let rules = configure_forced_capture(configure_multi_capture(configure_rules(
+                get_board_variant()?,
+            )));
+            let rules = if playing_against_ai {
+                configure_swap(rules)
+            } else {
+                rules
+            };
+            Board::with_rules(rules).expect("rules were validated")
+        }
+    };
+    let mut settings = Settings {
+        tiger_player,
+        goat_player,
+        ai_limit: AiLimit::Default,
+        opening_book: true,
+        ponder: false,
+        tiger_engine: EngineChoice::Minimax,
+        goat_engine: EngineChoice::Minimax,
+    };
 
-    println!("\nThanks for playing! Type 'q' to quit or press Enter to play again.");
+    // Configure how hard the AI searches if playing against AI
+    if tiger_player == Player::AI || goat_player == Player::AI {
+        settings.ai_limit = match cli.ai_limit() {
+            Some(limit) => limit,
+            None => configure_ai_limits(),
+        };
+        settings.opening_book = configure_opening_book();
+        if playing_against_ai {
+            settings.ponder = configure_pondering();
+        }
+    }
+    if tiger_player == Player::AI && goat_player == Player::AI {
+        settings.tiger_engine = choose_engine("tigers");
+        settings.goat_engine = choose_engine("goats");
+    }
+    settings.apply(&mut board, cli.seed);
+    configure_clock(&mut board, cli);
+    Some((board, settings))
 }
 
 fn main() {
+    let cli = Cli::parse();
+    let color = cli.color();
+    if !color {
+        colored::control::set_override(false);
+    }
+    DISPLAY
+        .set(DisplayOptions {
+            highlight_threatened_goats: !cli.hide_threats,
+            color,
+            ascii_lines: cli.ascii,
+            pieces: cli.pieces.into(),
+            theme: cli.theme(),
+            flipped: cli.flip,
+            coordinates_on_all_sides: cli.coordinates,
+            ..DisplayOptions::default()
+        })
+        .expect("the display is only set up here");
+    SCREEN_READER.store(cli.screen_reader, Ordering::SeqCst);
+    match &cli.command {
+        Some(CliCommand::Replay { file }) => {
+            replay(file);
+            return;
+        }
+        Some(CliCommand::Match(args)) => {
+            play_match(args);
+            return;
+        }
+        None => {}
+    }
+    let start = cli.start();
+    let seed = cli.seed;
+    if let Some(seed) = seed {
+        println!("Seed: {seed}");
+    }
+    // A crash still leaves the game autosaved as far as it got
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        write_pending_autosave();
+        default_hook(info);
+    }));
+    // Ctrl+C interrupts the AI, or asks whether to save at the move
+    // prompt; while a game is being set up it quits
+    let running = Arc::new(AtomicBool::new(true));
+    let r = running.clone();
+    ctrlc::set_handler(move || {
+        if !IN_GAME.load(Ordering::SeqCst) {
+            std::process::exit(130);
+        }
+        r.store(false, Ordering::SeqCst);
+        if AWAITING_MOVE.load(Ordering::SeqCst) {
+            print!("\nSave before quitting? (y/n): ");
+            let _ = io::stdout().flush();
+        }
+    })
+    .expect("Error setting Ctrl-C handler");
+    let mut autosave = Autosave::new();
+    let games_dir = cli.games_dir();
     loop {
-        let mut board = Board::new();
         print_instructions();
 
-        let (tiger_player, goat_player) = get_game_mode();
-        let playing_against_ai = tiger_player != goat_player;
-        let game_mode = get_game_mode_string(tiger_player, goat_player);
+        // A position given on the command line is played instead
+        let resumed = match start {
+            Some(_) => None,
+            None => autosave.offer_resume(),
+        };
+        let (mut board, mut settings) = match resumed {
+            Some((mut board, settings)) => {
+                settings.apply(&mut board, seed);
+                println!(
+                    "\nResuming the last game at move {}",
+                    board.fullmove_number()
+                );
+                (board, settings)
+            }
+            None => match new_game(&cli, start.as_ref()) {
+                Some(game) => game,
+                None => break,
+            },
+        };
+        let playing_against_ai = settings.playing_against_ai();
+        let mut game_mode = get_game_mode_string(settings.tiger_player, settings.goat_player);
+        // Each engine is away on a worker thread while it searches
+        let mut tiger_engine = Some(create_engine(settings.tiger_engine, seed));
+        let mut goat_engine = Some(create_engine(settings.goat_engine, seed));
 
-        // Configure AI time limit if playing against AI
-        if playing_against_ai || (tiger_player == Player::AI && goat_player == Player::AI) {
-            configure_ai_time_limit(&mut board);
-        }
+        show_search_progress(&mut board);
 
-        // Set up Ctrl+C handler
-        let running = Arc::new(AtomicBool::new(true));
-        let r = running.clone();
-        ctrlc::set_handler(move || {
-            r.store(false, Ordering::SeqCst);
-        })
-        .expect("Error setting Ctrl-C handler");
+        // An interrupt that ended the last game mustn't end this one
+        running.store(true, Ordering::SeqCst);
+        IN_GAME.store(true, Ordering::SeqCst);
+        // Whether to save, once Ctrl+C at the move prompt quits the game
+        let mut quit = None;
+        let hotseat =
+            settings.tiger_player == Player::Human && settings.goat_player == Player::Human;
+        AUTO_FLIP.store(cli.auto_flip && hotseat, Ordering::SeqCst);
 
+        let ai_vs_ai = settings.tiger_player == Player::AI && settings.goat_player == Player::AI;
         println!("\nStarting game...");
+        if ai_vs_ai {
+            println!("Press Enter to pause the game and look at it");
+        }
         println!("Current board:");
-        println!("{}", board.display_with_hints());
+        println!("{}", render_board(&board));
 
         // Main game loop
-        let mut tigers_turn = false;
         while !board.is_game_over() && running.load(Ordering::SeqCst) {
-            print_game_status(&board, tigers_turn, &game_mode);
-            println!("{}", board.display_with_hints());
+            autosave.update(&board, settings, ai_vs_ai);
+            if board.can_swap() {
+                // Whoever plays the tigers holds the option
+                let swap = match settings.tiger_player {
+                    Player::Human => match get_user_input("Swap sides with the AI? (y/n): ") {
+                        Some(input) => input.eq_ignore_ascii_case("y"),
+                        None => break,
+                    },
+                    Player::AI => board.ai_should_swap(),
+                };
+                if swap && board.swap_sides() {
+                    settings.swap_sides();
+                    std::mem::swap(&mut tiger_engine, &mut goat_engine);
+                    game_mode = get_game_mode_string(settings.tiger_player, settings.goat_player);
+                    println!("\n🔄 Sides swapped! {game_mode}");
+                }
+                board.drain_events();
+            }
+
+            // The board knows whose turn it is, undos and loads included
+            let tigers_turn = board.side_to_move() == Side::Tigers;
+            print_game_status(&board, &game_mode);
+            println!("{}", render_board(&board));
 
             let current_player = if tigers_turn {
-                tiger_player
+                settings.tiger_player
             } else {
-                goat_player
+                settings.goat_player
             };
 
             match current_player {
                 Player::Human => {
-                    if let Some(input) =
-                        get_user_input("Enter command (position(s) A1-E5, hint, undo, or quit): ")
-                    {
+                    // The prompts stop waiting when the player's flag falls
+                    let deadline = board
+                        .remaining_time(board.side_to_move())
+                        .map(|left| Instant::now() + left);
+                    AWAITING_MOVE.store(true, Ordering::SeqCst);
+                    let input = match get_user_input_until(
+                        "Enter command (position(s) A1-E5, hint, eval, analyze, moves, describe, flip, draw, undo, save, load, or quit): ",
+                        deadline,
+                    ) {
+                        Input::Line(input) => Some(input),
+                        Input::Closed => None,
+                        Input::TimedOut => {
+                            AWAITING_MOVE.store(false, Ordering::SeqCst);
+                            println!("\n⏰ Time is up!");
+                            continue;
+                        }
+                    };
+                    AWAITING_MOVE.store(false, Ordering::SeqCst);
+                    if !running.load(Ordering::SeqCst) {
+                        // Ctrl+C asked whether to save, and this is the answer
+                        let save = input.is_some_and(|answer| answer.eq_ignore_ascii_case("y"));
+                        quit = Some(save);
+                        break;
+                    }
+                    if let Some(input) = input {
+                        let (command, argument) =
+                            input.split_once(' ').unwrap_or((input.as_str(), ""));
+                        let argument = argument.trim();
+                        if command.eq_ignore_ascii_case("save") {
+                            if argument.is_empty() {
+                                println!("Please name a file to save to, e.g. 'save game.txt'");
+                            } else {
+                                match save::save(&board, argument) {
+                                    Ok(()) => println!("\n💾 Game saved to {argument}"),
+                                    Err(err) => println!("\nCould not save the game: {err}"),
+                                }
+                            }
+                            continue;
+                        }
+                        if command.eq_ignore_ascii_case("load") {
+                            if argument.is_empty() {
+                                println!("Please name a file to load, e.g. 'load game.txt'");
+                                continue;
+                            }
+                            match load_game(&board, argument) {
+                                Ok(loaded) => {
+                                    if loaded.swapped_at().is_some() != board.swapped_at().is_some()
+                                    {
+                                        settings.swap_sides();
+                                        game_mode = get_game_mode_string(
+                                            settings.tiger_player,
+                                            settings.goat_player,
+                                        );
+                                    }
+                                    board = loaded;
+                                    println!(
+                                        "\n📂 Loaded {argument}, move {}",
+                                        board.fullmove_number()
+                                    );
+                                    println!("Current board:");
+                                    println!("{}", render_board(&board));
+                                }
+                                Err(err) => println!("\nCould not load {argument}: {err}"),
+                            }
+                            continue;
+                        }
                         if input.eq_ignore_ascii_case("h") || input.eq_ignore_ascii_case("hint") {
                             println!("\n🤔 Thinking of a good move...");
-
-                            // Create a temporary board for AI analysis
-                            let mut temp_board = board.clone();
-                            let success = if tigers_turn {
-                                temp_board.ai_move_tiger()
-                            } else {
-                                temp_board.ai_move_goat()
+                            if !cli.keep_clock_running {
+                                board.pause_clock();
+                            }
+                            print_hint(&board, &cli);
+                            board.resume_clock();
+                            continue;
+                        }
+                        if command.eq_ignore_ascii_case("analyze") {
+                            let seconds = match argument {
+                                "" => Ok(cli.hint_time.unwrap_or(ANALYSIS_TIME)),
+                                argument => argument.parse::<u64>(),
                             };
-
-                            if success {
-                                // Compare the boards to find what move was made
-                                for i in 0..25 {
-                                    if board.cells[i] != temp_board.cells[i] {
-                                        if temp_board.cells[i] == Piece::Empty {
-                                            // This was the 'from' position
-                                            print!(
-                                                "\n💡 Suggested move: {}",
-                                                get_coordinate_string(i)
-                                            );
-                                        } else if board.cells[i] == Piece::Empty {
-                                            // This was the 'to' position
-                                            println!(" {}", get_coordinate_string(i));
-                                        }
+                            match seconds {
+                                Ok(seconds) if seconds > 0 => {
+                                    if !cli.keep_clock_running {
+                                        board.pause_clock();
                                     }
+                                    analyze(&board, seconds, cli.depth, &running);
+                                    board.resume_clock();
                                 }
-                            } else {
-                                println!("\n😕 No good moves available!");
+                                _ => println!(
+                                    "Please give the seconds to think as a whole number, e.g. 'analyze 5'"
+                                ),
+                            }
+                            continue;
+                        }
+                        if look_at_game(&board, &input) {
+                            continue;
+                        }
+                        if input.eq_ignore_ascii_case("draw") {
+                            // The clock waits for the answer
+                            if !cli.keep_clock_running {
+                                board.pause_clock();
                             }
+                            offer_draw(&mut board, settings, cli.draw_window);
+                            board.resume_clock();
                             continue;
                         }
                         if input.eq_ignore_ascii_case("u") || input.eq_ignore_ascii_case("undo") {
                             if board.can_undo() {
-                                // If playing against AI, undo both moves
+                                // If playing against AI, undo both moves; when
+                                // the AI moved first there is only its move
+                                board.undo();
                                 if playing_against_ai {
-                                    if board.can_undo() {
-                                        board.undo(); // Undo AI's move
-                                        if board.can_undo() {
-                                            board.undo(); // Undo player's move
-                                            println!(
-                                                "\nUndid both your move and the AI's response!"
-                                            );
-                                        } else {
-                                            println!("\nUndid the AI's move!");
-                                        }
+                                    board.undo();
+                                }
+                                let events = board.drain_events();
+                                let undone = events
+                                    .iter()
+                                    .filter(|event| matches!(event, GameEvent::MoveUndone(_)))
+                                    .count();
+                                if events.contains(&GameEvent::SwapUndone) {
+                                    settings.swap_sides();
+                                    game_mode = get_game_mode_string(
+                                        settings.tiger_player,
+                                        settings.goat_player,
+                                    );
+                                    println!("\nSides swapped back! {game_mode}");
+                                }
+                                match (playing_against_ai, undone) {
+                                    (true, 2) => {
+                                        println!("\nUndid both your move and the AI's response!")
                                     }
-                                } else {
-                                    board.undo(); // Just undo one move in human vs human
-                                    println!("\nMove undone!");
+                                    (true, _) => println!("\nUndid the AI's move!"),
+                                    (false, _) => println!("\nMove undone!"),
                                 }
-                                tigers_turn = !tigers_turn;
                                 println!("Current board:");
-                                println!("{}", board.display_with_hints());
+                                println!("{}", render_board(&board));
                                 continue;
                             } else {
                                 println!("No moves to undo!");
@@ -306,27 +2461,36 @@ fn main() {
 
                         if tigers_turn {
                             // Tiger's turn
-                            if let Some((from, to)) = parse_move(&input) {
+                            if let Some(path) = parse_path(board.topology(), &input) {
+                                // A chain of captures in one turn
+                                let jumps: Option<Vec<Jump>> = path
+                                    .windows(2)
+                                    .map(|pair| board.topology().jump_between(pair[0], pair[1]))
+                                    .collect();
+                                if !jumps.is_some_and(|jumps| board.multi_jump(&jumps)) {
+                                    println!("Invalid capture chain! Try again.");
+                                    continue;
+                                }
+                            } else if let Some((from, to)) = parse_move(board.topology(), &input) {
                                 // Two-step move provided
                                 if board.cells[from] != Piece::Tiger {
                                     println!(
                                         "No tiger at position {}! Try again.",
-                                        get_coordinate_string(from)
+                                        get_coordinate_string(board.topology(), from)
                                     );
                                     continue;
                                 }
 
-                                if !board.move_tiger(from, to) {
-                                    println!("Invalid tiger move! Try again.");
+                                if let Err(err) = board.try_move_tiger(from, to) {
+                                    print_tiger_move_error(&board, err);
                                     continue;
                                 }
-                                println!("Tiger moved! Captured goats: {}", board.captured_goats);
-                            } else if let Some(from) = parse_position(&input) {
+                            } else if let Some(from) = parse_position(board.topology(), &input) {
                                 // Single-step move: first select the piece
                                 if board.cells[from] != Piece::Tiger {
                                     println!(
                                         "No tiger at position {}! Try again.",
-                                        get_coordinate_string(from)
+                                        get_coordinate_string(board.topology(), from)
                                     );
                                     continue;
                                 }
@@ -334,19 +2498,22 @@ fn main() {
                                 // Show valid moves for selected tiger
                                 board.select_position(from);
                                 println!("\nValid moves marked with •");
-                                println!("{}", board.display_with_hints());
+                                println!("{}", render_board(&board));
 
-                                let to = match get_position("Enter position to move to (A1-E5): ") {
+                                let to = match get_position(
+                                    board.topology(),
+                                    "Enter position to move to (A1-E5): ",
+                                    deadline,
+                                ) {
                                     Some(pos) => pos,
                                     None => break,
                                 };
 
-                                if !board.move_tiger(from, to) {
-                                    println!("Invalid tiger move! Try again.");
+                                if let Err(err) = board.try_move_tiger(from, to) {
+                                    print_tiger_move_error(&board, err);
                                     board.clear_selection();
                                     continue;
                                 }
-                                println!("Tiger moved! Captured goats: {}", board.captured_goats);
                                 board.clear_selection();
                             } else {
                                 println!("Invalid command! Please enter position(s) (e.g., 'A1' or 'A1 A2'), 'h' for hint, 'u' for undo, or 'q' to quit");
@@ -355,23 +2522,37 @@ fn main() {
                         } else {
                             // Goat's turn
                             if board.goats_in_hand > 0 {
-                                if let Some(pos) = parse_position(&input) {
+                                if let Some(pos) = parse_position(board.topology(), &input) {
                                     if !board.place_goat(pos) {
                                         println!("Invalid move! Try again.");
                                         continue;
                                     }
-                                    println!("Goats remaining to place: {}", board.goats_in_hand);
+                                } else if let Some((from, to)) =
+                                    parse_move(board.topology(), &input)
+                                {
+                                    // Only allowed when the house rules permit it
+                                    if !board.rules().goats_may_move_during_placement {
+                                        println!(
+                                            "Goats can't move until all goats are placed ({} left in hand)!",
+                                            board.goats_in_hand
+                                        );
+                                        continue;
+                                    }
+                                    if !board.move_goat(from, to) {
+                                        println!("Invalid goat move! Try again.");
+                                        continue;
+                                    }
                                 } else {
                                     println!("Invalid command! Please enter a position (A1-E5), 'h' for hint, 'u' for undo, or 'q' to quit");
                                     continue;
                                 }
                             } else {
-                                if let Some((from, to)) = parse_move(&input) {
+                                if let Some((from, to)) = parse_move(board.topology(), &input) {
                                     // Two-step move provided
                                     if board.cells[from] != Piece::Goat {
                                         println!(
                                             "No goat at position {}! Try again.",
-                                            get_coordinate_string(from)
+                                            get_coordinate_string(board.topology(), from)
                                         );
                                         continue;
                                     }
@@ -380,13 +2561,13 @@ fn main() {
                                         println!("Invalid goat move! Try again.");
                                         continue;
                                     }
-                                    println!("Goat moved!");
-                                } else if let Some(from) = parse_position(&input) {
+                                } else if let Some(from) = parse_position(board.topology(), &input)
+                                {
                                     // Single-step move: first select the piece
                                     if board.cells[from] != Piece::Goat {
                                         println!(
                                             "No goat at position {}! Try again.",
-                                            get_coordinate_string(from)
+                                            get_coordinate_string(board.topology(), from)
                                         );
                                         continue;
                                     }
@@ -394,20 +2575,22 @@ fn main() {
                                     // Show valid moves for selected goat
                                     board.select_position(from);
                                     println!("\nValid moves marked with •");
-                                    println!("{}", board.display_with_hints());
+                                    println!("{}", render_board(&board));
 
-                                    let to =
-                                        match get_position("Enter position to move to (A1-E5): ") {
-                                            Some(pos) => pos,
-                                            None => break,
-                                        };
+                                    let to = match get_position(
+                                        board.topology(),
+                                        "Enter position to move to (A1-E5): ",
+                                        deadline,
+                                    ) {
+                                        Some(pos) => pos,
+                                        None => break,
+                                    };
 
                                     if !board.move_goat(from, to) {
                                         println!("Invalid goat move! Try again.");
                                         board.clear_selection();
                                         continue;
                                     }
-                                    println!("Goat moved!");
                                     board.clear_selection();
                                 } else {
                                     println!("Invalid command! Please enter position(s) (e.g., 'A1' or 'A1 A2'), 'h' for hint, 'u' for undo, or 'q' to quit");
@@ -420,24 +2603,41 @@ fn main() {
                     }
                 }
                 Player::AI => {
+                    // A line typed while the AIs play each other pauses the game
+                    if ai_vs_ai
+                        && matches!(read_line_until(Some(Instant::now())), Input::Line(_))
+                        && !pause_game(&board)
+                    {
+                        break;
+                    }
                     println!("AI is thinking... (Press Ctrl+C to interrupt)");
 
                     // Reset the running flag in case it was interrupted before
                     running.store(true, Ordering::SeqCst);
 
                     let start_time = std::time::Instant::now();
-                    let success = if tigers_turn {
-                        board.ai_move_tiger()
-                    } else {
-                        board.ai_move_goat()
+                    let slot = match board.side_to_move() {
+                        Side::Tigers => &mut tiger_engine,
+                        Side::Goats => &mut goat_engine,
                     };
+                    let forced = board.legal_move_list().len() == 1;
+                    let engine = slot.take().expect("engines come back after each search");
+                    let search = engine.start_search(board.clone(), *board.ai_config());
+
+                    // Keep an eye on Ctrl+C while the engine thinks
+                    while matches!(search.poll(), SearchStatus::Running(_)) {
+                        if !running.load(Ordering::SeqCst) {
+                            search.stop();
+                        }
+                        std::thread::sleep(Duration::from_millis(20));
+                    }
+                    let (played, engine) = search.await_result();
+                    *slot = Some(engine);
 
-                    // If we were interrupted, undo the move and break
+                    // If we were interrupted, leave the move unplayed and break
                     if !running.load(Ordering::SeqCst) {
                         println!("\nAI move interrupted!");
-                        if board.can_undo() {
-                            board.undo();
-                        }
+                        board.drain_events();
                         break;
                     }
 
@@ -447,37 +2647,75 @@ fn main() {
                         std::thread::sleep(Duration::from_millis(500) - elapsed);
                     }
 
-                    if !success {
+                    let Some(played) = played.filter(|played| board.make_move(&played.mv)) else {
                         println!("AI couldn't make a move!");
                         break;
-                    }
-
-                    if tigers_turn {
-                        println!("Tiger moved! Captured goats: {}", board.captured_goats);
-                    } else if board.goats_in_hand > 0 {
-                        println!("Goat placed! Remaining to place: {}", board.goats_in_hand);
-                    } else {
-                        println!("Goat moved!");
+                    };
+                    let mv = format_move(board.topology(), &played.mv);
+                    match played.stats.depth {
+                        0 if forced => println!("AI played {mv} (only move)"),
+                        0 => println!("AI played {mv} (book)"),
+                        depth => println!("AI played {mv} (depth {depth})"),
                     }
                 }
             }
 
+            print_events(&mut board);
             println!("\nCurrent board:");
-            println!("{}", board.display_with_hints());
-            tigers_turn = !tigers_turn;
+            println!("{}", render_board(&board));
         }
 
+        // Dropping the engines stops any search left pondering
+        drop((tiger_engine, goat_engine));
+        IN_GAME.store(false, Ordering::SeqCst);
+        let logged = games_dir
+            .as_deref()
+            .and_then(|dir| log_game(dir, &board, settings, &game_mode));
+        if let Some(save) = quit {
+            if save {
+                autosave.update(&board, settings, false);
+                autosave.flush();
+                println!("\n💾 Game saved, to be picked up next time");
+            } else {
+                autosave.remove();
+                println!("\nGame not saved");
+            }
+            print_game_logged(logged);
+            break;
+        }
+        if board.is_game_over() {
+            autosave.remove();
+        } else {
+            autosave.update(&board, settings, false);
+            autosave.flush();
+        }
         let interrupted = !running.load(Ordering::SeqCst);
-        let winner = board.get_winner();
+        let result = board.result();
 
-        print_game_end_screen(&board, winner, interrupted, &game_mode);
+        print_game_end_screen(&board, result, interrupted, &game_mode, logged);
+        if cli.review {
+            review_game(&board, settings, &cli);
+        }
 
-        // Ask to play again
-        if let Some(input) = get_user_input("") {
-            if input.eq_ignore_ascii_case("q") || input.eq_ignore_ascii_case("quit") {
-                break;
+        // Ask to play again; an empty line means yes
+        let again = loop {
+            println!(
+                "\nThanks for playing! Type 'review' to go over the game, 'q' to quit or press Enter to play again."
+            );
+            match read_line() {
+                Some(input)
+                    if input.eq_ignore_ascii_case("q") || input.eq_ignore_ascii_case("quit") =>
+                {
+                    break false
+                }
+                Some(input) if input.eq_ignore_ascii_case("review") => {
+                    review_game(&board, settings, &cli)
+                }
+                Some(_) => break true,
+                None => break false,
             }
-        } else {
+        };
+        if !again {
             break;
         }
     }
@@ -0,0 +1,271 @@
+//! Monte Carlo tree search, an alternative to the AI's minimax search.
+//!
+//! Instead of evaluating every line to a fixed depth, UCT grows a tree
+//! towards the moves that have done best in random games so far, while
+//! still trying the others now and then. Each iteration walks down the
+//! tree, adds one new position, plays a quick game out from it and counts
+//! the result in every position on the way back up. The move played is
+//! the one tried most often.
+
+use crate::{AiConfig, Board, EngineResult, Move, SearchStats, Side, Winner};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::sync::atomic::Ordering;
+use std::time::Instant;
+
+/// Iterations to run when [`AiConfig`] sets neither a time nor a node
+/// limit.
+const DEFAULT_ITERATIONS: u64 = 10_000;
+
+/// A UCT searcher, used through the [`Engine`](crate::Engine) trait. Its
+/// settings apply to every search it runs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mcts {
+    /// How much to favour moves tried less often over moves that have
+    /// done well. The usual choice is √2.
+    pub exploration: f64,
+    /// Moves to play in a random game before judging it by the
+    /// evaluation instead.
+    pub playout_cap: usize,
+    /// Seed for the random games. The same seed gives the same move in
+    /// the same position when searching a fixed number of iterations;
+    /// `None` seeds from the operating system.
+    pub seed: Option<u64>,
+    /// Games to play out before judging the unfinished ones by the
+    /// evaluation together, for evaluators that score a batch of
+    /// positions faster than one at a time. The results only count once
+    /// the whole batch is judged, so the tree grows a little less
+    /// sharply with larger batches. 1 judges every game as it ends.
+    pub leaf_batch: usize,
+}
+
+impl Default for Mcts {
+    fn default() -> Self {
+        Mcts {
+            exploration: std::f64::consts::SQRT_2,
+            playout_cap: 60,
+            seed: None,
+            leaf_batch: 1,
+        }
+    }
+}
+
+/// A position in the tree, reached by `mv` from `parent`.
+struct Node {
+    mv: Option<Move>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    /// Moves from here that have no node yet.
+    untried: Vec<Move>,
+    /// The side that played `mv`, whose results this node counts.
+    mover: Side,
+    /// Plies from the root.
+    depth: u32,
+    visits: u32,
+    /// Games won by `mover` through this node, draws counting half.
+    wins: f64,
+}
+
+impl Mcts {
+    /// Searches `board` for the side to move, for
+    /// [`Engine::best_move`](crate::Engine::best_move). The score is from
+    /// the tigers' side, from -1000 when the goats win every game through
+    /// the move to 1000 when the tigers do. The stats count iterations as
+    /// nodes, and the depth is that of the deepest position in the tree.
+    ///
+    /// `limits.time_limit` bounds the time spent and `limits.max_nodes`
+    /// the iterations run; `max_depth` doesn't apply. With neither set the
+    /// search runs 10,000 iterations. A search started with
+    /// [`Engine::start_search`](crate::Engine::start_search) also ends
+    /// when stopped.
+    pub(crate) fn search(&mut self, board: &Board, limits: &AiConfig) -> Option<EngineResult> {
+        let start = Instant::now();
+        let mut rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        let root_moves = board.legal_moves();
+        if root_moves.is_empty() || board.is_game_over() {
+            return None;
+        }
+        let mut tree = vec![Node {
+            mv: None,
+            parent: None,
+            children: Vec::new(),
+            untried: root_moves,
+            mover: board.side_to_move().opponent(),
+            depth: 0,
+            visits: 0,
+            wins: 0.0,
+        }];
+        let mut deepest = 0;
+
+        let max_iterations = match (limits.time_limit, limits.max_nodes) {
+            (None, None) => Some(DEFAULT_ITERATIONS),
+            (_, max_nodes) => max_nodes,
+        };
+        let mut iterations = 0;
+        let mut pending = Vec::new();
+        loop {
+            if max_iterations.is_some_and(|max| iterations >= max)
+                || limits
+                    .time_limit
+                    .is_some_and(|limit| iterations > 0 && start.elapsed() >= limit)
+                || board
+                    .search_stop
+                    .as_ref()
+                    .is_some_and(|stop| iterations > 0 && stop.load(Ordering::Relaxed))
+            {
+                break;
+            }
+            iterations += 1;
+
+            // Walk down the tree while every move has been tried
+            let mut position = board.clone();
+            let mut node = 0;
+            while tree[node].untried.is_empty() && !tree[node].children.is_empty() {
+                node = self.select(&tree, node);
+                let mv = tree[node].mv.as_ref().expect("only the root has no move");
+                position.make_move(mv);
+            }
+
+            // Add one position the tree hasn't seen
+            if !tree[node].untried.is_empty() && !position.is_game_over() {
+                let index = rand::Rng::gen_range(&mut rng, 0..tree[node].untried.len());
+                let mv = tree[node].untried.swap_remove(index);
+                let mover = position.side_to_move();
+                position.make_move(&mv);
+                let child = tree.len();
+                tree.push(Node {
+                    mv: Some(mv),
+                    parent: Some(node),
+                    children: Vec::new(),
+                    untried: if position.is_game_over() {
+                        Vec::new()
+                    } else {
+                        position.generate_legal_moves()
+                    },
+                    mover,
+                    depth: tree[node].depth + 1,
+                    visits: 0,
+                    wins: 0.0,
+                });
+                deepest = deepest.max(tree[child].depth);
+                tree[node].children.push(child);
+                node = child;
+            }
+
+            self.playout(&mut position, &mut rng);
+            pending.push((node, position));
+            if pending.len() >= self.leaf_batch {
+                back_up(&mut tree, board, &mut pending);
+            }
+        }
+        back_up(&mut tree, board, &mut pending);
+
+        let best = *tree[0]
+            .children
+            .iter()
+            .max_by_key(|&&child| tree[child].visits)?;
+        let node = &tree[best];
+        let tigers = match node.mover {
+            Side::Tigers => node.wins / node.visits as f64,
+            Side::Goats => 1.0 - node.wins / node.visits as f64,
+        };
+        Some(EngineResult {
+            mv: node.mv.clone()?,
+            score: ((tigers - 0.5) * 2000.0).round() as i32,
+            stats: SearchStats {
+                nodes: iterations,
+                qnodes: 0,
+                cutoffs: 0,
+                depth: deepest,
+                max_ply: deepest,
+                elapsed: start.elapsed(),
+            },
+        })
+    }
+
+    /// The child of `node` with the best upper confidence bound for the
+    /// side choosing there.
+    fn select(&self, tree: &[Node], node: usize) -> usize {
+        let parent_visits = (tree[node].visits.max(1) as f64).ln();
+        let bound = |child: usize| {
+            let child = &tree[child];
+            let visits = child.visits.max(1) as f64;
+            child.wins / visits + self.exploration * (parent_visits / visits).sqrt()
+        };
+        *tree[node]
+            .children
+            .iter()
+            .max_by(|&&a, &&b| bound(a).total_cmp(&bound(b)))
+            .expect("a node with no children isn't selected from")
+    }
+
+    /// Plays `position` out with random moves, tigers capturing whenever
+    /// they can and goats keeping out of reach when they can, for at most
+    /// `playout_cap` moves.
+    fn playout(&self, position: &mut Board, rng: &mut StdRng) {
+        for _ in 0..self.playout_cap {
+            if position.is_game_over() {
+                break;
+            }
+            let mut moves = position.generate_legal_moves();
+            if moves.is_empty() {
+                break;
+            }
+            moves.shuffle(rng);
+            let mv = match position.side_to_move() {
+                Side::Tigers => moves.iter().find(|mv| mv.is_capture()),
+                Side::Goats => moves.iter().find(|mv| {
+                    position.make_move(mv);
+                    let safe = !position.generate_legal_moves().iter().any(Move::is_capture);
+                    position.undo();
+                    safe
+                }),
+            }
+            .unwrap_or(&moves[0])
+            .clone();
+            position.make_move(&mv);
+        }
+    }
+}
+
+/// Counts the games played out from the nodes in `pending` everywhere on
+/// the way back up, emptying it. Each counts as the tigers' share of the
+/// result: 1 for a tiger win, 0 for a goat win and ½ for a draw. The games
+/// still going are judged together by `board`'s evaluation.
+fn back_up(tree: &mut [Node], board: &Board, pending: &mut Vec<(usize, Board)>) {
+    let (finished, going): (Vec<_>, Vec<_>) = pending
+        .drain(..)
+        .partition(|(_, position)| position.is_game_over());
+    let (going, positions): (Vec<usize>, Vec<Board>) = going.into_iter().unzip();
+    let scores = board.evaluate_positions(&positions);
+    let finished = finished.into_iter().map(|(node, position)| {
+        let tigers = match position.get_winner() {
+            Winner::Tigers => 1.0,
+            Winner::Goats => 0.0,
+            Winner::Draw | Winner::None => 0.5,
+        };
+        (node, tigers)
+    });
+    // About 3 in 4 for the side a captured goat ahead
+    let going = going
+        .into_iter()
+        .zip(scores)
+        .map(|(node, score)| (node, 1.0 / (1.0 + (-score as f64 / 100.0).exp())));
+
+    for (node, tigers) in finished.chain(going) {
+        let mut current = Some(node);
+        while let Some(index) = current {
+            let node = &mut tree[index];
+            node.visits += 1;
+            node.wins += match node.mover {
+                Side::Tigers => tigers,
+                Side::Goats => 1.0 - tigers,
+            };
+            current = node.parent;
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Text notation for moves, using the board's point names:
+//!
+//! - `C3` places a goat on C3,
+//! - `C3-C4` moves a goat or tiger from C3 to C4,
+//! - `A1xB1-C1` is a tiger on A1 capturing the goat on B1 and landing on
+//!   C1; chained captures continue with `xD1-E1` and so on.
+//!
+//! A game's moves are listed with [`format_history`].
+
+use crate::{Board, Move, Topology};
+
+/// Writes `mv` in move notation.
+pub fn format_move(topology: &Topology, mv: &Move) -> String {
+    let name = |pos| topology.point_name(pos);
+    match mv {
+        Move::PlaceGoat { position } => name(*position),
+        Move::MoveGoat { from, to }
+        | Move::MoveTiger {
+            from,
+            to,
+            captured_position: None,
+        } => format!("{}-{}", name(*from), name(*to)),
+        Move::MoveTiger {
+            from,
+            to,
+            captured_position: Some(over),
+        } => format!("{}x{}-{}", name(*from), name(*over), name(*to)),
+        Move::MultiJump(jumps) => {
+            let mut text = jumps
+                .first()
+                .map_or_else(String::new, |jump| name(jump.from));
+            for jump in jumps {
+                text.push_str(&format!("x{}-{}", name(jump.over), name(jump.to)));
+            }
+            text
+        }
+    }
+}
+
+/// Reads a move in move notation, as long as it is legal on `board`.
+/// Point names are case-insensitive.
+pub fn parse_move(board: &Board, text: &str) -> Option<Move> {
+    let text = text.trim();
+    board
+        .legal_moves()
+        .into_iter()
+        .find(|mv| format_move(board.topology(), mv).eq_ignore_ascii_case(text))
+}
+
+/// Number of goats `mv` captures.
+fn goats_captured(mv: &Move) -> u32 {
+    match mv {
+        Move::MoveTiger {
+            captured_position: Some(_),
+            ..
+        } => 1,
+        Move::MultiJump(jumps) => jumps.len() as u32,
+        _ => 0,
+    }
+}
+
+/// Lists the moves of `board`'s game, numbered, with `G` or `T` for the
+/// side that played each, and after each capture the number of goats
+/// captured so far:
+///
+/// ```text
+/// 1. G C3  T A1-B2  2. G A3  T B2xC3-D4 (1)
+/// ```
+///
+/// Lines break between numbered moves so that they stay within `width`
+/// columns where they can. A game that starts with the tigers to move
+/// begins with `1. ... T`.
+pub fn format_history(board: &Board, width: usize) -> String {
+    let history = board.move_history();
+    let mut captured = board.captured_goats - history.iter().map(goats_captured).sum::<u32>();
+    let mut entries: Vec<String> = Vec::new();
+    for (ply, mv) in (board.start_ply()..).zip(history) {
+        let mut text = format_move(board.topology(), mv);
+        if mv.is_capture() {
+            captured += goats_captured(mv);
+            text.push_str(&format!(" ({captured})"));
+        }
+        // Goats move on even plies, and each number covers a goat move
+        // and the tiger move after it
+        match (ply % 2, entries.last_mut()) {
+            (1, Some(entry)) if ply != board.start_ply() => {
+                entry.push_str(&format!("  T {text}"));
+            }
+            (1, _) => entries.push(format!("{}. ... T {text}", ply / 2 + 1)),
+            _ => entries.push(format!("{}. G {text}", ply / 2 + 1)),
+        }
+    }
+
+    let mut lines: Vec<String> = Vec::new();
+    for entry in entries {
+        match lines.last_mut() {
+            Some(line) if line.len() + 2 + entry.len() <= width => {
+                line.push_str("  ");
+                line.push_str(&entry);
+            }
+            _ => lines.push(entry),
+        }
+    }
+    lines.join("\n")
+}
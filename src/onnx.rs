@@ -0,0 +1,152 @@
+//! An evaluator backed by a neural network in ONNX format, behind the
+//! `onnx` feature.
+//!
+//! The network runs on [ONNX Runtime](https://onnxruntime.ai), loaded
+//! from its shared library when the first model is: point the
+//! `ORT_DYLIB_PATH` environment variable at it if it isn't on the library
+//! path.
+//!
+//! # Inputs and outputs
+//!
+//! The model takes one input, a `float32` tensor of shape
+//! `[batch, 6, points]` holding the [`observation`] of each position,
+//! the planes described in the [`selfplay`](crate::selfplay) module that
+//! writes training data in the same encoding.
+//!
+//! Its first output is the value head, one number per position from -1,
+//! a sure goat win, to 1, a sure tiger win, in a tensor of shape
+//! `[batch]` or `[batch, 1]`. The evaluator scales it by
+//! [`VALUE_SCALE`] for the search.
+
+use crate::selfplay::{observation, PLANES};
+use crate::{Board, Evaluator};
+use ort::session::Session;
+use ort::value::Tensor;
+use std::env::consts::{DLL_PREFIX, DLL_SUFFIX};
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// Points a value of 1 is worth to the search. Wins found by the search
+/// score ±10000, so even a network sure of the result leaves them
+/// preferred to any position it judges.
+pub const VALUE_SCALE: f32 = 1000.0;
+
+/// Scores positions with an ONNX value network. Set one on a board with
+/// [`Board::set_evaluator`]; give [`Mcts`](crate::Mcts) a `leaf_batch`
+/// above 1 to run the network on several positions at once.
+#[derive(Debug)]
+pub struct OnnxEvaluator {
+    session: Mutex<Session>,
+    /// Points on the board the model was trained for, if it says.
+    points: Option<usize>,
+}
+
+impl OnnxEvaluator {
+    /// Loads the model at `path`, checking that it takes a single input of
+    /// the shape described in the [module documentation](self). Fails if
+    /// ONNX Runtime can't be loaded, the file can't be read or the model
+    /// doesn't fit.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<OnnxEvaluator> {
+        let invalid = |message: String| io::Error::new(ErrorKind::InvalidData, message);
+        load_runtime()?;
+        let session = Session::builder()
+            .and_then(|mut builder| builder.commit_from_file(path))
+            .map_err(io::Error::other)?;
+
+        let [input] = session.inputs() else {
+            return Err(invalid(format!(
+                "model takes {} inputs, not 1",
+                session.inputs().len()
+            )));
+        };
+        let shape = input
+            .dtype()
+            .tensor_shape()
+            .ok_or_else(|| invalid("model input isn't a tensor".to_string()))?;
+        let points = match **shape {
+            [_, planes, points] if planes == PLANES as i64 || planes < 0 => {
+                usize::try_from(points).ok()
+            }
+            _ => {
+                return Err(invalid(format!(
+                    "model input has shape {shape:?}, not [batch, {PLANES}, points]"
+                )))
+            }
+        };
+        if session.outputs().is_empty() {
+            return Err(invalid("model has no outputs".to_string()));
+        }
+        Ok(OnnxEvaluator {
+            session: Mutex::new(session),
+            points,
+        })
+    }
+
+    /// Runs the network on `boards`, giving the value head's output for
+    /// each.
+    fn values(&self, boards: &[Board]) -> ort::Result<Vec<f32>> {
+        let points = boards.first().map_or(0, |board| board.cells.len());
+        let mut input = Vec::with_capacity(boards.len() * PLANES * points);
+        for board in boards {
+            input.extend(observation(board));
+        }
+        let tensor = Tensor::from_array(([boards.len(), PLANES, points], input))?;
+
+        let mut session = self.session.lock().unwrap_or_else(|err| err.into_inner());
+        let outputs = session.run(ort::inputs![tensor])?;
+        let (_, values) = outputs[0].try_extract_tensor::<f32>()?;
+        Ok(values.to_vec())
+    }
+}
+
+impl Evaluator for OnnxEvaluator {
+    fn evaluate(&self, board: &Board) -> i32 {
+        self.evaluate_batch(std::slice::from_ref(board))[0]
+    }
+
+    /// Scores the positions with one run of the network. A board of a
+    /// different size than the model's, or a failed run, falls back on
+    /// the built-in evaluation, as the search has no way to stop for an
+    /// error.
+    fn evaluate_batch(&self, boards: &[Board]) -> Vec<i32> {
+        let fits = |board: &Board| self.points.is_none_or(|points| board.cells.len() == points);
+        let same_size = boards
+            .windows(2)
+            .all(|pair| pair[0].cells.len() == pair[1].cells.len());
+        let values = if !boards.is_empty() && boards.iter().all(fits) && same_size {
+            self.values(boards).ok()
+        } else {
+            None
+        };
+        match values {
+            Some(values) if values.len() == boards.len() => values
+                .into_iter()
+                .map(|value| (value.clamp(-1.0, 1.0) * VALUE_SCALE).round() as i32)
+                .collect(),
+            _ => boards
+                .iter()
+                .map(|board| board.evaluate_detailed().total)
+                .collect(),
+        }
+    }
+}
+
+/// Loads ONNX Runtime if it isn't loaded yet. Loading it here reports a
+/// missing library as an error, where `ort` would panic on first use.
+fn load_runtime() -> io::Result<()> {
+    // `ort` only survives one attempt, so a failure is remembered
+    static RUNTIME: OnceLock<Result<(), String>> = OnceLock::new();
+    RUNTIME
+        .get_or_init(|| {
+            let runtime = match std::env::var_os("ORT_DYLIB_PATH") {
+                Some(path) if !path.is_empty() => PathBuf::from(path),
+                _ => PathBuf::from(format!("{DLL_PREFIX}onnxruntime{DLL_SUFFIX}")),
+            };
+            ort::init_from(runtime)
+                .map(drop)
+                .map_err(|err| err.to_string())
+        })
+        .clone()
+        .map_err(io::Error::other)
+}
@@ -0,0 +1,117 @@
+//! Counting the lines of play from a position, to check move generation.
+//!
+//! [`perft`] plays out every sequence of legal moves to a fixed depth and
+//! counts where they end. The counts for a handful of positions are pinned
+//! in the tests, so a change to how moves are generated, made or taken
+//! back that alters them shows up at once. [`perft_divide`] splits the
+//! count by first move, to narrow down which move a difference comes from.
+//! With the `parallel` feature, `perft_parallel` shares the counting out
+//! between threads for the deeper counts.
+
+use crate::{Board, Move};
+#[cfg(feature = "parallel")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Sequences of `depth` legal moves from `board`, for the side to move and
+/// in the current phase. A line that ends the game early counts for
+/// nothing, and depth 0 counts the position itself.
+pub fn perft(board: &Board, depth: u32) -> u64 {
+    count(&mut board.clone(), depth)
+}
+
+/// [`perft`] for each legal move from `board`, played first and counted
+/// `depth - 1` moves further, in the order [`Board::legal_moves`] gives
+/// them.
+pub fn perft_divide(board: &Board, depth: u32) -> Vec<(Move, u64)> {
+    if depth == 0 || board.is_game_over() {
+        return Vec::new();
+    }
+    let mut board = board.clone();
+    board
+        .legal_moves()
+        .into_iter()
+        .map(|mv| {
+            let nodes = count_after(&mut board, &mv, depth - 1);
+            (mv, nodes)
+        })
+        .collect()
+}
+
+/// [`perft`] counted on `threads` threads, each on its own copy of the
+/// board. The lines are split by their first move, and by their first two
+/// when that gives too few to keep every thread busy.
+#[cfg(feature = "parallel")]
+pub fn perft_parallel(board: &Board, depth: u32, threads: usize) -> u64 {
+    if depth < 2 || threads < 2 || board.is_game_over() {
+        return perft(board, depth);
+    }
+    let mut jobs = after_each_move(board);
+    let mut depth = depth - 1;
+    if jobs.len() < threads * 4 && depth > 1 {
+        // A line that ends the game after one move counts for nothing
+        jobs = jobs
+            .iter()
+            .filter(|board| !board.is_game_over())
+            .flat_map(after_each_move)
+            .collect();
+        depth -= 1;
+    }
+    let next = AtomicUsize::new(0);
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads.min(jobs.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut nodes = 0;
+                    while let Some(job) = jobs.get(next.fetch_add(1, Ordering::Relaxed)) {
+                        nodes += count(&mut job.clone(), depth);
+                    }
+                    nodes
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .map(|worker| worker.join().expect("perft worker panicked"))
+            .sum()
+    })
+}
+
+/// The position after each legal move from `board`.
+#[cfg(feature = "parallel")]
+fn after_each_move(board: &Board) -> Vec<Board> {
+    board
+        .legal_moves()
+        .iter()
+        .map(|mv| {
+            let mut after = board.clone();
+            assert!(after.make_move(mv), "generated an illegal move {mv:?}");
+            after
+        })
+        .collect()
+}
+
+fn count(board: &mut Board, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    if board.is_game_over() {
+        return 0;
+    }
+    let moves = board.generate_legal_moves();
+    if depth == 1 {
+        // Every move ends a line, whether or not it ends the game
+        return moves.len() as u64;
+    }
+    moves
+        .iter()
+        .map(|mv| count_after(board, mv, depth - 1))
+        .sum()
+}
+
+/// Plays `mv`, counts `depth` moves on and takes it back.
+fn count_after(board: &mut Board, mv: &Move, depth: u32) -> u64 {
+    assert!(board.make_move(mv), "generated an illegal move {mv:?}");
+    let nodes = count(board, depth);
+    assert!(board.undo(), "couldn't take back {mv:?}");
+    nodes
+}
@@ -0,0 +1,805 @@
+//! Positions on their own, apart from the game that led to them.
+//!
+//! A [`PositionView`] is the pieces, the counters and the side to move,
+//! with none of a [`Board`]'s history, clock or AI settings. It never
+//! changes once made, and its pieces and rules are shared rather than
+//! copied, so a view can be cloned cheaply and handed to any number of
+//! threads at once: one to draw the position, another to analyse it.
+//!
+//! The read-only questions about a position, such as its legal moves,
+//! threats and evaluation, are answered the same way for a board and a
+//! view, from the pieces they both borrow.
+
+use crate::{
+    Board, EvalBreakdown, EvalWeights, GameResult, Jump, Layout, Move, MultiCapture, Piece,
+    Position, RuleSet, Side, TerminationReason, Theme, Topology, Winner,
+};
+use colored::Colorize;
+use std::fmt::Display;
+use std::sync::Arc;
+
+/// A position frozen as it stood on a [`Board`], from
+/// [`Board::position`]. Turn it back into a board to play on with
+/// [`Board::from_position`].
+///
+/// A view knows nothing of the moves that led to it, so its
+/// [`result`](PositionView::result) is the one the pieces alone decide:
+/// the shuttle rule, clocks and adjudication need the game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PositionView {
+    pub(crate) cells: Arc<[Piece]>,
+    pub(crate) rules: Arc<RuleSet>,
+    pub(crate) goats_in_hand: u32,
+    pub(crate) captured_goats: u32,
+    pub(crate) ply: usize,
+}
+
+impl PositionView {
+    fn pieces(&self) -> Pieces<'_> {
+        Pieces {
+            cells: &self.cells,
+            rules: &self.rules,
+            goats_in_hand: self.goats_in_hand,
+            captured_goats: self.captured_goats,
+            ply: self.ply,
+        }
+    }
+
+    /// Every point of the board, in the topology's order.
+    pub fn cells(&self) -> &[Piece] {
+        &self.cells
+    }
+
+    /// Returns the piece at `pos`, or `None` if it's off the board.
+    pub fn get(&self, pos: Position) -> Option<Piece> {
+        self.cells.get(pos.0).copied()
+    }
+
+    pub fn goats_in_hand(&self) -> u32 {
+        self.goats_in_hand
+    }
+
+    pub fn captured_goats(&self) -> u32 {
+        self.captured_goats
+    }
+
+    /// Number of half-moves played before the position.
+    pub fn ply(&self) -> usize {
+        self.ply
+    }
+
+    /// The side whose turn it is. Goats move on even plies.
+    pub fn side_to_move(&self) -> Side {
+        self.pieces().side_to_move()
+    }
+
+    pub fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    /// The shape of the board the position is on.
+    pub fn topology(&self) -> &Topology {
+        &self.rules.topology
+    }
+
+    pub fn goats(&self) -> impl Iterator<Item = Position> + '_ {
+        self.pieces().positions_of(Piece::Goat)
+    }
+
+    pub fn tigers(&self) -> impl Iterator<Item = Position> + '_ {
+        self.pieces().positions_of(Piece::Tiger)
+    }
+
+    /// Every legal move for the side to move, as [`Board::legal_moves`].
+    pub fn legal_moves(&self) -> Vec<Move> {
+        self.pieces().legal_moves()
+    }
+
+    pub fn get_valid_tiger_moves(&self, pos: usize) -> Vec<Position> {
+        self.pieces().get_valid_tiger_moves(pos)
+    }
+
+    pub fn get_valid_goat_moves(&self, pos: usize) -> Vec<Position> {
+        self.pieces().get_valid_goat_moves(pos)
+    }
+
+    /// See [`Board::capturing_moves`].
+    pub fn capturing_moves(&self) -> Vec<Move> {
+        self.pieces().capturing_moves()
+    }
+
+    /// See [`Board::threatened_goats`].
+    pub fn threatened_goats(&self) -> Vec<usize> {
+        self.pieces().threatened_goats()
+    }
+
+    /// See [`Board::is_goat_threatened`].
+    pub fn is_goat_threatened(&self, pos: usize) -> bool {
+        self.pieces().is_goat_threatened(pos)
+    }
+
+    /// See [`Board::is_square_safe_for_goat`].
+    pub fn is_square_safe_for_goat(&self, pos: usize) -> bool {
+        self.pieces().is_square_safe_for_goat(pos)
+    }
+
+    /// The result the pieces decide on their own: enough goats captured,
+    /// no goats left to capture enough with, or the tigers trapped.
+    pub fn result(&self) -> Option<GameResult> {
+        self.pieces().result()
+    }
+
+    /// The evaluation [`Board::evaluate_detailed`] gives the position
+    /// with `weights`, a finished game scored by its result alone.
+    pub fn evaluate_detailed(&self, weights: &EvalWeights) -> EvalBreakdown {
+        let winner = self.result().map_or(Winner::None, |result| result.winner);
+        self.pieces().evaluate(weights, winner)
+    }
+
+    /// The position as [`Board::to_fen`] writes it.
+    pub fn to_fen(&self) -> String {
+        self.pieces().to_fen()
+    }
+}
+
+impl Display for PositionView {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.pieces().render(f)
+    }
+}
+
+impl From<&Board> for PositionView {
+    fn from(board: &Board) -> Self {
+        board.position()
+    }
+}
+
+/// The pieces of a board or a view, borrowed, which the read-only
+/// questions about a position are answered from.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Pieces<'a> {
+    pub(crate) cells: &'a [Piece],
+    pub(crate) rules: &'a RuleSet,
+    pub(crate) goats_in_hand: u32,
+    pub(crate) captured_goats: u32,
+    pub(crate) ply: usize,
+}
+
+impl<'a> Pieces<'a> {
+    pub(crate) fn side_to_move(self) -> Side {
+        if self.ply.is_multiple_of(2) {
+            Side::Goats
+        } else {
+            Side::Tigers
+        }
+    }
+
+    pub(crate) fn positions_of(self, piece: Piece) -> impl Iterator<Item = Position> + 'a {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(move |&(_, &cell)| cell == piece)
+            .map(|(pos, _)| Position(pos))
+    }
+
+    fn tigers(self) -> impl Iterator<Item = Position> + 'a {
+        self.positions_of(Piece::Tiger)
+    }
+
+    fn goats(self) -> impl Iterator<Item = Position> + 'a {
+        self.positions_of(Piece::Goat)
+    }
+
+    /// Tigers win once they've captured enough goats, goats once the
+    /// tigers can't move; the rules may also give up on tigers that can no
+    /// longer capture enough.
+    pub(crate) fn result(self) -> Option<GameResult> {
+        if self.captured_goats >= self.rules.capture_threshold {
+            return Some(GameResult {
+                winner: Winner::Tigers,
+                reason: TerminationReason::GoatsCaptured,
+            });
+        }
+
+        if let Some(outcome) = self.rules.adjudicate_hopeless {
+            if !self.tigers_can_still_win() {
+                return Some(GameResult {
+                    winner: outcome.winner(),
+                    reason: TerminationReason::CaptureThresholdUnreachable,
+                });
+            }
+        }
+
+        // If any tiger can move, game is not over
+        if self.any_tiger_can_move() {
+            return None;
+        }
+
+        // If we get here, no tiger can move
+        Some(GameResult {
+            winner: Winner::Goats,
+            reason: TerminationReason::TigersTrapped,
+        })
+    }
+
+    /// Whether some tiger has a legal move. Stops at the first one found
+    /// without building any move lists, as the search asks at every node.
+    pub(crate) fn any_tiger_can_move(self) -> bool {
+        let topology = &self.rules.topology;
+        self.cells.iter().enumerate().any(|(pos, &piece)| {
+            piece == Piece::Tiger
+                && (topology
+                    .neighbors(pos)
+                    .iter()
+                    .any(|&to| self.cells[to] == Piece::Empty)
+                    || topology.jumps_from(pos).iter().any(|jump| {
+                        self.cells[jump.over] == Piece::Goat && self.cells[jump.to] == Piece::Empty
+                    }))
+        })
+    }
+
+    /// Whether enough goats are left, on the board and in hand, for the
+    /// tigers to still reach the capture threshold.
+    pub(crate) fn tigers_can_still_win(self) -> bool {
+        let remaining = self.goats().count() as u32 + self.goats_in_hand;
+        self.captured_goats + remaining >= self.rules.capture_threshold
+    }
+
+    pub(crate) fn get_valid_tiger_moves(self, pos: usize) -> Vec<Position> {
+        // Step to an adjacent empty point
+        let mut moves: Vec<Position> = self
+            .rules
+            .topology
+            .neighbors(pos)
+            .iter()
+            .filter(|&&to| self.cells[to] == Piece::Empty)
+            .map(|&to| Position(to))
+            .collect();
+
+        // Or jump over a goat to an empty point beyond it
+        moves.extend(
+            self.rules
+                .topology
+                .jumps_from(pos)
+                .iter()
+                .filter(|jump| {
+                    self.cells[jump.over] == Piece::Goat && self.cells[jump.to] == Piece::Empty
+                })
+                .map(|jump| Position(jump.to)),
+        );
+        moves
+    }
+
+    pub(crate) fn get_valid_goat_moves(self, pos: usize) -> Vec<Position> {
+        if self.goats_in_hand > 0 && !self.rules.goats_may_move_during_placement {
+            return Vec::new();
+        }
+
+        self.rules
+            .topology
+            .neighbors(pos)
+            .iter()
+            .filter(|&&to| self.cells[to] == Piece::Empty)
+            .map(|&to| Position(to))
+            .collect()
+    }
+
+    pub(crate) fn get_captured_position(self, from: usize, to: usize) -> Option<usize> {
+        self.rules
+            .topology
+            .jump_between(from, to)
+            .map(|jump| jump.over)
+            .filter(|&over| self.cells[over] == Piece::Goat)
+    }
+
+    pub(crate) fn get_all_valid_tiger_moves(self) -> Vec<(usize, usize)> {
+        let mut all_moves = Vec::new();
+
+        // Find all tigers
+        for Position(pos) in self.tigers() {
+            // Get valid moves for this tiger
+            for move_pos in self.get_valid_tiger_moves(pos) {
+                all_moves.push((pos, move_pos.0));
+            }
+        }
+
+        all_moves
+    }
+
+    pub(crate) fn get_all_valid_goat_moves(self) -> Vec<(usize, usize)> {
+        let mut all_moves = Vec::new();
+
+        if self.goats_in_hand > 0 {
+            // Can place a new goat
+            for Position(pos) in self.positions_of(Piece::Empty) {
+                all_moves.push((pos, pos)); // From and to are same for placement
+            }
+            if !self.rules.goats_may_move_during_placement {
+                return all_moves; // Goats stay put until every goat is placed
+            }
+        }
+
+        // Move existing goats
+        for Position(pos) in self.goats() {
+            // Get valid moves for this goat
+            for move_pos in self.get_valid_goat_moves(pos) {
+                all_moves.push((pos, move_pos.0));
+            }
+        }
+
+        all_moves
+    }
+
+    /// The legal moves for the side to move.
+    pub(crate) fn legal_moves(self) -> Vec<Move> {
+        match self.side_to_move() {
+            Side::Tigers => self.tiger_moves(),
+            Side::Goats => self
+                .get_all_valid_goat_moves()
+                .into_iter()
+                .map(|(from, to)| {
+                    if from == to {
+                        Move::PlaceGoat { position: to }
+                    } else {
+                        Move::MoveGoat { from, to }
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    pub(crate) fn tiger_moves(self) -> Vec<Move> {
+        let mut moves = Vec::new();
+        for (from, to) in self.get_all_valid_tiger_moves() {
+            let captured_position = self.get_captured_position(from, to);
+            match captured_position {
+                Some(over) if self.rules.multi_capture != MultiCapture::Off => {
+                    let mut cells = self.cells.to_vec();
+                    let mut chain = vec![Jump { from, over, to }];
+                    cells[from] = Piece::Empty;
+                    cells[over] = Piece::Empty;
+                    cells[to] = Piece::Tiger;
+                    self.extend_chain(&mut cells, &mut chain, &mut moves);
+                }
+                _ => moves.push(Move::MoveTiger {
+                    from,
+                    to,
+                    captured_position,
+                }),
+            }
+        }
+        if self.rules.forced_capture && moves.iter().any(Move::is_capture) {
+            moves.retain(Move::is_capture);
+        }
+        moves
+    }
+
+    /// Adds the capture chain in `chain`, and every continuation of it, to
+    /// `moves`. `cells` holds the board as it stands after the chain.
+    fn extend_chain(self, cells: &mut [Piece], chain: &mut Vec<Jump>, moves: &mut Vec<Move>) {
+        let landing = chain.last().expect("chains start with a jump").to;
+        let continuations: Vec<Jump> = self
+            .rules
+            .topology
+            .jumps_from(landing)
+            .iter()
+            .filter(|jump| cells[jump.over] == Piece::Goat && cells[jump.to] == Piece::Empty)
+            .copied()
+            .collect();
+
+        // Forced captures leave no choice to stop a chain early either
+        let may_stop =
+            self.rules.multi_capture == MultiCapture::Optional && !self.rules.forced_capture;
+        if continuations.is_empty() || may_stop {
+            moves.push(match chain.as_slice() {
+                [jump] => Move::MoveTiger {
+                    from: jump.from,
+                    to: jump.to,
+                    captured_position: Some(jump.over),
+                },
+                _ => Move::MultiJump(chain.clone()),
+            });
+        }
+
+        for jump in continuations {
+            cells[jump.from] = Piece::Empty;
+            cells[jump.over] = Piece::Empty;
+            cells[jump.to] = Piece::Tiger;
+            chain.push(jump);
+            self.extend_chain(cells, chain, moves);
+            chain.pop();
+            cells[jump.to] = Piece::Empty;
+            cells[jump.over] = Piece::Goat;
+            cells[jump.from] = Piece::Tiger;
+        }
+    }
+
+    pub(crate) fn capturing_moves(self) -> Vec<Move> {
+        let mut moves = Vec::new();
+        for tiger in self.tigers() {
+            for &jump in self.rules.topology.jumps_from(tiger.0) {
+                if !self.is_open_jump(jump) {
+                    continue;
+                }
+                if self.rules.multi_capture == MultiCapture::Off {
+                    moves.push(Move::MoveTiger {
+                        from: jump.from,
+                        to: jump.to,
+                        captured_position: Some(jump.over),
+                    });
+                } else {
+                    let mut cells = self.cells.to_vec();
+                    cells[jump.from] = Piece::Empty;
+                    cells[jump.over] = Piece::Empty;
+                    cells[jump.to] = Piece::Tiger;
+                    self.extend_chain(&mut cells, &mut vec![jump], &mut moves);
+                }
+            }
+        }
+        moves
+    }
+
+    /// Whether a tiger stands ready to make `jump`: a tiger at its start,
+    /// a goat to capture and an empty point to land on.
+    fn is_open_jump(self, jump: Jump) -> bool {
+        self.cells[jump.from] == Piece::Tiger
+            && self.cells[jump.over] == Piece::Goat
+            && self.cells[jump.to] == Piece::Empty
+    }
+
+    pub(crate) fn threatened_goats(self) -> Vec<usize> {
+        self.goats()
+            .map(|goat| goat.0)
+            .filter(|&goat| self.is_goat_threatened(goat))
+            .collect()
+    }
+
+    pub(crate) fn is_goat_threatened(self, pos: usize) -> bool {
+        if self.cells.get(pos) != Some(&Piece::Goat) {
+            return false;
+        }
+        let topology = &self.rules.topology;
+        topology
+            .neighbors(pos)
+            .iter()
+            .filter(|&&tiger| self.cells[tiger] == Piece::Tiger)
+            .flat_map(|&tiger| topology.jumps_from(tiger))
+            .any(|&jump| jump.over == pos && self.cells[jump.to] == Piece::Empty)
+    }
+
+    pub(crate) fn is_square_safe_for_goat(self, pos: usize) -> bool {
+        if self
+            .cells
+            .get(pos)
+            .is_none_or(|&piece| piece == Piece::Tiger)
+        {
+            return false;
+        }
+        match self.goat_landings(pos, None)[..] {
+            [] => true,
+            [landing] => self.can_cover(pos, landing),
+            _ => false,
+        }
+    }
+
+    /// The points tigers could land on jumping a goat on `pos`, counting
+    /// `vacated` as empty for a goat about to leave it.
+    pub(crate) fn goat_landings(self, pos: usize, vacated: Option<usize>) -> Vec<usize> {
+        let topology = &self.rules.topology;
+        let mut landings: Vec<usize> = topology
+            .neighbors(pos)
+            .iter()
+            .filter(|&&tiger| self.cells[tiger] == Piece::Tiger)
+            .flat_map(|&tiger| topology.jumps_from(tiger))
+            .filter(|jump| {
+                jump.over == pos
+                    && (self.cells[jump.to] == Piece::Empty || Some(jump.to) == vacated)
+            })
+            .map(|jump| jump.to)
+            .collect();
+        landings.sort_unstable();
+        landings.dedup();
+        landings
+    }
+
+    /// Whether a goat other than the one on `pos` could move onto
+    /// `landing` in one go, without leaving a point a tiger could then
+    /// land on behind `pos` instead.
+    fn can_cover(self, pos: usize, landing: usize) -> bool {
+        if self.goats_in_hand > 0 {
+            return true;
+        }
+        self.rules
+            .topology
+            .neighbors(landing)
+            .iter()
+            .filter(|&&from| from != pos && self.cells[from] == Piece::Goat)
+            .any(|&from| self.goat_landings(pos, Some(from)) == [landing])
+    }
+
+    /// How many moves a tiger stepping from `from` to `to` would have
+    /// there, counting the step back.
+    pub(crate) fn tiger_moves_after_step(self, from: usize, to: usize) -> usize {
+        let topology = &self.rules.topology;
+        let free = |pos: usize| pos == from || self.cells[pos] == Piece::Empty;
+        let steps = topology
+            .neighbors(to)
+            .iter()
+            .filter(|&&next| free(next))
+            .count();
+        let jumps = topology
+            .jumps_from(to)
+            .iter()
+            .filter(|jump| self.cells[jump.over] == Piece::Goat && free(jump.to))
+            .count();
+        steps + jumps
+    }
+
+    /// How many moves the tiger on `tiger` would have if a goat stood on
+    /// `entry` that it couldn't jump.
+    pub(crate) fn tiger_moves_with_entry_blocked(self, tiger: usize, entry: usize) -> usize {
+        let topology = &self.rules.topology;
+        let free = |pos: usize| pos != entry && self.cells[pos] == Piece::Empty;
+        let steps = topology
+            .neighbors(tiger)
+            .iter()
+            .filter(|&&next| free(next))
+            .count();
+        let jumps = topology
+            .jumps_from(tiger)
+            .iter()
+            .filter(|jump| self.cells[jump.over] == Piece::Goat && free(jump.to))
+            .count();
+        steps + jumps
+    }
+
+    /// The evaluation of the position with `weights`, from the tigers'
+    /// side, term by term. A finished game, one with a `winner`, is
+    /// scored by its result alone.
+    pub(crate) fn evaluate(self, weights: &EvalWeights, winner: Winner) -> EvalBreakdown {
+        let mut score = EvalBreakdown::default();
+
+        // If game is over, return a large value
+        let result = match winner {
+            Winner::Tigers => Some(10000),
+            Winner::Goats => Some(-10000),
+            Winner::Draw => Some(0),
+            Winner::None => None,
+        };
+        if let Some(value) = result {
+            score.add("result", 1, value);
+            return score;
+        }
+
+        // Captured goats count most
+        score.add(
+            "captured goats",
+            self.captured_goats as i32,
+            weights.captured_goat,
+        );
+
+        // A game the tigers can't win any more is nearly as good as lost
+        score.add(
+            "tigers cannot win",
+            !self.tigers_can_still_win() as i32,
+            weights.tigers_cannot_win,
+        );
+
+        // Every tiger's moves, worked out once for all the terms below
+        let tiger_moves: Vec<Vec<Position>> = self
+            .tigers()
+            .map(|Position(pos)| self.get_valid_tiger_moves(pos))
+            .collect();
+
+        // Trapped tigers are out of the game until freed
+        let trapped_tigers = tiger_moves.iter().filter(|moves| moves.is_empty()).count();
+        score.add(
+            "trapped tigers",
+            trapped_tigers as i32,
+            weights.tiger_trapped,
+        );
+
+        // Tigers need room: every move counts, and so does being close to
+        // trapped before a tiger is fully hemmed in
+        let tiger_mobility: usize = tiger_moves.iter().map(Vec::len).sum();
+        score.add(
+            "tiger mobility",
+            tiger_mobility as i32,
+            weights.tiger_mobility,
+        );
+        let nearly_trapped = tiger_moves
+            .iter()
+            .filter(|moves| (1..=2).contains(&moves.len()))
+            .count();
+        score.add(
+            "nearly trapped tigers",
+            nearly_trapped as i32,
+            weights.tiger_nearly_trapped,
+        );
+
+        // A tiger that can only step into a dead end gets sealed in next
+        let pocketed = self
+            .tigers()
+            .zip(&tiger_moves)
+            .filter(|(Position(from), moves)| {
+                (1..=2).contains(&moves.len())
+                    && moves.iter().all(|&Position(to)| {
+                        self.get_captured_position(*from, to).is_none()
+                            && self.tiger_moves_after_step(*from, to) <= 1
+                    })
+            })
+            .count();
+        score.add("pocketed tigers", pocketed as i32, weights.tiger_pocketed);
+
+        // A tiger with a single way out is shut in by one goat on it,
+        // which costs the tigers more the more goats they still need
+        let sealable = self
+            .tigers()
+            .zip(&tiger_moves)
+            .filter(|(Position(tiger), moves)| {
+                (1..=2).contains(&moves.len())
+                    && moves
+                        .iter()
+                        .any(|&Position(to)| self.tiger_moves_with_entry_blocked(*tiger, to) == 0)
+            })
+            .count();
+        let goats_needed = self
+            .rules
+            .capture_threshold
+            .saturating_sub(self.captured_goats);
+        score.add(
+            "sealable tigers",
+            (sealable as u32 * goats_needed) as i32,
+            weights.tiger_sealable,
+        );
+
+        // Once every goat is placed, goats that can't move lose the game
+        let goat_mobility: usize = if self.goats_in_hand == 0 {
+            self.goats()
+                .map(|Position(pos)| self.get_valid_goat_moves(pos).len())
+                .sum()
+        } else {
+            0
+        };
+        score.add("goat mobility", goat_mobility as i32, weights.goat_mobility);
+
+        // Where the pieces stand, on the board the tables are for
+        if self.rules.topology.layout() == Layout::Grid(5) {
+            let (in_hand, starting) = (self.goats_in_hand, self.rules.starting_goats);
+            let tiger_squares = weights.tiger_squares.blend(in_hand, starting);
+            let goat_squares = weights.goat_squares.blend(in_hand, starting);
+            let tigers: i32 = self.tigers().map(|Position(pos)| tiger_squares[pos]).sum();
+            let goats: i32 = self.goats().map(|Position(pos)| goat_squares[pos]).sum();
+            score.add("tiger squares", 1, tigers);
+            score.add("goat squares", 1, goats);
+        }
+
+        // Goats hold together in walls, start out on the edge where they
+        // can't be surrounded, and are safe with the point behind them
+        // covered
+        let topology = &self.rules.topology;
+        let mut adjacent_goats = 0;
+        let mut edge_goats = 0;
+        let mut backed_goats = 0;
+        for Position(pos) in self.goats() {
+            adjacent_goats += topology
+                .neighbors(pos)
+                .iter()
+                .filter(|&&next| next > pos && self.cells[next] == Piece::Goat)
+                .count();
+            if !topology.is_interior(pos) {
+                edge_goats += 1;
+            }
+            let backed = topology.neighbors(pos).iter().all(|&next| {
+                topology
+                    .jumps_from(next)
+                    .iter()
+                    .filter(|jump| jump.over == pos)
+                    .all(|jump| self.cells[jump.to] != Piece::Empty)
+            });
+            if backed {
+                backed_goats += 1;
+            }
+        }
+        if self.goats_in_hand == 0 {
+            edge_goats = 0;
+        }
+        score.add(
+            "adjacent goats",
+            adjacent_goats as i32,
+            weights.goat_adjacency,
+        );
+        score.add("edge goats", edge_goats, weights.goat_on_edge);
+        score.add("backed goats", backed_goats, weights.goat_backed);
+
+        // Goats left open to a jump
+        let capturable_goats = self
+            .tigers()
+            .zip(&tiger_moves)
+            .flat_map(|(Position(from), moves)| {
+                moves
+                    .iter()
+                    .filter(move |Position(to)| self.get_captured_position(from, *to).is_some())
+            })
+            .count();
+        score.add(
+            "capturable goats",
+            capturable_goats as i32,
+            weights.goat_capturable,
+        );
+
+        // Goats no single reply can save
+        let lost_goats = self
+            .goats()
+            .filter(|&Position(pos)| !self.is_square_safe_for_goat(pos))
+            .count();
+        score.add("lost goats", lost_goats as i32, weights.goat_lost);
+
+        score
+    }
+
+    pub(crate) fn to_fen(self) -> String {
+        let mut rows = Vec::new();
+        for row in self.rules.topology.rows() {
+            let mut text = String::new();
+            let mut empty = 0;
+            for piece in row.iter().map(|&pos| self.cells[pos]) {
+                match piece {
+                    Piece::Empty => empty += 1,
+                    Piece::Tiger | Piece::Goat => {
+                        if empty > 0 {
+                            text.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        text.push(if piece == Piece::Tiger { 'T' } else { 'G' });
+                    }
+                }
+            }
+            if empty > 0 {
+                text.push_str(&empty.to_string());
+            }
+            rows.push(text);
+        }
+
+        let side = match self.side_to_move() {
+            Side::Goats => 'g',
+            Side::Tigers => 't',
+        };
+        format!(
+            "{} {} {} {} {}",
+            rows.join("/"),
+            side,
+            self.goats_in_hand,
+            self.captured_goats,
+            self.ply / 2 + 1
+        )
+    }
+
+    /// Draws the board, a row of points to a line.
+    pub(crate) fn render(self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let topology = &self.rules.topology;
+        for row in topology.rows() {
+            write!(f, "   ")?; // Initial spacing
+            let mut col = 0;
+            for &pos in &row {
+                // Indent to the point's column so uneven rows line up
+                let target = topology.coords(pos).1;
+                write!(f, "{}", "  ".repeat(target - col))?;
+                col = target + 1;
+
+                let theme = Theme::default();
+                let piece = match self.cells[pos] {
+                    Piece::Tiger => "T".color(theme.tiger).bold().to_string(),
+                    Piece::Goat => "G".color(theme.goat).bold().to_string(),
+                    Piece::Empty => "·".to_string(),
+                };
+                write!(f, "{piece}")?;
+                if pos != *row.last().unwrap() {
+                    write!(f, " ")?; // Add space between pieces for better readability
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,539 @@
+//! Tactic puzzles: a starting position and the lines that solve it.
+//!
+//! Puzzles are written in a small text format, one block of `key: value`
+//! lines per puzzle, with blank lines between puzzles and `#` starting a
+//! comment line:
+//!
+//! ```text
+//! title: Tigers to capture in 1
+//! fen: TG2T/5/2G2/5/T3T t 18 0 2
+//! solution: A1xB1-C1
+//!
+//! title: Goats to trap the last tiger
+//! to-move: goats
+//! in-hand: 0
+//! captured: 3
+//! diagram:
+//! T G G G T
+//! G G . G G
+//! G . G . G
+//! G G G G G
+//! T G . G T
+//! solution: C4-C5
+//! ```
+//!
+//! The position is given either as `fen`, in the format of
+//! [`Board::to_fen`], or as a `diagram` in the format of
+//! [`Board::from_diagram`] with optional `to-move`, `in-hand` and
+//! `captured` lines. Each `solution` lists the moves of one accepted line in
+//! [move notation](crate::notation), both sides' moves in turn.
+
+use crate::notation::{format_move, parse_move};
+use crate::{Board, GameRecord, Move, ParseError, RuleSet, Side, TerminationReason};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::collections::HashSet;
+use std::fmt::Display;
+
+/// The built-in puzzles shipped with the crate.
+const BUILTIN: &str = include_str!("../data/puzzles.txt");
+
+/// A position with one or more accepted solution lines.
+#[derive(Debug, Clone)]
+pub struct Puzzle {
+    title: String,
+    start: Board,
+    solutions: Vec<Vec<Move>>,
+    /// Position after each move of each solution, as FEN.
+    positions: Vec<Vec<String>>,
+}
+
+/// How far an attempt at a puzzle got.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PuzzleResult {
+    /// The moves reach the end of a solution.
+    Solved,
+    /// Every move so far is right, but the puzzle isn't finished yet.
+    Incomplete,
+    /// The move with this index is illegal or leaves every solution.
+    Wrong { ply: usize },
+}
+
+impl Puzzle {
+    /// Builds a puzzle starting from `start`. Returns `None` if there are
+    /// no solutions, or a solution is empty or contains an illegal move.
+    pub fn new(title: impl Into<String>, start: Board, solutions: Vec<Vec<Move>>) -> Option<Self> {
+        if solutions.is_empty() {
+            return None;
+        }
+        let mut positions = Vec::new();
+        for line in &solutions {
+            if line.is_empty() {
+                return None;
+            }
+            let mut board = start.clone();
+            let mut fens = Vec::new();
+            for mv in line {
+                if !board.make_move(mv) {
+                    return None;
+                }
+                fens.push(board.to_fen());
+            }
+            positions.push(fens);
+        }
+        Some(Puzzle {
+            title: title.into(),
+            start,
+            solutions,
+            positions,
+        })
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// The position the puzzle starts from.
+    pub fn board(&self) -> &Board {
+        &self.start
+    }
+
+    /// The side that has to find the solution.
+    pub fn side_to_move(&self) -> Side {
+        self.start.side_to_move()
+    }
+
+    pub fn solutions(&self) -> &[Vec<Move>] {
+        &self.solutions
+    }
+
+    /// Checks an attempt, given as the moves of both sides from the start.
+    ///
+    /// Moves are compared by the positions they lead to, so when several
+    /// solutions are listed an attempt may switch between them, e.g. by
+    /// playing two moves in the other line's order.
+    pub fn check(&self, moves: &[Move]) -> PuzzleResult {
+        let mut board = self.start.clone();
+        for (ply, mv) in moves.iter().enumerate() {
+            if !board.make_move(mv) {
+                return PuzzleResult::Wrong { ply };
+            }
+            let fen = board.to_fen();
+            if !self
+                .positions
+                .iter()
+                .any(|line| line.get(ply) == Some(&fen))
+            {
+                return PuzzleResult::Wrong { ply };
+            }
+        }
+
+        let fen = board.to_fen();
+        if self
+            .positions
+            .iter()
+            .any(|line| line.len() == moves.len() && line.last() == Some(&fen))
+        {
+            PuzzleResult::Solved
+        } else {
+            PuzzleResult::Incomplete
+        }
+    }
+}
+
+/// Writes the puzzle in the text format, with the position as a FEN.
+impl Display for Puzzle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "title: {}", self.title)?;
+        writeln!(f, "fen: {}", self.start.to_fen())?;
+        for line in &self.solutions {
+            let mut board = self.start.clone();
+            let mut moves = Vec::new();
+            for mv in line {
+                moves.push(format_move(board.topology(), mv));
+                board.make_move(mv);
+            }
+            writeln!(f, "solution: {}", moves.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+/// The puzzles that ship with the crate.
+pub fn builtin() -> Vec<Puzzle> {
+    parse_puzzles(BUILTIN).expect("built-in puzzles are valid")
+}
+
+/// Reads puzzles in the text format described in the [module
+/// documentation](self).
+pub fn parse_puzzles(text: &str) -> Result<Vec<Puzzle>, ParseError> {
+    let mut puzzles = Vec::new();
+    let mut block = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        if line.trim_start().starts_with('#') {
+            continue;
+        }
+        if line.trim().is_empty() {
+            if !block.is_empty() {
+                puzzles.push(parse_puzzle(&block)?);
+                block.clear();
+            }
+            continue;
+        }
+        block.push((index + 1, line));
+    }
+    if !block.is_empty() {
+        puzzles.push(parse_puzzle(&block)?);
+    }
+    Ok(puzzles)
+}
+
+/// One `key: value` line of a puzzle, with the column its value starts at.
+struct Field<'a> {
+    line: usize,
+    column: usize,
+    key: &'a str,
+    value: &'a str,
+}
+
+/// Parses one puzzle from its numbered, non-blank lines.
+fn parse_puzzle(lines: &[(usize, &str)]) -> Result<Puzzle, ParseError> {
+    let first_line = lines[0].0;
+    let mut fields = Vec::new();
+    let mut diagram: Option<(usize, String)> = None;
+    for &(line, text) in lines {
+        match text.split_once(':') {
+            Some((key, value)) => {
+                let column = key.len() + 2 + (value.len() - value.trim_start().len());
+                fields.push(Field {
+                    line,
+                    column,
+                    key: key.trim(),
+                    value: value.trim(),
+                });
+            }
+            // Lines without a key are rows of a diagram
+            None => match fields.last() {
+                Some(field) if field.key == "diagram" => {
+                    let (_, rows) = diagram.get_or_insert((line, String::new()));
+                    rows.push_str(text);
+                    rows.push('\n');
+                }
+                _ => return Err(ParseError::new(line, 1, "expected 'key: value'")),
+            },
+        }
+    }
+
+    let mut title = None;
+    let mut fen = None;
+    let mut side = Side::Goats;
+    let mut goats_in_hand = 0;
+    let mut captured_goats = 0;
+    let mut solutions = Vec::new();
+    for field in &fields {
+        let number = |what: &str| {
+            field.value.parse::<u32>().map_err(|_| {
+                ParseError::new(
+                    field.line,
+                    field.column,
+                    format!("invalid {what} '{}'", field.value),
+                )
+            })
+        };
+        match field.key {
+            "title" => title = Some(field.value.to_string()),
+            "fen" => fen = Some(field),
+            "to-move" => {
+                side = match field.value {
+                    "goats" => Side::Goats,
+                    "tigers" => Side::Tigers,
+                    _ => {
+                        return Err(ParseError::new(
+                            field.line,
+                            field.column,
+                            "side to move must be 'goats' or 'tigers'",
+                        ))
+                    }
+                }
+            }
+            "in-hand" => goats_in_hand = number("goats in hand")?,
+            "captured" => captured_goats = number("captured goats")?,
+            "diagram" | "solution" => {}
+            key => {
+                return Err(ParseError::new(
+                    field.line,
+                    1,
+                    format!("unknown key '{key}'"),
+                ))
+            }
+        }
+    }
+
+    let start = match (fen, &diagram) {
+        (Some(field), None) => Board::from_fen(field.value).map_err(|err| {
+            ParseError::new(field.line, field.column + err.column - 1, err.message)
+        })?,
+        (None, Some((line, rows))) => {
+            let mut board = Board::from_diagram(rows, goats_in_hand, captured_goats)
+                .map_err(|err| ParseError::new(line + err.line - 1, err.column, err.message))?;
+            if side == Side::Tigers {
+                board.ply += 1;
+            }
+            board
+        }
+        (Some(field), Some(_)) => {
+            return Err(ParseError::new(
+                field.line,
+                1,
+                "a puzzle has either a fen or a diagram, not both",
+            ))
+        }
+        (None, None) => {
+            return Err(ParseError::new(
+                first_line,
+                1,
+                "puzzle has no position, add a fen or a diagram",
+            ))
+        }
+    };
+
+    for field in fields.iter().filter(|field| field.key == "solution") {
+        let mut board = start.clone();
+        let mut line = Vec::new();
+        let mut column = field.column;
+        for token in field.value.split(' ') {
+            if !token.is_empty() {
+                let mv = parse_move(&board, token).ok_or_else(|| {
+                    ParseError::new(field.line, column, format!("illegal move '{token}'"))
+                })?;
+                board.make_move(&mv);
+                line.push(mv);
+            }
+            column += token.len() + 1;
+        }
+        if line.is_empty() {
+            return Err(ParseError::new(field.line, field.column, "empty solution"));
+        }
+        solutions.push(line);
+    }
+    if solutions.is_empty() {
+        return Err(ParseError::new(first_line, 1, "puzzle has no solution"));
+    }
+
+    let title = title.unwrap_or_else(|| format!("Puzzle on line {first_line}"));
+    Ok(Puzzle::new(title, start, solutions).expect("solutions were checked while parsing"))
+}
+
+/// Settings for [`generate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GeneratorOptions {
+    /// Longest solution to look for, in plies of both sides.
+    pub depth: usize,
+    /// Least the solution must gain for the solving side, in evaluation
+    /// points. A capture is worth 100.
+    pub min_swing: i32,
+    /// Puzzles with more accepted lines than this are left out, as the
+    /// defence has too many equally good answers to list.
+    pub max_solutions: usize,
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> Self {
+        GeneratorOptions {
+            depth: 3,
+            min_swing: 100,
+            max_solutions: 8,
+        }
+    }
+}
+
+/// What the solving side is trying to force.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Goal {
+    /// Capture a goat, beyond the given number already captured.
+    Capture(u32),
+    /// Trap every tiger.
+    Trap,
+}
+
+impl Goal {
+    fn reached(self, board: &Board) -> bool {
+        match self {
+            Goal::Capture(captured) => board.captured_goats > captured,
+            Goal::Trap => board
+                .result()
+                .is_some_and(|result| result.reason == TerminationReason::TigersTrapped),
+        }
+    }
+}
+
+/// Mines games for positions where one side can force a capture or trap
+/// the tigers within `options.depth` plies, with exactly one move that
+/// starts the win.
+///
+/// Only games played from the start under standard rules are used.
+/// Positions that appear in more than one game give one puzzle.
+pub fn generate(games: &[GameRecord], options: &GeneratorOptions) -> Vec<Puzzle> {
+    let mut puzzles = Vec::new();
+    let mut seen = HashSet::new();
+    for game in games {
+        if game.start_ply != 0 || game.rules != RuleSet::standard() {
+            continue;
+        }
+        let mut board = Board::new();
+        for mv in &game.moves {
+            if board.is_game_over() {
+                break;
+            }
+            if seen.insert(board.to_fen()) {
+                if let Some(puzzle) = find_puzzle(&board, options) {
+                    puzzles.push(puzzle);
+                }
+            }
+            if !board.make_move(mv) {
+                break;
+            }
+        }
+    }
+    puzzles
+}
+
+/// Plays `count` games of random legal moves, a cheap source of positions
+/// for [`generate`]. The same seed gives the same games.
+pub fn random_games(count: usize, seed: u64) -> Vec<GameRecord> {
+    // Cut off games where neither side makes progress
+    const MAX_PLIES: usize = 200;
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..count)
+        .map(|_| {
+            let mut board = Board::new();
+            while !board.is_game_over() && board.ply() < MAX_PLIES {
+                let moves = board.generate_legal_moves();
+                let Some(mv) = moves.choose(&mut rng) else {
+                    break;
+                };
+                board.make_move(mv);
+            }
+            board.record()
+        })
+        .collect()
+}
+
+/// Looks for a puzzle for the side to move in `board`.
+fn find_puzzle(board: &Board, options: &GeneratorOptions) -> Option<Puzzle> {
+    let solver = board.side_to_move();
+    let goal = match solver {
+        Side::Tigers => Goal::Capture(board.captured_goats),
+        Side::Goats => Goal::Trap,
+    };
+
+    // The shortest win makes the puzzle; its key move must be the only one
+    let moves = board.legal_moves();
+    let (plies, key) = (1..=options.depth).step_by(2).find_map(|plies| {
+        let winning: Vec<&Move> = moves
+            .iter()
+            .filter(|mv| {
+                let mut child = board.clone();
+                child.make_move(mv);
+                forces(&child, solver, goal, plies - 1)
+            })
+            .collect();
+        (!winning.is_empty()).then_some((plies, winning))
+    })?;
+    let [key] = key.as_slice() else {
+        return None;
+    };
+
+    let mut child = board.clone();
+    child.make_move(key);
+    let mut line = vec![(*key).clone()];
+    let mut solutions = Vec::new();
+    collect_lines(
+        &child,
+        solver,
+        goal,
+        plies - 1,
+        &mut line,
+        &mut solutions,
+        options.max_solutions,
+    );
+    if solutions.is_empty() || solutions.len() > options.max_solutions {
+        return None;
+    }
+
+    // Judge the gain by the first line; they all reach the goal
+    let mut end = board.clone();
+    for mv in &solutions[0] {
+        end.make_move(mv);
+    }
+    let swing = end.evaluate_position() - board.evaluate_position();
+    let swing = match solver {
+        Side::Tigers => swing,
+        Side::Goats => -swing,
+    };
+    if swing < options.min_swing {
+        return None;
+    }
+
+    let moves = plies.div_ceil(2);
+    let title = match goal {
+        Goal::Capture(_) => format!("Tigers to capture in {moves}"),
+        Goal::Trap => format!("Goats to trap the tigers in {moves}"),
+    };
+    Puzzle::new(title, board.clone(), solutions)
+}
+
+/// Whether `solver` can reach `goal` within `plies` plies whatever the
+/// other side does.
+fn forces(board: &Board, solver: Side, goal: Goal, plies: usize) -> bool {
+    if goal.reached(board) {
+        return true;
+    }
+    if plies == 0 || board.is_game_over() {
+        return false;
+    }
+    let moves = board.generate_legal_moves();
+    let mut children = moves.iter().map(|mv| {
+        let mut child = board.clone();
+        child.make_move(mv);
+        forces(&child, solver, goal, plies - 1)
+    });
+    if board.side_to_move() == solver {
+        children.any(|wins| wins)
+    } else {
+        !moves.is_empty() && children.all(|wins| wins)
+    }
+}
+
+/// Adds every line from `board` that keeps forcing `goal` to `lines`: all
+/// winning moves for the solver and all replies for the defender. Stops
+/// once there are more than `limit` lines.
+fn collect_lines(
+    board: &Board,
+    solver: Side,
+    goal: Goal,
+    plies: usize,
+    line: &mut Vec<Move>,
+    lines: &mut Vec<Vec<Move>>,
+    limit: usize,
+) {
+    if goal.reached(board) {
+        lines.push(line.clone());
+        return;
+    }
+    for mv in board.generate_legal_moves() {
+        if lines.len() > limit {
+            return;
+        }
+        let mut child = board.clone();
+        child.make_move(&mv);
+        if board.side_to_move() == solver && !forces(&child, solver, goal, plies - 1) {
+            continue;
+        }
+        line.push(mv);
+        collect_lines(&child, solver, goal, plies - 1, line, lines, limit);
+        line.pop();
+    }
+}
@@ -0,0 +1,253 @@
+//! Reviewing a game once it is over: how many points each move gave away
+//! against the best move the engine finds, and which moves were mistakes.
+//!
+//! A [`Review`] searches every position of a game to the same limits, and
+//! takes what a move lost as the drop in the score from the mover's side
+//! between the position before it and the position after it. A move the
+//! search would have played itself loses nothing. Each move is then
+//! classed by how much it lost, with [`Thresholds`] that can be changed.
+
+use crate::annotate::{annotate_move, MoveReason};
+use crate::notation::format_move;
+use crate::{AiConfig, Board, GameRecord, Move, Side, Topology, Winner};
+use std::fmt::{self, Display};
+
+/// How a game is reviewed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Review {
+    /// Limits on the search of each position. A fixed depth keeps reviews
+    /// of the same game the same, and the review quick.
+    pub limits: AiConfig,
+    pub thresholds: Thresholds,
+}
+
+impl Default for Review {
+    fn default() -> Self {
+        Review {
+            limits: AiConfig {
+                time_limit: None,
+                max_depth: Some(4),
+                ..AiConfig::default()
+            },
+            thresholds: Thresholds::default(),
+        }
+    }
+}
+
+impl Review {
+    /// Reviews every move of `record`. `progress` is called with the
+    /// positions searched so far and the positions to search, one more
+    /// than the moves, as each search ends.
+    ///
+    /// # Panics
+    ///
+    /// If the record's start position doesn't fit its rules or it has a
+    /// move that is illegal where it is played, which can't happen to a
+    /// record from [`Board::record`].
+    pub fn run(&self, record: &GameRecord, mut progress: impl FnMut(usize, usize)) -> GameReview {
+        let mut board = Board::from_fen_with_rules(&record.start, record.rules.clone())
+            .expect("the record's start position fits its rules");
+        let total = record.moves.len() + 1;
+        let (mut best, mut score) = self.search(&board);
+        progress(1, total);
+        let mut moves = Vec::with_capacity(record.moves.len());
+        for (index, mv) in record.moves.iter().enumerate() {
+            let before = board.clone();
+            assert!(board.make_move(mv), "illegal move in the record");
+            let (next_best, next_score) = self.search(&board);
+            progress(index + 2, total);
+
+            let side = before.side_to_move();
+            let own = |score: i32| match side {
+                Side::Tigers => score,
+                Side::Goats => -score,
+            };
+            let (loss, best_move) = match best {
+                Some(best) if best != *mv => ((own(score) - own(next_score)).max(0), Some(best)),
+                _ => (0, None),
+            };
+            let quality = match best_move {
+                None => MoveQuality::Best,
+                Some(_) => self.thresholds.classify(loss),
+            };
+            moves.push(MoveReview {
+                ply: before.ply(),
+                move_number: before.fullmove_number(),
+                side,
+                mv: mv.clone(),
+                best: best_move.map(|best| {
+                    let reason = annotate_move(&before, best.clone())
+                        .reasons
+                        .into_iter()
+                        .next();
+                    (best, reason)
+                }),
+                score_before: score,
+                score_after: next_score,
+                loss,
+                quality,
+            });
+            (best, score) = (next_best, next_score);
+        }
+        GameReview { moves }
+    }
+
+    /// The best move in `board` and its score from the tigers' side, or
+    /// no move and the score of the result once the game is over.
+    fn search(&self, board: &Board) -> (Option<Move>, i32) {
+        if let Some(result) = board.result() {
+            let score = match result.winner {
+                Winner::Tigers => 10000,
+                Winner::Goats => -10000,
+                Winner::Draw | Winner::None => 0,
+            };
+            return (None, score);
+        }
+        match board.top_moves(1, &self.limits).into_iter().next() {
+            Some((mv, score, _)) => (Some(mv), score),
+            None => (None, board.evaluate_detailed().total),
+        }
+    }
+}
+
+/// The points a move may lose before it counts as an inaccuracy, a
+/// mistake or a blunder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Thresholds {
+    pub inaccuracy: i32,
+    pub mistake: i32,
+    pub blunder: i32,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Thresholds {
+            inaccuracy: 50,
+            mistake: 150,
+            blunder: 300,
+        }
+    }
+}
+
+impl Thresholds {
+    /// Classes a move other than the best by the points it lost.
+    pub fn classify(&self, loss: i32) -> MoveQuality {
+        match loss {
+            loss if loss >= self.blunder => MoveQuality::Blunder,
+            loss if loss >= self.mistake => MoveQuality::Mistake,
+            loss if loss >= self.inaccuracy => MoveQuality::Inaccuracy,
+            _ => MoveQuality::Good,
+        }
+    }
+}
+
+/// How good a move was, from best to worst.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MoveQuality {
+    /// The move the search found.
+    Best,
+    /// Another move losing less than [`Thresholds::inaccuracy`].
+    Good,
+    Inaccuracy,
+    Mistake,
+    Blunder,
+}
+
+impl Display for MoveQuality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MoveQuality::Best => "best",
+            MoveQuality::Good => "good",
+            MoveQuality::Inaccuracy => "inaccuracy",
+            MoveQuality::Mistake => "mistake",
+            MoveQuality::Blunder => "blunder",
+        })
+    }
+}
+
+/// One move of a reviewed game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveReview {
+    /// Ply counter of the position the move was played in.
+    pub ply: usize,
+    /// Its move number, as [`Board::fullmove_number`] counts.
+    pub move_number: usize,
+    pub side: Side,
+    pub mv: Move,
+    /// The move the search found instead and the most telling thing it
+    /// does, if there is one; `None` when the move played was it.
+    pub best: Option<(Move, Option<MoveReason>)>,
+    /// The score of the position before the move, from the tigers' side.
+    pub score_before: i32,
+    /// The score of the position after the move, from the tigers' side.
+    pub score_after: i32,
+    /// Points the move lost, from the mover's side.
+    pub loss: i32,
+    pub quality: MoveQuality,
+}
+
+impl MoveReview {
+    /// The move in move notation with what it lost and the better move,
+    /// e.g. `Move 14: C3-C2 (−180) — B3-B2 leaves the tiger on A1 with no
+    /// moves`.
+    pub fn describe(&self, topology: &Topology) -> String {
+        let mut text = format!(
+            "Move {}: {} ({})",
+            self.move_number,
+            format_move(topology, &self.mv),
+            match self.loss {
+                0 => "0".to_string(),
+                loss => format!("−{loss}"),
+            }
+        );
+        if let Some((best, reason)) = &self.best {
+            let best = format_move(topology, best);
+            match reason {
+                Some(reason) => text += &format!(" — {best} {}", reason.describe(topology)),
+                None => text += &format!(" — {best} was better"),
+            }
+        }
+        text
+    }
+}
+
+/// Every move of a game, as [`Review::run`] judged them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameReview {
+    pub moves: Vec<MoveReview>,
+}
+
+impl GameReview {
+    /// The moves classed as inaccuracies or worse, the most costly first.
+    pub fn mistakes(&self) -> Vec<&MoveReview> {
+        let mut mistakes: Vec<&MoveReview> = self
+            .moves
+            .iter()
+            .filter(|review| review.quality >= MoveQuality::Inaccuracy)
+            .collect();
+        mistakes.sort_by_key(|review| (std::cmp::Reverse(review.loss), review.ply));
+        mistakes
+    }
+
+    /// How many of `side`'s moves were classed `quality`.
+    pub fn count(&self, side: Side, quality: MoveQuality) -> usize {
+        self.moves
+            .iter()
+            .filter(|review| review.side == side && review.quality == quality)
+            .count()
+    }
+
+    /// The points `side` lost on an average move.
+    pub fn average_loss(&self, side: Side) -> f64 {
+        let losses: Vec<i32> = self
+            .moves
+            .iter()
+            .filter(|review| review.side == side)
+            .map(|review| review.loss)
+            .collect();
+        match losses.len() {
+            0 => 0.0,
+            moves => losses.iter().sum::<i32>() as f64 / moves as f64,
+        }
+    }
+}
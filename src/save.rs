@@ -0,0 +1,345 @@
+//! Saved games: a game stopped part way, written to a file to be picked
+//! up again later.
+//!
+//! A save is a text file of `key: value` lines, with `#` starting a
+//! comment line. It holds the rules, the position the game started from
+//! and every move played since, so a loaded game can still take back moves
+//! made before it was saved:
+//!
+//! ```text
+//! # Baghchal saved game
+//! version: 1
+//! board: 5x5
+//! tigers: A1 E1 A5 E5
+//! starting goats: 20
+//! capture threshold: 5
+//! goats move during placement: no
+//! multi-capture: off
+//! forced capture: no
+//! shuttle rule: none
+//! swap after: none
+//! adjudicate hopeless: none
+//! start: T3T/5/5/5/T3T g 20 0 1
+//! moves: C3 A1-B2
+//! ```
+//!
+//! Games on a clock add the time each move took and the time left on each
+//! side's clock, in milliseconds, with `-` for a move played before the
+//! clock was set:
+//!
+//! ```text
+//! move times: 1200 3400
+//! clock: 598800 596600 0
+//! ```
+//!
+//! and games where the players swapped sides add `swapped at: <ply>`.
+//! Only boards built into the crate can be saved and loaded; an
+//! adjudicated result or an agreed draw isn't kept, so such a game loads as
+//! it stood before.
+//! Lines with other keys are ignored, so a program can keep its own
+//! settings in the same file.
+
+use crate::notation::{format_move, parse_move};
+use crate::{
+    Adjudication, Board, Clock, MultiCapture, ParseError, RuleSet, ShuttleRule, Side, Topology,
+};
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The version of the format [`to_string`] writes.
+const VERSION: u32 = 1;
+
+/// Writes `board`'s game in the format described in the [module
+/// documentation](self).
+pub fn to_string(board: &Board) -> String {
+    let rules = board.rules();
+    let topology = &rules.topology;
+    let points = |points: &[usize]| {
+        let names: Vec<String> = points.iter().map(|&pos| topology.point_name(pos)).collect();
+        names.join(" ")
+    };
+    let yes_no = |value: bool| if value { "yes" } else { "no" };
+    let adjudication = |outcome: Adjudication| match outcome {
+        Adjudication::GoatsWin => "goats",
+        Adjudication::Draw => "draw",
+    };
+
+    let record = board.record();
+    let moves: Vec<String> = record
+        .moves
+        .iter()
+        .map(|mv| format_move(topology, mv))
+        .collect();
+
+    let mut lines = vec![
+        "# Baghchal saved game".to_string(),
+        format!("version: {VERSION}"),
+        format!("board: {}", topology.name()),
+        format!("tigers: {}", points(&rules.tiger_squares)),
+        format!("starting goats: {}", rules.starting_goats),
+        format!("capture threshold: {}", rules.capture_threshold),
+        format!(
+            "goats move during placement: {}",
+            yes_no(rules.goats_may_move_during_placement)
+        ),
+        format!(
+            "multi-capture: {}",
+            match rules.multi_capture {
+                MultiCapture::Off => "off",
+                MultiCapture::Optional => "optional",
+                MultiCapture::Mandatory => "mandatory",
+            }
+        ),
+        format!("forced capture: {}", yes_no(rules.forced_capture)),
+        format!(
+            "shuttle rule: {}",
+            rules
+                .shuttle_rule
+                .map_or("none".to_string(), |rule| format!(
+                    "{} {}",
+                    rule.moves,
+                    adjudication(rule.outcome)
+                ))
+        ),
+        format!(
+            "swap after: {}",
+            rules
+                .swap_after
+                .map_or("none".to_string(), |ply| ply.to_string())
+        ),
+        format!(
+            "adjudicate hopeless: {}",
+            rules.adjudicate_hopeless.map_or("none", adjudication)
+        ),
+        format!("start: {}", record.start),
+        format!("moves: {}", moves.join(" ")),
+    ];
+    if let Some(clock) = board.clock() {
+        let times: Vec<String> = record
+            .move_times
+            .iter()
+            .map(|time| time.map_or("-".to_string(), |time| time.as_millis().to_string()))
+            .collect();
+        lines.push(format!("move times: {}", times.join(" ")));
+        lines.push(format!(
+            "clock: {} {} {}",
+            clock.remaining(Side::Goats).as_millis(),
+            clock.remaining(Side::Tigers).as_millis(),
+            clock.increment().as_millis()
+        ));
+    }
+    if let Some(ply) = record.swapped_at {
+        lines.push(format!("swapped at: {ply}"));
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Writes `board`'s game to a file, see [`to_string`].
+pub fn save(board: &Board, path: impl AsRef<Path>) -> io::Result<()> {
+    fs::write(path, to_string(board))
+}
+
+/// Reads a game saved in the format described in the [module
+/// documentation](self) from a file.
+pub fn load(path: impl AsRef<Path>) -> io::Result<Board> {
+    let text = fs::read_to_string(path)?;
+    parse(&text).map_err(|err| io::Error::new(ErrorKind::InvalidData, err))
+}
+
+/// Reads a game saved in the format described in the [module
+/// documentation](self), replaying its moves from the start position.
+pub fn parse(text: &str) -> Result<Board, ParseError> {
+    let mut fields = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let number = index + 1;
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            return Err(ParseError::new(number, 1, "expected 'key: value'"));
+        };
+        fields.push((number, key.trim(), value.trim(), key.len() + 2));
+    }
+    let field = |name: &str| fields.iter().find(|(_, key, _, _)| *key == name).copied();
+    let required = |name: &str| {
+        field(name).ok_or_else(|| {
+            ParseError::new(text.lines().count() + 1, 1, format!("missing '{name}'"))
+        })
+    };
+    let invalid = |(line, key, value, column): (usize, &str, &str, usize), what: &str| {
+        ParseError::new(
+            line,
+            column,
+            format!("invalid {key} '{value}', expected {what}"),
+        )
+    };
+    let number = |name: &str| {
+        let found = required(name)?;
+        found
+            .2
+            .parse::<u64>()
+            .map_err(|_| invalid(found, "a number"))
+    };
+    let yes_no = |name: &str| {
+        let found = required(name)?;
+        match found.2 {
+            "yes" => Ok(true),
+            "no" => Ok(false),
+            _ => Err(invalid(found, "yes or no")),
+        }
+    };
+    let adjudication = |found, value: &str| match value {
+        "goats" => Ok(Adjudication::GoatsWin),
+        "draw" => Ok(Adjudication::Draw),
+        _ => Err(invalid(found, "goats or draw")),
+    };
+    let optional = |name: &str| {
+        let found = required(name)?;
+        Ok::<_, ParseError>((found, (found.2 != "none").then_some(found.2)))
+    };
+
+    let version = required("version")?;
+    if version.2 != VERSION.to_string() {
+        return Err(ParseError::new(
+            version.0,
+            version.3,
+            format!("unsupported save version '{}'", version.2),
+        ));
+    }
+
+    let board = required("board")?;
+    let topology = match board.2 {
+        "aadu-puli-attam" => Topology::aadu_puli_attam(),
+        name => match name.split_once('x') {
+            Some((rows, cols)) if rows == cols => match rows.parse::<usize>() {
+                Ok(size) if size >= 3 && size * size <= RuleSet::MAX_POINTS => {
+                    Topology::alquerque(size)
+                }
+                _ => return Err(invalid(board, "a board built into the game")),
+            },
+            _ => return Err(invalid(board, "a board built into the game")),
+        },
+    };
+
+    let tigers = required("tigers")?;
+    let tiger_squares = tigers
+        .2
+        .split_whitespace()
+        .map(|name| topology.parse_point(name))
+        .collect::<Option<Vec<usize>>>()
+        .ok_or_else(|| invalid(tigers, "points on the board"))?;
+
+    let multi_capture = required("multi-capture")?;
+    let shuttle_rule = match optional("shuttle rule")? {
+        (_, None) => None,
+        (found, Some(value)) => {
+            let Some((moves, outcome)) = value.split_once(' ') else {
+                return Err(invalid(found, "a number of moves and an outcome"));
+            };
+            Some(ShuttleRule {
+                moves: moves
+                    .parse()
+                    .map_err(|_| invalid(found, "a number of moves"))?,
+                outcome: adjudication(found, outcome.trim())?,
+            })
+        }
+    };
+    let swap_after = match optional("swap after")? {
+        (_, None) => None,
+        (found, Some(value)) => Some(
+            value
+                .parse()
+                .map_err(|_| invalid(found, "a number of plies"))?,
+        ),
+    };
+    let adjudicate_hopeless = match optional("adjudicate hopeless")? {
+        (_, None) => None,
+        (found, Some(value)) => Some(adjudication(found, value)?),
+    };
+    let rules = RuleSet {
+        goats_may_move_during_placement: yes_no("goats move during placement")?,
+        capture_threshold: number("capture threshold")? as u32,
+        starting_goats: number("starting goats")? as u32,
+        tiger_squares,
+        topology: Arc::new(topology),
+        multi_capture: match multi_capture.2 {
+            "off" => MultiCapture::Off,
+            "optional" => MultiCapture::Optional,
+            "mandatory" => MultiCapture::Mandatory,
+            _ => return Err(invalid(multi_capture, "off, optional or mandatory")),
+        },
+        forced_capture: yes_no("forced capture")?,
+        shuttle_rule,
+        swap_after,
+        adjudicate_hopeless,
+    };
+
+    let start = required("start")?;
+    let mut game = Board::from_fen_with_rules(start.2, rules)
+        .map_err(|err| ParseError::new(start.0, start.3 + err.column - 1, err.message))?;
+
+    let moves = required("moves")?;
+    let mut column = moves.3;
+    for text in moves.2.split(' ') {
+        if !text.is_empty() {
+            let played = parse_move(&game, text).is_some_and(|mv| game.make_move(&mv));
+            if !played {
+                return Err(ParseError::new(
+                    moves.0,
+                    column,
+                    format!("illegal move '{text}'"),
+                ));
+            }
+        }
+        column += text.len() + 1;
+    }
+    game.drain_events();
+
+    if let Some(found) = field("move times") {
+        let times = found
+            .2
+            .split_whitespace()
+            .map(|time| match time {
+                "-" => Some(None),
+                _ => time.parse().ok().map(|ms| Some(Duration::from_millis(ms))),
+            })
+            .collect::<Option<Vec<_>>>()
+            .filter(|times| times.len() == game.move_times.len())
+            .ok_or_else(|| invalid(found, "a time for each move"))?;
+        game.move_times = times;
+    }
+    if let Some(found) = field("clock") {
+        let times = found
+            .2
+            .split_whitespace()
+            .map(|time| time.parse().ok().map(Duration::from_millis))
+            .collect::<Option<Vec<_>>>();
+        let Some(&[goats, tigers, increment]) = times.as_deref() else {
+            return Err(invalid(
+                found,
+                "the goats' time, the tigers' and the increment",
+            ));
+        };
+        game.set_clock(
+            Clock::new(goats)
+                .with_increment(increment)
+                .with_remaining(goats, tigers),
+        );
+        if game.is_game_over() {
+            game.pause_clock();
+        }
+    }
+    if let Some(found) = field("swapped at") {
+        let ply = found
+            .2
+            .parse()
+            .ok()
+            .filter(|&ply| game.rules().swap_after == Some(ply) && ply <= game.ply())
+            .ok_or_else(|| invalid(found, "the ply the rules let the players swap at"))?;
+        game.swapped_at = Some(ply);
+    }
+    Ok(game)
+}
@@ -0,0 +1,373 @@
+//! Games between engines, recorded position by position as training data
+//! for evaluation models.
+//!
+//! [`SelfPlay::run`] plays games and appends a row for every position in
+//! them to a file, as CSV or as JSON lines. A run picks up where the file
+//! leaves off, so an interrupted run can simply be started again.
+//!
+//! # Rows
+//!
+//! Each row describes a position and what happened from it, with the
+//! scores and the result from the side to move's point of view:
+//!
+//! | field    | contents                                                   |
+//! |----------|------------------------------------------------------------|
+//! | `game`   | The game, counting from 0 over the whole file.             |
+//! | `ply`    | Plies played before the position.                          |
+//! | `side`   | The side to move, `tigers` or `goats`.                     |
+//! | `fen`    | The position, see [`Board::to_fen`].                       |
+//! | `planes` | The position encoded by [`observation`].                   |
+//! | `policy` | The move played, encoded by [`policy_index`].              |
+//! | `score`  | The search score of the position.                          |
+//! | `result` | 1 if the side to move went on to win, -1 if it lost, 0 for a draw. |
+//!
+//! CSV files start with a header line naming the fields, and give the
+//! planes as one field of numbers separated by spaces. JSON lines give
+//! them as an array of numbers.
+//!
+//! # Observation planes
+//!
+//! [`observation`] encodes a position as [`PLANES`] planes, each holding
+//! one number per point of the board in [`Board::cells`] order:
+//!
+//! 0. 1 where a tiger stands, else 0.
+//! 1. 1 where a goat stands, else 0.
+//! 2. 1 where the point is empty, else 0.
+//! 3. 1 everywhere if the tigers are to move, else 0.
+//! 4. The goats still to be placed, as a fraction of the goats the game
+//!    starts with, everywhere.
+//! 5. The goats captured, as a fraction of the captures the tigers need to
+//!    win, everywhere.
+
+use crate::adjudication::settled;
+use crate::{AdjudicationPolicy, AiConfig, Board, Engine, Move, Piece, Side, Winner};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::fs::{self, OpenOptions};
+use std::io::{self, ErrorKind, Write};
+use std::path::Path;
+
+/// Observation planes per position, see the [module documentation](self).
+pub const PLANES: usize = 6;
+
+/// How a file of training data is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    Csv,
+    #[default]
+    JsonLines,
+}
+
+/// How training games are played and recorded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelfPlay {
+    /// Games the file should hold once the run is done, counting those
+    /// already in it.
+    pub games: usize,
+    /// Positions after which no new game is started, counting those
+    /// already in the file. The game under way is finished and recorded
+    /// in full. `None` stops only at `games`.
+    pub max_positions: Option<usize>,
+    /// Limits on each engine's search per move.
+    pub limits: AiConfig,
+    /// Points by which a move may score worse than the best and still be
+    /// played about a third as often, for variety. Moves are drawn in
+    /// proportion to `exp(score / temperature)` over the scores
+    /// [`Board::top_moves`] gives every move at `limits`, instead of
+    /// playing the engine's choice. 0 always plays the engine's choice.
+    pub temperature: f64,
+    /// Plies from the start of a game for which `temperature` applies.
+    pub temperature_plies: usize,
+    /// Seed for drawing moves. Each game is seeded from this and its
+    /// number, so a resumed run plays the games it would have played.
+    pub seed: u64,
+    /// Stops a game once it is decided or drawn in all but name, see
+    /// [`settled`].
+    pub adjudication: AdjudicationPolicy,
+    /// Plies after which a game still going is adjudicated, with
+    /// [`Board::adjudicate`].
+    pub max_plies: usize,
+    pub format: Format,
+}
+
+impl Default for SelfPlay {
+    fn default() -> Self {
+        SelfPlay {
+            games: 100,
+            max_positions: None,
+            limits: AiConfig::nodes(2_000),
+            temperature: 0.0,
+            temperature_plies: 20,
+            seed: 0,
+            adjudication: AdjudicationPolicy::default(),
+            max_plies: 300,
+            format: Format::default(),
+        }
+    }
+}
+
+/// A position from a training game, one row of the file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub game: usize,
+    pub ply: usize,
+    pub side: Side,
+    pub fen: String,
+    pub planes: Vec<f32>,
+    pub policy: usize,
+    /// From the side to move's point of view.
+    pub score: i32,
+    /// 1 for a win for the side to move, -1 for a loss and 0 for a draw.
+    pub result: i32,
+}
+
+impl Sample {
+    /// The row as a line of `format`, without the line break.
+    pub fn to_line(&self, format: Format) -> String {
+        let planes: Vec<String> = self.planes.iter().map(f32::to_string).collect();
+        let side = match self.side {
+            Side::Tigers => "tigers",
+            Side::Goats => "goats",
+        };
+        match format {
+            Format::Csv => format!(
+                "{},{},{side},{},{},{},{},{}",
+                self.game,
+                self.ply,
+                self.fen,
+                planes.join(" "),
+                self.policy,
+                self.score,
+                self.result,
+            ),
+            Format::JsonLines => format!(
+                "{{\"game\":{},\"ply\":{},\"side\":\"{side}\",\"fen\":\"{}\",\"planes\":[{}],\"policy\":{},\"score\":{},\"result\":{}}}",
+                self.game,
+                self.ply,
+                self.fen,
+                planes.join(","),
+                self.policy,
+                self.score,
+                self.result,
+            ),
+        }
+    }
+}
+
+/// What a [`SelfPlay::run`] left in its file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelfPlaySummary {
+    /// Games found in the file when the run started.
+    pub resumed_games: usize,
+    /// Games in the file now.
+    pub games: usize,
+    /// Positions in the file now.
+    pub positions: usize,
+}
+
+impl SelfPlay {
+    /// Plays games between engines made by `tigers` and `goats`, appending
+    /// their positions to the file at `path` until it holds `games` games
+    /// or `max_positions` positions. Every game gets new engines. A file
+    /// that already has games is carried on from where it ends, dropping
+    /// a last line cut short, so it must be in the same format.
+    pub fn run<T: Engine, G: Engine>(
+        &self,
+        path: impl AsRef<Path>,
+        mut tigers: impl FnMut() -> T,
+        mut goats: impl FnMut() -> G,
+    ) -> io::Result<SelfPlaySummary> {
+        let path = path.as_ref();
+        let (mut games, mut positions) = self.resume(path)?;
+        let resumed_games = games;
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        if file.metadata()?.len() == 0 && self.format == Format::Csv {
+            writeln!(file, "game,ply,side,fen,planes,policy,score,result")?;
+        }
+
+        while games < self.games && self.max_positions.is_none_or(|max| positions < max) {
+            let samples = self.play_game(games, &mut tigers(), &mut goats());
+            let mut text = String::new();
+            for sample in &samples {
+                text += &sample.to_line(self.format);
+                text.push('\n');
+            }
+            // A whole game at a time, so that a run cut short loses at
+            // most the game it was playing
+            file.write_all(text.as_bytes())?;
+            file.flush()?;
+            games += 1;
+            positions += samples.len();
+        }
+        Ok(SelfPlaySummary {
+            resumed_games,
+            games,
+            positions,
+        })
+    }
+
+    /// Counts the games and positions already in the file at `path`,
+    /// cutting off a last line that was never finished.
+    fn resume(&self, path: &Path) -> io::Result<(usize, usize)> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok((0, 0)),
+            Err(err) => return Err(err),
+        };
+        let complete = text.rfind('\n').map_or(0, |end| end + 1);
+        if complete < text.len() {
+            OpenOptions::new()
+                .write(true)
+                .open(path)?
+                .set_len(complete as u64)?;
+        }
+        let mut rows = text[..complete].lines();
+        if self.format == Format::Csv {
+            rows.next();
+        }
+        let mut last_game = None;
+        let mut positions = 0;
+        for row in rows {
+            let game = match self.format {
+                Format::Csv => row.split(',').next(),
+                Format::JsonLines => row
+                    .strip_prefix("{\"game\":")
+                    .and_then(|rest| rest.split(',').next()),
+            };
+            let game: usize = game.and_then(|game| game.parse().ok()).ok_or_else(|| {
+                io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("not a {:?} training data row: {row}", self.format),
+                )
+            })?;
+            last_game = Some(game);
+            positions += 1;
+        }
+        Ok((last_game.map_or(0, |game| game + 1), positions))
+    }
+
+    /// Plays game number `game` between `tigers` and `goats` from the
+    /// start, returning a sample for each position a move was played from.
+    pub fn play_game(
+        &self,
+        game: usize,
+        tigers: &mut dyn Engine,
+        goats: &mut dyn Engine,
+    ) -> Vec<Sample> {
+        let mut rng = StdRng::seed_from_u64(self.seed ^ (game as u64).wrapping_mul(0x9e37_79b9));
+        let mut board = Board::new();
+        board.set_use_opening_book(false);
+        board.set_ai_config(self.limits);
+        let mut samples = Vec::new();
+        let winner = loop {
+            if let Some(result) = settled(&board, &self.adjudication) {
+                break result.winner;
+            }
+            if board.ply() >= self.max_plies {
+                break board.adjudicate(&self.adjudication).winner;
+            }
+            let side = board.side_to_move();
+            let choice = if self.temperature > 0.0 && board.ply() < self.temperature_plies {
+                self.draw_move(&board, &mut rng)
+            } else {
+                match side {
+                    Side::Tigers => tigers.best_move(&board, &self.limits),
+                    Side::Goats => goats.best_move(&board, &self.limits),
+                }
+                .map(|result| (result.mv, result.score))
+            };
+            // A side to move with no legal move has lost already, so this
+            // only happens to an engine that gives up
+            let Some((mv, score)) = choice.filter(|(mv, _)| board.legal_moves().contains(mv))
+            else {
+                break board.adjudicate(&self.adjudication).winner;
+            };
+            samples.push(Sample {
+                game,
+                ply: board.ply(),
+                side,
+                fen: board.to_fen(),
+                planes: observation(&board),
+                policy: policy_index(&board, &mv),
+                score: match side {
+                    Side::Tigers => score,
+                    Side::Goats => -score,
+                },
+                result: 0,
+            });
+            board.make_move(&mv);
+        };
+        for sample in &mut samples {
+            sample.result = match (winner, sample.side) {
+                (Winner::Tigers, Side::Tigers) | (Winner::Goats, Side::Goats) => 1,
+                (Winner::Tigers, Side::Goats) | (Winner::Goats, Side::Tigers) => -1,
+                (Winner::Draw | Winner::None, _) => 0,
+            };
+        }
+        samples
+    }
+
+    /// A move drawn at `temperature` from the scores of every move, with
+    /// the best move's score, from the tigers' side.
+    fn draw_move(&self, board: &Board, rng: &mut StdRng) -> Option<(Move, i32)> {
+        let lines = board.top_moves(usize::MAX, &self.limits);
+        let best = lines.first()?.1;
+        let sign = match board.side_to_move() {
+            Side::Tigers => 1.0,
+            Side::Goats => -1.0,
+        };
+        // Relative to the best move, so the weights can't overflow
+        let weights: Vec<f64> = lines
+            .iter()
+            .map(|(_, score, _)| (sign * (score - best) as f64 / self.temperature).exp())
+            .collect();
+        let mut pick = rng.gen::<f64>() * weights.iter().sum::<f64>();
+        let index = weights
+            .iter()
+            .position(|&weight| {
+                pick -= weight;
+                pick < 0.0
+            })
+            .unwrap_or(0);
+        Some((lines[index].0.clone(), best))
+    }
+}
+
+/// `board` as [`PLANES`] planes of one number per point, see the [module
+/// documentation](self), plane after plane.
+pub fn observation(board: &Board) -> Vec<f32> {
+    let points = board.cells.len();
+    let mut planes = Vec::with_capacity(PLANES * points);
+    for piece in [Piece::Tiger, Piece::Goat, Piece::Empty] {
+        planes.extend(
+            board
+                .cells
+                .iter()
+                .map(|&cell| if cell == piece { 1.0 } else { 0.0 }),
+        );
+    }
+    let rules = board.rules();
+    let tigers_to_move = match board.side_to_move() {
+        Side::Tigers => 1.0,
+        Side::Goats => 0.0,
+    };
+    let in_hand = board.goats_in_hand as f32 / rules.starting_goats as f32;
+    let captured = board.captured_goats as f32 / rules.capture_threshold as f32;
+    for value in [tigers_to_move, in_hand, captured] {
+        planes.extend(std::iter::repeat_n(value, points));
+    }
+    planes
+}
+
+/// `mv` on `board` as a number below the square of the number of points,
+/// for policy targets: `from * points + to`, with a placement
+/// counted as a move from the point it fills and a chain of captures as a
+/// move from where it starts to where it ends.
+pub fn policy_index(board: &Board, mv: &Move) -> usize {
+    let (from, to) = match mv {
+        Move::PlaceGoat { position } => (*position, *position),
+        Move::MoveGoat { from, to } | Move::MoveTiger { from, to, .. } => (*from, *to),
+        Move::MultiJump(jumps) => (jumps[0].from, jumps[jumps.len() - 1].to),
+    };
+    from * board.cells.len() + to
+}
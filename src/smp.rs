@@ -0,0 +1,41 @@
+//! Searching on several threads at once, behind the `parallel` feature.
+//!
+//! With [`AiConfig::threads`](crate::AiConfig::threads) above one, the
+//! search runs as "lazy SMP": helper threads search the same position as
+//! the main thread, each on its own copy of the board, and every thread
+//! stores the scores it finds in the search's
+//! [`TranspositionTable`](crate::transposition::TranspositionTable). A
+//! thread that comes to a position another has already searched deeply
+//! enough takes the score from the table instead, so between them the
+//! threads get deeper than one would alone. Helpers start a ply apart
+//! from each other so they don't all work on the same depth. Only the
+//! main thread's move is played; the helpers are stopped once it has one.
+
+use crate::{Board, Move, SearchContext, Side};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Searches for `side`'s best move on `board` with `search`, with helper
+/// threads alongside if the configuration asks for more than one.
+pub(crate) fn search(board: &mut Board, side: Side, search: SearchContext) -> Option<(Move, i32)> {
+    let threads = search.config.threads;
+    if threads <= 1 {
+        return board.search_root(side, search);
+    }
+    let stop = Arc::new(AtomicBool::new(false));
+    let helpers: Vec<(Board, SearchContext)> = (1..threads)
+        .map(|helper| {
+            let mut position = board.clone();
+            position.search_progress = None;
+            (position, search.for_helper(helper, stop.clone()))
+        })
+        .collect();
+    std::thread::scope(|scope| {
+        for (mut position, context) in helpers {
+            scope.spawn(move || position.search_root(side, context));
+        }
+        let found = board.search_root(side, search);
+        stop.store(true, Ordering::Relaxed);
+        found
+    })
+}
@@ -0,0 +1,216 @@
+//! Exact solving of positions by exhaustive search.
+//!
+//! Unlike the AI, the solver never guesses: it only reports a result once
+//! every line has been played out to the end of the game. Small positions,
+//! such as endgames with few goats, can be solved outright; for larger ones
+//! the search gives up when it runs into its [`SolveLimits`].
+
+use crate::{Board, Piece, Side, Winner};
+use std::collections::HashMap;
+
+/// How much work [`solve`] may do before giving up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolveLimits {
+    /// Positions to visit, counting revisits across the deepening passes.
+    pub max_nodes: u64,
+    /// Longest line to look at, in plies.
+    pub max_depth: usize,
+    /// Positions to remember in the transposition table.
+    pub max_table_entries: usize,
+}
+
+impl Default for SolveLimits {
+    fn default() -> Self {
+        SolveLimits {
+            max_nodes: 1_000_000,
+            max_depth: 20,
+            max_table_entries: 1_000_000,
+        }
+    }
+}
+
+/// The value of a position for the side to move, with the number of plies
+/// until the game ends under best play: the winner wins as fast as it can
+/// and the loser holds out as long as it can.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolveResult {
+    /// The side to move wins.
+    Win { plies: usize },
+    /// The side to move loses.
+    Loss { plies: usize },
+    /// The game is adjudicated a draw.
+    Draw { plies: usize },
+    /// The limits were reached before the position was solved.
+    Unknown,
+}
+
+impl SolveResult {
+    /// Plies until the game ends, if solved.
+    pub fn plies(self) -> Option<usize> {
+        match self {
+            SolveResult::Win { plies }
+            | SolveResult::Loss { plies }
+            | SolveResult::Draw { plies } => Some(plies),
+            SolveResult::Unknown => None,
+        }
+    }
+}
+
+/// Solves `board` for the side to move.
+///
+/// Draws only come from house rules that adjudicate games, such as
+/// [`RuleSet::adjudicate_hopeless`](crate::RuleSet::adjudicate_hopeless);
+/// standard games can go on forever, and a position whose best line never
+/// ends is [`SolveResult::Unknown`].
+pub fn solve(board: &Board, limits: &SolveLimits) -> SolveResult {
+    let mut solver = Solver {
+        limits,
+        nodes: 0,
+        table: HashMap::new(),
+        symmetries: board.topology().symmetries(),
+        // The shuttle rule looks at the moves that led to a position, so
+        // equal positions don't always have equal values
+        use_table: board.rules().shuttle_rule.is_none(),
+    };
+
+    // Deepen one ply at a time, so the first result found is the fastest
+    for depth in 0..=limits.max_depth {
+        match solver.search(board, depth) {
+            Ok(SolveResult::Unknown) => {}
+            Ok(result) => return result,
+            Err(OutOfNodes) => break,
+        }
+    }
+    SolveResult::Unknown
+}
+
+/// The node budget ran out.
+struct OutOfNodes;
+
+/// What the table knows about a position.
+#[derive(Debug, Clone, Copy)]
+enum Entry {
+    Solved(SolveResult),
+    /// Unsolved when searched this many plies deep.
+    Unsolved(usize),
+}
+
+struct Solver<'a> {
+    limits: &'a SolveLimits,
+    nodes: u64,
+    table: HashMap<Vec<u8>, Entry>,
+    symmetries: Vec<Vec<usize>>,
+    use_table: bool,
+}
+
+impl Solver<'_> {
+    /// Solves `board` looking at most `depth` plies ahead.
+    fn search(&mut self, board: &Board, depth: usize) -> Result<SolveResult, OutOfNodes> {
+        self.nodes += 1;
+        if self.nodes > self.limits.max_nodes {
+            return Err(OutOfNodes);
+        }
+
+        let side = board.side_to_move();
+        if let Some(result) = board.result() {
+            return Ok(match (result.winner, side) {
+                (Winner::Draw | Winner::None, _) => SolveResult::Draw { plies: 0 },
+                (Winner::Goats, Side::Goats) | (Winner::Tigers, Side::Tigers) => {
+                    SolveResult::Win { plies: 0 }
+                }
+                _ => SolveResult::Loss { plies: 0 },
+            });
+        }
+        if depth == 0 {
+            return Ok(SolveResult::Unknown);
+        }
+
+        let key = self.use_table.then(|| self.key(board));
+        if let Some(key) = &key {
+            match self.table.get(key) {
+                // Results further away than the horizon would hide faster
+                // ones the deepening hasn't reached yet
+                Some(Entry::Solved(result))
+                    if result.plies().is_some_and(|plies| plies <= depth) =>
+                {
+                    return Ok(*result)
+                }
+                Some(Entry::Unsolved(searched)) if *searched >= depth => {
+                    return Ok(SolveResult::Unknown)
+                }
+                _ => {}
+            }
+        }
+
+        let mut fastest_win: Option<usize> = None;
+        let mut fastest_draw: Option<usize> = None;
+        let mut slowest_loss = 0;
+        let mut unsolved = false;
+        let moves = board.generate_legal_moves();
+        for mv in &moves {
+            let mut child = board.clone();
+            child.make_move(mv);
+            // The child's result is from the opponent's side
+            match self.search(&child, depth - 1)? {
+                SolveResult::Loss { plies } => {
+                    fastest_win = Some(fastest_win.map_or(plies + 1, |best| best.min(plies + 1)));
+                    if plies == 0 {
+                        break; // Nothing wins faster
+                    }
+                }
+                SolveResult::Win { plies } => slowest_loss = slowest_loss.max(plies + 1),
+                SolveResult::Draw { plies } => {
+                    fastest_draw = Some(fastest_draw.map_or(plies + 1, |best| best.min(plies + 1)));
+                }
+                SolveResult::Unknown => unsolved = true,
+            }
+        }
+
+        // A side without moves that hasn't lost is stuck in a position the
+        // rules don't decide
+        let result = match (fastest_win, fastest_draw) {
+            (Some(plies), _) => SolveResult::Win { plies },
+            _ if unsolved || moves.is_empty() => SolveResult::Unknown,
+            (None, Some(plies)) => SolveResult::Draw { plies },
+            (None, None) => SolveResult::Loss {
+                plies: slowest_loss,
+            },
+        };
+
+        if let Some(key) = key {
+            if self.table.len() < self.limits.max_table_entries || self.table.contains_key(&key) {
+                let entry = match result {
+                    SolveResult::Unknown => Entry::Unsolved(depth),
+                    solved => Entry::Solved(solved),
+                };
+                self.table.insert(key, entry);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Identifies a position up to the symmetries of the board, so that
+    /// mirrored and rotated copies share a table entry.
+    fn key(&self, board: &Board) -> Vec<u8> {
+        let code = |piece: Piece| match piece {
+            Piece::Empty => 0,
+            Piece::Goat => 1,
+            Piece::Tiger => 2,
+        };
+        let mut best: Option<Vec<u8>> = None;
+        for symmetry in &self.symmetries {
+            let mut key = vec![0; board.cells.len()];
+            for (pos, &piece) in board.cells.iter().enumerate() {
+                key[symmetry[pos]] = code(piece);
+            }
+            if best.as_ref().is_none_or(|best| key < *best) {
+                best = Some(key);
+            }
+        }
+        let mut key = best.unwrap_or_default();
+        key.push(board.goats_in_hand as u8);
+        key.push(board.captured_goats as u8);
+        key.push(board.side_to_move() as u8);
+        key
+    }
+}
@@ -0,0 +1,423 @@
+//! Endgame tablebases: every position with all goats placed and only a
+//! few left on the board, solved by retrograde analysis.
+//!
+//! A standard game never gets there, since the tigers win after five
+//! captures, but handicap games and variants with a higher capture
+//! threshold do. Building a table takes a while for anything but tiny
+//! limits, so it is done ahead of time, e.g. with the `generate_tablebase`
+//! example, and loaded at startup.
+//!
+//! The file format is little-endian: the magic bytes `BCTB`, a version
+//! byte, the goat limit as a byte, the length of the rules description as a
+//! `u16` and the description itself, the entry count as a `u64`, and then
+//! one `u64` key and `u16` value per position, sorted by key.
+
+use crate::{Board, Piece, RuleSet, Side, Winner};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+use std::sync::OnceLock;
+
+const MAGIC: &[u8; 4] = b"BCTB";
+const VERSION: u8 = 1;
+
+/// The table installed with [`install`], consulted by the AI.
+static INSTALLED: OnceLock<Tablebase> = OnceLock::new();
+
+/// The value of a position for the side to move under perfect play.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// The side to move wins in this many plies at most.
+    Win { plies: usize },
+    /// The side to move loses, holding out for this many plies.
+    Loss { plies: usize },
+    /// Neither side can force a win.
+    Draw,
+}
+
+impl Outcome {
+    fn encode(self) -> u16 {
+        match self {
+            Outcome::Draw => 0,
+            Outcome::Win { plies } => plies as u16 * 2 + 1,
+            Outcome::Loss { plies } => plies as u16 * 2 + 2,
+        }
+    }
+
+    fn decode(value: u16) -> Outcome {
+        match value {
+            0 => Outcome::Draw,
+            _ if value % 2 == 1 => Outcome::Win {
+                plies: (value / 2) as usize,
+            },
+            _ => Outcome::Loss {
+                plies: (value / 2 - 1) as usize,
+            },
+        }
+    }
+}
+
+/// Solved positions with at most a given number of goats on the board and
+/// none in hand, for one set of rules.
+#[derive(Debug, Clone)]
+pub struct Tablebase {
+    rules: String,
+    max_goats: usize,
+    /// Canonical key and encoded outcome of every position, sorted by key.
+    entries: Vec<(u64, u16)>,
+    symmetries: Vec<Vec<usize>>,
+}
+
+impl Tablebase {
+    /// Solves every position under `rules` with at most `max_goats` goats
+    /// on the board and none in hand.
+    ///
+    /// Returns `None` for rules the table can't describe: boards of more
+    /// than 26 points, which don't fit the packed key, and the shuttle
+    /// rule, which depends on how a position was reached.
+    pub fn generate(rules: &RuleSet, max_goats: usize) -> Option<Tablebase> {
+        if rules.topology.len() > 26 || rules.shuttle_rule.is_some() {
+            return None;
+        }
+        let mut template = Board::with_rules(rules.clone()).ok()?;
+        template.goats_in_hand = 0;
+        let symmetries = rules.topology.symmetries();
+        let points: Vec<usize> = (0..rules.topology.len()).collect();
+
+        // Every position, up to symmetry
+        let mut keys = Vec::new();
+        let mut index = HashMap::new();
+        for tigers in combinations(&points, rules.tiger_squares.len()) {
+            let free: Vec<usize> = points
+                .iter()
+                .copied()
+                .filter(|pos| !tigers.contains(pos))
+                .collect();
+            for goat_count in 0..=max_goats.min(free.len()) {
+                for goats in combinations(&free, goat_count) {
+                    for captured in 0..rules.capture_threshold {
+                        for ply in 0..2 {
+                            let board = position(&template, &tigers, &goats, captured, ply);
+                            let key = canonical_key(&board, &symmetries);
+                            index.entry(key).or_insert_with(|| {
+                                keys.push(key);
+                                keys.len() - 1
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        // Link each position to the positions its moves lead to. Moves that
+        // end the game are settled on the spot.
+        let mut outcomes: Vec<Option<Outcome>> = vec![None; keys.len()];
+        let mut predecessors: Vec<Vec<u32>> = vec![Vec::new(); keys.len()];
+        let mut open_moves = vec![0u32; keys.len()];
+        let mut longest_loss = vec![0usize; keys.len()];
+        let mut can_draw = vec![false; keys.len()];
+        let mut queue = VecDeque::new();
+        for (i, &key) in keys.iter().enumerate() {
+            let board = unpack(&template, key);
+            if let Some(result) = board.result() {
+                outcomes[i] = Some(match result.winner {
+                    Winner::Draw | Winner::None => Outcome::Draw,
+                    winner if wins(winner, board.side_to_move()) => Outcome::Win { plies: 0 },
+                    _ => Outcome::Loss { plies: 0 },
+                });
+                continue;
+            }
+
+            let side = board.side_to_move();
+            let mut immediate_win = false;
+            for mv in board.generate_legal_moves() {
+                let mut child = board.clone();
+                child.make_move(&mv);
+                match child.result() {
+                    Some(result) if wins(result.winner, side) => immediate_win = true,
+                    Some(result) if result.winner == Winner::Draw => can_draw[i] = true,
+                    Some(_) => longest_loss[i] = longest_loss[i].max(1),
+                    None => match index.get(&canonical_key(&child, &symmetries)) {
+                        Some(&j) => {
+                            predecessors[j].push(i as u32);
+                            open_moves[i] += 1;
+                        }
+                        // Can't happen for consistent rules; don't guess
+                        None => can_draw[i] = true,
+                    },
+                }
+            }
+            if immediate_win {
+                outcomes[i] = Some(Outcome::Win { plies: 1 });
+                queue.push_back(i);
+            } else if open_moves[i] == 0 && longest_loss[i] > 0 && !can_draw[i] {
+                outcomes[i] = Some(Outcome::Loss { plies: 1 });
+                queue.push_back(i);
+            }
+        }
+
+        // Work backwards from the settled positions, nearest first, so wins
+        // are as fast and losses as slow as possible
+        while let Some(i) = queue.pop_front() {
+            for &p in &predecessors[i] {
+                let p = p as usize;
+                if outcomes[p].is_some() {
+                    continue;
+                }
+                match outcomes[i] {
+                    Some(Outcome::Loss { plies }) => {
+                        outcomes[p] = Some(Outcome::Win { plies: plies + 1 });
+                        queue.push_back(p);
+                    }
+                    Some(Outcome::Win { plies }) => {
+                        open_moves[p] -= 1;
+                        longest_loss[p] = longest_loss[p].max(plies + 1);
+                        if open_moves[p] == 0 && !can_draw[p] {
+                            outcomes[p] = Some(Outcome::Loss {
+                                plies: longest_loss[p],
+                            });
+                            queue.push_back(p);
+                        }
+                    }
+                    Some(Outcome::Draw) | None => {}
+                }
+            }
+        }
+
+        let mut entries: Vec<(u64, u16)> = keys
+            .iter()
+            .zip(&outcomes)
+            .map(|(&key, outcome)| (key, outcome.unwrap_or(Outcome::Draw).encode()))
+            .collect();
+        entries.sort_unstable();
+        Some(Tablebase {
+            rules: describe(rules),
+            max_goats,
+            entries,
+            symmetries,
+        })
+    }
+
+    /// Reads a table written by [`Tablebase::save`] for the given rules.
+    pub fn load(path: impl AsRef<Path>, rules: &RuleSet) -> io::Result<Tablebase> {
+        let data = fs::read(path)?;
+        let invalid = |message: &str| io::Error::new(ErrorKind::InvalidData, message.to_string());
+        let mut reader = Reader { data: &data };
+
+        if reader.take(4)? != MAGIC || reader.take(1)? != [VERSION] {
+            return Err(invalid("not a tablebase file"));
+        }
+        let max_goats = reader.take(1)?[0] as usize;
+        let rules_len = u16::from_le_bytes(reader.array()?) as usize;
+        let stored_rules = String::from_utf8_lossy(reader.take(rules_len)?).into_owned();
+        if stored_rules != describe(rules) {
+            return Err(invalid("tablebase was built for other rules"));
+        }
+        let count = u64::from_le_bytes(reader.array()?) as usize;
+        let mut entries = Vec::with_capacity(count.min(data.len() / 10));
+        for _ in 0..count {
+            let key = u64::from_le_bytes(reader.array()?);
+            let value = u16::from_le_bytes(reader.array()?);
+            entries.push((key, value));
+        }
+
+        Ok(Tablebase {
+            rules: stored_rules,
+            max_goats,
+            entries,
+            symmetries: rules.topology.symmetries(),
+        })
+    }
+
+    /// Writes the table to `path` in the format described in the module
+    /// documentation.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut data = Vec::with_capacity(16 + self.rules.len() + self.entries.len() * 10);
+        data.extend_from_slice(MAGIC);
+        data.push(VERSION);
+        data.push(self.max_goats as u8);
+        data.extend_from_slice(&(self.rules.len() as u16).to_le_bytes());
+        data.extend_from_slice(self.rules.as_bytes());
+        data.extend_from_slice(&(self.entries.len() as u64).to_le_bytes());
+        for &(key, value) in &self.entries {
+            data.extend_from_slice(&key.to_le_bytes());
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        fs::write(path, data)
+    }
+
+    /// Number of positions in the table, counting symmetric ones once.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Most goats on the board in any position of the table.
+    pub fn max_goats(&self) -> usize {
+        self.max_goats
+    }
+
+    /// Looks up `board` for the side to move. Returns `None` if the
+    /// position isn't covered by the table.
+    pub fn probe(&self, board: &Board) -> Option<Outcome> {
+        self.probe_for(board, board.side_to_move())
+    }
+
+    /// Like [`Tablebase::probe`], with `side` to move. The search plays
+    /// moves without advancing the ply counter, so it has to say.
+    pub(crate) fn probe_for(&self, board: &Board, side: Side) -> Option<Outcome> {
+        if board.goats_in_hand != 0
+            || board.captured_goats >= board.rules().capture_threshold
+            || board.goats().count() > self.max_goats
+            || describe(board.rules()) != self.rules
+        {
+            return None;
+        }
+        let mut key = u64::MAX;
+        for symmetry in &self.symmetries {
+            key = key.min(pack(board, symmetry, side));
+        }
+        let found = self.entries.binary_search_by_key(&key, |&(key, _)| key);
+        found.ok().map(|i| Outcome::decode(self.entries[i].1))
+    }
+}
+
+/// Makes `tablebase` the table the AI consults. Only one table can be
+/// installed; a second one is handed back.
+pub fn install(tablebase: Tablebase) -> Result<(), Tablebase> {
+    INSTALLED.set(tablebase)
+}
+
+/// Looks up `board` in the installed table, if there is one.
+pub fn probe(board: &Board) -> Option<Outcome> {
+    INSTALLED.get()?.probe(board)
+}
+
+/// Looks up `board` in the installed table with `side` to move.
+pub(crate) fn probe_for(board: &Board, side: Side) -> Option<Outcome> {
+    INSTALLED.get()?.probe_for(board, side)
+}
+
+/// Whether `winner` is the same side as `side`.
+fn wins(winner: Winner, side: Side) -> bool {
+    matches!(
+        (winner, side),
+        (Winner::Goats, Side::Goats) | (Winner::Tigers, Side::Tigers)
+    )
+}
+
+/// The rules that decide a table's values, as text.
+fn describe(rules: &RuleSet) -> String {
+    format!(
+        "{} tigers={} threshold={} multi_capture={:?} forced_capture={} hopeless={:?}",
+        rules.topology.name(),
+        rules.tiger_squares.len(),
+        rules.capture_threshold,
+        rules.multi_capture,
+        rules.forced_capture,
+        rules.adjudicate_hopeless,
+    )
+}
+
+/// Packs a position seen through `symmetry` into 64 bits: two bits per
+/// point, then the captured goats and the side to move.
+fn pack(board: &Board, symmetry: &[usize], side: Side) -> u64 {
+    let mut key = 0;
+    for (pos, &piece) in board.cells.iter().enumerate() {
+        let code = match piece {
+            Piece::Empty => 0,
+            Piece::Goat => 1,
+            Piece::Tiger => 2,
+        };
+        key |= code << (2 * symmetry[pos]);
+    }
+    key |= (board.captured_goats as u64) << 52;
+    key |= ((side == Side::Tigers) as u64) << 58;
+    key
+}
+
+fn canonical_key(board: &Board, symmetries: &[Vec<usize>]) -> u64 {
+    let side = board.side_to_move();
+    symmetries
+        .iter()
+        .map(|symmetry| pack(board, symmetry, side))
+        .min()
+        .expect("the identity is always a symmetry")
+}
+
+/// The position a key was packed from, as seen through the identity.
+fn unpack(template: &Board, key: u64) -> Board {
+    let mut board = template.clone();
+    for pos in 0..board.cells.len() {
+        board.cells[pos] = match (key >> (2 * pos)) & 3 {
+            1 => Piece::Goat,
+            2 => Piece::Tiger,
+            _ => Piece::Empty,
+        };
+    }
+    board.captured_goats = ((key >> 52) & 0x3f) as u32;
+    board.ply = ((key >> 58) & 1) as usize;
+    board
+}
+
+fn position(
+    template: &Board,
+    tigers: &[usize],
+    goats: &[usize],
+    captured: u32,
+    ply: usize,
+) -> Board {
+    let mut board = template.clone();
+    board.cells.fill(Piece::Empty);
+    for &pos in tigers {
+        board.cells[pos] = Piece::Tiger;
+    }
+    for &pos in goats {
+        board.cells[pos] = Piece::Goat;
+    }
+    board.captured_goats = captured;
+    board.ply = ply;
+    board
+}
+
+/// Every way of choosing `k` items from `items`, in order.
+fn combinations(items: &[usize], k: usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![Vec::new()];
+    }
+    let mut result = Vec::new();
+    for (i, &first) in items.iter().enumerate() {
+        for mut rest in combinations(&items[i + 1..], k - 1) {
+            rest.insert(0, first);
+            result.push(rest);
+        }
+    }
+    result
+}
+
+/// Reads a tablebase file front to back.
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        if self.data.len() < len {
+            return Err(io::Error::new(
+                ErrorKind::UnexpectedEof,
+                "tablebase file is truncated",
+            ));
+        }
+        let (taken, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        Ok(self.take(N)?.try_into().expect("took N bytes"))
+    }
+}
@@ -0,0 +1,175 @@
+//! Colour themes: the colours the board is drawn in, by what they mark
+//! rather than by name, so a player who can't tell red from yellow can
+//! swap them for colours they can.
+//!
+//! A theme file is made of `key: value` lines like a save, with `#`
+//! starting a comment line. `theme` names the built-in theme to start
+//! from, and each other key sets the colour of one role:
+//!
+//! ```text
+//! # Baghchal theme
+//! theme: colorblind
+//! goat: bright white
+//! borders: #808080
+//! ```
+//!
+//! A colour is one of the sixteen terminal colours, such as `red` or
+//! `bright yellow`, or `#rrggbb` on terminals that take any colour.
+//! Roles left out keep the colour of the theme started from, the default
+//! one if there's no `theme` line.
+
+use crate::ParseError;
+use colored::Color;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+
+/// The colours [`Board::display_with`](crate::Board::display_with) draws
+/// with, see [`DisplayOptions::theme`](crate::DisplayOptions::theme).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub tiger: Color,
+    pub goat: Color,
+    /// The points a selected piece can move to.
+    pub hint: Color,
+    /// A capture: the points a selected tiger would capture by moving to,
+    /// the goats a tiger could capture next and the points the last move
+    /// captured on.
+    pub danger: Color,
+    /// The selected piece.
+    pub selection: Color,
+    /// The lines between the points, the point the last move left and the
+    /// borders of the status panels.
+    pub borders: Color,
+}
+
+impl Theme {
+    /// The names [`Theme::builtin`] knows.
+    pub const NAMES: [&'static str; 3] = ["default", "high-contrast", "colorblind"];
+
+    /// Bright colours on the lines of the terminal's own.
+    pub fn high_contrast() -> Self {
+        Theme {
+            tiger: Color::BrightRed,
+            goat: Color::BrightWhite,
+            hint: Color::BrightGreen,
+            danger: Color::BrightMagenta,
+            selection: Color::BrightCyan,
+            borders: Color::White,
+        }
+    }
+
+    /// Blue tigers and yellow goats, with no red against green, which
+    /// players with either kind of red-green colour blindness can tell
+    /// apart.
+    pub fn colorblind() -> Self {
+        Theme {
+            tiger: Color::BrightBlue,
+            goat: Color::BrightYellow,
+            hint: Color::BrightCyan,
+            danger: Color::BrightMagenta,
+            selection: Color::BrightWhite,
+            borders: Color::White,
+        }
+    }
+
+    /// The built-in theme called `name`, one of [`Theme::NAMES`].
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Theme::default()),
+            "high-contrast" => Some(Theme::high_contrast()),
+            "colorblind" => Some(Theme::colorblind()),
+            _ => None,
+        }
+    }
+
+    /// Reads a theme in the format described in the [module
+    /// documentation](self) from a file.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        Theme::parse(&text).map_err(|err| io::Error::new(ErrorKind::InvalidData, err))
+    }
+
+    /// Reads a theme in the format described in the [module
+    /// documentation](self).
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let mut fields = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let number = index + 1;
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once(':') else {
+                return Err(ParseError::new(number, 1, "expected 'key: value'"));
+            };
+            // The column the value starts in
+            let column = key.len() + 2 + value.len() - value.trim_start().len();
+            fields.push((number, key.trim(), value.trim(), column));
+        }
+
+        let mut theme = Theme::default();
+        if let Some(&(line, _, name, column)) = fields.iter().find(|field| field.1 == "theme") {
+            theme = Theme::builtin(name).ok_or_else(|| {
+                ParseError::new(
+                    line,
+                    column,
+                    format!(
+                        "unknown theme '{name}', expected {}",
+                        Theme::NAMES.join(", ")
+                    ),
+                )
+            })?;
+        }
+        for (line, key, value, column) in fields {
+            let role = match key {
+                "theme" => continue,
+                "tiger" => &mut theme.tiger,
+                "goat" => &mut theme.goat,
+                "hint" => &mut theme.hint,
+                "danger" => &mut theme.danger,
+                "selection" => &mut theme.selection,
+                "borders" => &mut theme.borders,
+                _ => return Err(ParseError::new(line, 1, format!("unknown role '{key}'"))),
+            };
+            *role = parse_color(value).ok_or_else(|| {
+                ParseError::new(
+                    line,
+                    column,
+                    format!("invalid {key} '{value}', expected a colour"),
+                )
+            })?;
+        }
+        Ok(theme)
+    }
+}
+
+impl Default for Theme {
+    /// Red tigers and yellow goats.
+    fn default() -> Self {
+        Theme {
+            tiger: Color::BrightRed,
+            goat: Color::BrightYellow,
+            hint: Color::BrightGreen,
+            danger: Color::Red,
+            selection: Color::BrightCyan,
+            borders: Color::BrightBlack,
+        }
+    }
+}
+
+/// A colour by name, with `-` or `_` for the space in the bright ones, or
+/// as `#rrggbb`.
+fn parse_color(text: &str) -> Option<Color> {
+    if let Some(hex) = text.strip_prefix('#') {
+        let channel = |at: usize| u8::from_str_radix(hex.get(at..at + 2)?, 16).ok();
+        return match hex.len() {
+            6 => Some(Color::TrueColor {
+                r: channel(0)?,
+                g: channel(2)?,
+                b: channel(4)?,
+            }),
+            _ => None,
+        };
+    }
+    text.replace(['-', '_'], " ").parse().ok()
+}
@@ -0,0 +1,329 @@
+//! Board shapes: which points exist, how they are connected, and where a
+//! tiger can jump.
+//!
+//! A topology is described by its lines. Two points next to each other on a
+//! line are adjacent, and three consecutive points on a line form a jump: a
+//! tiger on the first point can capture a goat on the second by landing on
+//! the third.
+
+/// A capturing jump along a line: from `from`, over `over`, landing on `to`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Jump {
+    pub from: usize,
+    pub over: usize,
+    pub to: usize,
+}
+
+/// How a board is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// A square grid with the given number of points per side.
+    Grid(usize),
+    /// An apex joined to the rows below it by a fan of lines.
+    Triangle,
+    /// Anything else built with [`Topology::from_lines`].
+    Custom,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Topology {
+    name: String,
+    layout: Layout,
+    /// Layout position (row, column) of each point, used for rendering and
+    /// for telling orthogonal lines from diagonal ones.
+    coords: Vec<(usize, usize)>,
+    neighbors: Vec<Vec<usize>>,
+    jumps: Vec<Vec<Jump>>,
+    interior: Vec<bool>,
+}
+
+impl Topology {
+    /// Builds a topology from point coordinates and the lines connecting
+    /// them. Each line lists point indices in order along the line.
+    pub fn from_lines(name: &str, coords: Vec<(usize, usize)>, lines: &[Vec<usize>]) -> Self {
+        let count = coords.len();
+        let mut neighbors = vec![Vec::new(); count];
+        let mut jumps = vec![Vec::new(); count];
+
+        for line in lines {
+            for pair in line.windows(2) {
+                let (a, b) = (pair[0], pair[1]);
+                if !neighbors[a].contains(&b) {
+                    neighbors[a].push(b);
+                    neighbors[b].push(a);
+                }
+            }
+            for triple in line.windows(3) {
+                let (a, over, b) = (triple[0], triple[1], triple[2]);
+                jumps[a].push(Jump {
+                    from: a,
+                    over,
+                    to: b,
+                });
+                jumps[b].push(Jump {
+                    from: b,
+                    over,
+                    to: a,
+                });
+            }
+        }
+
+        for list in &mut neighbors {
+            list.sort_unstable();
+        }
+        for list in &mut jumps {
+            list.sort_unstable_by_key(|jump| jump.to);
+        }
+
+        let last_row = coords.iter().map(|&(row, _)| row).max().unwrap_or(0);
+        let interior = coords
+            .iter()
+            .map(|&(row, col)| {
+                let row_cols = coords.iter().filter(|&&(r, _)| r == row).map(|&(_, c)| c);
+                let first = row_cols.clone().min().unwrap_or(col);
+                let last = row_cols.max().unwrap_or(col);
+                row != 0 && row != last_row && col != first && col != last
+            })
+            .collect();
+
+        Topology {
+            name: name.to_string(),
+            layout: Layout::Custom,
+            coords,
+            neighbors,
+            jumps,
+            interior,
+        }
+    }
+
+    /// The standard Bagh-Chal board: a 5×5 Alquerque grid.
+    pub fn standard() -> Self {
+        Self::alquerque(5)
+    }
+
+    /// An N×N Alquerque grid: orthogonal lines everywhere and diagonals
+    /// through every point whose row and column add up to an even number.
+    pub fn alquerque(size: usize) -> Self {
+        let index = |row: usize, col: usize| row * size + col;
+        let coords = (0..size * size).map(|p| (p / size, p % size)).collect();
+        let mut lines = Vec::new();
+
+        for i in 0..size {
+            lines.push((0..size).map(|col| index(i, col)).collect());
+            lines.push((0..size).map(|row| index(row, i)).collect());
+        }
+
+        // Down-right diagonals have constant row - col, down-left ones have
+        // constant row + col; only the even ones are drawn.
+        for start_col in (0..size).step_by(2) {
+            lines.push(
+                (0..size - start_col)
+                    .map(|k| index(k, start_col + k))
+                    .collect(),
+            );
+            lines.push((0..=start_col).map(|k| index(k, start_col - k)).collect());
+        }
+        for start_row in (2..size).step_by(2) {
+            lines.push(
+                (0..size - start_row)
+                    .map(|k| index(start_row + k, k))
+                    .collect(),
+            );
+        }
+        let last_col = size - 1;
+        let first_row = if last_col.is_multiple_of(2) { 2 } else { 1 };
+        for start_row in (first_row..size).step_by(2) {
+            lines.push(
+                (0..size - start_row)
+                    .map(|k| index(start_row + k, last_col - k))
+                    .collect(),
+            );
+        }
+        lines.retain(|line: &Vec<usize>| line.len() > 1);
+
+        let name = format!("{size}x{size}");
+        let mut topology = Self::from_lines(&name, coords, &lines);
+        topology.layout = Layout::Grid(size);
+        topology
+    }
+
+    /// The 23-point Aadu Puli Attam board: an apex with four lines fanning
+    /// down through four rows, crossed by a rectangle whose three rows
+    /// stick out one point on either side of the triangle.
+    ///
+    /// Point 0 is the apex; the rectangle rows hold points 1-6, 7-12 and
+    /// 13-18 from left to right, and the base of the triangle 19-22.
+    pub fn aadu_puli_attam() -> Self {
+        let index = |row: usize, col: usize| match row {
+            0 => 0,
+            1..=3 => 1 + (row - 1) * 6 + col,
+            _ => 19 + col - 1,
+        };
+        let mut coords = vec![(0, 2)];
+        for row in 1..=3 {
+            coords.extend((0..6).map(|col| (row, col)));
+        }
+        coords.extend((1..5).map(|col| (4, col)));
+
+        let mut lines = Vec::new();
+        for col in 1..5 {
+            lines.push(
+                std::iter::once(0)
+                    .chain((1..=4).map(|row| index(row, col)))
+                    .collect(),
+            );
+        }
+        for row in 1..=3 {
+            lines.push((0..6).map(|col| index(row, col)).collect());
+        }
+        lines.push((1..5).map(|col| index(4, col)).collect());
+        for col in [0, 5] {
+            lines.push((1..=3).map(|row| index(row, col)).collect());
+        }
+
+        let mut topology = Self::from_lines("aadu-puli-attam", coords, &lines);
+        topology.layout = Layout::Triangle;
+        topology
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// Number of points on the board.
+    pub fn len(&self) -> usize {
+        self.coords.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.coords.is_empty()
+    }
+
+    /// Layout position (row, column) of a point.
+    pub fn coords(&self, pos: usize) -> (usize, usize) {
+        self.coords[pos]
+    }
+
+    /// Name of a point in grid notation: a column letter followed by a
+    /// 1-based row number, e.g. `C3`.
+    pub fn point_name(&self, pos: usize) -> String {
+        let (row, col) = self.coords[pos];
+        format!("{}{}", (b'A' + col as u8) as char, row + 1)
+    }
+
+    /// Finds the point with the given name, as produced by
+    /// [`Topology::point_name`]. The column letter may be lower case.
+    pub fn parse_point(&self, name: &str) -> Option<usize> {
+        let mut chars = name.chars();
+        let letter = chars.next()?.to_ascii_uppercase();
+        if !letter.is_ascii_uppercase() {
+            return None;
+        }
+        let col = (letter as u8 - b'A') as usize;
+        let row = chars.as_str().parse::<usize>().ok()?.checked_sub(1)?;
+        self.coords.iter().position(|&coords| coords == (row, col))
+    }
+
+    /// Whether `pos` lies inside the board rather than on its outer edge:
+    /// not in the first or last row, nor at either end of its row.
+    pub fn is_interior(&self, pos: usize) -> bool {
+        self.interior[pos]
+    }
+
+    /// Points grouped by layout row from top to bottom, each row ordered
+    /// left to right.
+    pub fn rows(&self) -> Vec<Vec<usize>> {
+        let mut rows: Vec<Vec<usize>> = Vec::new();
+        for (pos, &(row, _)) in self.coords.iter().enumerate() {
+            if rows.len() <= row {
+                rows.resize(row + 1, Vec::new());
+            }
+            rows[row].push(pos);
+        }
+        for row in &mut rows {
+            row.sort_unstable_by_key(|&pos| self.coords[pos].1);
+        }
+        rows.retain(|row| !row.is_empty());
+        rows
+    }
+
+    /// Points directly connected to `pos` by a line.
+    pub fn neighbors(&self, pos: usize) -> &[usize] {
+        &self.neighbors[pos]
+    }
+
+    /// Every jump a tiger standing on `pos` could make.
+    pub fn jumps_from(&self, pos: usize) -> &[Jump] {
+        &self.jumps[pos]
+    }
+
+    /// Returns the jump from `from` to `to`, if the two are a jump apart.
+    pub fn jump_between(&self, from: usize, to: usize) -> Option<Jump> {
+        self.jumps
+            .get(from)?
+            .iter()
+            .find(|jump| jump.to == to)
+            .copied()
+    }
+
+    /// Permutations of the points that map the board onto itself with all
+    /// its lines: the rotations and reflections of the layout that fit.
+    /// The identity comes first. `symmetry[pos]` is where `pos` goes.
+    pub fn symmetries(&self) -> Vec<Vec<usize>> {
+        let last_row = self.coords.iter().map(|&(row, _)| row).max().unwrap_or(0);
+        let last_col = self.coords.iter().map(|&(_, col)| col).max().unwrap_or(0);
+        let transforms: [&dyn Fn(usize, usize) -> (usize, usize); 8] = [
+            &|row, col| (row, col),
+            &|row, col| (row, last_col - col),
+            &|row, col| (last_row - row, col),
+            &|row, col| (last_row - row, last_col - col),
+            &|row, col| (col, row),
+            &|row, col| (col, last_row - row),
+            &|row, col| (last_col - col, row),
+            &|row, col| (last_col - col, last_row - row),
+        ];
+
+        transforms
+            .iter()
+            .filter_map(|transform| {
+                let symmetry = self
+                    .coords
+                    .iter()
+                    .map(|&(row, col)| {
+                        let target = transform(row, col);
+                        self.coords.iter().position(|&coords| coords == target)
+                    })
+                    .collect::<Option<Vec<usize>>>()?;
+                let keeps_lines = (0..self.len()).all(|pos| {
+                    self.neighbors[pos]
+                        .iter()
+                        .all(|&other| self.neighbors[symmetry[pos]].contains(&symmetry[other]))
+                        && self.jumps[pos].iter().all(|jump| {
+                            self.jump_between(symmetry[jump.from], symmetry[jump.to])
+                                .is_some_and(|image| image.over == symmetry[jump.over])
+                        })
+                });
+                keeps_lines.then_some(symmetry)
+            })
+            .collect()
+    }
+
+    /// Whether any diagonal line passes through `pos`.
+    pub fn has_diagonals(&self, pos: usize) -> bool {
+        let (row, col) = self.coords[pos];
+        self.neighbors[pos].iter().any(|&other| {
+            let (other_row, other_col) = self.coords[other];
+            other_row != row && other_col != col
+        })
+    }
+}
+
+impl Default for Topology {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
@@ -0,0 +1,344 @@
+//! Matches between two engines, to tell whether a change to one makes it
+//! stronger.
+//!
+//! A [`Tournament`] plays pairs of games in which each engine takes each
+//! side once from the same opening, so that neither gains from the side it
+//! happens to play. Engines search a fixed number of nodes per move, so a
+//! match gives the same games on any hardware. The [`TournamentResult`]
+//! estimates the difference in Elo rating between the engines, and prints
+//! as a table or as JSON.
+
+use crate::adjudication::settled;
+use crate::{AdjudicationPolicy, AiConfig, Board, Engine, GameResult, Move, Side, Winner};
+use std::fmt::{self, Display};
+use std::time::{Duration, Instant};
+
+/// How a match between two engines is played.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tournament {
+    /// Pairs of games to play. Each engine plays the tigers in one game of
+    /// every pair and the goats in the other.
+    pub pairs: usize,
+    /// Limits on each engine's search per move. A node limit keeps the
+    /// games the same from one run to the next.
+    pub limits: AiConfig,
+    /// Moves to start games from, for variety, each used for a pair of
+    /// games in turn. Games start from the initial position if empty.
+    pub openings: Vec<Vec<Move>>,
+    /// Stops a game once it is decided or drawn in all but name, see
+    /// [`settled`].
+    pub adjudication: AdjudicationPolicy,
+    /// Plies after which a game still going is adjudicated, with
+    /// [`Board::adjudicate`], from the start of the opening.
+    pub max_plies: usize,
+}
+
+impl Default for Tournament {
+    fn default() -> Self {
+        Tournament {
+            pairs: 10,
+            limits: AiConfig::nodes(5_000),
+            openings: Vec::new(),
+            adjudication: AdjudicationPolicy::default(),
+            max_plies: 300,
+        }
+    }
+}
+
+impl Tournament {
+    /// Plays the match between engines made by `a` and `b`. Every game
+    /// gets new engines, so that nothing one learns carries over to the
+    /// next.
+    ///
+    /// # Panics
+    ///
+    /// If an opening has a move that is illegal where it is played.
+    pub fn run<A: Engine, B: Engine>(
+        &self,
+        mut a: impl FnMut() -> A,
+        mut b: impl FnMut() -> B,
+    ) -> TournamentResult {
+        let mut games = Vec::with_capacity(self.pairs * 2);
+        for pair in 0..self.pairs {
+            let opening = (!self.openings.is_empty()).then(|| pair % self.openings.len());
+            for a_plays in [Side::Tigers, Side::Goats] {
+                let mut engine_a = a();
+                let mut engine_b = b();
+                let (tigers, goats): (&mut dyn Engine, &mut dyn Engine) = match a_plays {
+                    Side::Tigers => (&mut engine_a, &mut engine_b),
+                    Side::Goats => (&mut engine_b, &mut engine_a),
+                };
+                let moves = opening.map_or(&[][..], |opening| &self.openings[opening]);
+                let (result, plies) = self.play_game(moves, tigers, goats);
+                games.push(GameOutcome {
+                    pair,
+                    opening,
+                    a_plays,
+                    result,
+                    plies,
+                });
+            }
+        }
+        TournamentResult { games }
+    }
+
+    /// Plays one game from `opening`, returning its result and length.
+    fn play_game(
+        &self,
+        opening: &[Move],
+        tigers: &mut dyn Engine,
+        goats: &mut dyn Engine,
+    ) -> (GameResult, usize) {
+        let mut board = Board::new();
+        board.set_use_opening_book(false);
+        board.set_ai_config(self.limits);
+        for (i, mv) in opening.iter().enumerate() {
+            assert!(board.make_move(mv), "opening move {} is illegal", i + 1);
+        }
+        let result = play_out(
+            &mut board,
+            tigers,
+            goats,
+            &self.adjudication,
+            self.max_plies,
+            None,
+            |_, _| {},
+        );
+        (result, board.ply())
+    }
+}
+
+/// Plays `board` on between `tigers` and `goats` until it is settled, see
+/// [`settled`], or reaches `max_plies`, and adjudicates it then if the
+/// rules haven't ended it. `limits`, the tigers' and the goats', replace
+/// the board's AI settings before each of that side's moves, and `timed`
+/// is told how long each move took.
+pub(crate) fn play_out(
+    board: &mut Board,
+    tigers: &mut dyn Engine,
+    goats: &mut dyn Engine,
+    adjudication: &AdjudicationPolicy,
+    max_plies: usize,
+    limits: Option<(AiConfig, AiConfig)>,
+    mut timed: impl FnMut(Side, Duration),
+) -> GameResult {
+    loop {
+        if let Some(result) = settled(board, adjudication) {
+            if board.result().is_none() {
+                board.adjudicate(adjudication);
+            }
+            return result;
+        }
+        if board.ply() >= max_plies {
+            return board.adjudicate(adjudication);
+        }
+        let side = board.side_to_move();
+        if let Some((tiger_limits, goat_limits)) = limits {
+            board.set_ai_config(match side {
+                Side::Tigers => tiger_limits,
+                Side::Goats => goat_limits,
+            });
+        }
+        let started = Instant::now();
+        let played = match side {
+            Side::Tigers => board.play_engine_move(tigers),
+            Side::Goats => board.play_engine_move(goats),
+        };
+        timed(side, started.elapsed());
+        if played.is_none() {
+            // A side to move with no legal move has lost already, so this
+            // only happens to an engine that gives up
+            return board.adjudicate(adjudication);
+        }
+    }
+}
+
+/// One game of a match, seen from the first engine, "A".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameOutcome {
+    /// The pair of games this one belongs to, counting from 0.
+    pub pair: usize,
+    /// Index of the opening the game started from, if any.
+    pub opening: Option<usize>,
+    /// The side engine A played.
+    pub a_plays: Side,
+    pub result: GameResult,
+    /// Length of the game, opening included.
+    pub plies: usize,
+}
+
+impl GameOutcome {
+    /// Whether engine A won the game, `None` for a draw.
+    pub fn a_won(&self) -> Option<bool> {
+        match (self.result.winner, self.a_plays) {
+            (Winner::Tigers, side) => Some(side == Side::Tigers),
+            (Winner::Goats, side) => Some(side == Side::Goats),
+            (Winner::Draw | Winner::None, _) => None,
+        }
+    }
+
+    /// Engine A's score: 1 for a win, ½ for a draw and 0 for a loss.
+    pub fn score(&self) -> f64 {
+        match self.a_won() {
+            Some(true) => 1.0,
+            Some(false) => 0.0,
+            None => 0.5,
+        }
+    }
+}
+
+/// A rating difference with its 95% confidence interval, `difference ±
+/// margin`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Elo {
+    pub difference: f64,
+    pub margin: f64,
+}
+
+/// The games of a match, in the order they were played.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TournamentResult {
+    pub games: Vec<GameOutcome>,
+}
+
+impl TournamentResult {
+    /// Engine A's wins, draws and losses, counting only games in which it
+    /// played `side`, or all of them for `None`.
+    pub fn record(&self, side: Option<Side>) -> (usize, usize, usize) {
+        let mut record = (0, 0, 0);
+        for game in &self.games {
+            if side.is_some_and(|side| side != game.a_plays) {
+                continue;
+            }
+            match game.a_won() {
+                Some(true) => record.0 += 1,
+                Some(false) => record.2 += 1,
+                None => record.1 += 1,
+            }
+        }
+        record
+    }
+
+    /// Engine A's share of the points, from 0 to 1.
+    pub fn score(&self) -> f64 {
+        if self.games.is_empty() {
+            return 0.5;
+        }
+        self.games.iter().map(GameOutcome::score).sum::<f64>() / self.games.len() as f64
+    }
+
+    /// How much stronger engine A is than engine B, from the share of the
+    /// points it scored. The margin comes from the spread of the game
+    /// results. A match one engine won outright is counted as half a point
+    /// short of a clean sweep, so the estimate stays finite.
+    pub fn elo(&self) -> Elo {
+        if self.games.is_empty() {
+            return Elo {
+                difference: 0.0,
+                margin: 0.0,
+            };
+        }
+        let games = self.games.len() as f64;
+        let floor = 0.5 / games;
+        let score = self.score().clamp(floor, 1.0 - floor);
+        let variance = self
+            .games
+            .iter()
+            .map(|game| (game.score() - score).powi(2))
+            .sum::<f64>()
+            / games;
+        let spread = 1.96 * (variance / games).sqrt();
+        let low = elo_from_score((score - spread).clamp(floor, 1.0 - floor));
+        let high = elo_from_score((score + spread).clamp(floor, 1.0 - floor));
+        Elo {
+            difference: elo_from_score(score),
+            margin: (high - low) / 2.0,
+        }
+    }
+
+    /// The match as a JSON object: the totals, the Elo estimate and every
+    /// game.
+    pub fn to_json(&self) -> String {
+        let (wins, draws, losses) = self.record(None);
+        let elo = self.elo();
+        let games: Vec<String> = self
+            .games
+            .iter()
+            .map(|game| {
+                format!(
+                    "{{\"pair\":{},\"opening\":{},\"a_plays\":\"{}\",\"winner\":\"{}\",\"reason\":\"{:?}\",\"plies\":{}}}",
+                    game.pair,
+                    game.opening
+                        .map_or("null".to_string(), |opening| opening.to_string()),
+                    side_name(game.a_plays),
+                    winner_name(game.result.winner),
+                    game.result.reason,
+                    game.plies,
+                )
+            })
+            .collect();
+        format!(
+            "{{\"games\":{},\"wins\":{wins},\"draws\":{draws},\"losses\":{losses},\"score\":{:.4},\"elo\":{:.1},\"elo_margin\":{:.1},\"results\":[{}]}}",
+            self.games.len(),
+            self.score(),
+            elo.difference,
+            elo.margin,
+            games.join(","),
+        )
+    }
+}
+
+impl Display for TournamentResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<16} {:>6} {:>6} {:>6} {:>6} {:>7}",
+            "engine A", "games", "wins", "draws", "losses", "score"
+        )?;
+        for (label, side) in [
+            ("as tigers", Some(Side::Tigers)),
+            ("as goats", Some(Side::Goats)),
+            ("total", None),
+        ] {
+            let (wins, draws, losses) = self.record(side);
+            let games = wins + draws + losses;
+            let score = if games == 0 {
+                0.0
+            } else {
+                (wins as f64 + draws as f64 / 2.0) / games as f64
+            };
+            writeln!(
+                f,
+                "{label:<16} {games:>6} {wins:>6} {draws:>6} {losses:>6} {:>6.1}%",
+                score * 100.0
+            )?;
+        }
+        let elo = self.elo();
+        write!(
+            f,
+            "Elo difference: {:+.1} ± {:.1} (95%)",
+            elo.difference, elo.margin
+        )
+    }
+}
+
+/// The Elo difference at which the stronger side expects `score`.
+fn elo_from_score(score: f64) -> f64 {
+    400.0 * (score / (1.0 - score)).log10()
+}
+
+fn side_name(side: Side) -> &'static str {
+    match side {
+        Side::Tigers => "tigers",
+        Side::Goats => "goats",
+    }
+}
+
+/// How a game's winner is written in JSON.
+pub(crate) fn winner_name(winner: Winner) -> &'static str {
+    match winner {
+        Winner::Tigers => "tigers",
+        Winner::Goats => "goats",
+        Winner::Draw | Winner::None => "draw",
+    }
+}
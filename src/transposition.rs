@@ -0,0 +1,163 @@
+//! Scores of positions the AI has searched, kept for the rest of the game.
+//!
+//! Every search stores what it finds in a [`TranspositionTable`], keyed by
+//! `position_key`, and takes a score from it when a position comes up
+//! again deep enough. The table outlives the search: the AI's next move
+//! searches much of the same tree, so it starts from what the last one
+//! found. Each search is a new generation, and an entry left by an
+//! earlier one gives way to any new score, while within a search the
+//! deeper score is kept.
+//!
+//! The table is shared without locks, by the threads of a multithreaded
+//! search and by a pondering search: each entry is two atomic words, the
+//! position's key mixed with the data and the data itself, so an entry
+//! torn by two threads writing at once no longer matches its key and is
+//! ignored.
+
+use std::fmt;
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+
+/// Entries in the table, 16 bytes each.
+pub(crate) const TABLE_ENTRIES: usize = 1 << 18;
+
+/// Scores at least this far from 0 are decided games, which the search
+/// scores by their distance from the root.
+const DECIDED: i32 = 9000;
+
+/// What a stored score says about the position's true score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bound {
+    Exact = 1,
+    /// The true score is at least this: the search was cut off above.
+    Lower = 2,
+    /// The true score is at most this: no move reached the window.
+    Upper = 3,
+}
+
+/// Scores of positions searched this game, keyed by `position_key`.
+pub(crate) struct TranspositionTable {
+    entries: Vec<[AtomicU64; 2]>,
+    /// The search storing scores now, counting up from 0 and wrapping.
+    generation: AtomicU8,
+}
+
+impl TranspositionTable {
+    pub(crate) fn new(entries: usize) -> Self {
+        TranspositionTable {
+            entries: (0..entries)
+                .map(|_| [AtomicU64::new(0), AtomicU64::new(0)])
+                .collect(),
+            generation: AtomicU8::new(0),
+        }
+    }
+
+    /// Starts a new generation, so that the scores stored so far give way
+    /// to the next search's.
+    pub(crate) fn new_search(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// The score stored for `key`, if it was searched at least `depth`
+    /// plies deep and settles the window `alpha..beta`. `ply` is how far
+    /// the position is from the root, for scoring decided games.
+    pub(crate) fn probe(
+        &self,
+        key: u64,
+        depth: i32,
+        alpha: i32,
+        beta: i32,
+        ply: usize,
+    ) -> Option<i32> {
+        let [check, data] = &self.entries[self.index(key)];
+        let data = data.load(Ordering::Relaxed);
+        if check.load(Ordering::Relaxed) ^ data != key {
+            return None;
+        }
+        let score = data as u32 as i32;
+        let bound = match data >> 40 & 0b11 {
+            1 => Bound::Exact,
+            2 => Bound::Lower,
+            3 => Bound::Upper,
+            _ => return None,
+        };
+        if stored_depth(data) < depth {
+            return None;
+        }
+        let score = from_stored(score, ply);
+        match bound {
+            Bound::Exact => Some(score),
+            Bound::Lower if score >= beta => Some(score),
+            Bound::Upper if score <= alpha => Some(score),
+            _ => None,
+        }
+    }
+
+    /// Stores `score`, found searching `depth` plies deep with the window
+    /// `alpha..beta`, unless the entry holds a deeper score from this
+    /// search.
+    pub(crate) fn store(
+        &self,
+        key: u64,
+        depth: i32,
+        score: i32,
+        alpha: i32,
+        beta: i32,
+        ply: usize,
+    ) {
+        let generation = self.generation.load(Ordering::Relaxed);
+        let [check, stored] = &self.entries[self.index(key)];
+        let old = stored.load(Ordering::Relaxed);
+        if old >> 48 & 0xff == generation as u64 && stored_depth(old) > depth {
+            return;
+        }
+        let bound = if score <= alpha {
+            Bound::Upper
+        } else if score >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        let data = to_stored(score, ply) as u32 as u64
+            | (depth.clamp(0, 255) as u64) << 32
+            | (bound as u64) << 40
+            | (generation as u64) << 48;
+        check.store(key ^ data, Ordering::Relaxed);
+        stored.store(data, Ordering::Relaxed);
+    }
+
+    fn index(&self, key: u64) -> usize {
+        (key % self.entries.len() as u64) as usize
+    }
+}
+
+impl fmt::Debug for TranspositionTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TranspositionTable")
+            .field("entries", &self.entries.len())
+            .field("generation", &self.generation)
+            .finish()
+    }
+}
+
+fn stored_depth(data: u64) -> i32 {
+    (data >> 32 & 0xff) as i32
+}
+
+/// A decided score as seen from the position, rather than the root, so
+/// it can be used wherever the position comes up again. Saturating, as
+/// a node with no move to try scores `i32::MIN` or `i32::MAX`.
+fn to_stored(score: i32, ply: usize) -> i32 {
+    if score.saturating_abs() >= DECIDED {
+        score.signum() * score.saturating_abs().saturating_add(ply as i32)
+    } else {
+        score
+    }
+}
+
+fn from_stored(score: i32, ply: usize) -> i32 {
+    if score.saturating_abs() >= DECIDED {
+        score.signum() * (score.saturating_abs() - ply as i32)
+    } else {
+        score
+    }
+}
@@ -0,0 +1,110 @@
+use baghchal::adjudication::{settled, SearchThreshold};
+use baghchal::{
+    adjudicate, AdjudicationPolicy, Board, GameEvent, GameResult, TerminationReason, Winner,
+};
+
+fn adjudicated(winner: Winner) -> GameResult {
+    GameResult {
+        winner,
+        reason: TerminationReason::Adjudicated,
+    }
+}
+
+#[test]
+fn test_adjudicate_repetition() {
+    let mut board = Board::from_fen("T3T/5/2G2/5/T3T t 0 1 20").unwrap();
+    let policy = AdjudicationPolicy::default();
+    for round in 0..2 {
+        assert!(board.move_tiger(0, 1));
+        assert!(board.move_goat(12, 13));
+        assert!(board.move_tiger(1, 0));
+        assert!(board.move_goat(13, 12));
+        if round == 0 {
+            // Twice isn't enough, and the goats have too few left to lose
+            assert_eq!(adjudicate(&board, &policy), adjudicated(Winner::Goats));
+        }
+    }
+    assert_eq!(adjudicate(&board, &policy), adjudicated(Winner::Draw));
+}
+
+#[test]
+fn test_settled_leaves_undecided_games_open() {
+    let policy = AdjudicationPolicy {
+        goats_win_when_hopeless: false,
+        ..AdjudicationPolicy::default()
+    };
+    let mut board = Board::from_fen("T3T/5/2G2/5/T3T t 0 1 20").unwrap();
+    for _ in 0..2 {
+        // Nothing decides the game yet, where adjudicating calls it a draw
+        assert_eq!(settled(&board, &policy), None);
+        assert_eq!(adjudicate(&board, &policy), adjudicated(Winner::Draw));
+        assert!(board.move_tiger(0, 1));
+        assert!(board.move_goat(12, 13));
+        assert!(board.move_tiger(1, 0));
+        assert!(board.move_goat(13, 12));
+    }
+    assert_eq!(settled(&board, &policy), Some(adjudicated(Winner::Draw)));
+}
+
+#[test]
+fn test_adjudicate_material() {
+    let board = Board::from_fen("T3T/1G3/GGGGG/GGGGG/T3T t 4 3 16").unwrap();
+    let policy = AdjudicationPolicy {
+        tigers_win_at_captures: Some(3),
+        ..AdjudicationPolicy::default()
+    };
+    assert_eq!(adjudicate(&board, &policy), adjudicated(Winner::Tigers));
+    let policy = AdjudicationPolicy {
+        tigers_win_at_captures: Some(4),
+        ..policy
+    };
+    assert_eq!(adjudicate(&board, &policy), adjudicated(Winner::Draw));
+}
+
+#[test]
+fn test_adjudicate_search_threshold() {
+    // The tigers win a goat next move whatever happens
+    let board = Board::from_fen("TG2T/5/2G2/5/T3T t 18 0 2").unwrap();
+    let threshold = SearchThreshold {
+        depth: 2,
+        score: 50,
+        plies: 1,
+    };
+    let policy = AdjudicationPolicy {
+        search: Some(threshold),
+        ..AdjudicationPolicy::default()
+    };
+    assert_eq!(adjudicate(&board, &policy), adjudicated(Winner::Tigers));
+
+    // Not if the advantage has to have lasted longer than the game
+    let policy = AdjudicationPolicy {
+        search: Some(SearchThreshold {
+            plies: 3,
+            ..threshold
+        }),
+        ..policy
+    };
+    assert_eq!(adjudicate(&board, &policy), adjudicated(Winner::Draw));
+}
+
+#[test]
+fn test_board_adjudicate_ends_the_game() {
+    let mut board = Board::new();
+    assert!(board.place_goat(12));
+    board.drain_events();
+
+    let policy = AdjudicationPolicy::default();
+    let result = board.adjudicate(&policy);
+    assert_eq!(result, adjudicated(Winner::Draw));
+    assert!(board.is_game_over());
+    assert_eq!(board.drain_events(), vec![GameEvent::GameOver(result)]);
+
+    let record = board.record();
+    assert_eq!(record.result, Some(result));
+    assert_eq!(record.adjudication, Some(policy));
+
+    // Taking a move back reopens the game
+    assert!(board.undo());
+    assert!(!board.is_game_over());
+    assert_eq!(board.record().adjudication, None);
+}
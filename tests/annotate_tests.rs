@@ -0,0 +1,122 @@
+use baghchal::annotate::MoveReason;
+use baghchal::notation::parse_move;
+use baghchal::{annotate_move, Board, Jump, Move, MultiCapture, RuleSet};
+
+/// The annotation of `mv`, in move notation, played from `fen`.
+fn annotate(fen: &str, mv: &str) -> String {
+    let board = Board::from_fen(fen).unwrap();
+    let mv = parse_move(&board, mv).unwrap();
+    annotate_move(&board, mv).describe(board.topology())
+}
+
+#[test]
+fn test_annotates_captures() {
+    assert_eq!(
+        annotate("T3T/1G3/5/5/T3T t 19 0 2", "A1xB2-C3"),
+        "A1xB2-C3: captures the goat on B2; develops toward the centre"
+    );
+}
+
+#[test]
+fn test_annotates_goats_saved_from_capture() {
+    assert_eq!(
+        annotate("T3T/1G3/5/5/T3T g 19 0 2", "C3"),
+        "C3: blocks the capture of the goat on B2; develops toward the centre"
+    );
+    assert_eq!(
+        annotate("T3T/1G3/3T1/GGGGG/GGGGT g 0 0 30", "B2-C2"),
+        "B2-C2: moves the goat on B2 out of danger"
+    );
+}
+
+#[test]
+fn test_annotates_goats_left_hanging() {
+    assert_eq!(
+        annotate("T3T/5/5/5/T3T g 20 0 1", "B2"),
+        "B2: leaves the goat on B2 open to capture; develops toward the centre"
+    );
+}
+
+#[test]
+fn test_annotates_trapped_tigers() {
+    assert_eq!(
+        annotate("T1GGT/GG1GG/G1G1G/GG1GG/TGGGT g 4 0 17", "B1"),
+        "B1: wins the game; leaves the tiger on A1 with no moves"
+    );
+}
+
+#[test]
+fn test_annotates_tigers_walking_into_pockets() {
+    assert_eq!(
+        annotate("1GG1T/GGGG1/GGT2/1G1G1/T3T t 10 0 11", "C3xB2-A1"),
+        "C3xB2-A1: captures the goat on B2; threatens the goats on D2, B4 and D4 at once; \
+         walks the tiger into a pocket on A1"
+    );
+}
+
+#[test]
+fn test_annotates_double_threats() {
+    let board = Board::from_fen("4T/2T2/1G1G1/5/T3T t 18 0 2").unwrap();
+    let annotation = annotate_move(&board, parse_move(&board, "C2-C3").unwrap());
+    assert_eq!(
+        annotation.reasons,
+        vec![MoveReason::DoubleThreat(vec![11, 13])]
+    );
+    assert_eq!(
+        annotation.describe(board.topology()),
+        "C2-C3: threatens the goats on B3 and D3 at once"
+    );
+}
+
+#[test]
+fn test_quiet_and_illegal_moves_have_no_reasons() {
+    assert_eq!(
+        annotate("T3T/5/2G2/5/T3T t 19 0 1", "A1-B1"),
+        "A1-B1: a quiet move"
+    );
+    let board = Board::new();
+    let annotation = annotate_move(&board, Move::MoveGoat { from: 1, to: 2 });
+    assert!(annotation.reasons.is_empty());
+}
+
+#[test]
+fn test_describes_each_kind_of_move() {
+    let describe = |board: &Board, mv: Move| annotate_move(board, mv).describe(board.topology());
+    let board = Board::from_fen("T3T/1G3/5/5/T3T g 19 0 2").unwrap();
+    assert_eq!(
+        describe(&board, Move::PlaceGoat { position: 12 }),
+        "C3: blocks the capture of the goat on B2; develops toward the centre"
+    );
+    let board = Board::from_fen("T3T/1G3/5/5/T3T g 0 0 30").unwrap();
+    assert_eq!(
+        describe(&board, Move::MoveGoat { from: 6, to: 7 }),
+        "B2-C2: moves the goat on B2 out of danger"
+    );
+    let board = Board::from_fen("T3T/1G3/5/5/T3T t 19 0 2").unwrap();
+    let quiet = Move::MoveTiger {
+        from: 4,
+        to: 3,
+        captured_position: None,
+    };
+    assert_eq!(describe(&board, quiet), "E1-D1: a quiet move");
+    let capture = Move::MoveTiger {
+        from: 0,
+        to: 12,
+        captured_position: Some(6),
+    };
+    assert_eq!(
+        describe(&board, capture),
+        "A1xB2-C3: captures the goat on B2; develops toward the centre"
+    );
+
+    let rules = RuleSet {
+        multi_capture: MultiCapture::Optional,
+        ..RuleSet::standard()
+    };
+    let board = Board::from_fen_with_rules("TG1G1/2G2/5/5/5 t 0 0 1", rules).unwrap();
+    let jump = |from, over, to| Jump { from, over, to };
+    assert_eq!(
+        describe(&board, Move::MultiJump(vec![jump(0, 1, 2), jump(2, 3, 4)])),
+        "A1xB1-C1xD1-E1: captures the goats on B1 and D1"
+    );
+}
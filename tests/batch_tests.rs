@@ -0,0 +1,93 @@
+use baghchal::{save, AiConfig, Batch, BatchResult, Mcts, MinimaxEngine, Side};
+
+fn small_batch() -> Batch {
+    Batch {
+        games: 3,
+        tiger_limits: AiConfig::nodes(200),
+        goat_limits: AiConfig::nodes(100),
+        seed: 11,
+        max_plies: 40,
+        ..Batch::default()
+    }
+}
+
+fn play(batch: &Batch) -> BatchResult {
+    batch
+        .run(
+            |_| MinimaxEngine::new(),
+            |seed| Mcts {
+                seed: Some(seed),
+                playout_cap: 10,
+                ..Mcts::default()
+            },
+            |_| {},
+        )
+        .unwrap()
+}
+
+#[test]
+fn test_batch_summary_adds_up() {
+    let dir = std::env::temp_dir().join(format!("baghchal-batch-{}", std::process::id()));
+    let batch = Batch {
+        records: Some(dir.clone()),
+        ..small_batch()
+    };
+    let mut reported = Vec::new();
+    let result = batch
+        .run(
+            |_| MinimaxEngine::new(),
+            |_| MinimaxEngine::new(),
+            |game| reported.push(game.game),
+        )
+        .unwrap();
+    assert_eq!(reported, [0, 1, 2]);
+
+    let (tiger_wins, draws, goat_wins) = result.record();
+    assert_eq!(tiger_wins + draws + goat_wins, 3);
+    let plies: usize = result.games.iter().map(|game| game.plies).sum();
+    assert_eq!(result.average_plies(), plies as f64 / 3.0);
+    for game in &result.games {
+        assert!(game.plies <= 40);
+        assert_eq!(game.seed, 11 + game.game as u64);
+        let saved = save::load(dir.join(format!("game-{:04}.txt", game.game + 1))).unwrap();
+        assert_eq!(saved.ply(), game.plies);
+        assert_eq!(saved.captured_goats, game.captures);
+    }
+    let moves: usize = result.games.iter().map(|game| game.plies / 2).sum();
+    assert_eq!(
+        result.time_per_move(Side::Tigers),
+        result.total_time(Side::Tigers) / moves as u32
+    );
+
+    let json = result.to_json();
+    assert!(json.starts_with(&format!(
+        "{{\"games\":3,\"tiger_wins\":{tiger_wins},\"draws\":{draws},\"goat_wins\":{goat_wins},"
+    )));
+    assert_eq!(json.matches("\"seed\":").count(), 3);
+    let summary = result.to_string();
+    for (label, count) in [
+        ("tigers won", tiger_wins),
+        ("drawn", draws),
+        ("goats won", goat_wins),
+        ("total", 3),
+    ] {
+        assert!(
+            summary.contains(&format!("{label:<16} {count:>6}")),
+            "{summary}"
+        );
+    }
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_batch_repeats_itself_from_its_seed() {
+    let outcomes = |result: BatchResult| -> Vec<_> {
+        result
+            .games
+            .iter()
+            .map(|game| (game.result, game.plies, game.captures))
+            .collect()
+    };
+    let batch = small_batch();
+    assert_eq!(outcomes(play(&batch)), outcomes(play(&batch)));
+}
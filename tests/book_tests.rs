@@ -0,0 +1,73 @@
+// These tests still drive the AI through the deprecated `ai_move_*` wrappers.
+#![allow(deprecated)]
+
+use baghchal::book::{builtin, Book};
+use baghchal::{Board, Move, Piece};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[test]
+fn test_builtin_book_covers_the_opening() {
+    let book = builtin();
+    assert!(!book.is_empty());
+
+    // Follow the book through the first four placements
+    let mut board = Board::new();
+    for _ in 0..7 {
+        let mv = book.probe(&board).expect("position is in the book");
+        assert!(board.make_move(&mv));
+    }
+    assert_eq!(board.goats_in_hand, 16);
+}
+
+#[test]
+fn test_book_probe_turns_moves_with_the_board() {
+    let book = Book::parse("TG2T/5/5/5/T3T t 19 0 1: A1-A2 3").unwrap();
+    assert_eq!(book.len(), 1);
+    let probe = |fen: &str| book.moves(&Board::from_fen(fen).unwrap());
+
+    let tiger = |from, to| Move::MoveTiger {
+        from,
+        to,
+        captured_position: None,
+    };
+    assert_eq!(probe("TG2T/5/5/5/T3T t 19 0 1"), vec![(tiger(0, 5), 3)]);
+    // Mirrored left to right
+    assert_eq!(probe("T2GT/5/5/5/T3T t 19 0 1"), vec![(tiger(4, 9), 3)]);
+    // Mirrored along the diagonal
+    assert_eq!(probe("T3T/G4/5/5/T3T t 19 0 1"), vec![(tiger(0, 1), 3)]);
+    // Goats to move is another position
+    assert!(probe("TG2T/5/5/5/T3T g 19 0 1").is_empty());
+}
+
+#[test]
+fn test_ai_plays_from_the_book() {
+    let book = Arc::new(Book::parse("T3T/5/5/5/T3T g 20 0 1: A3").unwrap());
+    let mut board = Board::new();
+    board.set_opening_book(book);
+    assert!(board.uses_opening_book());
+    assert!(board.ai_move_goat());
+    // A3 or one of its mirror images
+    assert!([2, 10, 14, 22]
+        .iter()
+        .any(|&pos| board.cells[pos] == Piece::Goat));
+
+    let mut board = Board::new();
+    board.set_use_opening_book(false);
+    board.set_ai_time_limit(Duration::from_secs(1));
+    assert!(board.ai_move_goat());
+    assert_eq!(board.goats_in_hand, 19);
+}
+
+#[test]
+fn test_book_parse_errors() {
+    let err = Book::parse("T3T/5/5/5/T3T g 20 0 1: C3 2, A1").unwrap_err();
+    assert_eq!((err.line, err.column), (1, 31));
+    assert!(err.message.contains("A1"));
+
+    let err = Book::parse("# openings\nT3T/5/5/5/T3T g 20 0 1: C3 heavy").unwrap_err();
+    assert_eq!((err.line, err.column), (2, 25));
+
+    let err = Book::parse("T3T/5/5/5/T3T g 20 0 1 C3").unwrap_err();
+    assert_eq!(err.line, 1);
+}
@@ -0,0 +1,861 @@
+//! Drives the game in the terminal with piped input.
+
+use baghchal::notation::parse_move;
+use baghchal::{save, Board, Clock};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+/// Human vs human on the standard board, default rules, no clock.
+const SETUP: &str = "1\n1\n\n\n\n\n";
+
+/// An empty directory for one test's files, autosave included.
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("baghchal-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Runs the game with `input` piped in and `dir` for its data, returning
+/// what it printed.
+fn run_cli(dir: &Path, input: &str) -> String {
+    run_cli_with_args(dir, &[], input)
+}
+
+/// [`run_cli`] with arguments on the command line.
+fn run_cli_with_args(dir: &Path, args: &[&str], input: &str) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_baghchal"))
+        .args(args)
+        .env("BAGHCHAL_DATA_DIR", dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn play(board: &mut Board, moves: &[&str]) {
+    for text in moves {
+        let mv = parse_move(board, text).unwrap();
+        assert!(board.make_move(&mv), "{text}");
+    }
+}
+
+#[test]
+fn test_cli_saves_and_loads_games() {
+    let dir = test_dir("save");
+    let path = dir.join("game.txt");
+    let path = path.to_str().unwrap();
+
+    let output = run_cli(&dir, &format!("{SETUP}C3\nA1 B2\nsave {path}\nq\nq\n"));
+    assert!(output.contains(&format!("Game saved to {path}")));
+    let mut expected = Board::new();
+    play(&mut expected, &["C3", "A1-B2"]);
+    assert_eq!(save::load(path).unwrap().to_fen(), expected.to_fen());
+
+    // The game left unfinished is offered first; decline it
+    let output = run_cli(&dir, &format!("n\n{SETUP}load {path}\nu\nu\nu\nq\nq\n"));
+    assert!(output.contains(&format!("Loaded {path}, move 2")));
+    assert_eq!(output.matches("Move undone!").count(), 2);
+    assert!(output.contains("No moves to undo!"));
+
+    let missing = dir.join("none.txt");
+    let output = run_cli(
+        &dir,
+        &format!("n\n{SETUP}load {}\nq\nq\n", missing.display()),
+    );
+    assert!(output.contains("Could not load"));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cli_resumes_an_unfinished_game() {
+    let dir = test_dir("resume");
+    let output = run_cli(&dir, &format!("{SETUP}C3\nA1 B2\nq\nq\n"));
+    assert!(!output.contains("Resume last game?"));
+    let autosave = dir.join("autosave.txt");
+    let mut expected = Board::new();
+    play(&mut expected, &["C3", "A1-B2"]);
+    assert_eq!(save::load(&autosave).unwrap().to_fen(), expected.to_fen());
+
+    // Resuming skips the menus and keeps the moves to undo
+    let output = run_cli(&dir, "y\nu\nq\nq\n");
+    assert!(output.contains("Resume last game? (y/n)"));
+    assert!(output.contains("Resuming the last game at move 2"));
+    assert!(!output.contains("Select game mode"));
+    assert!(output.contains("Move undone!"));
+    expected.undo();
+    assert_eq!(save::load(&autosave).unwrap().to_fen(), expected.to_fen());
+
+    // Declining starts afresh and forgets the old game
+    let output = run_cli(&dir, "n\nq\n");
+    assert!(output.contains("Select game mode"));
+    assert!(!autosave.exists());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cli_resumes_with_the_game_mode_and_ai_settings() {
+    let dir = test_dir("settings");
+    let settings = "players: human ai\nai: depth 1\nopening book: no\n\
+                    ponder: no\nengines: minimax minimax\n";
+    std::fs::write(
+        dir.join("autosave.txt"),
+        save::to_string(&Board::new()) + settings,
+    )
+    .unwrap();
+
+    // The AI has the goats, so it moves first, searching one ply
+    let output = run_cli(&dir, "y\nq\nq\n");
+    assert!(output.contains("Human (Tigers) vs AI (Goats)"));
+    assert!(output.contains("(depth 1)"));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cli_removes_the_autosave_of_a_finished_game() {
+    let dir = test_dir("finished");
+    // The tigers take their fifth goat with the next move
+    let board = Board::from_fen("GT2G/GT1GT/GGGGG/GGGGG/TG1GG t 0 4 21").unwrap();
+    let settings = "players: human human\nai: default\nopening book: yes\n\
+                    ponder: no\nengines: minimax minimax\n";
+    let autosave = dir.join("autosave.txt");
+    std::fs::write(&autosave, save::to_string(&board) + settings).unwrap();
+
+    let output = run_cli(&dir, "y\nA5 C5\nq\n");
+    assert!(output.contains("The Tigers are victorious!"));
+    assert!(!autosave.exists());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cli_reports_a_corrupt_autosave() {
+    let dir = test_dir("corrupt");
+    let autosave = dir.join("autosave.txt");
+    std::fs::write(&autosave, "version: 1\nboard: 5x5\n").unwrap();
+    let output = run_cli(&dir, "y\nq\n");
+    assert!(output.contains("Could not resume the last game"));
+    assert!(output.contains("Select game mode"));
+    assert!(!autosave.exists());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cli_replays_a_saved_game() {
+    let dir = test_dir("replay");
+    let path = dir.join("game.txt");
+    let mut board = Board::from_fen("GT2G/GT1GT/GGGGG/GGGGG/TG1GG t 0 4 21").unwrap();
+    play(&mut board, &["B2-C1", "D2-C2", "A5xB5-C5"]);
+    save::save(&board, &path).unwrap();
+    let path = path.to_str().unwrap();
+
+    let output = run_cli_with_args(&dir, &["replay", path], "p\nn\nn\nn\nn\ng 1\ng 9\nq\n");
+    assert!(output.contains("Ply 0 of 3"));
+    assert!(output.contains("Already at the start"));
+    assert!(output.contains("Last move: D2-C2 (Goats)"));
+    assert_eq!(output.matches("Ply 3 of 3").count(), 1);
+    assert!(output.contains("Last move: A5xB5-C5 (Tigers)"));
+    assert!(output.contains("Result: Tigers"));
+    assert!(output.contains("That was the last move"));
+    assert_eq!(output.matches("Ply 1 of 3").count(), 2);
+    assert!(output.contains("Enter a ply from 0 to 3"));
+
+    // The last position is over, so eval gives the result
+    let output = run_cli_with_args(&dir, &["replay", path], "n\nn\nn\ne\nq\n");
+    assert!(output.contains("The game is over: the tigers won (enough goats were captured)"));
+    assert!(!output.contains("total for the"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_baghchal"))
+        .args(["replay", "none.txt"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("Couldn't load none.txt"));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cli_starts_from_the_command_line() {
+    let dir = test_dir("args");
+    let moves = dir.join("moves.txt");
+    std::fs::write(&moves, "1. C3 A1-B2\n2. A3\n").unwrap();
+    let path = dir.join("game.txt");
+    let path = path.to_str().unwrap();
+
+    // Only the clock is left to ask for
+    let output = run_cli_with_args(
+        &dir,
+        &[
+            "--mode",
+            "1",
+            "--no-color",
+            "--moves",
+            moves.to_str().unwrap(),
+        ],
+        &format!("\nsave {path}\nq\nq\n"),
+    );
+    assert!(!output.contains("Select game mode"));
+    assert!(!output.contains("Resume last game"));
+    assert!(!output.contains('\x1b'));
+    let mut expected = Board::new();
+    play(&mut expected, &["C3", "A1-B2", "A3"]);
+    assert_eq!(save::load(path).unwrap().to_fen(), expected.to_fen());
+
+    let output = run_cli_with_args(
+        &dir,
+        &[
+            "--tigers", "human", "--goats", "ai", "--depth", "1", "--load", path,
+        ],
+        "n\nn\n\nq\nq\n",
+    );
+    assert!(output.contains("Human (Tigers) vs AI (Goats)"));
+    assert!(!output.contains("limit the AI"));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cli_rejects_bad_arguments() {
+    let cases: [&[&str]; 8] = [
+        &["--fen", "T3T/5/5/5/T3T g 20 0 1", "--load", "game.txt"],
+        &["--mode", "5"],
+        &["--mode", "1", "--tigers", "ai"],
+        &["--depth", "2", "--time-limit", "1"],
+        &["--fen", "not a position"],
+        &["--theme", "colorblind", "--theme-file", "theme.txt"],
+        &["--games-dir", "games", "--no-game-log"],
+        &["--review-thresholds", "150,50,300"],
+    ];
+    for args in cases {
+        let output = Command::new(env!("CARGO_BIN_EXE_baghchal"))
+            .args(args)
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(2), "{args:?}");
+        assert!(
+            String::from_utf8(output.stderr)
+                .unwrap()
+                .starts_with("error:"),
+            "{args:?}"
+        );
+    }
+}
+
+#[test]
+fn test_cli_lists_the_moves_played() {
+    let dir = test_dir("moves");
+    let output = run_cli(&dir, &format!("{SETUP}moves\nC3\nA1 B2\nhistory\nq\nq\n"));
+    assert!(output.contains("No moves played yet"));
+    assert!(output.contains("1. G C3  T A1-B2"));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cli_lists_the_moves_with_an_ai_game_paused() {
+    let dir = test_dir("spectate");
+    let moves = dir.join("moves.txt");
+    std::fs::write(&moves, "C3 A1-B2\n").unwrap();
+    let args = [
+        "--mode",
+        "4",
+        "--depth",
+        "1",
+        "--moves",
+        moves.to_str().unwrap(),
+    ];
+    // Enter pauses the game before a move, which the spectator then quits
+    let output = run_cli_with_args(&dir, &args, "n\n\n\n\n\nmoves\nq\nq\n");
+    assert_eq!(output.matches("Game paused").count(), 2);
+    assert!(output.contains("1. G C3  T A1-B2"), "{output}");
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cli_undo_against_the_ai_gives_the_turn_back() {
+    let dir = test_dir("undo");
+    let path = dir.join("game.txt");
+    let path = path.to_str().unwrap();
+    let start = Board::new().to_fen();
+
+    // Book and pondering off, no clock; the AI has the tigers
+    let output = run_cli_with_args(
+        &dir,
+        &["--mode", "3", "--depth", "1", "--no-color", "--fen", &start],
+        &format!("n\nn\n\nC3\nu\nsave {path}\nq\nq\n"),
+    );
+    assert!(output.contains("Undid both your move and the AI's response!"));
+    let last_turn = output.rsplit("Current Turn:").next().unwrap();
+    assert!(last_turn.trim_start().starts_with("Goats"), "{output}");
+    assert_eq!(output.matches("AI played").count(), 1);
+
+    let saved = save::load(path).unwrap();
+    assert_eq!(saved.to_fen(), start);
+    assert_eq!(saved.goats_in_hand, 20);
+    assert_eq!(saved.captured_goats, 0);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cli_hints_name_the_move_in_notation() {
+    let dir = test_dir("hint");
+    let output = run_cli_with_args(
+        &dir,
+        &[
+            "--mode",
+            "2",
+            "--depth",
+            "3",
+            "--seed",
+            "1",
+            "--fen",
+            "T3T/1G3/5/5/T3T t 19 0 2",
+        ],
+        "n\nn\n\nhint\nq\nq\n",
+    );
+    assert!(
+        output.contains("💡 Suggested move: A1xB2-C3: captures the goat on B2"),
+        "{output}"
+    );
+    // The tigers are a goat up after it
+    assert!(
+        output.contains("   Score +205, tigers better (depth 3)"),
+        "{output}"
+    );
+    assert!(output.contains("   Line: A1xB2-C3 A1 E1-D2\n"), "{output}");
+
+    let output = run_cli_with_args(
+        &dir,
+        &[
+            "--mode",
+            "1",
+            "--plain-hints",
+            "--fen",
+            "T3T/5/5/5/T3T g 20 0 1",
+        ],
+        "\nhint\nq\nq\n",
+    );
+    assert!(output.contains("💡 Suggested move: "), "{output}");
+    assert!(!output.contains("   Score"), "{output}");
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cli_plays_a_second_game() {
+    let dir = test_dir("again");
+    // The tigers take their fifth goat, then Enter starts another game
+    let fen = "GT2G/GT1GT/GGGGG/GGGGG/TG1GG t 0 4 21";
+    let output = run_cli_with_args(
+        &dir,
+        &["--mode", "1", "--fen", fen],
+        "\nA5 C5\n\n\nA5 C5\nq\n",
+    );
+    assert_eq!(output.matches("The Tigers are victorious!").count(), 2);
+    assert_eq!(output.matches("Starting game...").count(), 2);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cli_picks_minimax_when_enter_is_pressed() {
+    let dir = test_dir("engine");
+    let fen = "GT2G/GT1GT/GGGGG/GGGGG/TG1GG t 0 4 21";
+    // No opening book, Enter for both engines, no clock
+    let output = run_cli_with_args(
+        &dir,
+        &["--mode", "4", "--depth", "1", "--fen", fen],
+        "n\n\n\n\n",
+    );
+    assert_eq!(output.matches("press Enter for minimax").count(), 2);
+    assert!(!output.contains("Please enter"));
+    // Either capture wins, but the Monte Carlo search doesn't stop at
+    // the depth asked for
+    assert!(output.contains("(depth 1)"), "{output}");
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cli_logs_every_game() {
+    let dir = test_dir("log");
+    let games = dir.join("games");
+    let fen = "GT2G/GT1GT/GGGGG/GGGGG/TG1GG t 0 4 21";
+    let args = [
+        "--mode",
+        "1",
+        "--fen",
+        fen,
+        "--games-dir",
+        games.to_str().unwrap(),
+    ];
+    // A win, a game quit part way, then one with no move to log
+    let output = run_cli_with_args(&dir, &args, "\nA5 C5\n\n\nB2 C2\nq\n\n\nq\nq\n");
+    assert_eq!(output.matches("Game logged to").count(), 2);
+
+    let logs: Vec<PathBuf> = std::fs::read_dir(&games)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(logs.len(), 2);
+    for path in &logs {
+        assert!(output.contains(&path.display().to_string()));
+    }
+    let (unfinished, won): (Vec<_>, Vec<_>) = logs.iter().partition(|path| {
+        std::fs::read_to_string(path)
+            .unwrap()
+            .contains("result: unfinished\n")
+    });
+    let won = std::fs::read_to_string(won[0]).unwrap();
+    assert!(won.contains("moves: A5xB5-C5\n"), "{won}");
+    assert!(won.contains("players: human human\n"));
+    assert!(won.contains("mode: Human vs Human\n"));
+    assert!(won.contains("result: tigers won (enough goats were captured)\n"));
+    let mut expected = Board::from_fen(fen).unwrap();
+    play(&mut expected, &["B2-C2"]);
+    assert_eq!(
+        save::load(unfinished[0]).unwrap().to_fen(),
+        expected.to_fen()
+    );
+
+    // Logging can be turned off, and is in the data directory by default
+    run_cli_with_args(
+        &dir,
+        &["--mode", "1", "--fen", fen, "--no-game-log"],
+        "\nA5 C5\nq\n",
+    );
+    assert_eq!(std::fs::read_dir(&games).unwrap().count(), 2);
+    run_cli_with_args(&dir, &["--mode", "1", "--fen", fen], "\nA5 C5\nq\n");
+    assert_eq!(std::fs::read_dir(&games).unwrap().count(), 3);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cli_reviews_the_game() {
+    let dir = test_dir("review");
+    // B2-C2 misses the tigers' fifth capture
+    let fen = "GT2G/GT1GT/GGGGG/GGGGG/TG1GG t 0 4 21";
+    let args = ["--mode", "1", "--fen", fen, "--review-depth", "2"];
+    let output = run_cli_with_args(&dir, &args, "\nB2 C2\nq\nreview\nq\n");
+    assert!(output.contains("Type 'review' to go over the game"));
+    assert!(output.contains("Reviewing the game... 2/2"));
+    assert!(output.contains("Game review, searching 2 plies deep:"));
+    let missed = output
+        .lines()
+        .find(|line| line.contains("Move 21: B2-C2 (−"))
+        .unwrap_or_else(|| panic!("{output}"));
+    assert!(
+        missed.ends_with("— E2xD2-C2 wins the game (blunder, tigers)"),
+        "{missed}"
+    );
+
+    // With the thresholds raised past what it lost, and without asking
+    let args = [
+        &args[..],
+        &["--review", "--review-thresholds", "1,2,100000"],
+    ]
+    .concat();
+    let output = run_cli_with_args(&dir, &args, "\nB2 C2\nq\nq\n");
+    assert!(output.contains("Biggest mistakes:"));
+    assert!(output.contains("E2xD2-C2 wins the game (mistake, tigers)"));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+/// Presses Ctrl+C at the move prompt of a human vs human game once
+/// `input` is in, then answers `answer` to whether to save, returning what
+/// the game printed.
+#[cfg(unix)]
+fn interrupt_at_the_prompt(dir: &Path, input: &str, answer: &str) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_baghchal"))
+        .args(["--mode", "1"])
+        .env("BAGHCHAL_DATA_DIR", dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    stdin.write_all(input.as_bytes()).unwrap();
+    stdin.flush().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let status = Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    stdin.write_all(answer.as_bytes()).unwrap();
+    drop(stdin);
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[cfg(unix)]
+#[test]
+fn test_cli_offers_to_save_on_ctrl_c() {
+    let dir = test_dir("interrupt");
+    let autosave = dir.join("autosave.txt");
+    let output = interrupt_at_the_prompt(&dir, "1\n\n\n\n\nC3\n", "y\n");
+    assert!(output.contains("Save before quitting? (y/n)"));
+    assert!(output.contains("Game saved"));
+    assert!(!output.contains("GAME OVER"));
+    let mut expected = Board::new();
+    play(&mut expected, &["C3"]);
+    assert_eq!(save::load(&autosave).unwrap().to_fen(), expected.to_fen());
+
+    // Declining the saved game first
+    let output = interrupt_at_the_prompt(&dir, "n\n1\n\n\n\n\nC3\n", "n\n");
+    assert!(output.contains("Game not saved"));
+    assert!(!autosave.exists());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cli_draws_by_agreement() {
+    let dir = test_dir("draw");
+    let output = run_cli(&dir, &format!("{SETUP}C3\ndraw\nn\ndraw\ny\nq\n"));
+    assert!(output.contains("The tigers offer a draw — accept? (y/n)"));
+    assert!(output.contains("The draw was declined; the game goes on"));
+    assert!(output.contains("The game is a draw!"));
+    assert!(output.contains("the players agreed to a draw"));
+    assert!(!dir.join("autosave.txt").exists());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cli_ends_the_game_when_a_flag_falls_at_the_prompt() {
+    let dir = test_dir("flag");
+    let path = dir.join("game.txt");
+    let mut board = Board::new();
+    board.set_clock(Clock::new(Duration::from_millis(300)));
+    save::save(&board, &path).unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_baghchal"))
+        .args(["--mode", "1", "--load", path.to_str().unwrap()])
+        .env("BAGHCHAL_DATA_DIR", &dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // Nothing is typed until well after the goats' time is up
+    let mut stdin = child.stdin.take().unwrap();
+    std::thread::sleep(Duration::from_millis(1500));
+    stdin.write_all(b"q\n").unwrap();
+    drop(stdin);
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    let output = String::from_utf8(output.stdout).unwrap();
+    assert!(output.contains("Time is up!"), "{output}");
+    assert!(output.contains("The Tigers are victorious!"), "{output}");
+    assert!(output.contains("ran out of time"), "{output}");
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cli_sets_up_a_clock_with_an_increment() {
+    let dir = test_dir("increment");
+    let path = dir.join("game.txt");
+    let path = path.to_str().unwrap();
+    let output = run_cli(&dir, &format!("1\n1\n\n\n\n5\n3\nsave {path}\nq\nq\n"));
+    assert!(output.contains("Each side has 5 minutes, plus 3 seconds a move"));
+    let saved = save::load(path).unwrap();
+    assert_eq!(saved.clock().unwrap().increment(), Duration::from_secs(3));
+
+    let output = run_cli_with_args(
+        &dir,
+        &["--mode", "1", "--clock", "2"],
+        &format!("n\n1\n\n\n\nsave {path}\nq\nq\n"),
+    );
+    assert!(output.contains("Each side has 2 minutes"));
+    assert!(!output.contains("Seconds added"));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cli_shows_the_evaluation() {
+    let dir = test_dir("eval");
+    let output = run_cli(&dir, &format!("{SETUP}C3\neval\nq\n"));
+    assert!(output.contains("Evaluation, with points from the tigers' side"));
+    assert!(output.contains("tiger mobility"));
+    assert!(output.contains("total for the tigers"));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cli_analyzes_the_position() {
+    let dir = test_dir("analyze");
+    let output = run_cli_with_args(
+        &dir,
+        &[
+            "--mode",
+            "1",
+            "--depth",
+            "3",
+            "--fen",
+            "T3T/1G3/5/5/T3T t 19 0 2",
+        ],
+        "\nanalyze 60\nanalyze soon\nq\nq\n",
+    );
+    assert!(output.contains("Best moves for the tigers, scored from the tigers' side"));
+    assert!(output.contains("  1. A1xB2-C3"), "{output}");
+    assert!(output.contains("  3. "), "{output}");
+    assert!(output.contains("Please give the seconds to think as a whole number"));
+    // No move was played
+    assert!(!output.contains("Move: 3"), "{output}");
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_cli_stops_the_analysis_on_ctrl_c() {
+    let dir = test_dir("analyze-interrupt");
+    let output = interrupt_at_the_prompt(&dir, "1\n\n\n\n\nC3\nanalyze 60\n", "q\n");
+    assert!(output.contains("Analysis stopped"), "{output}");
+    assert!(output.contains("  1. "), "{output}");
+    assert!(!output.contains("Save before quitting?"));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cli_warns_about_capturable_goats() {
+    let dir = test_dir("threats");
+    let args = ["--mode", "1", "--fen", "T3T/1G3/5/3G1/T3T g 18 0 2"];
+    let output = run_cli_with_args(&dir, &args, "\nq\nq\n");
+    assert!(
+        output.contains("2 goats can be captured: B2, D4"),
+        "{output}"
+    );
+
+    let args = [&args[..], &["--hide-threats"]].concat();
+    let output = run_cli_with_args(&dir, &args, "\nq\nq\n");
+    assert!(!output.contains("can be captured"), "{output}");
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cli_prints_plain_ascii() {
+    let dir = test_dir("ascii");
+    let input = format!("{SETUP}C3\nq\nq\n");
+    let output = run_cli_with_args(&dir, &["--no-color", "--ascii"], &input);
+    assert!(!output.contains('\x1b'));
+    assert!(output.contains(" 3   .---.--[G]--.---.\n     | \\ | / | \\ | / |\n"));
+    assert!(output.contains("+-------------------------------------------+\n| Human vs Human"));
+    assert!(output.contains("|                   GAME OVER!                    |"));
+    assert!(!output.contains('║'));
+
+    // NO_COLOR does the same as --no-color, keeping the box-drawing lines
+    let dir = test_dir("ascii");
+    let mut child = Command::new(env!("CARGO_BIN_EXE_baghchal"))
+        .env("BAGHCHAL_DATA_DIR", &dir)
+        .env("NO_COLOR", "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    stdin.write_all(input.as_bytes()).unwrap();
+    drop(stdin);
+    let output = String::from_utf8(child.wait_with_output().unwrap().stdout).unwrap();
+    assert!(!output.contains('\x1b'));
+    assert!(output.contains(" 3   .───.──[G]──.───."), "{output}");
+    assert!(output.contains("║ Current Turn: Tigers"));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cli_draws_pieces_as_emoji() {
+    let dir = test_dir("emoji");
+    let output = run_cli_with_args(&dir, &["--pieces", "emoji"], &format!("{SETUP}q\nq\n"));
+    assert!(output.contains("🐯 = Tiger, 🐐 = Goat"));
+    assert!(output.contains("Current Turn: 🐐 "));
+    assert!(output.contains(" 5   🐯──·───·───·───🐯"), "{output}");
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cli_draws_with_the_theme_asked_for() {
+    let dir = test_dir("theme");
+    std::fs::write(dir.join("theme.txt"), "tiger: bright blue\n").unwrap();
+    let run = |args: &[&str]| {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_baghchal"))
+            .args(["--mode", "1", "--fen", "T3T/5/5/5/T3T g 20 0 1"])
+            .args(args)
+            .env("BAGHCHAL_DATA_DIR", &dir)
+            .env("CLICOLOR_FORCE", "1")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut stdin = child.stdin.take().unwrap();
+        // A theme that doesn't parse ends the game before it reads any
+        let _ = stdin.write_all(b"\nq\nq\n");
+        drop(stdin);
+        child.wait_with_output().unwrap()
+    };
+    let tigers_in = |output: &[u8], code: &str| {
+        String::from_utf8_lossy(output).contains(&format!("\x1b[{code}mT\x1b[0m"))
+    };
+
+    // theme.txt in the data directory is picked up, and --theme takes
+    // its place
+    let output = run(&[]);
+    assert!(tigers_in(&output.stdout, "94"));
+    let output = run(&["--theme", "high-contrast"]);
+    assert!(tigers_in(&output.stdout, "91"));
+    assert!(!tigers_in(&output.stdout, "94"));
+
+    std::fs::write(dir.join("theme.txt"), "tiger: plaid\n").unwrap();
+    let output = run(&[]);
+    assert_eq!(output.status.code(), Some(2));
+    let error = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        error.contains("line 1, column 8: invalid tiger 'plaid', expected a colour"),
+        "{error}"
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+/// Which way up each board in `output` was drawn, `true` for turned
+/// round.
+fn board_orientations(output: &str) -> Vec<bool> {
+    let mut headers: Vec<(usize, bool)> = output
+        .match_indices("     A   B   C   D   E\n")
+        .map(|(at, _)| (at, false))
+        .chain(
+            output
+                .match_indices("     E   D   C   B   A\n")
+                .map(|(at, _)| (at, true)),
+        )
+        .collect();
+    headers.sort_unstable();
+    headers.into_iter().map(|(_, turned)| turned).collect()
+}
+
+#[test]
+fn test_cli_flips_the_board() {
+    let dir = test_dir("flip");
+    let output = run_cli_with_args(
+        &dir,
+        &["--no-color", "--coordinates"],
+        &format!("{SETUP}flip\nflip\nq\nq\n"),
+    );
+    // The letters go under the board as well, and each flip turns it round
+    assert!(output.contains(" 1   T───.───.───.───T   1\n     E   D   C   B   A\n"));
+    let upright = [false, false];
+    let turned = [true, true];
+    assert_eq!(
+        board_orientations(&output),
+        [upright, upright, turned, upright, upright].concat(),
+        "{output}"
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    // With --auto-flip the board faces whoever is to move
+    let dir = test_dir("flip");
+    let output = run_cli_with_args(
+        &dir,
+        &["--no-color", "--auto-flip"],
+        &format!("{SETUP}C3\nA1 B2\nq\nq\n"),
+    );
+    assert_eq!(
+        board_orientations(&output),
+        [false, false, true, true, false, false, false],
+        "{output}"
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cli_describes_the_position() {
+    let dir = test_dir("describe");
+    let output = run_cli(&dir, &format!("{SETUP}C3\ndescribe\nq\nq\n"));
+    assert!(output.contains(
+        "Tigers on A1, E1, A5, E5. Goats on C3. 19 goats in hand, 0 captured. \
+         Last move C3. Tigers to move.\n"
+    ));
+
+    // The screen reader mode describes the position in place of the board
+    let output = run_cli_with_args(
+        &dir,
+        &[
+            "--mode",
+            "1",
+            "--screen-reader",
+            "--fen",
+            "T3T/5/5/5/T3T g 20 0 1",
+        ],
+        "\nC3\nq\nq\n",
+    );
+    assert!(!output.contains("A   B   C   D   E"), "{output}");
+    assert!(output.contains("No goats on the board. 20 goats in hand"));
+    assert!(output.contains("Goats on C3. 19 goats in hand, 0 captured."));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+/// The number after `"key":` in `json`.
+fn json_number(json: &str, key: &str) -> f64 {
+    let start = json.find(&format!("\"{key}\":")).unwrap() + key.len() + 3;
+    let end = json[start..].find([',', '}']).unwrap();
+    json[start..start + end].parse().unwrap()
+}
+
+#[test]
+fn test_cli_plays_a_match() {
+    let dir = test_dir("match");
+    let out = dir.join("results.json");
+    let records = dir.join("games");
+    let output = run_cli_with_args(
+        &dir,
+        &[
+            "match",
+            "--games",
+            "3",
+            "--tiger-depth",
+            "1",
+            "--goat-level",
+            "beginner",
+            "--goat-time",
+            "50ms",
+            "--max-plies",
+            "80",
+            "--out",
+            out.to_str().unwrap(),
+            "--records",
+            records.to_str().unwrap(),
+        ],
+        "",
+    );
+    assert!(!output.contains("A   B   C"), "{output}");
+    assert_eq!(output.matches("Game ").count(), 3, "{output}");
+
+    // The summary adds up, in the table and in the JSON
+    let json = std::fs::read_to_string(&out).unwrap();
+    let wins = json_number(&json, "tiger_wins")
+        + json_number(&json, "draws")
+        + json_number(&json, "goat_wins");
+    assert_eq!(wins, 3.0, "{json}");
+    assert_eq!(json.matches("\"plies\":").count(), 3);
+    assert!(json_number(&json, "average_plies") <= 80.0);
+    assert!(output.contains("total                 3\n"), "{output}");
+    for game in 1..=3 {
+        let path = records.join(format!("game-{game:04}.txt"));
+        assert!(save::load(path).is_ok());
+    }
+
+    let status = Command::new(env!("CARGO_BIN_EXE_baghchal"))
+        .args(["match", "--tiger-time", "soon"])
+        .stderr(Stdio::null())
+        .status()
+        .unwrap();
+    assert_eq!(status.code(), Some(2));
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,177 @@
+use baghchal::{
+    Board, Clock, GameEvent, GameResult, ManualTime, MinimaxEngine, Phase, Side, TerminationReason,
+    TimeBudget, TimeSource, Winner,
+};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+fn secs(seconds: u64) -> Duration {
+    Duration::from_secs(seconds)
+}
+
+/// A standard board on a one-minute clock driven by the returned time.
+fn board_on_clock() -> (Board, Arc<ManualTime>) {
+    let time = Arc::new(ManualTime::new());
+    let mut board = Board::new();
+    board.set_clock(Clock::with_source(secs(60), time.clone()));
+    (board, time)
+}
+
+#[test]
+fn test_clock_runs_for_side_to_move() {
+    let (mut board, time) = board_on_clock();
+    time.advance(secs(10));
+    assert_eq!(board.remaining_time(Side::Goats), Some(secs(50)));
+    assert_eq!(board.remaining_time(Side::Tigers), Some(secs(60)));
+
+    assert!(board.place_goat(12));
+    time.advance(secs(5));
+    assert_eq!(board.remaining_time(Side::Goats), Some(secs(50)));
+    assert_eq!(board.remaining_time(Side::Tigers), Some(secs(55)));
+
+    assert!(board.move_tiger(0, 1));
+    assert_eq!(
+        board.record().move_times,
+        vec![Some(secs(10)), Some(secs(5))]
+    );
+}
+
+#[test]
+fn test_paused_time_is_not_charged() {
+    let (mut board, time) = board_on_clock();
+    time.advance(secs(3));
+    board.pause_clock();
+    time.advance(secs(30));
+    assert_eq!(board.remaining_time(Side::Goats), Some(secs(57)));
+
+    board.resume_clock();
+    time.advance(secs(2));
+    assert!(board.place_goat(12));
+    assert_eq!(board.record().move_times, vec![Some(secs(5))]);
+}
+
+#[test]
+fn test_timeout_ends_the_game() {
+    let (mut board, time) = board_on_clock();
+    assert!(board.place_goat(12));
+    time.advance(secs(60));
+    assert_eq!(
+        board.result(),
+        Some(GameResult {
+            winner: Winner::Goats,
+            reason: TerminationReason::Timeout,
+        })
+    );
+
+    // A move made after the flag fell doesn't save the tigers
+    board.drain_events();
+    assert!(board.move_tiger(0, 1));
+    assert_eq!(board.get_winner(), Winner::Goats);
+    assert!(matches!(
+        board.drain_events().last(),
+        Some(GameEvent::GameOver(GameResult {
+            reason: TerminationReason::Timeout,
+            ..
+        }))
+    ));
+}
+
+#[test]
+fn test_undo_refunds_time() {
+    let (mut board, time) = board_on_clock();
+    time.advance(secs(10));
+    assert!(board.place_goat(12));
+    time.advance(secs(4));
+
+    assert!(board.undo());
+    assert_eq!(board.remaining_time(Side::Goats), Some(secs(60)));
+    assert_eq!(board.remaining_time(Side::Tigers), Some(secs(56)));
+    assert!(board.record().move_times.is_empty());
+}
+
+#[test]
+fn test_moves_without_clock_have_no_time() {
+    let mut board = Board::new();
+    assert!(board.place_goat(12));
+    assert_eq!(board.remaining_time(Side::Goats), None);
+    assert_eq!(board.record().move_times, vec![None]);
+}
+
+#[test]
+fn test_increment_is_added_after_each_move() {
+    let time = Arc::new(ManualTime::new());
+    let mut board = Board::new();
+    board.set_clock(Clock::with_source(secs(60), time.clone()).with_increment(secs(2)));
+    time.advance(secs(5));
+    assert!(board.place_goat(12));
+    assert_eq!(board.remaining_time(Side::Goats), Some(secs(57)));
+
+    // Taking the move back takes the increment back too
+    time.advance(secs(1));
+    assert!(board.undo());
+    assert_eq!(board.remaining_time(Side::Goats), Some(secs(60)));
+    assert_eq!(board.remaining_time(Side::Tigers), Some(secs(59)));
+}
+
+#[test]
+fn test_time_budget_favours_the_movement_phase() {
+    let placement = TimeBudget::plan(secs(60), secs(0), Phase::Placement, 5);
+    let movement = TimeBudget::plan(secs(60), secs(0), Phase::Movement, 5);
+    assert!(placement.soft < movement.soft);
+    for budget in [placement, movement] {
+        assert!(budget.soft <= budget.hard);
+        assert!(budget.hard <= secs(20));
+    }
+
+    // Little time left is spent sparingly, an increment generously
+    let short = TimeBudget::plan(Duration::from_millis(300), secs(0), Phase::Movement, 40);
+    assert!(short.hard <= Duration::from_millis(90));
+    let increment = TimeBudget::plan(secs(60), secs(2), Phase::Movement, 5);
+    assert!(increment.soft > movement.soft);
+}
+
+/// Time that moves on by a tenth of a millisecond every time it is read,
+/// so that a search takes simulated time in step with the work it does.
+#[derive(Debug, Default)]
+struct Ticking {
+    now: Mutex<Duration>,
+}
+
+impl TimeSource for Ticking {
+    fn now(&self) -> Duration {
+        let mut now = self.now.lock().unwrap();
+        *now += Duration::from_micros(100);
+        *now
+    }
+}
+
+#[test]
+fn test_ai_never_flags_on_a_thirty_second_clock() {
+    let mut board = Board::new();
+    board.set_clock(Clock::with_source(secs(30), Arc::new(Ticking::default())));
+    let mut engine = MinimaxEngine::new();
+    while board.result().is_none() && board.record().moves.len() < 200 {
+        let side = board.side_to_move();
+        let before = board.remaining_time(side).unwrap();
+        assert!(board.play_engine_move(&mut engine).is_some());
+        let took = board.record().move_times.last().copied().flatten().unwrap();
+        assert!(took < before, "{took:?} of {before:?}");
+    }
+    assert_ne!(
+        board.result().map(|result| result.reason),
+        Some(TerminationReason::Timeout)
+    );
+    assert!(board.remaining_time(Side::Goats).unwrap() > Duration::ZERO);
+    assert!(board.remaining_time(Side::Tigers).unwrap() > Duration::ZERO);
+}
+
+#[test]
+fn test_only_move_is_played_at_once() {
+    let mut board = Board::from_fen("T1GGT/GG1GG/G1G1G/GG1GG/TGGGT t 4 0 16").unwrap();
+    let time = Arc::new(Ticking::default());
+    board.set_clock(Clock::with_source(secs(30), time));
+    let played = board.play_engine_move(&mut MinimaxEngine::new()).unwrap();
+    assert_eq!(played.stats.nodes, 0);
+    let took = board.record().move_times[0].unwrap();
+    assert!(took < Duration::from_millis(5), "{took:?}");
+}
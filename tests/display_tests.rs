@@ -0,0 +1,555 @@
+use baghchal::notation::parse_move;
+use baghchal::{Board, DisplayOptions, Piece, PieceStyle, RuleSet, Theme};
+use colored::{Color, Colorize};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::BTreeMap;
+
+/// `text` with the terminal colour codes taken out.
+fn strip_colors(text: &str) -> String {
+    let mut plain = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip to the end of the escape sequence
+            for c in chars.by_ref() {
+                if c == 'm' {
+                    break;
+                }
+            }
+        } else {
+            plain.push(c);
+        }
+    }
+    plain
+}
+
+fn play(board: &mut Board, moves: &[&str]) {
+    for text in moves {
+        let mv = parse_move(board, text).unwrap();
+        assert!(board.make_move(&mv), "{text}");
+    }
+}
+
+/// The board as drawn with `options`, without colours.
+fn render(board: &Board, options: &DisplayOptions) -> String {
+    strip_colors(&board.display_with(options))
+}
+
+#[test]
+fn test_placement_is_bracketed() {
+    let mut board = Board::new();
+    play(&mut board, &["C3"]);
+    assert_eq!(
+        render(&board, &DisplayOptions::default()),
+        "     A   B   C   D   E
+ 1   T───·───·───·───T
+     │ ╲ │ ╱ │ ╲ │ ╱ │
+ 2   ·───·───·───·───·
+     │ ╱ │ ╲ │ ╱ │ ╲ │
+ 3   ·───·──[G]──·───·
+     │ ╲ │ ╱ │ ╲ │ ╱ │
+ 4   ·───·───·───·───·
+     │ ╱ │ ╲ │ ╱ │ ╲ │
+ 5   T───·───·───·───T
+"
+    );
+}
+
+#[test]
+fn test_quiet_move_marks_the_point_it_left() {
+    let mut board = Board::new();
+    play(&mut board, &["C3", "A1-B2"]);
+    assert_eq!(
+        render(&board, &DisplayOptions::default()),
+        "     A   B   C   D   E
+ 1   ◦───·───·───·───T
+     │ ╲ │ ╱ │ ╲ │ ╱ │
+ 2   ·──[T]──·───·───·
+     │ ╱ │ ╲ │ ╱ │ ╲ │
+ 3   ·───·───G!──·───·
+     │ ╲ │ ╱ │ ╲ │ ╱ │
+ 4   ·───·───·───·───·
+     │ ╱ │ ╲ │ ╱ │ ╲ │
+ 5   T───·───·───·───T
+   ⚠ 1 goat can be captured: C3
+"
+    );
+}
+
+#[test]
+fn test_capture_marks_the_captured_goat() {
+    let mut board = Board::from_fen("T3T/1G3/5/5/T3T t 19 0 2").unwrap();
+    play(&mut board, &["A1xB2-C3"]);
+    assert_eq!(board.last_capture(), vec![6]);
+    assert_eq!(
+        render(&board, &DisplayOptions::default()),
+        "     A   B   C   D   E
+ 1   ◦───·───·───·───T
+     │ ╲ │ ╱ │ ╲ │ ╱ │
+ 2   ·───✗───·───·───·
+     │ ╱ │ ╲ │ ╱ │ ╲ │
+ 3   ·───·──[T]──·───·
+     │ ╲ │ ╱ │ ╲ │ ╱ │
+ 4   ·───·───·───·───·
+     │ ╱ │ ╲ │ ╱ │ ╲ │
+ 5   T───·───·───·───T
+"
+    );
+
+    // The next move clears the marks
+    play(&mut board, &["A2"]);
+    assert!(board.last_capture().is_empty());
+    assert!(!render(&board, &DisplayOptions::default()).contains('✗'));
+}
+
+#[test]
+fn test_valid_moves_show_over_the_last_move() {
+    let mut board = Board::from_fen("T3T/1G3/5/5/T3T t 19 0 2").unwrap();
+    play(&mut board, &["A1xB2-C3"]);
+    board.selected_position = Some(12);
+    let highlighted = render(&board, &DisplayOptions::default());
+    assert!(
+        highlighted.contains(" 2   ·───•───•───•───·"),
+        "{highlighted}"
+    );
+    assert!(
+        highlighted.contains(" 3   ·───•──[T]──•───·"),
+        "{highlighted}"
+    );
+
+    let options = DisplayOptions {
+        highlight_last_move: false,
+        ..DisplayOptions::default()
+    };
+    assert_eq!(
+        render(&board, &options),
+        "     A   B   C   D   E
+ 1   ·───·───·───·───T
+     │ ╲ │ ╱ │ ╲ │ ╱ │
+ 2   ·───•───•───•───·
+     │ ╱ │ ╲ │ ╱ │ ╲ │
+ 3   ·───•───T───•───·
+     │ ╲ │ ╱ │ ╲ │ ╱ │
+ 4   ·───•───•───•───·
+     │ ╱ │ ╲ │ ╱ │ ╲ │
+ 5   T───·───·───·───T
+"
+    );
+}
+
+#[test]
+fn test_triangle_board_brackets_the_last_move() {
+    let mut board = Board::with_rules(RuleSet::aadu_puli_attam()).unwrap();
+    let first = board.legal_moves()[0].clone();
+    assert!(board.make_move(&first));
+    let rendered = render(&board, &DisplayOptions::default());
+    assert!(
+        rendered.contains(" 2  [G]──·───T───T───·───·\n"),
+        "{rendered}"
+    );
+}
+
+#[test]
+fn test_capturable_goats_are_marked_and_listed() {
+    let mut board = Board::from_fen("T3T/1G3/5/3G1/T3T g 18 0 2").unwrap();
+    assert_eq!(
+        render(&board, &DisplayOptions::default()),
+        "     A   B   C   D   E
+ 1   T───·───·───·───T
+     │ ╲ │ ╱ │ ╲ │ ╱ │
+ 2   ·───G!──·───·───·
+     │ ╱ │ ╲ │ ╱ │ ╲ │
+ 3   ·───·───·───·───·
+     │ ╲ │ ╱ │ ╲ │ ╱ │
+ 4   ·───·───·───G!──·
+     │ ╱ │ ╲ │ ╱ │ ╲ │
+ 5   T───·───·───·───T
+   ⚠ 2 goats can be captured: B2, D4
+"
+    );
+
+    // C3 is where both jumps land, so covering it saves both goats
+    play(&mut board, &["C3"]);
+    let rendered = render(&board, &DisplayOptions::default());
+    assert!(rendered.contains(" 2   ·───G───·───·───·"), "{rendered}");
+    assert!(!rendered.contains('⚠'), "{rendered}");
+    assert!(board.undo());
+    assert!(render(&board, &DisplayOptions::default()).contains("2 goats can be captured"));
+
+    let options = DisplayOptions {
+        highlight_threatened_goats: false,
+        ..DisplayOptions::default()
+    };
+    let rendered = render(&board, &options);
+    assert!(!rendered.contains('!'));
+    assert!(!rendered.contains('⚠'));
+}
+
+#[test]
+fn test_goat_with_no_landing_point_is_not_marked() {
+    // The point beyond B2 from A1 is taken, and no other line runs
+    // through B2 from a tiger
+    let board = Board::from_fen("T3T/1G3/2G2/5/T3T t 18 0 3").unwrap();
+    let rendered = render(&board, &DisplayOptions::default());
+    assert!(!rendered.contains('⚠'), "{rendered}");
+    assert!(rendered.contains(" 2   ·───G───·"), "{rendered}");
+}
+
+#[test]
+fn test_selected_tiger_shows_captures_apart_from_quiet_moves() {
+    let mut board = Board::from_fen("T3T/1G3/5/5/T3T t 19 0 2").unwrap();
+    assert_eq!(board.would_capture(0, 12), Some(6));
+    assert_eq!(board.would_capture(0, 1), None);
+    assert_eq!(board.would_capture(4, 12), None);
+    assert!(board.select_position(0));
+    assert_eq!(
+        render(&board, &DisplayOptions::default()),
+        "     A   B   C   D   E
+ 1   T───•───·───·───T
+     │ ╲ │ ╱ │ ╲ │ ╱ │
+ 2   •───G!──·───·───·
+     │ ╱ │ ╲ │ ╱ │ ╲ │
+ 3   ·───·───‼───·───·
+     │ ╲ │ ╱ │ ╲ │ ╱ │
+ 4   ·───·───·───·───·
+     │ ╱ │ ╲ │ ╱ │ ╲ │
+ 5   T───·───·───·───T
+   ⚠ 1 goat can be captured: B2
+"
+    );
+
+    // The goat it would take is dimmed, and only while the tiger is
+    // selected
+    colored::control::set_override(true);
+    let dimmed = "G".color(Theme::default().goat).dimmed().to_string();
+    assert!(board.display_with_hints().contains(&dimmed));
+    board.clear_selection();
+    assert!(!board.display_with_hints().contains(&dimmed));
+    assert!(!render(&board, &DisplayOptions::default()).contains('‼'));
+}
+
+#[test]
+fn test_selected_goat_shows_plain_destinations() {
+    let mut board = Board::from_fen("T3T/1G3/5/5/T3T g 0 0 30").unwrap();
+    assert!(board.select_position(6));
+    let rendered = render(&board, &DisplayOptions::default());
+    assert!(!rendered.contains('‼'), "{rendered}");
+    assert_eq!(rendered.matches('•').count(), 7, "{rendered}");
+}
+
+#[test]
+fn test_lines_follow_the_topology() {
+    // Diagonals only run through every other point, and the fan under the
+    // apex of the triangle takes three lines
+    let board = Board::with_rules(RuleSet::alquerque(7, 30)).unwrap();
+    let rendered = render(&board, &DisplayOptions::default());
+    assert!(rendered.contains(" 1   T───·───·───·───·───·───T\n     │ ╲ │ ╱ │ ╲ │ ╱ │ ╲ │ ╱ │\n"));
+    assert!(rendered.contains(" 6   ·───·───·───·───·───·───·\n     │ ╱ │ ╲ │ ╱ │ ╲ │ ╱ │ ╲ │\n"));
+
+    let board = Board::with_rules(RuleSet::aadu_puli_attam()).unwrap();
+    assert_eq!(
+        render(&board, &DisplayOptions::default()),
+        "     A   B   C   D   E   F
+ 1           T
+            ╱│╲╲
+           ╱ │ ╲ ╲
+          ╱  │  ╲  ╲
+ 2   ·───·───T───T───·───·
+     │   │   │   │   │   │
+ 3   ·───·───·───·───·───·
+     │   │   │   │   │   │
+ 4   ·───·───·───·───·───·
+         │   │   │   │
+ 5       ·───·───·───·
+"
+    );
+}
+
+#[test]
+fn test_plain_output_has_no_escape_codes() {
+    colored::control::set_override(true);
+    let mut board = Board::from_fen("T3T/1G3/5/3G1/T3T t 18 0 2").unwrap();
+    assert!(board.select_position(0));
+    let options = DisplayOptions {
+        color: false,
+        ascii_lines: true,
+        ..DisplayOptions::default()
+    };
+    let plain = board.display_with(&options);
+    assert!(plain.is_ascii(), "{plain}");
+    assert_eq!(
+        plain,
+        "     A   B   C   D   E
+ 1   T---*---.---.---T
+     | \\ | / | \\ | / |
+ 2   *---g!--.---.---.
+     | / | \\ | / | \\ |
+ 3   .---.---X---.---.
+     | \\ | / | \\ | / |
+ 4   .---.---.---G!--.
+     | / | \\ | / | \\ |
+ 5   T---.---.---.---T
+   ! 2 goats can be captured: B2, D4
+"
+    );
+
+    // Colours off on their own keep the box-drawing lines
+    let options = DisplayOptions {
+        color: false,
+        ..DisplayOptions::default()
+    };
+    let plain = board.display_with(&options);
+    assert!(!plain.contains('\x1b'));
+    assert!(plain.contains(" 1   T───*───.───.───T"), "{plain}");
+}
+
+#[test]
+fn test_symbols_replace_the_letters() {
+    let mut board = Board::from_fen("T3T/1G3/5/3G1/T3T t 18 0 2").unwrap();
+    play(&mut board, &["E1-D2"]);
+    let options = DisplayOptions {
+        pieces: PieceStyle::Symbols,
+        ..DisplayOptions::default()
+    };
+    assert_eq!(
+        render(&board, &options),
+        "     A   B   C   D   E
+ 1   ▲───·───·───·───◦
+     │ ╲ │ ╱ │ ╲ │ ╱ │
+ 2   ·───●!──·──[▲]──·
+     │ ╱ │ ╲ │ ╱ │ ╲ │
+ 3   ·───·───·───·───·
+     │ ╲ │ ╱ │ ╲ │ ╱ │
+ 4   ·───·───·───●!──·
+     │ ╱ │ ╲ │ ╱ │ ╲ │
+ 5   ▲───·───·───·───▲
+   ⚠ 2 goats can be captured: B2, D4
+"
+    );
+}
+
+#[test]
+fn test_emoji_keep_the_board_aligned() {
+    let mut board = Board::from_fen("T3T/1G3/5/3G1/T3T t 18 0 2").unwrap();
+    play(&mut board, &["E1-D2"]);
+    assert!(board.select_position(0));
+    let options = DisplayOptions {
+        pieces: PieceStyle::Emoji,
+        ..DisplayOptions::default()
+    };
+    // Each emoji takes the column after it, so the lines below still
+    // meet the points
+    assert_eq!(
+        render(&board, &options),
+        "     A   B   C   D   E
+ 1   🐯──•───·───·───◦
+     │ ╲ │ ╱ │ ╲ │ ╱ │
+ 2   •───🐐!─·──[🐯]─·
+     │ ╱ │ ╲ │ ╱ │ ╲ │
+ 3   ·───·───‼───·───·
+     │ ╲ │ ╱ │ ╲ │ ╱ │
+ 4   ·───·───·───🐐!─·
+     │ ╱ │ ╲ │ ╱ │ ╲ │
+ 5   🐯──·───·───·───🐯
+   ⚠ 2 goats can be captured: B2, D4
+"
+    );
+
+    // Without colour the pieces go back to letters
+    let options = DisplayOptions {
+        color: false,
+        ..options
+    };
+    assert!(render(&board, &options).contains(" 5   T───.───.───.───T\n"));
+}
+
+#[test]
+fn test_each_theme_tells_tigers_from_goats() {
+    colored::control::set_override(true);
+    let board = Board::from_fen("T3T/5/2G2/5/T3T g 0 0 30").unwrap();
+    for name in Theme::NAMES {
+        let theme = Theme::builtin(name).unwrap();
+        let options = DisplayOptions {
+            theme,
+            ..DisplayOptions::default()
+        };
+        let display = board.display_with(&options);
+        let tiger = "T".color(theme.tiger).to_string();
+        let goat = "G".color(theme.goat).to_string();
+        assert!(display.contains(&tiger), "{name}");
+        assert!(display.contains(&goat), "{name}");
+        assert_ne!(tiger.replace('T', ""), goat.replace('G', ""), "{name}");
+    }
+}
+
+#[test]
+fn test_theme_files_start_from_a_builtin_theme() {
+    let theme = Theme::parse(
+        "# Baghchal theme
+theme: colorblind
+goat: bright white
+borders: #808080
+",
+    )
+    .unwrap();
+    assert_eq!(theme.tiger, Theme::colorblind().tiger);
+    assert_eq!(theme.goat, Color::BrightWhite);
+    assert_eq!(
+        theme.borders,
+        Color::TrueColor {
+            r: 128,
+            g: 128,
+            b: 128
+        }
+    );
+    // Without a theme line the roles left out are the default ones
+    let theme = Theme::parse("tiger: bright_blue").unwrap();
+    assert_eq!(theme.tiger, Color::BrightBlue);
+    assert_eq!(theme.goat, Theme::default().goat);
+
+    let err = Theme::parse("theme: sepia").unwrap_err();
+    assert_eq!((err.line, err.column), (1, 8), "{err}");
+    let err = Theme::parse("tiger: red\nwolf: grey").unwrap_err();
+    assert_eq!(err.to_string(), "line 2, column 1: unknown role 'wolf'");
+    let err = Theme::parse("goat: #12345").unwrap_err();
+    assert_eq!(err.message, "invalid goat '#12345', expected a colour");
+}
+
+#[test]
+fn test_flipped_board_reads_from_the_other_side() {
+    let mut board = Board::from_fen("T3T/1G3/5/3G1/T3T t 18 0 2").unwrap();
+    play(&mut board, &["E1-D2"]);
+    assert!(board.select_position(0));
+    let options = DisplayOptions {
+        flipped: true,
+        coordinates_on_all_sides: true,
+        ..DisplayOptions::default()
+    };
+    assert_eq!(
+        render(&board, &options),
+        "     E   D   C   B   A
+ 5   T───·───·───·───T   5
+     │ ╲ │ ╱ │ ╲ │ ╱ │
+ 4   ·───G!──·───·───·   4
+     │ ╱ │ ╲ │ ╱ │ ╲ │
+ 3   ·───·───‼───·───·   3
+     │ ╲ │ ╱ │ ╲ │ ╱ │
+ 2   ·──[T]──·───G!──•   2
+     │ ╱ │ ╲ │ ╱ │ ╲ │
+ 1   ◦───·───·───•───T   1
+     E   D   C   B   A
+   ⚠ 2 goats can be captured: B2, D4
+"
+    );
+}
+
+/// What is drawn on and either side of each point, by the point's name,
+/// read back off the coordinates; the lines beside a point are left out,
+/// as they turn round with the board.
+fn points(rendered: &str) -> BTreeMap<String, [char; 3]> {
+    let mut lines = rendered
+        .lines()
+        .map(|line| line.chars().collect::<Vec<_>>());
+    let letters: Vec<(usize, char)> = lines
+        .next()
+        .unwrap()
+        .into_iter()
+        .enumerate()
+        .filter(|(_, c)| c.is_ascii_uppercase())
+        .collect();
+    let mut points = BTreeMap::new();
+    for line in lines {
+        let label: String = line.iter().take(2).collect();
+        let Ok(row) = label.trim().parse::<usize>() else {
+            continue;
+        };
+        for &(at, letter) in &letters {
+            let cell = |at: usize| match line.get(at).copied().unwrap_or(' ') {
+                '─' => ' ',
+                c => c,
+            };
+            if cell(at) != ' ' {
+                points.insert(
+                    format!("{letter}{row}"),
+                    [cell(at - 1), cell(at), cell(at + 1)],
+                );
+            }
+        }
+    }
+    points
+}
+
+#[test]
+fn test_flipping_keeps_every_mark_on_its_point() {
+    let mut rng = StdRng::seed_from_u64(0xF11B);
+    for rules in [RuleSet::default(), RuleSet::aadu_puli_attam()] {
+        for _ in 0..50 {
+            let mut board = Board::with_rules(rules.clone()).unwrap();
+            for _ in 0..rng.gen_range(0..40) {
+                let moves = board.legal_moves();
+                if moves.is_empty() {
+                    break;
+                }
+                assert!(board.make_move(&moves[rng.gen_range(0..moves.len())]));
+            }
+            if rng.gen_bool(0.5) {
+                let pieces: Vec<usize> = (0..board.cells.len())
+                    .filter(|&pos| board.cells[pos] != Piece::Empty)
+                    .collect();
+                board.select_position(pieces[rng.gen_range(0..pieces.len())]);
+            }
+
+            let options = DisplayOptions {
+                coordinates_on_all_sides: rng.gen_bool(0.5),
+                ..DisplayOptions::default()
+            };
+            let flipped = DisplayOptions {
+                flipped: true,
+                ..options
+            };
+            let upright = render(&board, &options);
+            let turned = render(&board, &flipped);
+            assert_eq!(points(&upright).len(), board.cells.len(), "{upright}");
+            assert_eq!(points(&turned), points(&upright), "{upright}\n{turned}");
+            let twice = DisplayOptions {
+                flipped: !flipped.flipped,
+                ..flipped
+            };
+            assert_eq!(render(&board, &twice), upright);
+        }
+    }
+}
+
+#[test]
+fn test_description_of_the_start() {
+    assert_eq!(
+        Board::new().describe(),
+        "Tigers on A1, E1, A5, E5. No goats on the board. 20 goats in hand, 0 captured. \
+         Goats to move."
+    );
+}
+
+#[test]
+fn test_description_lists_threats_and_blocked_jumps() {
+    let mut board = Board::from_fen("T3T/1G3/2G2/5/T3T g 18 1 3").unwrap();
+    play(&mut board, &["A2"]);
+    assert_eq!(
+        board.describe(),
+        "Tigers on A1, E1, A5, E5. Goats on A2, B2, C3. 17 goats in hand, 1 captured. \
+         Last move A2. Tigers to move. \
+         Goat on A2 is threatened by the tiger on A1 (would land on A3). \
+         Goat on B2 is threatened by the tiger on A1 (would land on C3 — blocked)."
+    );
+}
+
+#[test]
+fn test_description_of_a_finished_game() {
+    let mut board = Board::from_fen("T3T/1G3/5/5/T3T t 0 4 40").unwrap();
+    play(&mut board, &["A1xB2-C3"]);
+    assert_eq!(
+        board.describe(),
+        "Tigers on E1, C3, A5, E5. No goats on the board. 0 goats in hand, 5 captured. \
+         Last move A1xB2-C3. Tigers won: enough goats were captured."
+    );
+}
@@ -0,0 +1,92 @@
+use baghchal::{AiConfig, Board, Engine, Mcts, MinimaxEngine, SearchStatus};
+use std::time::{Duration, Instant};
+
+/// Searches until stopped.
+fn unlimited() -> AiConfig {
+    AiConfig {
+        time_limit: None,
+        max_depth: None,
+        max_nodes: None,
+        ..AiConfig::default()
+    }
+}
+
+fn midgame() -> Board {
+    let mut board = Board::from_fen("T1G1T/1G3/2G2/3G1/T3T t 16 0 5").unwrap();
+    board.set_use_opening_book(false);
+    board
+}
+
+#[test]
+fn test_stopping_a_search_returns_its_best_move() {
+    let board = midgame();
+    let search = MinimaxEngine::new().start_search(board.clone(), unlimited());
+
+    // The search reports its progress while it runs
+    let started = Instant::now();
+    let info = loop {
+        match search.poll() {
+            SearchStatus::Running(Some(info)) if info.depth >= 3 => break info,
+            SearchStatus::Running(_) => std::thread::sleep(Duration::from_millis(5)),
+            SearchStatus::Finished(result) => panic!("finished unstopped: {result:?}"),
+        }
+        assert!(started.elapsed() < Duration::from_secs(10));
+    };
+    assert!(!info.pv.is_empty());
+
+    let stopped = Instant::now();
+    search.stop();
+    let (result, _engine) = search.await_result();
+    assert!(stopped.elapsed() < Duration::from_millis(500));
+    let result = result.unwrap();
+    assert!(board.legal_moves().contains(&result.mv));
+    assert!(result.stats.depth >= info.depth);
+}
+
+#[test]
+fn test_background_search_matches_best_move() {
+    let board = midgame();
+    let limits = AiConfig::nodes(5_000);
+    let expected = MinimaxEngine::new().best_move(&board, &limits).unwrap();
+
+    let search = MinimaxEngine::new().start_search(board.clone(), limits);
+    let (result, mut engine) = search.await_result();
+    assert_eq!(result.as_ref().map(|result| &result.mv), Some(&expected.mv));
+
+    // The engine comes back ready for the next search
+    assert!(engine.best_move(&board, &limits).is_some());
+}
+
+#[test]
+fn test_finished_search_polls_as_finished() {
+    // The goats have trapped every tiger
+    let board = Board::from_fen("TGGGT/GGGGG/GGG1G/GGGGG/TGGGT t 0 0 30").unwrap();
+    let search = MinimaxEngine::new().start_search(board, unlimited());
+    let started = Instant::now();
+    while search.poll() == SearchStatus::Running(None) {
+        assert!(started.elapsed() < Duration::from_secs(5));
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    assert_eq!(search.poll(), SearchStatus::Finished(None));
+    assert_eq!(search.await_result().0, None);
+}
+
+#[test]
+fn test_monte_carlo_search_can_be_stopped() {
+    let board = midgame();
+    let engine: Box<dyn Engine + Send> = Box::new(Mcts {
+        seed: Some(1),
+        ..Mcts::default()
+    });
+    let limits = AiConfig {
+        max_nodes: Some(u64::MAX),
+        ..unlimited()
+    };
+    let search = engine.start_search(board.clone(), limits);
+    std::thread::sleep(Duration::from_millis(100));
+    let stopped = Instant::now();
+    search.stop();
+    let (result, _engine) = search.await_result();
+    assert!(stopped.elapsed() < Duration::from_millis(500));
+    assert!(board.legal_moves().contains(&result.unwrap().mv));
+}
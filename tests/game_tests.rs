@@ -1,4 +1,26 @@
-use baghchal::{Board, Piece, Winner};
+// These tests still drive the AI through the deprecated `ai_move_*` wrappers.
+#![allow(deprecated)]
+
+use baghchal::notation::{format_history, format_move, parse_move};
+use baghchal::puzzle::builtin;
+use baghchal::{
+    Adjudication, AiConfig, Blunders, Board, DefaultEvaluator, Difficulty, EvalBreakdown, EvalTerm,
+    EvalWeights, Evaluator, Extensions, GameEvent, GameResult, Jump, Move, MoveError, MultiCapture,
+    Piece, PieceSquareTable, Position, RuleError, RuleSet, ShuttleRule, Side, TerminationReason,
+    Winner,
+};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Rules that let goats move before all of them are placed, for tests
+/// that exercise goat movement without placing every goat first.
+fn lenient() -> RuleSet {
+    RuleSet {
+        goats_may_move_during_placement: true,
+        ..RuleSet::standard()
+    }
+}
 
 #[test]
 fn test_initial_board() {
@@ -29,12 +51,18 @@ fn test_place_goat() {
 #[test]
 fn test_tiger_basic_moves() {
     let mut board = Board::new();
+    // The goats place theirs out of the way in between
+    assert!(board.place_goat(22));
 
     // Valid moves
     assert!(board.move_tiger(0, 1)); // Right
+    assert!(board.place_goat(23));
     assert!(board.move_tiger(1, 0)); // Left
+    assert!(board.place_goat(17));
     assert!(board.move_tiger(0, 5)); // Down
+    assert!(board.place_goat(18));
     assert!(board.move_tiger(5, 0)); // Up
+    assert!(board.place_goat(16));
 
     // Invalid moves
     assert!(!board.move_tiger(12, 13)); // No tiger at source
@@ -45,6 +73,7 @@ fn test_tiger_basic_moves() {
 #[test]
 fn test_tiger_diagonal_moves() {
     let mut board = Board::new();
+    assert!(board.place_goat(22));
 
     // Valid diagonal moves from corner
     assert!(board.move_tiger(0, 6)); // Diagonal from top-left
@@ -52,6 +81,7 @@ fn test_tiger_diagonal_moves() {
     // Reset tiger position
     board.cells[6] = Piece::Empty;
     board.cells[0] = Piece::Tiger;
+    assert!(board.place_goat(23));
 
     // Invalid diagonal moves
     assert!(!board.move_tiger(1, 7)); // Not a diagonal position
@@ -79,10 +109,11 @@ fn test_tiger_captures() {
     // Invalid captures
     board.cells[12] = Piece::Empty;
     board.cells[0] = Piece::Tiger;
+    assert!(board.place_goat(22));
     assert!(!board.move_tiger(0, 2)); // No goat to capture
 
-    board.place_goat(1);
-    board.place_goat(2);
+    board.cells[1] = Piece::Goat;
+    board.cells[2] = Piece::Goat;
     assert!(!board.move_tiger(0, 2)); // Destination occupied
 }
 
@@ -133,32 +164,48 @@ fn test_diagonal_positions() {
 #[test]
 fn test_diagonal_positions_and_moves() {
     let mut board = Board::new();
+    assert!(board.place_goat(22));
 
     // Test diagonal moves from corner (0)
     assert!(board.move_tiger(0, 6)); // Down-right diagonal
 
     // Reset board
     board = Board::new();
+    assert!(board.place_goat(22));
     assert!(board.move_tiger(4, 8)); // Down-left diagonal
 
     // Test diagonal moves from middle positions
-    board = Board::new();
-    board.cells[12] = Piece::Tiger; // Place tiger in center
-    board.cells[0] = Piece::Empty; // Remove tiger from corner
-
-    // All valid diagonal moves from center
+    board = Board::from_diagram(
+        "
+        . . . . T
+        . . . . .
+        . . T . .
+        . . . . .
+        T . . . T
+        ",
+        20,
+        0,
+    )
+    .unwrap();
+
+    // All valid diagonal moves from center, with a goat placed on the
+    // top row before each
+    assert!(board.place_goat(0));
     assert!(board.move_tiger(12, 6)); // Up-left
     board.cells[6] = Piece::Empty;
     board.cells[12] = Piece::Tiger;
 
+    assert!(board.place_goat(1));
     assert!(board.move_tiger(12, 8)); // Up-right
     board.cells[8] = Piece::Empty;
     board.cells[12] = Piece::Tiger;
 
+    assert!(board.place_goat(2));
     assert!(board.move_tiger(12, 16)); // Down-left
     board.cells[16] = Piece::Empty;
     board.cells[12] = Piece::Tiger;
 
+    assert!(board.place_goat(3));
     assert!(board.move_tiger(12, 18)); // Down-right
 }
 
@@ -173,10 +220,19 @@ fn test_diagonal_captures() {
     assert_eq!(board.cells[6], Piece::Empty); // Goat should be captured
 
     // Test diagonal capture from center
-    board = Board::new();
-    board.cells[12] = Piece::Tiger; // Place tiger in center
-    board.cells[4] = Piece::Empty; // Remove tiger from corner
-    board.place_goat(8); // Place goat for capture
+    board = Board::from_diagram(
+        "
+        T . . . .
+        . . . G .
+        . . T . .
+        . . . . .
+        T . . . T
+        ",
+        19,
+        0,
+    )
+    .unwrap();
+    assert!(board.place_goat(22));
     assert!(board.move_tiger(12, 4)); // Should capture diagonally up-right
     assert_eq!(board.captured_goats, 1);
     assert_eq!(board.cells[8], Piece::Empty);
@@ -189,11 +245,19 @@ fn test_diagonal_captures() {
 
 #[test]
 fn test_invalid_diagonal_moves() {
-    let mut board = Board::new();
-
     // Test moves from non-diagonal positions
-    board.cells[1] = Piece::Tiger; // Place tiger in non-diagonal position
-    board.cells[0] = Piece::Empty; // Remove tiger from corner
+    let mut board = Board::from_diagram(
+        "
+        . T . . T
+        . . . . .
+        . . . . .
+        . . . . .
+        T . . . T
+        ",
+        20,
+        0,
+    )
+    .unwrap();
 
     // Attempt invalid diagonal moves
     assert!(!board.move_tiger(1, 7)); // Should not allow diagonal move
@@ -206,16 +270,21 @@ fn test_invalid_diagonal_moves() {
 
 #[test]
 fn test_goat_basic_moves() {
-    let mut board = Board::new();
+    let mut board = Board::with_rules(lenient()).unwrap();
 
     // Place a goat
     board.place_goat(12); // Center position
 
-    // Test orthogonal moves
+    // Test orthogonal moves, the tiger on E1 stepping aside in between
+    assert!(board.move_tiger(4, 3));
     assert!(board.move_goat(12, 11)); // Left
+    assert!(board.move_tiger(3, 4));
     assert!(board.move_goat(11, 12)); // Right
+    assert!(board.move_tiger(4, 3));
     assert!(board.move_goat(12, 7)); // Up
+    assert!(board.move_tiger(3, 4));
     assert!(board.move_goat(7, 12)); // Down
+    assert!(board.move_tiger(4, 3));
 
     // Test invalid moves
     assert!(!board.move_goat(12, 14)); // Too far
@@ -225,29 +294,35 @@ fn test_goat_basic_moves() {
 
 #[test]
 fn test_goat_diagonal_moves() {
-    let mut board = Board::new();
+    let mut board = Board::with_rules(lenient()).unwrap();
 
     // Place a goat at center (diagonal position)
     board.place_goat(12);
 
-    // Test valid diagonal moves
+    // Test valid diagonal moves, the tiger on E1 stepping aside in
+    // between
+    assert!(board.move_tiger(4, 3));
     assert!(board.move_goat(12, 6)); // Up-left
     board.cells[12] = Piece::Goat; // Reset
     board.cells[6] = Piece::Empty;
 
+    assert!(board.move_tiger(3, 4));
     assert!(board.move_goat(12, 8)); // Up-right
     board.cells[12] = Piece::Goat; // Reset
     board.cells[8] = Piece::Empty;
 
+    assert!(board.move_tiger(4, 3));
     assert!(board.move_goat(12, 16)); // Down-left
     board.cells[12] = Piece::Goat; // Reset
     board.cells[16] = Piece::Empty;
 
+    assert!(board.move_tiger(3, 4));
     assert!(board.move_goat(12, 18)); // Down-right
 
     // Test invalid diagonal moves
-    board = Board::new();
+    board = Board::with_rules(lenient()).unwrap();
     board.place_goat(7); // Non-diagonal position
+    assert!(board.move_tiger(4, 3));
     assert!(!board.move_goat(7, 13)); // Cannot move diagonally from non-diagonal position
 }
 
@@ -269,33 +344,87 @@ fn test_tiger_win() {
 
 #[test]
 fn test_goat_win() {
-    let mut board = Board::new();
+    // All tigers in the top row, surrounded by goats
+    let board = Board::from_diagram(
+        "
+        T T T T G
+        G G G G G
+        G G G G G
+        . . . . .
+        . . . . .
+        ",
+        9,
+        0,
+    )
+    .unwrap();
 
-    // Place all tigers in the top row
-    board.cells[0] = Piece::Tiger;
-    board.cells[1] = Piece::Tiger;
-    board.cells[2] = Piece::Tiger;
-    board.cells[3] = Piece::Tiger;
+    assert_eq!(board.get_winner(), Winner::Goats);
+    assert!(board.is_game_over());
+}
 
-    // Surround them with goats
-    board.cells[4] = Piece::Goat;
-    board.cells[5] = Piece::Goat;
-    board.cells[6] = Piece::Goat;
-    board.cells[7] = Piece::Goat;
-    board.cells[8] = Piece::Goat;
-    board.cells[9] = Piece::Goat;
-    board.cells[10] = Piece::Goat;
-    board.cells[11] = Piece::Goat;
-    board.cells[12] = Piece::Goat;
-    board.cells[13] = Piece::Goat;
-    board.cells[14] = Piece::Goat;
-
-    // Remove tigers from the bottom row
-    board.cells[20] = Piece::Empty;
-    board.cells[24] = Piece::Empty;
+#[test]
+fn test_result_reports_reason() {
+    let mut board = Board::new();
+    assert_eq!(board.result(), None);
+
+    board.captured_goats = 5;
+    assert_eq!(
+        board.result(),
+        Some(GameResult {
+            winner: Winner::Tigers,
+            reason: TerminationReason::GoatsCaptured,
+        })
+    );
 
+    // Trap the lone remaining tiger in the corner
+    let board = Board::from_diagram(
+        "
+        T G G . .
+        G G . . .
+        G . G . .
+        . . . . .
+        . . . . .
+        ",
+        14,
+        0,
+    )
+    .unwrap();
+    assert_eq!(
+        board.result(),
+        Some(GameResult {
+            winner: Winner::Goats,
+            reason: TerminationReason::TigersTrapped,
+        })
+    );
     assert_eq!(board.get_winner(), Winner::Goats);
-    assert!(board.is_game_over());
+}
+
+#[test]
+fn test_record_contains_moves_and_result() {
+    let mut board = Board::new();
+    board.place_goat(1);
+    board.move_tiger(0, 2);
+
+    let record = board.record();
+    assert_eq!(
+        record.moves,
+        vec![
+            Move::PlaceGoat { position: 1 },
+            Move::MoveTiger {
+                from: 0,
+                to: 2,
+                captured_position: Some(1),
+            },
+        ]
+    );
+    assert_eq!(record.result, None);
+
+    board.captured_goats = 5;
+    assert_eq!(record.result, None);
+    assert_eq!(
+        board.record().result.map(|r| r.winner),
+        Some(Winner::Tigers)
+    );
 }
 
 #[test]
@@ -423,7 +552,14 @@ fn test_ai_tiger_strategic_move() {
     // Place a goat
     board.place_goat(13);
 
-    // AI tiger should move to a position that could lead to a capture
+    // AI tiger should move to a position that could lead to a capture.
+    // A fixed depth, as how deep two seconds gets varies from run to run.
+    board.set_ai_config(AiConfig {
+        time_limit: None,
+        max_depth: Some(3),
+        max_nodes: None,
+        ..AiConfig::default()
+    });
     assert!(board.ai_move_tiger());
 
     // Verify that at least one tiger is adjacent to the goat
@@ -434,7 +570,7 @@ fn test_ai_tiger_strategic_move() {
             let col = pos % 5;
             let goat_row = 13 / 5;
             let goat_col = 13 % 5;
-            (row as i32 - goat_row as i32).abs() <= 1 && (col as i32 - goat_col as i32).abs() <= 1
+            row.abs_diff(goat_row) <= 1 && col.abs_diff(goat_col) <= 1
         });
 
     assert!(
@@ -443,150 +579,3089 @@ fn test_ai_tiger_strategic_move() {
     );
 }
 
-#[cfg(test)]
-mod tests {
-    use baghchal::{Board, Piece};
+#[test]
+fn test_piece_iterators() {
+    let board = Board::from_fen("T3T/2G2/2G2/5/T3T g 18 0 2").unwrap();
 
-    #[test]
-    fn test_undo_place_goat() {
-        let mut board = Board::new();
-        let pos = 12; // Center position
+    let tigers: Vec<Position> = board.tigers().collect();
+    assert_eq!(
+        tigers,
+        vec![Position(0), Position(4), Position(20), Position(24)]
+    );
 
-        // Place a goat
-        assert!(board.place_goat(pos));
-        assert_eq!(board.cells[pos], Piece::Goat);
-        assert_eq!(board.goats_in_hand, 19);
+    let goats: Vec<Position> = board.goats().collect();
+    assert_eq!(goats, vec![Position(7), Position(12)]);
 
-        // Undo the placement
-        assert!(board.can_undo());
-        assert!(board.undo());
-        assert_eq!(board.cells[pos], Piece::Empty);
-        assert_eq!(board.goats_in_hand, 20);
-    }
+    assert_eq!(board.empty_cells().count(), 25 - 4 - 2);
+    assert!(board
+        .empty_cells()
+        .all(|Position(p)| board.cells[p] == Piece::Empty));
 
-    #[test]
-    fn test_undo_move_goat() {
-        let mut board = Board::new();
-        let start_pos = 12;
-        let move_to = 13;
+    // iter_pieces visits every cell exactly once, in order
+    let pieces: Vec<(Position, Piece)> = board.iter_pieces().collect();
+    assert_eq!(pieces.len(), 25);
+    assert_eq!(pieces[12], (Position(12), Piece::Goat));
+    assert_eq!(pieces[0], (Position(0), Piece::Tiger));
+}
 
-        // Place a goat first
-        assert!(board.place_goat(start_pos));
-        assert_eq!(board.cells[start_pos], Piece::Goat);
+#[test]
+fn test_ply_and_fullmove_counters() {
+    let mut board = Board::new();
+    assert_eq!(board.ply(), 0);
+    assert_eq!(board.fullmove_number(), 1);
+    assert_eq!(board.side_to_move(), Side::Goats);
 
-        // Move the goat
-        assert!(board.move_goat(start_pos, move_to));
-        assert_eq!(board.cells[start_pos], Piece::Empty);
-        assert_eq!(board.cells[move_to], Piece::Goat);
+    assert!(board.place_goat(12));
+    assert_eq!(board.ply(), 1);
+    assert_eq!(board.fullmove_number(), 1);
+    assert_eq!(board.side_to_move(), Side::Tigers);
 
-        // Undo the move
-        assert!(board.can_undo());
-        assert!(board.undo());
-        assert_eq!(board.cells[start_pos], Piece::Goat);
-        assert_eq!(board.cells[move_to], Piece::Empty);
-    }
+    assert!(board.move_tiger(0, 5));
+    assert!(board.place_goat(7));
+    assert_eq!(board.ply(), 3);
+    assert_eq!(board.fullmove_number(), 2);
+
+    // An odd number of undos hands the turn back to the tigers
+    assert!(board.undo());
+    assert_eq!(board.ply(), 2);
+    assert_eq!(board.fullmove_number(), 2);
+    assert_eq!(board.side_to_move(), Side::Goats);
+    assert!(board.undo());
+    assert!(board.undo());
+    assert_eq!(board.ply(), 0);
+    assert_eq!(board.fullmove_number(), 1);
+
+    // Nothing left to undo, counters stay put
+    assert!(!board.undo());
+    assert_eq!(board.ply(), 0);
+}
 
-    #[test]
-    fn test_undo_tiger_move_without_capture() {
-        let mut board = Board::new();
-        let start_pos = 0; // Top-left tiger
-        let move_to = 5;
+#[test]
+fn test_fen_round_trip() {
+    let mut board = Board::new();
+    assert_eq!(board.to_fen(), "T3T/5/5/5/T3T g 20 0 1");
 
-        // Move tiger
-        assert!(board.move_tiger(start_pos, move_to));
-        assert_eq!(board.cells[start_pos], Piece::Empty);
-        assert_eq!(board.cells[move_to], Piece::Tiger);
-        assert_eq!(board.captured_goats, 0);
+    board.place_goat(1);
+    board.move_tiger(0, 2);
+    board.place_goat(12);
+    assert_eq!(board.to_fen(), "2T1T/5/2G2/5/T3T t 18 1 2");
+
+    let parsed = Board::from_fen(&board.to_fen()).unwrap();
+    assert_eq!(parsed.cells, board.cells);
+    assert_eq!(parsed.goats_in_hand, 18);
+    assert_eq!(parsed.captured_goats, 1);
+    assert_eq!(parsed.ply(), 3);
+    assert_eq!(parsed.to_fen(), board.to_fen());
+}
 
-        // Undo the move
-        assert!(board.can_undo());
-        assert!(board.undo());
-        assert_eq!(board.cells[start_pos], Piece::Tiger);
-        assert_eq!(board.cells[move_to], Piece::Empty);
-        assert_eq!(board.captured_goats, 0);
-    }
+#[test]
+fn test_custom_position_with_nonzero_ply() {
+    let mut board = Board::from_fen("T3T/5/2G2/5/T3T t 19 0 7").unwrap();
+    assert_eq!(board.ply(), 13);
+    assert_eq!(board.side_to_move(), Side::Tigers);
+    assert!(!board.can_undo());
+
+    assert!(board.move_tiger(0, 1));
+    assert_eq!(board.ply(), 14);
+    assert_eq!(board.fullmove_number(), 8);
+    assert_eq!(board.record().start_ply, 13);
+
+    // Undo stops at the starting position rather than rewinding past it
+    assert!(board.undo());
+    assert!(!board.undo());
+    assert_eq!(board.ply(), 13);
+}
 
-    #[test]
-    fn test_undo_tiger_capture() {
-        let mut board = Board::new();
-        let tiger_pos = 0;
-        let goat_pos = 5;
-        let capture_to = 10;
+#[test]
+fn test_fen_errors_point_at_problem() {
+    let err = Board::from_fen("T3T/5/5/5/T3X g 20 0 1").unwrap_err();
+    assert_eq!((err.line, err.column), (1, 13));
 
-        // Place a goat to be captured
-        assert!(board.place_goat(goat_pos));
-        assert_eq!(board.cells[goat_pos], Piece::Goat);
+    let err = Board::from_fen("T3T/5/5/5 g 20 0 1").unwrap_err();
+    assert_eq!(err.column, 10);
 
-        // Capture the goat
-        assert!(board.move_tiger(tiger_pos, capture_to));
-        assert_eq!(board.cells[tiger_pos], Piece::Empty);
-        assert_eq!(board.cells[goat_pos], Piece::Empty);
-        assert_eq!(board.cells[capture_to], Piece::Tiger);
-        assert_eq!(board.captured_goats, 1);
+    let err = Board::from_fen("T3T/5/5/5/T3T x 20 0 1").unwrap_err();
+    assert_eq!(err.column, 15);
 
-        // Undo the capture
-        assert!(board.can_undo());
-        assert!(board.undo());
-        assert_eq!(board.cells[tiger_pos], Piece::Tiger);
-        assert_eq!(board.cells[goat_pos], Piece::Goat);
-        assert_eq!(board.cells[capture_to], Piece::Empty);
-        assert_eq!(board.captured_goats, 0);
+    assert!(Board::from_fen("T3T/5/5/5/T3T g 20 0").is_err());
+    assert!(Board::from_fen("T4T/5/5/5/T3T g 20 0 1").is_err());
+    assert!(Board::from_fen("T3T/5/5/5/T3T g 20 0 0").is_err());
+}
+
+#[test]
+fn test_index_and_get() {
+    let mut board = Board::new();
+    assert_eq!(board[Position(0)], Piece::Tiger);
+    assert_eq!(board[Position(12)], Piece::Empty);
+    assert_eq!(board.get(Position(24)), Some(Piece::Tiger));
+
+    // Out of range lookups don't panic
+    assert_eq!(board.get(Position(25)), None);
+    assert_eq!(board.get(Position(usize::MAX)), None);
+
+    board[Position(12)] = Piece::Goat;
+    assert_eq!(board.cells[12], Piece::Goat);
+    assert_eq!(board.get(Position(12)), Some(Piece::Goat));
+}
+
+#[test]
+fn test_index_agrees_with_move_generators() {
+    let mut board = Board::new();
+    for pos in [1, 6, 7, 12, 13, 18] {
+        board.place_goat(pos);
     }
 
-    #[test]
-    fn test_multiple_undo() {
-        let mut board = Board::new();
+    for tiger in board.tigers() {
+        assert_eq!(board[tiger], Piece::Tiger);
+        for dest in board.get_valid_tiger_moves(tiger.0) {
+            assert_eq!(board[dest], Piece::Empty);
+            if let Some(captured) = board.get_captured_position(tiger.0, dest.0) {
+                assert_eq!(board[Position(captured)], Piece::Goat);
+            }
+        }
+    }
+    for goat in board.goats() {
+        assert_eq!(board[goat], Piece::Goat);
+        for dest in board.get_valid_goat_moves(goat.0) {
+            assert_eq!(board.get(dest), Some(Piece::Empty));
+        }
+    }
+}
 
-        // Make several moves
-        assert!(board.place_goat(12)); // Place goat in center
-        assert_eq!(board.goats_in_hand, 19);
+#[test]
+fn test_diagram_round_trip() {
+    let board = Board::from_fen("T3T/2G2/2G2/5/T3T g 18 0 2").unwrap();
+    let diagram = board.to_diagram();
+    assert_eq!(
+        diagram,
+        "T . . . T\n. . G . .\n. . G . .\n. . . . .\nT . . . T\n"
+    );
 
-        assert!(board.move_tiger(0, 5)); // Move tiger
-        assert_eq!(board.goats_in_hand, 19);
+    let parsed = Board::from_diagram(&diagram, 18, 0).unwrap();
+    assert_eq!(parsed.cells, board.cells);
+    assert_eq!(parsed.goats_in_hand, 18);
 
-        assert!(board.place_goat(7)); // Place another goat
-        assert_eq!(board.goats_in_hand, 18);
+    // Spacing is optional and indentation is ignored
+    let compact = Board::from_diagram("  T...T\n..G..\n..G..\n.....\nT...T", 18, 0).unwrap();
+    assert_eq!(compact.cells, board.cells);
+}
 
-        // Undo all moves in reverse order
-        assert!(board.can_undo());
-        assert!(board.undo()); // Undo goat placement
-        assert_eq!(board.cells[7], Piece::Empty);
-        assert_eq!(board.goats_in_hand, 19);
+#[test]
+fn test_diagram_errors_are_labelled() {
+    // Unknown character
+    let err = Board::from_diagram(
+        "T . . . T\n. . X . .\n. . . . .\n. . . . .\nT . . . T",
+        20,
+        0,
+    )
+    .unwrap_err();
+    assert_eq!((err.line, err.column), (2, 5));
+    assert!(err.to_string().contains("'X'"));
+
+    // Short row
+    let err = Board::from_diagram("T . . . T\n. . . .\n. . . . .\n. . . . .\nT . . . T", 20, 0)
+        .unwrap_err();
+    assert_eq!(err.line, 2);
+
+    // Long row
+    let err = Board::from_diagram(
+        "T . . . T .\n. . . . .\n. . . . .\n. . . . .\nT . . . T",
+        20,
+        0,
+    )
+    .unwrap_err();
+    assert_eq!((err.line, err.column), (1, 11));
+
+    // Too few and too many rows
+    assert!(Board::from_diagram("T . . . T\n. . . . .", 20, 0).is_err());
+    let err = Board::from_diagram(
+        "T . . . T\n. . . . .\n. . . . .\n. . . . .\nT . . . T\n. . . . .",
+        20,
+        0,
+    )
+    .unwrap_err();
+    assert_eq!(err.line, 6);
+}
 
-        assert!(board.can_undo());
-        assert!(board.undo()); // Undo tiger move
-        assert_eq!(board.cells[0], Piece::Tiger);
-        assert_eq!(board.cells[5], Piece::Empty);
-        assert_eq!(board.goats_in_hand, 19);
+#[test]
+fn test_events_for_moves_and_captures() {
+    let mut board = Board::new();
+    assert!(board.drain_events().is_empty());
 
-        assert!(board.can_undo());
-        assert!(board.undo()); // Undo first goat placement
-        assert_eq!(board.cells[12], Piece::Empty);
-        assert_eq!(board.goats_in_hand, 20);
+    board.place_goat(1);
+    board.move_tiger(0, 2);
+    assert_eq!(
+        board.drain_events(),
+        vec![
+            GameEvent::Moved(Move::PlaceGoat { position: 1 }),
+            GameEvent::Moved(Move::MoveTiger {
+                from: 0,
+                to: 2,
+                captured_position: Some(1),
+            }),
+            GameEvent::Captured { square: 1 },
+        ]
+    );
 
-        // No more moves to undo
-        assert!(!board.can_undo());
-        assert!(!board.undo());
+    // Draining empties the queue
+    assert!(board.drain_events().is_empty());
+
+    // Illegal moves produce no events
+    assert!(!board.move_tiger(2, 12));
+    assert!(board.drain_events().is_empty());
+}
+
+#[test]
+fn test_events_for_undo() {
+    let mut board = Board::new();
+    board.place_goat(1);
+    board.move_tiger(0, 2);
+    board.drain_events();
+
+    board.undo();
+    assert_eq!(
+        board.drain_events(),
+        vec![
+            GameEvent::CaptureUndone { square: 1 },
+            GameEvent::MoveUndone(Move::MoveTiger {
+                from: 0,
+                to: 2,
+                captured_position: Some(1),
+            }),
+        ]
+    );
+}
+
+#[test]
+fn test_game_over_event() {
+    let mut board = Board::new();
+    board.captured_goats = 4;
+    board.place_goat(1);
+    board.move_tiger(0, 2);
+
+    let events = board.drain_events();
+    assert_eq!(
+        events.last(),
+        Some(&GameEvent::GameOver(GameResult {
+            winner: Winner::Tigers,
+            reason: TerminationReason::GoatsCaptured,
+        }))
+    );
+}
+
+#[test]
+fn test_goats_cannot_move_during_placement() {
+    let mut board = Board::new();
+    assert!(board.place_goat(12));
+    assert!(board.move_tiger(0, 1));
+
+    // Standard rules: the goat stays put while goats remain in hand
+    assert!(board.get_valid_goat_moves(12).is_empty());
+    assert!(!board.move_goat(12, 13));
+    assert_eq!(board.cells[12], Piece::Goat);
+    assert!(board
+        .get_all_valid_goat_moves()
+        .iter()
+        .all(|&(from, to)| from == to));
+
+    // Once every goat is placed, moving is allowed
+    board.goats_in_hand = 0;
+    assert!(board.move_goat(12, 13));
+}
+
+#[test]
+fn test_lenient_rules_mix_placement_and_movement() {
+    let mut board = Board::with_rules(lenient()).unwrap();
+    assert!(board.place_goat(12));
+    assert!(board.move_tiger(0, 1));
+
+    let moves = board.legal_moves();
+    assert!(moves.contains(&Move::PlaceGoat { position: 7 }));
+    assert!(moves.contains(&Move::MoveGoat { from: 12, to: 13 }));
+    assert!(board.move_goat(12, 13));
+}
+
+#[test]
+fn test_legal_moves_follow_side_to_move() {
+    let mut board = Board::new();
+    let moves = board.legal_moves();
+    assert_eq!(moves.len(), 21);
+    assert!(moves.iter().all(|mv| matches!(mv, Move::PlaceGoat { .. })));
+
+    board.place_goat(1);
+    let moves = board.legal_moves();
+    assert!(moves.contains(&Move::MoveTiger {
+        from: 0,
+        to: 2,
+        captured_position: Some(1),
+    }));
+    assert!(moves.contains(&Move::MoveTiger {
+        from: 0,
+        to: 5,
+        captured_position: None,
+    }));
+}
+
+#[test]
+fn test_moves_out_of_turn_are_refused() {
+    let mut board = Board::new();
+    assert!(!board.move_tiger(0, 5));
+    assert_eq!(board.try_move_tiger(0, 5), Err(MoveError::Illegal));
+    assert!(!board.make_move(&Move::MoveTiger {
+        from: 0,
+        to: 5,
+        captured_position: None,
+    }));
+    assert_eq!(board.ply(), 0);
+    assert_eq!(board.side_to_move(), Side::Goats);
+    assert_eq!(board.cells[0], Piece::Tiger);
+
+    let mut board = Board::with_rules(lenient()).unwrap();
+    assert!(board.place_goat(12));
+    assert!(!board.place_goat(13));
+    assert!(!board.move_goat(12, 13));
+    assert_eq!(board.ply(), 1);
+    assert_eq!(board.side_to_move(), Side::Tigers);
+}
+
+#[test]
+fn test_legal_moves_are_generated_once_per_position() {
+    let mut board = Board::new();
+    let start = board.legal_move_list();
+    let generated = board.legal_move_generations();
+    for _ in 0..3 {
+        assert_eq!(board.legal_move_list(), start);
+        assert_eq!(board.legal_moves(), start.to_vec());
     }
+    assert_eq!(board.legal_move_generations(), generated);
+
+    // Moving, taking back and resetting all bring new moves
+    assert!(board.place_goat(1));
+    let after = board.legal_move_list();
+    assert_ne!(after, start);
+    assert!(after.iter().any(Move::is_capture));
+    assert!(board.undo());
+    assert_eq!(board.legal_move_list(), start);
+    assert!(board.place_goat(1));
+    assert!(board.legal_move_list().iter().any(Move::is_capture));
+    board.reset();
+    assert_eq!(board.legal_move_list(), start);
+    assert_eq!(board.legal_move_generations(), generated + 4);
+
+    // So does writing to the board directly
+    board.cells[6] = Piece::Goat;
+    board.goats_in_hand -= 1;
+    assert_eq!(board.legal_move_list().len(), 20);
+    board.cells[6] = Piece::Empty;
+    board.goats_in_hand += 1;
+    assert_eq!(board.legal_move_list(), start);
+}
 
-    #[test]
-    fn test_undo_clears_selection() {
-        let mut board = Board::new();
+#[test]
+fn test_searching_leaves_the_legal_moves_alone() {
+    let mut board = Board::from_fen("T3T/1G3/2G2/5/T3T t 18 0 2").unwrap();
+    board.set_use_opening_book(false);
+    let before = board.legal_move_list();
+    let config = AiConfig {
+        max_depth: Some(3),
+        max_nodes: None,
+        ..AiConfig::nodes(0)
+    };
+    let generated = board.legal_move_generations();
+    let (mv, _) = board.suggest_move(&config).unwrap();
+    assert_eq!(board.legal_move_list(), before);
+    assert_eq!(board.legal_move_generations(), generated);
+
+    board.set_ai_config(config);
+    assert!(board.ai_move_tiger());
+    assert_eq!(board.record().moves[0], mv);
+    let mut expected = Board::from_fen("T3T/1G3/2G2/5/T3T t 18 0 2").unwrap();
+    assert!(expected.make_move(&mv));
+    assert_eq!(board.legal_moves(), expected.legal_moves());
+}
 
-        // Place a goat and select it
-        assert!(board.place_goat(12));
-        board.select_position(12);
-        assert_eq!(board.selected_position, Some(12));
+#[test]
+fn test_ai_time_limit() {
+    let mut board = Board::from_fen("T3T/1G3/2G2/5/T3T t 18 0 2").unwrap();
+    assert_eq!(board.ai_time_limit(), Some(Duration::from_secs(2)));
+    board.set_ai_time_limit(Duration::from_millis(100));
+    assert_eq!(board.ai_time_limit(), Some(Duration::from_millis(100)));
+
+    let legal = board.legal_moves();
+    let start = Instant::now();
+    assert!(board.ai_move_tiger());
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert!(legal.contains(&board.record().moves[0]));
+}
 
-        // Undo should clear the selection
-        assert!(board.undo());
-        assert_eq!(board.selected_position, None);
+#[test]
+fn test_ai_config_limits() {
+    let fen = "T3T/1G3/5/5/T3T t 18 0 2";
+
+    // Depth 1 is searched in full however little time there is
+    let mut board = Board::from_fen(fen).unwrap();
+    board.set_ai_config(AiConfig {
+        time_limit: Some(Duration::ZERO),
+        max_depth: Some(1),
+        max_nodes: None,
+        ponder: false,
+        tie_margin: 0,
+        seed: None,
+        blunders: None,
+        contempt: 0,
+        null_move: true,
+        extensions: Extensions::default(),
+        safe_goats: false,
+        threads: 1,
+    });
+    assert!(board.ai_move_tiger());
+    assert_eq!(board.captured_goats, 1);
+
+    // Without a clock the search stops at the depth or node limit
+    for config in [
+        AiConfig {
+            time_limit: None,
+            max_depth: Some(3),
+            max_nodes: None,
+            ponder: false,
+            tie_margin: 0,
+            seed: None,
+            blunders: None,
+            contempt: 0,
+            null_move: true,
+            extensions: Extensions::default(),
+            safe_goats: false,
+            threads: 1,
+        },
+        AiConfig {
+            time_limit: None,
+            max_depth: None,
+            max_nodes: Some(5_000),
+            ponder: false,
+            tie_margin: 0,
+            seed: None,
+            blunders: None,
+            contempt: 0,
+            null_move: true,
+            extensions: Extensions::default(),
+            safe_goats: false,
+            threads: 1,
+        },
+    ] {
+        let mut board = Board::from_fen(fen).unwrap();
+        board.set_ai_config(config);
+        assert_eq!(board.ai_config(), &config);
+        assert!(board.ai_move_tiger());
+        assert_eq!(board.captured_goats, 1);
     }
+}
 
-    #[test]
-    fn test_undo_empty_history() {
-        let mut board = Board::new();
-        assert!(!board.can_undo());
-        assert!(!board.undo());
+#[test]
+fn test_ai_node_limit_is_deterministic() {
+    let pinned = [
+        ("4T/1T3/2G2/3G1/T3T t 18 0 2", "E1-D2"),
+        ("T2GT/1G3/2G2/1T3/4T g 16 0 3", "C1"),
+        ("TGGGT/G3G/G1T1G/G3G/TGGG1 g 0 0 30", "E2-D2"),
+    ];
+    for (fen, expected) in pinned {
+        for _ in 0..2 {
+            let mut board = Board::from_fen(fen).unwrap();
+            board.set_ai_config(AiConfig::nodes(20_000));
+            board.set_use_opening_book(false);
+            let moved = match board.side_to_move() {
+                Side::Tigers => board.ai_move_tiger(),
+                Side::Goats => board.ai_move_goat(),
+            };
+            assert!(moved);
+            let mv = &board.record().moves[0];
+            assert_eq!(format_move(board.topology(), mv), expected, "{fen}");
+        }
     }
 }
+
+#[test]
+fn test_ai_completes_depth_one_in_a_millisecond() {
+    let mut board = Board::from_fen("T3T/1G3/5/5/T3T t 18 0 2").unwrap();
+    board.set_ai_time_limit(Duration::from_millis(1));
+    assert_eq!(board.last_search(), None);
+    assert!(board.ai_move_tiger());
+    // The capture is found even at depth 1
+    assert_eq!(board.captured_goats, 1);
+    let search = board.last_search().unwrap();
+    assert!(search.depth >= 1);
+    assert!(search.nodes > 0);
+
+    // Moves from the opening book aren't searched
+    let mut board = Board::new();
+    assert!(board.ai_move_goat());
+    assert_eq!(board.last_search(), None);
+}
+
+#[test]
+fn test_ai_moves_record_search_stats() {
+    let mut board = Board::from_fen("4T/1T3/2G2/3G1/T3T t 18 0 2").unwrap();
+    board.set_use_opening_book(false);
+    board.set_ai_config(AiConfig {
+        time_limit: None,
+        max_depth: Some(4),
+        max_nodes: None,
+        ponder: false,
+        tie_margin: 0,
+        seed: None,
+        blunders: None,
+        contempt: 0,
+        null_move: true,
+        extensions: Extensions::default(),
+        safe_goats: false,
+        threads: 1,
+    });
+    assert_eq!(board.last_search_stats(), None);
+    for _ in 0..2 {
+        let moved = match board.side_to_move() {
+            Side::Tigers => board.ai_move_tiger(),
+            Side::Goats => board.ai_move_goat(),
+        };
+        assert!(moved);
+        let stats = *board.last_search_stats().unwrap();
+        let search = board.last_search().unwrap();
+        assert!(stats.nodes > 0);
+        assert!(stats.depth >= 1);
+        assert_eq!((stats.nodes, stats.depth), (search.nodes, search.depth));
+        // Extensions and quiescence search each take a line at most four
+        // plies further
+        assert!(stats.qnodes > 0);
+        assert!(stats.max_ply <= stats.depth + 8);
+        assert!(stats.cutoffs > 0 && stats.cutoffs < stats.nodes);
+        assert!(stats.to_string().contains("qnodes"));
+    }
+
+    // Moves from the opening book aren't searched
+    let mut board = Board::new();
+    assert!(board.ai_move_goat());
+    assert_eq!(board.last_search_stats(), None);
+}
+
+/// The moves an AI-vs-AI game from the start goes through under `config`.
+fn self_play(config: AiConfig, moves: usize) -> Vec<Move> {
+    let mut board = Board::new();
+    board.set_use_opening_book(false);
+    board.set_ai_config(config);
+    for _ in 0..moves {
+        let moved = match board.side_to_move() {
+            Side::Tigers => board.ai_move_tiger(),
+            Side::Goats => board.ai_move_goat(),
+        };
+        assert!(moved);
+    }
+    board.record().moves.clone()
+}
+
+#[test]
+fn test_zero_tie_margin_ignores_the_seed() {
+    let config = AiConfig {
+        time_limit: None,
+        max_depth: Some(3),
+        max_nodes: None,
+        ponder: false,
+        tie_margin: 0,
+        seed: None,
+        blunders: None,
+        contempt: 0,
+        null_move: true,
+        extensions: Extensions::default(),
+        safe_goats: false,
+        threads: 1,
+    };
+    let game = self_play(config, 12);
+    for seed in [1, 2] {
+        let seeded = AiConfig {
+            seed: Some(seed),
+            ..config
+        };
+        assert_eq!(self_play(seeded, 12), game);
+    }
+}
+
+#[test]
+fn test_seeded_tie_breaking_reproduces_games() {
+    let config = |seed| AiConfig {
+        time_limit: None,
+        max_depth: Some(3),
+        max_nodes: None,
+        ponder: false,
+        tie_margin: 20,
+        seed: Some(seed),
+        blunders: None,
+        contempt: 0,
+        null_move: true,
+        extensions: Extensions::default(),
+        safe_goats: false,
+        threads: 1,
+    };
+    let games: Vec<Vec<Move>> = (0..4).map(|seed| self_play(config(seed), 12)).collect();
+    assert_eq!(self_play(config(2), 12), games[2]);
+    assert!(games.iter().any(|game| *game != games[0]));
+}
+
+/// Whether `mv` hands the game to the other side, at once or with its
+/// reply.
+fn loses_at_once(board: &Board, mv: &Move) -> bool {
+    let loser = board.side_to_move();
+    let lost = |board: &Board| match board.get_winner() {
+        Winner::Tigers => loser == Side::Goats,
+        Winner::Goats => loser == Side::Tigers,
+        _ => false,
+    };
+    let mut after = board.clone();
+    assert!(after.make_move(mv));
+    lost(&after)
+        || after.legal_moves().iter().any(|reply| {
+            let mut next = after.clone();
+            next.make_move(reply) && lost(&next)
+        })
+}
+
+#[test]
+fn test_blunders_never_play_the_top_move() {
+    let best = AiConfig {
+        time_limit: None,
+        max_depth: Some(3),
+        max_nodes: None,
+        ponder: false,
+        tie_margin: 0,
+        seed: None,
+        blunders: None,
+        contempt: 0,
+        null_move: true,
+        extensions: Extensions::default(),
+        safe_goats: false,
+        threads: 1,
+    };
+    let game = self_play(best, 10);
+    let mut board = Board::new();
+    board.set_use_opening_book(false);
+    for mv in &game {
+        let (top, _) = board.suggest_move(&best).unwrap();
+        for seed in 0..4 {
+            let config = AiConfig {
+                seed: Some(seed),
+                blunders: Some(Blunders {
+                    chance: 100,
+                    margin: 10_000,
+                }),
+                ..best
+            };
+            let (blunder, _) = board.suggest_move(&config).unwrap();
+            assert_ne!(blunder, top, "{}", board.to_fen());
+            assert!(board.legal_moves().contains(&blunder));
+            assert!(!loses_at_once(&board, &blunder), "{}", board.to_fen());
+        }
+        assert!(board.make_move(mv));
+    }
+}
+
+#[test]
+fn test_blunders_never_hand_over_the_game() {
+    // One more capture wins for the tigers, and a goat placed next to a
+    // tiger with the point behind it empty is taken at once
+    let board = Board::from_fen("T3T/5/5/5/T3T g 16 4 5").unwrap();
+    let config = AiConfig {
+        blunders: Some(Blunders {
+            chance: 100,
+            margin: i32::MAX,
+        }),
+        ..AiConfig::nodes(2_000)
+    };
+    for seed in 0..8 {
+        let (mv, _) = board
+            .suggest_move(&AiConfig {
+                seed: Some(seed),
+                ..config
+            })
+            .unwrap();
+        assert!(!loses_at_once(&board, &mv), "{mv:?}");
+    }
+}
+
+#[test]
+fn test_zero_blunder_chance_plays_the_top_move() {
+    let best = AiConfig::nodes(3_000);
+    let board = Board::from_fen("T3T/1G3/5/5/T3T g 19 0 2").unwrap();
+    let config = AiConfig {
+        seed: Some(7),
+        blunders: Some(Blunders {
+            chance: 0,
+            margin: 10_000,
+        }),
+        ..best
+    };
+    assert_eq!(board.suggest_move(&config), board.suggest_move(&best));
+}
+
+#[test]
+fn test_mirrored_goat_placements_are_searched_once() {
+    // The start is symmetric every way, so 21 placements come down to 5.
+    // Searching them all took 11,933 nodes.
+    let mut board = Board::from_fen("T3T/5/5/5/T3T g 20 0 1").unwrap();
+    board.set_use_opening_book(false);
+    let config = AiConfig {
+        time_limit: None,
+        max_depth: Some(5),
+        max_nodes: None,
+        ponder: false,
+        tie_margin: 0,
+        seed: None,
+        blunders: None,
+        contempt: 0,
+        null_move: true,
+        extensions: Extensions::default(),
+        safe_goats: false,
+        threads: 1,
+    };
+    board.set_ai_config(config);
+    assert!(board.ai_move_goat());
+    assert_eq!(
+        format_move(board.topology(), &board.record().moves[0]),
+        "C3"
+    );
+    let search = board.last_search().unwrap();
+    assert_eq!(search.score, 28);
+    assert!(search.nodes * 3 < 11_933, "{}", search.nodes);
+
+    // Only the A1-E5 diagonal is a mirror here. Any placement may be
+    // picked when every move counts as a tie, mirrored ones included, and
+    // each is played as it stands on the board.
+    let board = Board::from_fen("T3T/1G3/5/5/T3T g 19 0 2").unwrap();
+    let mut played = Vec::new();
+    for seed in 0..40 {
+        let config = AiConfig {
+            max_depth: Some(2),
+            tie_margin: 10_000,
+            seed: Some(seed),
+            ..config
+        };
+        let (mv, _) = board.suggest_move(&config).unwrap();
+        assert!(board.legal_moves().contains(&mv), "{mv:?}");
+        if !played.contains(&mv) {
+            played.push(mv);
+        }
+    }
+    // 11 placements are left after dropping mirror images
+    assert!(played.len() > 11, "{}", played.len());
+}
+
+#[test]
+fn test_ai_keeps_its_search_tables_between_moves() {
+    let mut board = Board::new();
+    board.set_use_opening_book(false);
+    board.set_ai_config(AiConfig {
+        time_limit: None,
+        max_depth: Some(7),
+        max_nodes: None,
+        ponder: false,
+        tie_margin: 0,
+        seed: None,
+        blunders: None,
+        contempt: 0,
+        null_move: true,
+        extensions: Extensions::default(),
+        safe_goats: false,
+        threads: 1,
+    });
+    // The AI plays both sides for nine moves, keeping its tables
+    while board.ply() < 9 {
+        assert!(match board.side_to_move() {
+            Side::Tigers => board.ai_move_tiger(),
+            Side::Goats => board.ai_move_goat(),
+        });
+    }
+    // Then searches the tenth, a tiger's, to the same depth with them and
+    // from scratch
+    let mut fresh = board.clone();
+    fresh.clear_search_tables();
+    assert!(board.ai_move_tiger() && fresh.ai_move_tiger());
+    let warm = board.last_search().unwrap().nodes;
+    let cold = fresh.last_search().unwrap().nodes;
+    println!("move 10 at depth 7: {warm} nodes with the kept tables, {cold} without");
+    assert!(warm * 3 <= cold * 2, "warm {warm}, cold {cold}");
+}
+
+#[test]
+fn test_ai_ponders_on_the_expected_reply() {
+    let limit = Duration::from_millis(300);
+    let start_pondering = || {
+        let mut board = Board::from_fen("4T/1T3/2G2/3G1/T3T t 18 0 2").unwrap();
+        board.set_use_opening_book(false);
+        board.set_ai_config(AiConfig {
+            time_limit: Some(limit),
+            max_depth: None,
+            max_nodes: None,
+            ponder: true,
+            tie_margin: 0,
+            seed: None,
+            blunders: None,
+            contempt: 0,
+            null_move: true,
+            extensions: Extensions::default(),
+            safe_goats: false,
+            threads: 1,
+        });
+        assert!(board.ai_move_tiger());
+        assert!(board.is_pondering());
+        assert!(!board.clone().is_pondering());
+        let expected = board.last_search().unwrap().pv[1].clone();
+        (board, expected)
+    };
+
+    // The reply it expected: the search has run while the goats thought
+    let (mut board, expected) = start_pondering();
+    std::thread::sleep(limit + Duration::from_millis(100));
+    assert!(board.make_move(&expected));
+    let start = Instant::now();
+    assert!(board.ai_move_tiger());
+    assert!(start.elapsed() < limit / 3, "{:?}", start.elapsed());
+    assert!(board.last_search().is_some());
+
+    // Any other reply: the search starts again on the real position
+    let (mut board, expected) = start_pondering();
+    let other = board
+        .legal_moves()
+        .into_iter()
+        .find(|mv| *mv != expected)
+        .unwrap();
+    assert!(board.make_move(&other));
+    let start = Instant::now();
+    assert!(board.ai_move_tiger());
+    assert!(start.elapsed() >= limit);
+
+    board.stop_pondering();
+    assert!(!board.is_pondering());
+}
+
+#[test]
+fn test_search_reports_progress_with_a_legal_line() {
+    let fen = "4T/1T3/2G2/3G1/T3T t 18 0 2";
+    let mut board = Board::from_fen(fen).unwrap();
+    board.set_use_opening_book(false);
+    let config = AiConfig {
+        time_limit: None,
+        max_depth: Some(4),
+        max_nodes: None,
+        ponder: false,
+        tie_margin: 0,
+        seed: None,
+        blunders: None,
+        contempt: 0,
+        null_move: true,
+        extensions: Extensions::default(),
+        safe_goats: false,
+        threads: 1,
+    };
+    board.set_ai_config(config);
+    let reports = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sink = reports.clone();
+    board.set_search_progress(move |info| sink.lock().unwrap().push(info.clone()));
+    let (suggested, score) = board.suggest_move(&config).unwrap();
+    reports.lock().unwrap().clear();
+
+    assert!(board.ai_move_tiger());
+    let reports = reports.lock().unwrap();
+    let depths: Vec<u32> = reports.iter().map(|info| info.depth).collect();
+    assert_eq!(depths, [1, 2, 3, 4]);
+    for info in reports.iter() {
+        assert!(!info.pv.is_empty() && info.pv.len() <= info.depth as usize);
+        let mut line = Board::from_fen(fen).unwrap();
+        for mv in &info.pv {
+            assert!(line.make_move(mv), "depth {}: {:?}", info.depth, info.pv);
+        }
+    }
+
+    let search = board.last_search().unwrap();
+    let last = reports.last().unwrap();
+    assert_eq!((search.depth, search.score), (last.depth, last.score));
+    assert_eq!(search.pv, last.pv);
+    assert_eq!(search.pv[0], board.record().moves[0]);
+    assert_eq!(search.pv[0], suggested);
+    assert_eq!(search.score, score);
+}
+
+#[test]
+fn test_move_ordering_cuts_the_search() {
+    // Nodes a fixed-depth search visited before moves were ordered, in
+    // the opening and in a middlegame with captures about
+    for (fen, depth, unordered) in [
+        ("T3T/5/5/5/T3T g 20 0 1", 4, 23_731),
+        ("4T/1T3/2G2/3G1/T3T t 18 0 2", 4, 23_451),
+        ("GT2G/GT1GT/GGGGG/GGGGG/TG1GG t 0 3 21", 5, 10_963),
+    ] {
+        let mut board = Board::from_fen(fen).unwrap();
+        board.set_use_opening_book(false);
+        board.set_ai_config(AiConfig {
+            time_limit: None,
+            max_depth: Some(depth),
+            max_nodes: None,
+            ponder: false,
+            tie_margin: 0,
+            seed: None,
+            blunders: None,
+            contempt: 0,
+            null_move: true,
+            extensions: Extensions::default(),
+            safe_goats: false,
+            threads: 1,
+        });
+        let moved = match board.side_to_move() {
+            Side::Tigers => board.ai_move_tiger(),
+            Side::Goats => board.ai_move_goat(),
+        };
+        assert!(moved);
+        let search = board.last_search().unwrap();
+        assert_eq!(search.depth, depth);
+        assert!(search.nodes * 3 / 2 < unordered, "{fen}: {}", search.nodes);
+    }
+}
+
+#[test]
+fn test_killers_and_history_cut_the_search() {
+    // Nodes and moves of a fixed-depth search that only put captures
+    // first. Remembering cutoffs takes fewer nodes to find the same move.
+    for (fen, depth, captures_first, best) in [
+        ("4T/1T3/2G2/3G1/T3T t 18 0 2", 5, 50_390, "E1-D2"),
+        (
+            "GT2G/GT1GT/GGGGG/GGGGG/TG1GG t 0 3 21",
+            6,
+            10_574,
+            "A5xB5-C5",
+        ),
+        ("1GG1T/TGGT1/GGG2/GG3/1TGG1 t 8 1 12", 5, 7_632, "E1-E2"),
+    ] {
+        let mut board = Board::from_fen(fen).unwrap();
+        board.set_use_opening_book(false);
+        board.set_ai_config(AiConfig {
+            time_limit: None,
+            max_depth: Some(depth),
+            max_nodes: None,
+            ponder: false,
+            tie_margin: 0,
+            seed: None,
+            blunders: None,
+            contempt: 0,
+            null_move: true,
+            extensions: Extensions::default(),
+            safe_goats: false,
+            threads: 1,
+        });
+        let moved = match board.side_to_move() {
+            Side::Tigers => board.ai_move_tiger(),
+            Side::Goats => board.ai_move_goat(),
+        };
+        assert!(moved);
+        let played = board.record().moves[0].clone();
+        assert_eq!(format_move(board.topology(), &played), best, "{fen}");
+        let nodes = board.last_search().unwrap().nodes;
+        assert!(nodes * 5 / 4 < captures_first, "{fen}: {nodes}");
+    }
+}
+
+#[test]
+fn test_pvs_matches_full_window_search() {
+    // Move, score and nodes from searching every move with the full
+    // alpha-beta window, no null moves and no extensions, at a fixed depth
+    let corpus = [
+        ("T3T/5/5/5/T3T g 20 0 1", 5, "C3", 28, 30607),
+        ("T3T/1G3/5/5/T3T t 19 0 1", 5, "A1xB2-C3", 209, 23382),
+        ("4T/1T3/2G2/3G1/T3T t 18 0 2", 5, "E1-D2", 93, 25917),
+        ("T2GT/1G3/2G2/1T3/4T g 16 0 3", 5, "C1", 143, 24184),
+        (
+            "TGGGT/G3G/G1T1G/G3G/TGGG1 g 0 0 30",
+            5,
+            "E2-D2",
+            -142,
+            28717,
+        ),
+        (
+            "GT2G/GT1GT/GGGGG/GGGGG/TG1GG t 0 3 21",
+            6,
+            "A5xB5-C5",
+            107,
+            7400,
+        ),
+        ("G1T2/2G1T/GGT2/GGGG1/T1GGG t 8 1 12", 5, "C1-B2", -5, 7623),
+        (
+            "1GG1T/TGGT1/GGG2/GG3/1TGG1 t 8 1 12",
+            5,
+            "E1-E2",
+            -235,
+            4596,
+        ),
+        (
+            "2T1T/2GGG/GGGGG/GGGGT/TGG2 t 5 1 15",
+            6,
+            "C1-B2",
+            -441,
+            3451,
+        ),
+        (
+            "G2GG/TGTGG/GGGGG/G1GG1/GT1T1 t 5 0 15",
+            6,
+            "D5-E5",
+            -565,
+            2564,
+        ),
+    ];
+    for (fen, depth, best, score, full_window_nodes) in corpus {
+        let mut board = Board::from_fen(fen).unwrap();
+        board.set_use_opening_book(false);
+        let config = AiConfig {
+            time_limit: None,
+            max_depth: Some(depth),
+            max_nodes: None,
+            ponder: false,
+            tie_margin: 0,
+            seed: None,
+            blunders: None,
+            contempt: 0,
+            null_move: false,
+            extensions: Extensions::NONE,
+            safe_goats: false,
+            threads: 1,
+        };
+        let (mv, found) = board.suggest_move(&config).unwrap();
+        assert_eq!(format_move(board.topology(), &mv), best, "{fen}");
+        assert_eq!(found, score, "{fen}");
+
+        board.set_ai_config(config);
+        let moved = match board.side_to_move() {
+            Side::Tigers => board.ai_move_tiger(),
+            Side::Goats => board.ai_move_goat(),
+        };
+        assert!(moved);
+        let nodes = board.last_search().unwrap().nodes;
+        assert!(nodes < full_window_nodes, "{fen}: {nodes}");
+    }
+}
+
+fn depth_with_null_move(depth: u32, null_move: bool) -> AiConfig {
+    AiConfig {
+        max_depth: Some(depth),
+        max_nodes: None,
+        null_move,
+        ..AiConfig::nodes(0)
+    }
+}
+
+#[test]
+fn test_null_move_still_solves_the_puzzles() {
+    for puzzle in builtin() {
+        let mut board = puzzle.board().clone();
+        board.set_use_opening_book(false);
+        for null_move in [false, true] {
+            let (mv, _) = board
+                .suggest_move(&depth_with_null_move(4, null_move))
+                .unwrap();
+            assert!(
+                puzzle.solutions().iter().any(|line| line[0] == mv),
+                "{}: {}",
+                puzzle.title(),
+                format_move(board.topology(), &mv)
+            );
+        }
+    }
+}
+
+#[test]
+fn test_null_move_prunes_without_changing_the_move() {
+    for fen in [
+        "T3T/5/5/5/T3T g 20 0 1",
+        "GGGGG/GGGTG/T1T1G/1TGG1/GGGGG g 0 3 25",
+        "GT2G/GT1GT/GGGGG/GGGGG/TG1GG t 0 3 21",
+    ] {
+        let mut searched = Vec::new();
+        for null_move in [false, true] {
+            let mut board = Board::from_fen(fen).unwrap();
+            board.set_use_opening_book(false);
+            board.set_ai_config(depth_with_null_move(6, null_move));
+            let moved = match board.side_to_move() {
+                Side::Tigers => board.ai_move_tiger(),
+                Side::Goats => board.ai_move_goat(),
+            };
+            assert!(moved);
+            let nodes = board.last_search().unwrap().nodes;
+            searched.push((board.record().moves[0].clone(), nodes));
+        }
+        assert_eq!(searched[0].0, searched[1].0, "{fen}");
+        assert!(searched[1].1 < searched[0].1, "{fen}: {searched:?}");
+    }
+}
+
+#[test]
+fn test_quiescence_sees_a_block_that_loses_a_goat() {
+    // A1-B2 would attack C3, and the goat that blocks on D4 is open to
+    // E4xD4-C4. Two-ply goats used to stop at the block and place on D2.
+    let mut board = Board::from_fen("T3T/5/2G2/4T/T4 g 19 0 2").unwrap();
+    board.set_use_opening_book(false);
+    let config = AiConfig {
+        time_limit: None,
+        max_depth: Some(2),
+        max_nodes: None,
+        ..AiConfig::default()
+    };
+    let (mv, _) = board.suggest_move(&config).unwrap();
+    assert_ne!(format_move(board.topology(), &mv), "D2");
+    // Whatever the tigers do next, the goats can leave them no capture
+    assert!(board.make_move(&mv));
+    for tiger in board.legal_moves() {
+        let mut after = board.clone();
+        assert!(after.make_move(&tiger));
+        let safe = after.legal_moves().iter().any(|goat| {
+            let mut after = after.clone();
+            after.make_move(goat) && !after.legal_moves().iter().any(Move::is_capture)
+        });
+        assert!(safe, "{}", after.to_fen());
+    }
+}
+
+#[test]
+fn test_quiescence_still_takes_a_free_goat() {
+    // The goat on B2 is lost whatever the tigers do first, but playing on
+    // past the depth mustn't make a later capture look better than this one
+    let board = Board::from_fen("T3T/1G3/5/5/T3T t 18 0 2").unwrap();
+    for depth in 1..=4 {
+        let config = AiConfig {
+            time_limit: None,
+            max_depth: Some(depth),
+            max_nodes: None,
+            ..AiConfig::default()
+        };
+        let (mv, _) = board.suggest_move(&config).unwrap();
+        assert_eq!(format_move(board.topology(), &mv), "A1xB2-C3", "{depth}");
+    }
+}
+
+#[test]
+fn test_extensions_see_traps_sooner() {
+    // Goats to trap the tigers in three moves. Within the node budget
+    // only the extended search gets deep enough to see it
+    for (fen, nodes, trap) in [
+        ("TGGGG/GGTGG/GGGGG/G1GGT/GTGG1 g 0 1 22", 700, "D4-E5"),
+        ("GGTGG/GTGTG/GGGGG/1GGGG/GGT1G g 0 1 21", 200, "E5-D5"),
+    ] {
+        let mut board = Board::from_fen(fen).unwrap();
+        board.set_use_opening_book(false);
+        let (mv, score) = board.suggest_move(&AiConfig::nodes(nodes)).unwrap();
+        assert_eq!(format_move(board.topology(), &mv), trap, "{fen}");
+        assert_eq!(score, 5 - 10000, "{fen}");
+
+        let flat = AiConfig {
+            extensions: Extensions::NONE,
+            safe_goats: false,
+            ..AiConfig::nodes(nodes)
+        };
+        let (_, score) = board.suggest_move(&flat).unwrap();
+        assert!(score > -9000, "{fen}: {score}");
+    }
+}
+
+#[test]
+fn test_ai_takes_the_quickest_win() {
+    // D2xC3-B4 wins on the tigers' next move, three plies from here;
+    // B5xC5-D5 and the quiet moves that also win take five
+    let mut board = Board::from_fen("GG1GG/G2TG/1GG1T/G1G1G/1TG1T t 5 3 15").unwrap();
+    board.set_use_opening_book(false);
+    let config = AiConfig {
+        time_limit: None,
+        max_depth: Some(6),
+        max_nodes: None,
+        ponder: false,
+        tie_margin: 0,
+        seed: None,
+        blunders: None,
+        contempt: 0,
+        null_move: true,
+        extensions: Extensions::default(),
+        safe_goats: false,
+        threads: 1,
+    };
+    let (mv, score) = board.suggest_move(&config).unwrap();
+    assert_eq!(format_move(board.topology(), &mv), "D2xC3-B4");
+    assert_eq!(score, 10000 - 3);
+
+    board.set_ai_config(config);
+    while board.result().is_none() {
+        let moved = match board.side_to_move() {
+            Side::Tigers => board.ai_move_tiger(),
+            Side::Goats => board.ai_move_goat(),
+        };
+        assert!(moved);
+    }
+    assert_eq!(board.get_winner(), Winner::Tigers);
+    assert_eq!(board.record().moves.len(), 3);
+}
+
+#[test]
+fn test_cut_off_depth_never_does_worse_than_depth_one() {
+    let depth = |plies| AiConfig {
+        time_limit: None,
+        max_depth: Some(plies),
+        max_nodes: None,
+        ponder: false,
+        tie_margin: 0,
+        seed: None,
+        blunders: None,
+        contempt: 0,
+        null_move: true,
+        extensions: Extensions::default(),
+        safe_goats: false,
+        threads: 1,
+    };
+    // The score of the position after `mv` with one reply searched, as
+    // the second ply of the search from `board` sees it
+    let two_ply_score = |board: &Board, mv: &Move| {
+        let mut after = board.clone();
+        assert!(after.make_move(mv));
+        after.suggest_move(&depth(1)).unwrap().1
+    };
+
+    for fen in [
+        "4T/1T3/2G2/3G1/T3T t 18 0 2",
+        "T2GT/1G3/2G2/1T3/4T g 16 0 3",
+        "TGGGT/G3G/G1T1G/G3G/TGGG1 g 0 0 30",
+    ] {
+        let mut board = Board::from_fen(fen).unwrap();
+        board.set_use_opening_book(false);
+        let (first_choice, _) = board.suggest_move(&depth(1)).unwrap();
+        let baseline = two_ply_score(&board, &first_choice);
+        let tigers = board.side_to_move() == Side::Tigers;
+
+        let mut cut_off = 0;
+        for nodes in (1..400).step_by(7) {
+            let mut searched = board.clone();
+            searched.set_ai_config(AiConfig::nodes(nodes));
+            assert!(match searched.side_to_move() {
+                Side::Tigers => searched.ai_move_tiger(),
+                Side::Goats => searched.ai_move_goat(),
+            });
+            // Only a search stopped partway through the second ply
+            if searched.last_search().unwrap().depth != 1 {
+                continue;
+            }
+            cut_off += 1;
+            let played = searched.record().moves.last().unwrap().clone();
+            let score = two_ply_score(&board, &played);
+            if tigers {
+                assert!(score >= baseline, "{fen} with {nodes} nodes");
+            } else {
+                assert!(score <= baseline, "{fen} with {nodes} nodes");
+            }
+        }
+        assert!(cut_off > 0, "{fen}");
+    }
+}
+
+#[test]
+fn test_suggest_move_leaves_the_board_alone() {
+    let mut board = Board::from_fen("T3T/1G3/5/5/T3T t 18 0 2").unwrap();
+    let config = AiConfig::nodes(5_000);
+    let fen = board.to_fen();
+    let (mv, score) = board.suggest_move(&config).unwrap();
+    assert_eq!(
+        mv,
+        Move::MoveTiger {
+            from: 0,
+            to: 12,
+            captured_position: Some(6),
+        }
+    );
+    assert!(score > 0);
+    assert_eq!(board.to_fen(), fen);
+    assert!(board.record().moves.is_empty());
+    assert!(board.drain_events().is_empty());
+
+    // The AI plays the move it suggests
+    board.set_ai_config(config);
+    assert!(board.ai_move_tiger());
+    assert_eq!(board.record().moves, vec![mv]);
+}
+
+#[test]
+fn test_top_moves_rank_the_only_capture_first() {
+    let board = Board::from_fen("T3T/1G3/5/5/T3T t 18 0 2").unwrap();
+    let fen = board.to_fen();
+    let config = AiConfig {
+        max_depth: Some(2),
+        max_nodes: None,
+        ..AiConfig::nodes(0)
+    };
+    let lines = board.top_moves(3, &config);
+    assert_eq!(lines.len(), 3);
+    let capture = Move::MoveTiger {
+        from: 0,
+        to: 12,
+        captured_position: Some(6),
+    };
+    assert_eq!(lines[0].0, capture);
+    // Quiescence sees the goat on B2 can't be saved whatever the tigers
+    // play, so the capture leads by less than a goat
+    assert!(lines[0].1 > lines[1].1, "{lines:?}");
+    assert!(lines.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+    assert_eq!(board.to_fen(), fen);
+
+    // Every line starts with its move and can be played out
+    for (mv, _, line) in &lines {
+        assert_eq!(line.first(), Some(mv));
+        let mut replay = board.clone();
+        for mv in line {
+            assert!(replay.make_move(mv), "{line:?}");
+        }
+    }
+}
+
+#[test]
+fn test_top_moves_agree_with_the_best_move() {
+    let board = Board::from_fen("T3T/1G3/1G3/5/T3T g 17 0 2").unwrap();
+    let config = AiConfig {
+        max_depth: Some(3),
+        max_nodes: None,
+        ..AiConfig::nodes(0)
+    };
+    let lines = board.top_moves(4, &config);
+    assert_eq!(lines.len(), 4);
+    assert!(lines.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+    let mut single = board.clone();
+    single.set_use_opening_book(false);
+    let (_, score) = single.suggest_move(&config).unwrap();
+    assert_eq!(lines[0].1, score);
+    assert!(board.top_moves(0, &config).is_empty());
+}
+
+#[test]
+fn test_stopped_search_keeps_what_it_found() {
+    let mut board = Board::from_fen("T3T/1G3/5/5/T3T t 18 0 2").unwrap();
+    let stop = Arc::new(AtomicBool::new(true));
+    board.set_search_stop(stop);
+    let config = AiConfig {
+        time_limit: Some(Duration::from_secs(60)),
+        max_depth: None,
+        max_nodes: None,
+        ..AiConfig::default()
+    };
+    let started = Instant::now();
+    let lines = board.top_moves(3, &config);
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert!(!lines.is_empty());
+    assert!(board.legal_moves().contains(&lines[0].0));
+}
+
+/// Three captures down, with the goats able to go back to the position
+/// from three plies ago: A4-A3 and back, while a tiger steps out and back.
+fn goats_can_repeat() -> Board {
+    let mut board = Board::from_fen("TT1TT/5/5/GGGGG/GGGGG t 0 3 30").unwrap();
+    for mv in [
+        Move::MoveTiger {
+            from: 1,
+            to: 2,
+            captured_position: None,
+        },
+        Move::MoveGoat { from: 15, to: 10 },
+        Move::MoveTiger {
+            from: 2,
+            to: 1,
+            captured_position: None,
+        },
+    ] {
+        assert!(board.make_move(&mv));
+    }
+    board.set_use_opening_book(false);
+    board
+}
+
+#[test]
+fn test_losing_goats_repeat_the_position() {
+    let board = goats_can_repeat();
+    let config = AiConfig {
+        max_depth: Some(4),
+        max_nodes: None,
+        ..AiConfig::nodes(0)
+    };
+    let (mv, score) = board.suggest_move(&config).unwrap();
+    assert_eq!(mv, Move::MoveGoat { from: 10, to: 15 });
+    assert_eq!(score, 0);
+
+    // Any other move leaves the tigers ahead
+    let lines = board.top_moves(2, &config);
+    assert_eq!(lines[0].0, mv);
+    assert!(lines[1].1 > 0);
+}
+
+#[test]
+fn test_contempt_steers_away_from_repetition() {
+    let board = goats_can_repeat();
+    let config = AiConfig {
+        max_depth: Some(4),
+        max_nodes: None,
+        contempt: 500,
+        ..AiConfig::nodes(0)
+    };
+    let (mv, score) = board.suggest_move(&config).unwrap();
+    assert_ne!(mv, Move::MoveGoat { from: 10, to: 15 });
+    assert!(score > 0 && score < 500);
+}
+
+#[test]
+fn test_ai_goats_take_away_tiger_moves() {
+    // Every tiger has three moves. Without mobility terms the goats placed
+    // on B2 for the inner point; now they seal a corner, leaving a tiger
+    // two moves and close to trapped.
+    let mut board = Board::from_fen("4T/T4/4G/5/T3T g 19 0 2").unwrap();
+    let nearly_trapped = |board: &Board| {
+        board
+            .tigers()
+            .filter(|tiger| board.get_valid_tiger_moves(tiger.0).len() == 2)
+            .count()
+    };
+    assert_eq!(nearly_trapped(&board), 0);
+    board.set_ai_config(AiConfig {
+        time_limit: None,
+        max_depth: Some(1),
+        max_nodes: None,
+        ponder: false,
+        tie_margin: 0,
+        seed: None,
+        blunders: None,
+        contempt: 0,
+        null_move: true,
+        extensions: Extensions::default(),
+        safe_goats: false,
+        threads: 1,
+    });
+    assert!(board.ai_move_goat());
+    assert_eq!(nearly_trapped(&board), 1);
+}
+
+#[test]
+fn test_zeroed_eval_weight_drops_its_term() {
+    // Every placement keeps the one capture, so the captures term adds the
+    // same to each and can't change the goats' choice
+    let mut board = Board::from_fen("T3T/5/5/5/T3T g 19 1 2").unwrap();
+    let config = AiConfig {
+        time_limit: None,
+        max_depth: Some(1),
+        max_nodes: None,
+        ponder: false,
+        tie_margin: 0,
+        seed: None,
+        blunders: None,
+        contempt: 0,
+        null_move: true,
+        extensions: Extensions::default(),
+        safe_goats: false,
+        threads: 1,
+    };
+    let (_, with_captures) = board.suggest_move(&config).unwrap();
+    board.set_eval_weights(EvalWeights {
+        captured_goat: 0,
+        ..EvalWeights::default()
+    });
+    let (_, without_captures) = board.suggest_move(&config).unwrap();
+    assert_eq!(with_captures - without_captures, 100);
+
+    let zero = EvalWeights {
+        captured_goat: 0,
+        tigers_cannot_win: 0,
+        tiger_trapped: 0,
+        tiger_mobility: 0,
+        tiger_nearly_trapped: 0,
+        tiger_pocketed: 0,
+        tiger_sealable: 0,
+        goat_mobility: 0,
+        tiger_squares: PieceSquareTable::default(),
+        goat_squares: PieceSquareTable::default(),
+        goat_capturable: 0,
+        goat_lost: 0,
+        goat_adjacency: 0,
+        goat_on_edge: 0,
+        goat_backed: 0,
+    };
+    board.set_eval_weights(zero);
+    assert_eq!(board.suggest_move(&config).unwrap().1, 0);
+    board.set_eval_weights(EvalWeights {
+        captured_goat: 7,
+        ..zero
+    });
+    assert_eq!(board.eval_weights().captured_goat, 7);
+    assert_eq!(board.suggest_move(&config).unwrap().1, 7);
+}
+
+#[test]
+fn test_eval_breakdown_adds_up() {
+    for fen in [
+        "T3T/5/5/5/T3T g 20 0 1",
+        "T3T/5/5/5/TG2T g 19 0 2",
+        "2T1T/2GGG/GGGGG/GGGGT/TGG2 t 5 1 15",
+        "TGGGT/G3G/G1T1G/G3G/TGGG1 g 0 0 30",
+    ] {
+        let board = Board::from_fen(fen).unwrap();
+        let breakdown = board.evaluate_detailed();
+        let sum: i32 = breakdown.terms.iter().map(|term| term.contribution).sum();
+        assert_eq!(sum, breakdown.total, "{fen}");
+        for term in &breakdown.terms {
+            assert_eq!(term.contribution, term.count * term.weight, "{fen}");
+        }
+    }
+
+    let board = Board::from_fen("T3T/5/5/5/TG2T g 19 0 2").unwrap();
+    let breakdown = board.evaluate_detailed();
+    let mobility = breakdown
+        .terms
+        .iter()
+        .find(|term| term.name == "tiger mobility")
+        .unwrap();
+    assert_eq!(mobility.weight, EvalWeights::default().tiger_mobility);
+    // Three moves for each corner tiger, the jump over B5 included
+    assert_eq!(mobility.count, 12);
+}
+
+#[test]
+fn test_eval_report_lists_every_term_for_the_side_to_move() {
+    let term = |name, count, weight| EvalTerm {
+        name,
+        count,
+        weight,
+        contribution: count * weight,
+    };
+    let breakdown = EvalBreakdown {
+        total: -160,
+        terms: vec![
+            term("captured goats", 0, 100),
+            term("trapped tigers", 2, -50),
+            term("goat mobility", 30, -2),
+        ],
+    };
+    assert_eq!(
+        breakdown.report(Side::Goats),
+        [
+            "term                   count weight  points",
+            "captured goats             0    100      +0",
+            "trapped tigers             2    -50    -100",
+            "goat mobility             30     -2     -60",
+            "total for the goats                    +160",
+            "Goats are clearly better",
+        ]
+        .join("\n")
+    );
+    let report = breakdown.report(Side::Tigers);
+    assert!(report.contains("total for the tigers                   -160"));
+
+    for (total, verdict) in [
+        (0, "The position is about even"),
+        (-29, "The position is about even"),
+        (30, "Tigers are slightly better"),
+        (400, "Tigers are winning"),
+    ] {
+        let breakdown = EvalBreakdown {
+            total,
+            terms: Vec::new(),
+        };
+        assert!(breakdown.report(Side::Tigers).ends_with(verdict), "{total}");
+    }
+}
+
+/// The contribution of the term called `name` to the evaluation of `fen`.
+fn eval_term(fen: &str, name: &str) -> i32 {
+    let board = Board::from_fen(fen).unwrap();
+    let breakdown = board.evaluate_detailed();
+    let term = breakdown.terms.iter().find(|term| term.name == name);
+    term.unwrap().contribution
+}
+
+#[test]
+fn test_piece_square_tables_favour_strong_points() {
+    // A tiger leaving the centre for the edge helps the goats, in either
+    // phase
+    for (centre, edge) in [
+        ("T3T/5/2T2/5/T3G g 19 0 2", "T3T/5/4T/5/T3G g 19 0 2"),
+        (
+            "TGGGT/GGGGG/GGTGG/GGGG1/GGGG1 g 0 0 40",
+            "TGGGT/GGGGG/GGGGG/GGGGT/GGGG1 g 0 0 40",
+        ),
+    ] {
+        let (centre, edge) = (
+            eval_term(centre, "tiger squares"),
+            eval_term(edge, "tiger squares"),
+        );
+        assert!(centre > edge, "{centre} {edge}");
+    }
+
+    // A goat leaving the centre for a corner helps the tigers
+    let centre = eval_term("T3T/5/2G2/5/T2T1 t 19 0 1", "goat squares");
+    let corner = eval_term("T3T/5/5/5/T2TG t 19 0 1", "goat squares");
+    assert!(centre < 0 && corner < 0 && centre < corner);
+
+    // Only the standard board has tables
+    let board = Board::with_rules(RuleSet::alquerque(7, 30)).unwrap();
+    let breakdown = board.evaluate_detailed();
+    assert!(!breakdown
+        .terms
+        .iter()
+        .any(|term| term.name.ends_with("squares")));
+}
+
+#[test]
+fn test_tigers_decline_a_capture_that_walks_into_a_pocket() {
+    // C1xB1-A1 wins a goat, but leaves the tiger in the corner with B1
+    // its only way out
+    let fen = "1GT1G/1G1GG/GGGGT/GT1G1/2T1G t 6 2 14";
+    let mut board = Board::from_fen(fen).unwrap();
+    board.set_use_opening_book(false);
+    let bait = parse_move(&board, "C1xB1-A1").unwrap();
+    assert!(board.is_self_trapping(&bait));
+    assert!(!board.is_self_trapping(&parse_move(&board, "C1-C2").unwrap()));
+
+    let config = AiConfig {
+        max_depth: Some(2),
+        max_nodes: None,
+        ..AiConfig::nodes(0)
+    };
+    board.set_eval_weights(EvalWeights {
+        tiger_sealable: 0,
+        ..EvalWeights::default()
+    });
+    assert_eq!(board.suggest_move(&config).unwrap().0, bait);
+
+    board.set_eval_weights(EvalWeights::default());
+    assert_ne!(board.suggest_move(&config).unwrap().0, bait);
+}
+
+#[test]
+fn test_eval_breakdown_of_a_finished_game() {
+    let board = Board::from_fen("T3T/5/5/5/T3T g 15 5 11").unwrap();
+    assert_eq!(board.get_winner(), Winner::Tigers);
+    let breakdown = board.evaluate_detailed();
+    assert_eq!(breakdown.total, 10000);
+    assert_eq!(breakdown.terms.len(), 1);
+    assert_eq!(breakdown.terms[0].name, "result");
+}
+
+/// Scores every position the same, however it stands.
+#[derive(Debug)]
+struct Constant(i32);
+
+impl Evaluator for Constant {
+    fn evaluate(&self, _board: &Board) -> i32 {
+        self.0
+    }
+}
+
+#[test]
+fn test_search_uses_custom_evaluator() {
+    let config = AiConfig {
+        time_limit: None,
+        max_depth: Some(2),
+        max_nodes: None,
+        ponder: false,
+        tie_margin: 0,
+        seed: None,
+        blunders: None,
+        contempt: 0,
+        null_move: true,
+        extensions: Extensions::default(),
+        safe_goats: false,
+        threads: 1,
+    };
+    let mut board = Board::from_fen("T3T/5/5/5/T3T g 20 0 1").unwrap();
+    board.set_use_opening_book(false);
+    let built_in = board.suggest_move(&config);
+
+    board.set_evaluator(Arc::new(Constant(42)));
+    assert_eq!(board.suggest_move(&config).unwrap().1, 42);
+    board.set_evaluator(Arc::new(DefaultEvaluator));
+    assert_eq!(board.suggest_move(&config), built_in);
+}
+
+#[test]
+fn test_custom_evaluator_cannot_override_a_win() {
+    // The jump over B5 is the fifth capture, whatever the evaluator says
+    let mut board = Board::from_fen("T3T/5/5/5/TG2T t 15 4 6").unwrap();
+    board.set_evaluator(Arc::new(Constant(-9000)));
+    board.set_ai_config(AiConfig {
+        time_limit: None,
+        max_depth: Some(2),
+        max_nodes: None,
+        ponder: false,
+        tie_margin: 0,
+        seed: None,
+        blunders: None,
+        contempt: 0,
+        null_move: true,
+        extensions: Extensions::default(),
+        safe_goats: false,
+        threads: 1,
+    });
+    let (_, score) = board.suggest_move(board.ai_config()).unwrap();
+    assert_eq!(score, 10000 - 1);
+    assert!(board.ai_move_tiger());
+    assert_eq!(board.get_winner(), Winner::Tigers);
+}
+
+#[test]
+fn test_ai_tiger_stays_out_of_pockets() {
+    // E4-E5 leaves the tiger D5 and the way back, and goats on D5 and
+    // then E4 shut it in. A one-ply search that only counts moves steps
+    // into the corner anyway.
+    let mut board = Board::from_fen("2T1T/2GGG/GGGGG/GGGGT/TGG2 t 5 1 15").unwrap();
+    board.set_ai_config(AiConfig {
+        time_limit: None,
+        max_depth: Some(1),
+        max_nodes: None,
+        ponder: false,
+        tie_margin: 0,
+        seed: None,
+        blunders: None,
+        contempt: 0,
+        null_move: true,
+        extensions: Extensions::default(),
+        safe_goats: false,
+        threads: 1,
+    });
+    assert!(board.ai_move_tiger());
+    assert_eq!(board.cells[19], Piece::Tiger);
+    assert_eq!(board.cells[24], Piece::Empty);
+}
+
+#[test]
+fn test_ai_goats_back_up_a_hanging_goat() {
+    // The tiger on A5 can jump the goat on B5 until C5 is covered
+    let mut board = Board::from_fen("T3T/5/5/5/TG2T g 19 0 2").unwrap();
+    board.set_ai_config(AiConfig {
+        time_limit: None,
+        max_depth: Some(1),
+        max_nodes: None,
+        ponder: false,
+        tie_margin: 0,
+        seed: None,
+        blunders: None,
+        contempt: 0,
+        null_move: true,
+        extensions: Extensions::default(),
+        safe_goats: false,
+        threads: 1,
+    });
+    assert!(board.ai_move_goat());
+    assert_eq!(board.cells[22], Piece::Goat);
+    assert!(board
+        .get_valid_tiger_moves(20)
+        .iter()
+        .all(|landing| landing.0 != 22));
+}
+
+#[test]
+fn test_ai_goats_build_walls_on_the_edge() {
+    // Without structure terms the goats left C5 on its own and placed on
+    // the inner point C2; now they extend the wall along the edge
+    let mut board = Board::from_fen("T3T/5/5/5/T1G1T g 19 0 2").unwrap();
+    board.set_ai_config(AiConfig {
+        time_limit: None,
+        max_depth: Some(3),
+        max_nodes: None,
+        ponder: false,
+        tie_margin: 0,
+        seed: None,
+        blunders: None,
+        contempt: 0,
+        null_move: true,
+        extensions: Extensions::default(),
+        safe_goats: false,
+        threads: 1,
+    });
+    assert!(board.ai_move_goat());
+    assert!(board.cells[21] == Piece::Goat || board.cells[23] == Piece::Goat);
+    assert_eq!(board.goats().count(), 2);
+}
+
+#[test]
+fn test_ai_goat_respects_placement_rule() {
+    let mut board = Board::new();
+    board.set_ai_time_limit(Duration::from_secs(1));
+    assert!(board.place_goat(12));
+    assert!(board.move_tiger(0, 1));
+    assert!(board.ai_move_goat());
+
+    // The AI placed a new goat rather than moving the existing one
+    assert_eq!(board.cells[12], Piece::Goat);
+    assert_eq!(board.goats_in_hand, 18);
+}
+
+#[test]
+fn test_capture_threshold_is_validated() {
+    for threshold in [0, 21] {
+        let rules = RuleSet {
+            capture_threshold: threshold,
+            ..RuleSet::default()
+        };
+        assert_eq!(
+            Board::with_rules(rules).unwrap_err(),
+            RuleError::CaptureThresholdOutOfRange(threshold)
+        );
+    }
+    for threshold in [1, 5, 20] {
+        let rules = RuleSet {
+            capture_threshold: threshold,
+            ..RuleSet::default()
+        };
+        assert!(Board::with_rules(rules).is_ok());
+    }
+}
+
+#[test]
+fn test_custom_capture_threshold_ends_game() {
+    let rules = RuleSet {
+        capture_threshold: 3,
+        ..RuleSet::default()
+    };
+    let mut board = Board::with_rules(rules).unwrap();
+    for capture in 1..=3 {
+        assert!(!board.is_game_over());
+        board.place_goat(1);
+        assert!(board.move_tiger(0, 2));
+        assert_eq!(board.captured_goats, capture);
+        board.cells[2] = Piece::Empty;
+        board.cells[0] = Piece::Tiger;
+    }
+    assert_eq!(board.get_winner(), Winner::Tigers);
+    assert_eq!(
+        board.result().map(|r| r.reason),
+        Some(TerminationReason::GoatsCaptured)
+    );
+}
+
+#[test]
+fn test_ai_respects_capture_threshold() {
+    let rules = RuleSet {
+        capture_threshold: 3,
+        ..RuleSet::default()
+    };
+    let mut board = Board::with_rules(rules).unwrap();
+    board.captured_goats = 2;
+    board.set_ai_time_limit(Duration::from_secs(1));
+
+    // Two goats are hanging; either capture ends the game at threshold 3
+    board.place_goat(1);
+    board.place_goat(9);
+    assert!(board.ai_move_tiger());
+    assert_eq!(board.captured_goats, 3);
+    assert!(board.is_game_over());
+}
+
+#[test]
+fn test_handicap_starting_goats() {
+    let rules = RuleSet {
+        starting_goats: 15,
+        ..RuleSet::default()
+    };
+    let mut board = Board::with_rules(rules).unwrap();
+    assert_eq!(board.goats_in_hand, 15);
+    assert!(board.to_fen().contains(" 15 0 1"));
+    assert_eq!(board.record().rules.starting_goats, 15);
+
+    // The AI places from the smaller pool without assuming twenty goats
+    board.set_ai_time_limit(Duration::from_secs(1));
+    assert!(board.ai_move_goat());
+    assert_eq!(board.goats_in_hand, 14);
+    assert_eq!(board.goats().count(), 1);
+}
+
+#[test]
+fn test_starting_goats_are_validated() {
+    let invalid = [
+        (0, RuleError::StartingGoatsOutOfRange(0)),
+        (21, RuleError::StartingGoatsOutOfRange(21)),
+        (
+            4,
+            RuleError::ThresholdExceedsGoats {
+                threshold: 5,
+                goats: 4,
+            },
+        ),
+    ];
+    for (goats, err) in invalid {
+        let rules = RuleSet {
+            starting_goats: goats,
+            ..RuleSet::default()
+        };
+        assert_eq!(Board::with_rules(rules).unwrap_err(), err);
+    }
+
+    let rules = RuleSet {
+        starting_goats: 4,
+        capture_threshold: 2,
+        ..RuleSet::default()
+    };
+    assert_eq!(Board::with_rules(rules).unwrap().goats_in_hand, 4);
+}
+
+fn three_tigers() -> RuleSet {
+    RuleSet {
+        tiger_squares: vec![0, 2, 4],
+        ..RuleSet::default()
+    }
+}
+
+#[test]
+fn test_custom_tiger_squares() {
+    let mut board = Board::with_rules(three_tigers()).unwrap();
+    let tigers: Vec<Position> = board.tigers().collect();
+    assert_eq!(tigers, vec![Position(0), Position(2), Position(4)]);
+
+    // reset() returns the tigers to their configured squares
+    board.place_goat(12);
+    assert!(board.move_tiger(2, 7));
+    board.reset();
+    assert_eq!(board.tigers().count(), 3);
+    assert_eq!(board.cells[2], Piece::Tiger);
+    assert_eq!(board.cells[7], Piece::Empty);
+    assert_eq!(board.goats_in_hand, 20);
+    assert!(!board.can_undo());
+}
+
+#[test]
+fn test_three_tigers_can_win_by_captures() {
+    let mut board = Board::with_rules(three_tigers()).unwrap();
+    for _ in 0..5 {
+        assert!(!board.is_game_over());
+        board.place_goat(5);
+        assert!(board.move_tiger(0, 10));
+        board.cells[10] = Piece::Empty;
+        board.cells[0] = Piece::Tiger;
+    }
+    assert_eq!(board.get_winner(), Winner::Tigers);
+}
+
+#[test]
+fn test_three_tigers_can_be_trapped() {
+    // Two rows of goats wall the top row off but for E3, and the tiger
+    // on E1 can still jump E2
+    let mut board =
+        Board::from_fen_with_rules("TGTGT/GGGGG/GGGG1/5/5 g 9 0 12", three_tigers()).unwrap();
+    assert!(!board.is_game_over());
+    assert!(board.place_goat(14));
+    assert_eq!(
+        board.result().map(|r| r.reason),
+        Some(TerminationReason::TigersTrapped)
+    );
+}
+
+#[test]
+fn test_tiger_squares_are_validated() {
+    let cases = [
+        (vec![], RuleError::NoTigers),
+        (vec![0, 25], RuleError::TigerSquareOutOfBounds(25)),
+        (vec![0, 4, 0], RuleError::DuplicateTigerSquare(0)),
+    ];
+    for (squares, err) in cases {
+        let rules = RuleSet {
+            tiger_squares: squares,
+            ..RuleSet::default()
+        };
+        assert_eq!(Board::with_rules(rules).unwrap_err(), err);
+    }
+
+    // Twenty goats plus five tigers leave no free point
+    let rules = RuleSet {
+        tiger_squares: vec![0, 1, 2, 3, 4],
+        ..RuleSet::default()
+    };
+    assert_eq!(
+        Board::with_rules(rules).unwrap_err(),
+        RuleError::StartingGoatsOutOfRange(20)
+    );
+}
+
+fn aadu_puli_attam(diagram: &str, goats_in_hand: u32) -> Board {
+    Board::from_diagram_with_rules(diagram, RuleSet::aadu_puli_attam(), goats_in_hand, 0).unwrap()
+}
+
+#[test]
+fn test_aadu_puli_attam_setup() {
+    let board = Board::with_rules(RuleSet::aadu_puli_attam()).unwrap();
+    assert_eq!(board.cells.len(), 23);
+    assert_eq!(board.tigers().count(), 3);
+    assert_eq!(board.goats_in_hand, 15);
+    assert_eq!(board.to_fen(), "T/2TT2/6/6/4 g 15 0 1");
+
+    let parsed = Board::from_fen_with_rules(&board.to_fen(), RuleSet::aadu_puli_attam()).unwrap();
+    assert_eq!(parsed.cells, board.cells);
+    // A standard-board FEN doesn't fit the triangle
+    assert!(
+        Board::from_fen_with_rules("T3T/5/5/5/T3T g 20 0 1", RuleSet::aadu_puli_attam()).is_err()
+    );
+}
+
+#[test]
+fn test_aadu_puli_attam_captures() {
+    // The apex tiger jumps straight down a line of the triangle
+    let mut board =
+        Board::from_fen_with_rules("T/2G3/6/6/4 t 0 0 1", RuleSet::aadu_puli_attam()).unwrap();
+    assert!(board.move_tiger(0, 9));
+    assert_eq!(board.cells[3], Piece::Empty);
+    assert_eq!(board.captured_goats, 1);
+
+    // Along a row and down a side column of the rectangle
+    let board = aadu_puli_attam(
+        ".
+         T . . . . .
+         G G . . . .
+         . . . . . .
+         . . . .",
+        0,
+    );
+    let moves: Vec<usize> = board.get_valid_tiger_moves(1).iter().map(|p| p.0).collect();
+    assert!(moves.contains(&13));
+    assert!(!moves.contains(&9)); // not diagonally
+    let board = aadu_puli_attam(
+        ".
+         . . . . . .
+         T G . . . .
+         . . . . . .
+         . . . .",
+        0,
+    );
+    assert_eq!(board.get_captured_position(7, 9), Some(8));
+
+    // The side columns end at the rectangle, so there's no jump off it
+    let board = aadu_puli_attam(
+        ".
+         . . . . . .
+         . . . . . .
+         T . . . . .
+         G . . .",
+        0,
+    );
+    assert_eq!(
+        board.get_valid_tiger_moves(13),
+        vec![Position(7), Position(14)]
+    );
+}
+
+#[test]
+fn test_aadu_puli_attam_trapped_tiger() {
+    // A tiger on the apex boxed in by two rows of goats
+    let board = aadu_puli_attam(
+        "T
+         . G G G G .
+         . G G G G .
+         . . . . . .
+         . . . .",
+        0,
+    );
+    assert_eq!(
+        board.result(),
+        Some(GameResult {
+            winner: Winner::Goats,
+            reason: TerminationReason::TigersTrapped,
+        })
+    );
+
+    // With a gap in the second row the front goat can be taken
+    let board = aadu_puli_attam(
+        "T
+         . G G G G .
+         . G . G G .
+         . . . . . .
+         . . . .",
+        0,
+    );
+    assert_eq!(board.result(), None);
+    assert_eq!(board.get_valid_tiger_moves(0), vec![Position(9)]);
+}
+
+#[test]
+fn test_aadu_puli_attam_ai_takes_capture() {
+    let mut board =
+        Board::from_fen_with_rules("T/2G3/6/3G2/4 t 0 0 1", RuleSet::aadu_puli_attam()).unwrap();
+    board.set_ai_time_limit(Duration::from_secs(1));
+    assert!(board.ai_move_tiger());
+    assert_eq!(board.captured_goats, 1);
+}
+
+#[test]
+fn test_seven_by_seven_scripted_game() {
+    let rules = RuleSet {
+        capture_threshold: 3,
+        ..RuleSet::alquerque(7, 30)
+    };
+    let mut board = Board::with_rules(rules).unwrap();
+    assert_eq!(board.cells.len(), 49);
+    assert_eq!(
+        board.tigers().map(|p| p.0).collect::<Vec<_>>(),
+        vec![0, 6, 42, 48]
+    );
+    assert_eq!(board.to_fen(), "T5T/7/7/7/7/7/T5T g 30 0 1");
+
+    let display = board.display_with_hints();
+    assert!(display.contains("A   B   C   D   E   F   G"));
+    assert!(display.lines().any(|line| line.starts_with(" 7 ")));
+
+    // Goats hand the tigers three captures in a row
+    assert!(board.place_goat(1)); // B1
+    assert!(board.move_tiger(0, 2)); // over B1
+    assert!(board.place_goat(24)); // D4, the centre
+    assert!(board.move_tiger(6, 5));
+    assert!(board.place_goat(9)); // C2
+    assert!(board.move_tiger(2, 16)); // over C2
+    assert!(board.place_goat(40)); // F6
+
+    // A diagonal jump out of the corner
+    assert!(board.move_tiger(48, 32)); // over F6
+    assert_eq!(board.get_captured_position(48, 32), None);
+    assert_eq!(board.captured_goats, 3);
+    assert_eq!(
+        board.result(),
+        Some(GameResult {
+            winner: Winner::Tigers,
+            reason: TerminationReason::GoatsCaptured,
+        })
+    );
+    assert_eq!(board.record().moves.len(), 8);
+
+    let parsed = Board::from_fen_with_rules(&board.to_fen(), board.rules().clone()).unwrap();
+    assert_eq!(parsed.cells, board.cells);
+}
+
+#[test]
+fn test_larger_boards_are_validated_against_their_size() {
+    // 44 goats and 4 tigers leave one free point on a 7×7 board
+    assert!(Board::with_rules(RuleSet::alquerque(7, 44)).is_ok());
+    assert_eq!(
+        Board::with_rules(RuleSet::alquerque(7, 45)).unwrap_err(),
+        RuleError::StartingGoatsOutOfRange(45)
+    );
+    let rules = RuleSet {
+        tiger_squares: vec![0, 48],
+        ..RuleSet::alquerque(6, 20)
+    };
+    assert_eq!(
+        Board::with_rules(rules).unwrap_err(),
+        RuleError::TigerSquareOutOfBounds(48)
+    );
+
+    // The search has room for an 11×11 board but no more
+    assert!(Board::with_rules(RuleSet::alquerque(11, 20)).is_ok());
+    assert_eq!(
+        Board::with_rules(RuleSet::alquerque(12, 20)).unwrap_err(),
+        RuleError::TooManyPoints(144)
+    );
+}
+
+fn chain_position(multi_capture: MultiCapture) -> Board {
+    // A1 can jump B1 and then either D1 or C2
+    let rules = RuleSet {
+        multi_capture,
+        ..RuleSet::standard()
+    };
+    Board::from_fen_with_rules("TG1G1/2G2/5/5/5 t 0 0 1", rules).unwrap()
+}
+
+fn jump(from: usize, over: usize, to: usize) -> Jump {
+    Jump { from, over, to }
+}
+
+#[test]
+fn test_multi_capture_legal_moves() {
+    let single = Move::MoveTiger {
+        from: 0,
+        to: 2,
+        captured_position: Some(1),
+    };
+    let right = Move::MultiJump(vec![jump(0, 1, 2), jump(2, 3, 4)]);
+    let down = Move::MultiJump(vec![jump(0, 1, 2), jump(2, 7, 12)]);
+
+    let moves = chain_position(MultiCapture::Off).legal_moves();
+    assert!(moves.contains(&single));
+    assert!(!moves.iter().any(|mv| matches!(mv, Move::MultiJump(_))));
+
+    // Optional: the tiger can stop after any jump
+    let moves = chain_position(MultiCapture::Optional).legal_moves();
+    assert!(moves.contains(&single));
+    assert!(moves.contains(&right));
+    assert!(moves.contains(&down));
+    assert_eq!(moves.len(), 5);
+
+    // Mandatory: only complete chains
+    let moves = chain_position(MultiCapture::Mandatory).legal_moves();
+    assert!(!moves.contains(&single));
+    assert!(moves.contains(&right));
+    assert!(moves.contains(&down));
+    assert_eq!(moves.len(), 4);
+    assert!(!chain_position(MultiCapture::Mandatory).move_tiger(0, 2));
+    assert!(chain_position(MultiCapture::Optional).move_tiger(0, 2));
+}
+
+#[test]
+fn test_forced_capture() {
+    let rules = RuleSet {
+        forced_capture: true,
+        ..RuleSet::standard()
+    };
+    let mut board = Board::from_fen_with_rules("TG3/5/5/5/4T t 0 0 1", rules).unwrap();
+    assert_eq!(
+        board.legal_moves(),
+        vec![Move::MoveTiger {
+            from: 0,
+            to: 2,
+            captured_position: Some(1),
+        }]
+    );
+
+    // The other tiger can't step aside while A1 has a capture
+    assert_eq!(
+        board.try_move_tiger(24, 23),
+        Err(MoveError::CaptureRequired)
+    );
+    assert_eq!(board.try_move_tiger(0, 5), Err(MoveError::CaptureRequired));
+    assert_eq!(board.try_move_tiger(0, 12), Err(MoveError::Illegal));
+    assert_eq!(board.try_move_tiger(0, 2), Ok(()));
+    assert_eq!(board.captured_goats, 1);
+}
+
+#[test]
+fn test_forced_capture_forces_chains() {
+    let mut board = chain_position(MultiCapture::Optional);
+    let mut rules = board.rules().clone();
+    rules.forced_capture = true;
+    board = Board::from_fen_with_rules(&board.to_fen(), rules).unwrap();
+
+    // Only the two full chains are left, as if chaining were mandatory
+    let moves = board.legal_moves();
+    assert_eq!(
+        moves,
+        vec![
+            Move::MultiJump(vec![jump(0, 1, 2), jump(2, 3, 4)]),
+            Move::MultiJump(vec![jump(0, 1, 2), jump(2, 7, 12)]),
+        ]
+    );
+    assert!(!board.move_tiger(0, 2));
+    assert!(board.multi_jump(&[jump(0, 1, 2), jump(2, 3, 4)]));
+}
+
+#[test]
+fn test_threats_follow_the_diagonals() {
+    // A1 has a diagonal through B2, D1 none through C2
+    let board = Board::from_diagram(
+        "
+        T . . T .
+        . G G . .
+        . . . . .
+        . . . . .
+        T . . . T
+        ",
+        17,
+        0,
+    )
+    .unwrap();
+    assert_eq!(board.threatened_goats(), vec![6]);
+    assert!(board.is_goat_threatened(6));
+    assert!(!board.is_goat_threatened(7));
+    assert_eq!(
+        board.capturing_moves(),
+        vec![Move::MoveTiger {
+            from: 0,
+            to: 12,
+            captured_position: Some(6),
+        }]
+    );
+
+    // Nothing to threaten off a goat
+    assert!(!board.is_goat_threatened(0));
+    assert!(!board.is_goat_threatened(12));
+    assert!(!board.is_goat_threatened(25));
+}
+
+#[test]
+fn test_blocked_landing_points_stop_threats() {
+    let board = Board::from_diagram(
+        "
+        T . . . T
+        . G . . .
+        . . G . .
+        . . . . .
+        T . . . T
+        ",
+        18,
+        0,
+    )
+    .unwrap();
+    assert!(!board.is_goat_threatened(6));
+    assert!(board.threatened_goats().is_empty());
+    assert!(board.capturing_moves().is_empty());
+}
+
+#[test]
+fn test_goat_threatened_by_two_tigers() {
+    let board = Board::from_diagram(
+        "
+        . . . . .
+        . . T . .
+        . T G . .
+        . . . . .
+        T . . . T
+        ",
+        19,
+        0,
+    )
+    .unwrap();
+    assert_eq!(board.threatened_goats(), vec![12]);
+    let mut moves = board.capturing_moves();
+    moves.sort_by_key(|mv| format!("{mv:?}"));
+    assert_eq!(
+        moves,
+        vec![
+            Move::MoveTiger {
+                from: 11,
+                to: 13,
+                captured_position: Some(12),
+            },
+            Move::MoveTiger {
+                from: 7,
+                to: 17,
+                captured_position: Some(12),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_square_safety_looks_past_parried_threats() {
+    // A goat can still be placed on C5 to shut the jump over B5
+    let board = Board::from_diagram(
+        "
+        T G . . T
+        . . . . .
+        . . . . .
+        . . . . .
+        T . . . T
+        ",
+        19,
+        0,
+    )
+    .unwrap();
+    assert!(board.is_goat_threatened(1));
+    assert!(board.is_square_safe_for_goat(1));
+    assert!(board.is_square_safe_for_goat(3));
+    assert!(!board.is_square_safe_for_goat(0));
+
+    // Two tigers landing on different points can't both be stopped
+    let board = Board::from_diagram(
+        "
+        . . . . .
+        . . T . .
+        . T G . .
+        . . . . .
+        T . . . T
+        ",
+        19,
+        0,
+    )
+    .unwrap();
+    assert!(!board.is_square_safe_for_goat(12));
+    let breakdown = board.evaluate_detailed();
+    let lost = breakdown
+        .terms
+        .iter()
+        .find(|term| term.name == "lost goats");
+    assert_eq!(lost.unwrap().count, 1);
+}
+
+#[test]
+fn test_square_safety_needs_a_goat_that_can_cover() {
+    // D2 is the only goat next to C2, and leaving it lets B4 land there
+    let diagram = "
+        . . . . .
+        . T T . .
+        . . G . .
+        . . . G .
+        T . . . T
+        ";
+    let board = Board::from_diagram(diagram, 0, 0).unwrap();
+    assert!(board.is_goat_threatened(12));
+    assert!(!board.is_square_safe_for_goat(12));
+
+    // A goat on C1 steps up without opening anything
+    let covered = diagram.replacen("T . . . T", "T . G . T", 1);
+    let board = Board::from_diagram(&covered, 0, 0).unwrap();
+    assert!(board.is_goat_threatened(12));
+    assert!(board.is_square_safe_for_goat(12));
+    let breakdown = board.evaluate_detailed();
+    let lost = breakdown
+        .terms
+        .iter()
+        .find(|term| term.name == "lost goats");
+    assert_eq!(lost.unwrap().count, 0);
+}
+
+#[test]
+fn test_capturing_moves_match_the_legal_captures() {
+    for multi_capture in [
+        MultiCapture::Off,
+        MultiCapture::Optional,
+        MultiCapture::Mandatory,
+    ] {
+        let board = chain_position(multi_capture);
+        let captures: Vec<Move> = board
+            .legal_moves()
+            .into_iter()
+            .filter(Move::is_capture)
+            .collect();
+        assert_eq!(board.capturing_moves(), captures);
+        assert_eq!(board.threatened_goats(), vec![1]);
+    }
+}
+
+#[test]
+fn test_multi_jump_and_undo() {
+    let mut board = chain_position(MultiCapture::Mandatory);
+    let start = board.cells.clone();
+
+    // Chains have to follow the lines and can't be cut short
+    assert!(!board.multi_jump(&[jump(0, 1, 2)]));
+    assert!(!board.multi_jump(&[jump(0, 1, 2), jump(2, 8, 14)]));
+
+    assert!(board.multi_jump(&[jump(0, 1, 2), jump(2, 3, 4)]));
+    assert_eq!(board.cells[4], Piece::Tiger);
+    assert_eq!(board.cells[1], Piece::Empty);
+    assert_eq!(board.cells[3], Piece::Empty);
+    assert_eq!(board.captured_goats, 2);
+    assert_eq!(board.captures().len(), 2);
+    assert_eq!(board.ply(), 2);
+    let events = board.drain_events();
+    assert_eq!(
+        events[1..],
+        [
+            GameEvent::Captured { square: 1 },
+            GameEvent::Captured { square: 3 }
+        ]
+    );
+
+    // The whole chain comes back in one undo
+    assert!(board.undo());
+    assert_eq!(board.cells, start);
+    assert_eq!(board.captured_goats, 0);
+    assert!(board.captures().is_empty());
+    assert_eq!(board.ply(), 1);
+    assert_eq!(
+        board.drain_events(),
+        vec![
+            GameEvent::CaptureUndone { square: 3 },
+            GameEvent::CaptureUndone { square: 1 },
+            GameEvent::MoveUndone(Move::MultiJump(vec![jump(0, 1, 2), jump(2, 3, 4)])),
+        ]
+    );
+}
+
+#[test]
+fn test_ai_plays_capture_chains() {
+    let rules = RuleSet {
+        multi_capture: MultiCapture::Mandatory,
+        ..RuleSet::standard()
+    };
+    let mut board = Board::from_fen_with_rules("TG1G1/5/5/5/5 t 0 0 1", rules).unwrap();
+    board.set_ai_time_limit(Duration::from_secs(1));
+    assert!(board.ai_move_tiger());
+    assert_eq!(board.captured_goats, 2);
+    assert_eq!(board.cells[4], Piece::Tiger);
+}
+
+fn shuttle_rules(outcome: Adjudication) -> RuleSet {
+    RuleSet {
+        shuttle_rule: Some(ShuttleRule { moves: 3, outcome }),
+        ..RuleSet::standard()
+    }
+}
+
+#[test]
+fn test_tiger_shuttling_loses() {
+    let mut board = Board::from_fen_with_rules(
+        "T4/5/2G2/5/4G g 0 0 1",
+        shuttle_rules(Adjudication::GoatsWin),
+    )
+    .unwrap();
+
+    // The goat shuttles too, which is fine
+    assert!(board.move_goat(24, 23));
+    assert!(board.move_tiger(0, 1));
+    assert!(board.move_goat(23, 24));
+    assert!(board.move_tiger(1, 0));
+    assert!(board.move_goat(24, 23));
+    assert_eq!(board.result(), None);
+    assert!(board.move_tiger(0, 1));
+    assert_eq!(
+        board.result(),
+        Some(GameResult {
+            winner: Winner::Goats,
+            reason: TerminationReason::TigerShuttling,
+        })
+    );
+
+    // Taking the last move back reopens the game
+    board.undo();
+    assert!(!board.is_game_over());
+}
+
+#[test]
+fn test_shuttling_needs_one_tiger_going_back_and_forth() {
+    let mut board =
+        Board::from_fen_with_rules("T3T/5/2G2/5/5 t 0 0 1", shuttle_rules(Adjudication::Draw))
+            .unwrap();
+
+    // Two tigers taking turns never shuttle on their own
+    for (from, to) in [(0, 1), (4, 3), (1, 0), (3, 4), (0, 1), (4, 3)] {
+        assert!(board.move_tiger(from, to));
+        assert!(board.move_goat(12, 13) || board.move_goat(13, 12));
+    }
+    assert!(!board.is_game_over());
+
+    // A capture breaks the pattern as well
+    let mut board =
+        Board::from_fen_with_rules("T4/G4/5/5/4G t 0 0 1", shuttle_rules(Adjudication::Draw))
+            .unwrap();
+    assert!(board.move_tiger(0, 1));
+    assert!(board.move_goat(24, 23));
+    assert!(board.move_tiger(1, 0));
+    assert!(board.move_goat(23, 24));
+    assert!(board.move_tiger(0, 10)); // over A2
+    assert!(board.move_goat(24, 23));
+    assert!(board.move_tiger(10, 5));
+    assert!(board.move_goat(23, 24));
+    assert!(board.move_tiger(5, 10));
+    assert!(!board.is_game_over());
+    assert!(board.move_goat(24, 23));
+    assert!(board.move_tiger(10, 5));
+    assert_eq!(board.get_winner(), Winner::Draw);
+    assert!(board.is_game_over());
+}
+
+#[test]
+fn test_shuttle_rule_is_validated() {
+    let rules = RuleSet {
+        shuttle_rule: Some(ShuttleRule {
+            moves: 1,
+            outcome: Adjudication::GoatsWin,
+        }),
+        ..RuleSet::standard()
+    };
+    assert_eq!(
+        Board::with_rules(rules).unwrap_err(),
+        RuleError::ShuttleMovesTooFew(1)
+    );
+}
+
+#[test]
+fn test_ai_tiger_avoids_shuttle_loss() {
+    let mut board = Board::from_fen_with_rules(
+        "1T3/5/5/5/4G t 0 0 1",
+        shuttle_rules(Adjudication::GoatsWin),
+    )
+    .unwrap();
+    assert!(board.move_tiger(1, 0));
+    assert!(board.move_goat(24, 23));
+    assert!(board.move_tiger(0, 1));
+    assert!(board.move_goat(23, 24));
+
+    board.set_ai_time_limit(Duration::from_secs(1));
+    assert!(board.ai_move_tiger());
+    assert_eq!(board.cells[0], Piece::Empty);
+    assert!(!board.is_game_over());
+}
+
+#[test]
+fn test_tigers_can_still_win() {
+    // One capture and three goats left can't add up to five
+    let fen = "T3T/5/1GGG1/5/T3T t 0 1 9";
+    let board = Board::from_fen(fen).unwrap();
+    assert!(!board.tigers_can_still_win());
+    assert_eq!(board.result(), None);
+
+    let board = Board::from_fen("T3T/5/1GGG1/5/T3T t 1 1 9").unwrap();
+    assert!(board.tigers_can_still_win());
+
+    let rules = RuleSet {
+        adjudicate_hopeless: Some(Adjudication::GoatsWin),
+        ..RuleSet::standard()
+    };
+    let board = Board::from_fen_with_rules(fen, rules).unwrap();
+    assert_eq!(
+        board.result(),
+        Some(GameResult {
+            winner: Winner::Goats,
+            reason: TerminationReason::CaptureThresholdUnreachable,
+        })
+    );
+}
+
+#[test]
+fn test_swap_sides() {
+    let rules = RuleSet {
+        swap_after: Some(2),
+        ..RuleSet::standard()
+    };
+    let mut board = Board::with_rules(rules).unwrap();
+    assert!(!board.can_swap());
+    assert!(!board.swap_sides());
+
+    assert!(board.place_goat(12));
+    assert!(board.move_tiger(0, 1));
+    assert!(board.can_swap());
+    board.drain_events();
+    assert!(board.swap_sides());
+    assert_eq!(board.drain_events(), vec![GameEvent::SidesSwapped]);
+
+    // Only once
+    assert!(!board.can_swap());
+    assert!(board.place_goat(13));
+    assert!(!board.can_swap());
+    assert_eq!(board.record().swapped_at, Some(2));
+}
+
+#[test]
+fn test_undo_across_swap() {
+    let rules = RuleSet {
+        swap_after: Some(2),
+        ..RuleSet::standard()
+    };
+    let mut board = Board::with_rules(rules).unwrap();
+    assert!(board.place_goat(12));
+    assert!(board.move_tiger(0, 1));
+    assert!(board.swap_sides());
+    assert!(board.place_goat(13));
+    board.drain_events();
+
+    // Back to the swap point: the swap still stands
+    assert!(board.undo());
+    assert_eq!(board.swapped_at(), Some(2));
+    assert!(!board.drain_events().contains(&GameEvent::SwapUndone));
+
+    // Past it: the swap is taken back and can be made again
+    assert!(board.undo());
+    assert_eq!(board.swapped_at(), None);
+    assert_eq!(board.drain_events().last(), Some(&GameEvent::SwapUndone));
+    assert!(board.move_tiger(0, 1));
+    assert!(board.can_swap());
+}
+
+#[test]
+fn test_history_numbers_moves_and_counts_captures() {
+    let mut board = Board::new();
+    assert_eq!(format_history(&board, 72), "");
+    for text in ["C3", "A1-B2", "A3", "B2-B3", "E4", "B3xC3-D3"] {
+        let mv = parse_move(&board, text).unwrap();
+        assert!(board.make_move(&mv), "{text}");
+    }
+    assert_eq!(
+        format_history(&board, 72),
+        "1. G C3  T A1-B2  2. G A3  T B2-B3  3. G E4  T B3xC3-D3 (1)"
+    );
+    // Lines break between numbered moves only
+    assert_eq!(
+        format_history(&board, 20),
+        "1. G C3  T A1-B2\n2. G A3  T B2-B3\n3. G E4  T B3xC3-D3 (1)"
+    );
+
+    let mut board = Board::from_fen("T3T/1G3/5/5/T3T t 19 2 1").unwrap();
+    let mv = parse_move(&board, "A1-A2").unwrap();
+    assert!(board.make_move(&mv));
+    let mv = parse_move(&board, "C3").unwrap();
+    assert!(board.make_move(&mv));
+    assert_eq!(format_history(&board, 72), "1. ... T A1-A2  2. G C3");
+}
+
+#[test]
+fn test_draw_offers() {
+    let mut board = Board::new();
+    assert!(!board.accept_draw());
+    assert!(board.place_goat(12));
+
+    // Declined: the game goes on
+    assert!(board.offer_draw());
+    assert_eq!(board.draw_offer(), Some(Side::Tigers));
+    board.decline_draw();
+    assert_eq!(board.draw_offer(), None);
+    assert!(!board.is_game_over());
+
+    // The offering side may still move; the other side's move ends it
+    assert!(board.offer_draw());
+    assert!(board.move_tiger(0, 1));
+    assert_eq!(board.draw_offer(), Some(Side::Tigers));
+    assert!(board.place_goat(2));
+    assert_eq!(board.draw_offer(), None);
+    assert!(!board.accept_draw());
+
+    assert!(board.offer_draw());
+    board.drain_events();
+    assert!(board.accept_draw());
+    let agreed = GameResult {
+        winner: Winner::Draw,
+        reason: TerminationReason::Agreement,
+    };
+    assert_eq!(board.result(), Some(agreed));
+    assert_eq!(board.drain_events(), vec![GameEvent::GameOver(agreed)]);
+    assert!(!board.offer_draw());
+
+    // Taking a move back reopens the game
+    assert!(board.undo());
+    assert_eq!(board.result(), None);
+}
+
+#[test]
+fn test_ai_accepts_draws_unless_well_ahead() {
+    // The tigers have four goats and threaten a fifth
+    let mut board = Board::from_fen("T3T/1G3/5/5/T3T t 15 4 10").unwrap();
+    board.set_ai_time_limit(Duration::from_millis(200));
+    assert!(!board.ai_accepts_draw(Side::Tigers, 50));
+    assert!(board.ai_accepts_draw(Side::Goats, 50));
+    assert!(board.ai_accepts_draw(Side::Tigers, 20_000));
+}
+
+#[test]
+fn test_goto_ply_steps_through_the_game() {
+    let mut board = Board::new();
+    assert!(board.place_goat(12));
+    assert!(board.move_tiger(0, 6));
+    assert!(board.place_goat(2));
+    let end = board.to_fen();
+
+    assert!(board.goto_ply(1));
+    assert_eq!(board.ply(), 1);
+    assert_eq!(board.last_move(), Some(&Move::PlaceGoat { position: 12 }));
+    assert!(board.goto_ply(0));
+    assert_eq!(board.to_fen(), Board::new().to_fen());
+    assert_eq!(board.last_move(), None);
+
+    // Forward again through the moves taken back
+    assert!(board.goto_ply(3));
+    assert_eq!(board.to_fen(), end);
+    assert!(!board.goto_ply(4));
+    assert_eq!(board.ply(), 3);
+
+    // A new move drops the moves there were to go forward to
+    assert!(board.goto_ply(1));
+    assert!(board.move_tiger(4, 8));
+    assert!(!board.goto_ply(3));
+    assert!(board.goto_ply(1));
+    assert!(board.goto_ply(2));
+    assert_eq!(
+        board.last_move(),
+        Some(&Move::MoveTiger {
+            from: 4,
+            to: 8,
+            captured_position: None
+        })
+    );
+}
+
+#[test]
+fn test_goto_ply_stays_within_a_game_started_from_a_position() {
+    let mut board = Board::from_fen("T3T/1G3/5/5/T3T t 19 0 1").unwrap();
+    assert_eq!(board.start_ply(), board.ply());
+    let start = board.ply();
+    assert!(board.move_tiger(0, 5));
+    assert!(!board.goto_ply(start - 1));
+    assert!(board.goto_ply(start));
+    assert!(!board.can_undo());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::lenient;
+    use baghchal::{Board, CaptureEvent, Piece};
+
+    #[test]
+    fn test_undo_place_goat() {
+        let mut board = Board::new();
+        let pos = 12; // Center position
+
+        // Place a goat
+        assert!(board.place_goat(pos));
+        assert_eq!(board.cells[pos], Piece::Goat);
+        assert_eq!(board.goats_in_hand, 19);
+
+        // Undo the placement
+        assert!(board.can_undo());
+        assert!(board.undo());
+        assert_eq!(board.cells[pos], Piece::Empty);
+        assert_eq!(board.goats_in_hand, 20);
+    }
+
+    #[test]
+    fn test_undo_move_goat() {
+        let mut board = Board::with_rules(lenient()).unwrap();
+        let start_pos = 12;
+        let move_to = 13;
+
+        // Place a goat first
+        assert!(board.place_goat(start_pos));
+        assert_eq!(board.cells[start_pos], Piece::Goat);
+
+        // Move the goat once a tiger has moved
+        assert!(board.move_tiger(4, 3));
+        assert!(board.move_goat(start_pos, move_to));
+        assert_eq!(board.cells[start_pos], Piece::Empty);
+        assert_eq!(board.cells[move_to], Piece::Goat);
+
+        // Undo the move
+        assert!(board.can_undo());
+        assert!(board.undo());
+        assert_eq!(board.cells[start_pos], Piece::Goat);
+        assert_eq!(board.cells[move_to], Piece::Empty);
+    }
+
+    #[test]
+    fn test_undo_tiger_move_without_capture() {
+        let mut board = Board::new();
+        let start_pos = 0; // Top-left tiger
+        let move_to = 5;
+
+        // Move tiger once a goat is placed
+        assert!(board.place_goat(22));
+        assert!(board.move_tiger(start_pos, move_to));
+        assert_eq!(board.cells[start_pos], Piece::Empty);
+        assert_eq!(board.cells[move_to], Piece::Tiger);
+        assert_eq!(board.captured_goats, 0);
+
+        // Undo the move
+        assert!(board.can_undo());
+        assert!(board.undo());
+        assert_eq!(board.cells[start_pos], Piece::Tiger);
+        assert_eq!(board.cells[move_to], Piece::Empty);
+        assert_eq!(board.captured_goats, 0);
+    }
+
+    #[test]
+    fn test_undo_tiger_capture() {
+        let mut board = Board::new();
+        let tiger_pos = 0;
+        let goat_pos = 5;
+        let capture_to = 10;
+
+        // Place a goat to be captured
+        assert!(board.place_goat(goat_pos));
+        assert_eq!(board.cells[goat_pos], Piece::Goat);
+
+        // Capture the goat
+        assert!(board.move_tiger(tiger_pos, capture_to));
+        assert_eq!(board.cells[tiger_pos], Piece::Empty);
+        assert_eq!(board.cells[goat_pos], Piece::Empty);
+        assert_eq!(board.cells[capture_to], Piece::Tiger);
+        assert_eq!(board.captured_goats, 1);
+
+        // Undo the capture
+        assert!(board.can_undo());
+        assert!(board.undo());
+        assert_eq!(board.cells[tiger_pos], Piece::Tiger);
+        assert_eq!(board.cells[goat_pos], Piece::Goat);
+        assert_eq!(board.cells[capture_to], Piece::Empty);
+        assert_eq!(board.captured_goats, 0);
+    }
+
+    #[test]
+    fn test_multiple_undo() {
+        let mut board = Board::new();
+
+        // Make several moves
+        assert!(board.place_goat(12)); // Place goat in center
+        assert_eq!(board.goats_in_hand, 19);
+
+        assert!(board.move_tiger(0, 5)); // Move tiger
+        assert_eq!(board.goats_in_hand, 19);
+
+        assert!(board.place_goat(7)); // Place another goat
+        assert_eq!(board.goats_in_hand, 18);
+
+        // Undo all moves in reverse order
+        assert!(board.can_undo());
+        assert!(board.undo()); // Undo goat placement
+        assert_eq!(board.cells[7], Piece::Empty);
+        assert_eq!(board.goats_in_hand, 19);
+
+        assert!(board.can_undo());
+        assert!(board.undo()); // Undo tiger move
+        assert_eq!(board.cells[0], Piece::Tiger);
+        assert_eq!(board.cells[5], Piece::Empty);
+        assert_eq!(board.goats_in_hand, 19);
+
+        assert!(board.can_undo());
+        assert!(board.undo()); // Undo first goat placement
+        assert_eq!(board.cells[12], Piece::Empty);
+        assert_eq!(board.goats_in_hand, 20);
+
+        // No more moves to undo
+        assert!(!board.can_undo());
+        assert!(!board.undo());
+    }
+
+    #[test]
+    fn test_undo_clears_selection() {
+        let mut board = Board::new();
+
+        // Place a goat and select it
+        assert!(board.place_goat(12));
+        board.select_position(12);
+        assert_eq!(board.selected_position, Some(12));
+
+        // Undo should clear the selection
+        assert!(board.undo());
+        assert_eq!(board.selected_position, None);
+    }
+
+    #[test]
+    fn test_undo_empty_history() {
+        let mut board = Board::new();
+        assert!(!board.can_undo());
+        assert!(!board.undo());
+    }
+
+    #[test]
+    fn test_capture_log_records_captures_in_order() {
+        let mut board = Board::new();
+        assert!(board.captures().is_empty());
+
+        assert!(board.place_goat(1));
+        assert!(board.move_tiger(0, 2)); // Capture on B1
+        assert!(board.place_goat(9));
+        assert!(board.move_tiger(4, 14)); // Capture on E2
+
+        assert_eq!(
+            board.captures(),
+            &[
+                CaptureEvent {
+                    ply: 1,
+                    square: 1,
+                    by_tiger_from: 0,
+                    by_tiger_to: 2,
+                },
+                CaptureEvent {
+                    ply: 3,
+                    square: 9,
+                    by_tiger_from: 4,
+                    by_tiger_to: 14,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_undo_capture_removes_event() {
+        let mut board = Board::new();
+        assert!(board.place_goat(1));
+        assert!(board.move_tiger(0, 2));
+        assert!(board.place_goat(12));
+        assert_eq!(board.captures().len(), 1);
+
+        // Undoing a non-capturing move leaves the log alone
+        assert!(board.undo());
+        assert_eq!(board.captures().len(), 1);
+
+        // Undoing the capture removes its event
+        assert!(board.undo());
+        assert!(board.captures().is_empty());
+        assert_eq!(board.cells[1], Piece::Goat);
+    }
+}
+
+fn depth_with_safe_goats(depth: u32, safe_goats: bool) -> AiConfig {
+    AiConfig {
+        max_depth: Some(depth),
+        max_nodes: None,
+        safe_goats,
+        ..AiConfig::nodes(0)
+    }
+}
+
+#[test]
+fn test_safe_goats_keep_out_of_reach_when_they_can() {
+    // At depth 2 the goats play these moves, leaving a goat for the
+    // taking with safe moves to spare
+    for (fen, hangs) in [
+        ("4T/T4/GGG2/2G2/TT3 g 15 1 6", "B4"),
+        ("4T/T4/T2GG/2T2/1G1G1 g 15 1 6", "D4"),
+    ] {
+        let board = Board::from_fen(fen).unwrap();
+        let (mv, _) = board
+            .suggest_move(&depth_with_safe_goats(2, false))
+            .unwrap();
+        assert_eq!(format_move(board.topology(), &mv), hangs, "{fen}");
+
+        let (mv, _) = board.suggest_move(&depth_with_safe_goats(2, true)).unwrap();
+        let mut after = board.clone();
+        assert!(after.make_move(&mv));
+        assert_eq!(after.threatened_goats(), Vec::<usize>::new(), "{fen}");
+    }
+}
+
+#[test]
+fn test_safe_goats_still_move_when_every_move_hangs_a_goat() {
+    // A1 threatens B1 and A2, and a placement can only cover one of them
+    let board = Board::from_fen("TG2T/G4/5/5/T3T g 18 0 2").unwrap();
+    let (mv, _) = board.suggest_move(&depth_with_safe_goats(2, true)).unwrap();
+    assert!(board.legal_moves().contains(&mv));
+}
+
+#[test]
+fn test_easier_presets_keep_goats_safe() {
+    for (difficulty, safe) in [
+        (Difficulty::Beginner, false),
+        (Difficulty::Easy, true),
+        (Difficulty::Medium, true),
+        (Difficulty::Hard, false),
+    ] {
+        assert_eq!(AiConfig::preset(difficulty, None).safe_goats, safe);
+    }
+}
+
+#[test]
+fn test_position_view_answers_like_the_board() {
+    for fen in [
+        "T3T/5/5/5/T3T g 20 0 1",
+        "GT2G/GT1GT/GGGGG/GGGGG/TG1GG t 0 3 21",
+        "TG2T/G4/5/5/T3T g 18 0 2",
+        "GTGT1/GGGGG/GGGGG/TGGGG/GGGGT g 0 0 15",
+    ] {
+        let board = Board::from_fen(fen).unwrap();
+        let view = board.position();
+        assert_eq!(view.to_fen(), fen);
+        assert_eq!(view.side_to_move(), board.side_to_move());
+        assert_eq!(view.legal_moves(), board.legal_moves(), "{fen}");
+        assert_eq!(view.capturing_moves(), board.capturing_moves(), "{fen}");
+        assert_eq!(view.threatened_goats(), board.threatened_goats(), "{fen}");
+        assert_eq!(view.result(), board.result(), "{fen}");
+        assert_eq!(
+            view.evaluate_detailed(board.eval_weights()),
+            board.evaluate_detailed(),
+            "{fen}"
+        );
+        assert_eq!(view.to_string(), board.to_string());
+    }
+}
+
+#[test]
+fn test_board_from_position_plays_on_from_there() {
+    let mut board = Board::new();
+    for mv in ["C3", "A1-B2", "A3"] {
+        assert!(board.make_move(&parse_move(&board, mv).unwrap()));
+    }
+    let mut copy = Board::from_position(board.position());
+    assert_eq!(copy.to_fen(), board.to_fen());
+    assert!(!copy.can_undo());
+    assert!(copy.make_move(&parse_move(&copy, "B2-B1").unwrap()));
+}
+
+#[test]
+fn test_position_views_are_shared_across_threads() {
+    let board = Board::from_fen("GT2G/GT1GT/GGGGG/GGGGG/TG1GG t 0 3 21").unwrap();
+    let view = board.position();
+    let expected = board.legal_moves();
+    std::thread::scope(|scope| {
+        let render = scope.spawn(|| view.to_string());
+        let analyse = scope.spawn(|| view.clone().legal_moves());
+        assert_eq!(render.join().unwrap(), board.to_string());
+        assert_eq!(analyse.join().unwrap(), expected);
+    });
+}
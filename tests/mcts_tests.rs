@@ -0,0 +1,113 @@
+use baghchal::notation::format_move;
+use baghchal::{AiConfig, Board, Engine, Evaluator, Mcts, MinimaxEngine, Side};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+fn mcts_move(fen: &str, iterations: u64, seed: u64) -> (String, i32) {
+    let board = Board::from_fen(fen).unwrap();
+    let mut mcts = Mcts {
+        seed: Some(seed),
+        ..Mcts::default()
+    };
+    let result = mcts
+        .best_move(&board, &AiConfig::nodes(iterations))
+        .unwrap();
+    (format_move(board.topology(), &result.mv), result.score)
+}
+
+#[test]
+fn test_mcts_takes_the_winning_capture() {
+    for seed in 0..3 {
+        let (mv, score) = mcts_move("T3T/1G3/5/5/T3T t 15 4 16", 2_000, seed);
+        assert_eq!(mv, "A1xB2-C3");
+        assert_eq!(score, 1000);
+    }
+}
+
+#[test]
+fn test_mcts_blocks_the_fifth_capture() {
+    // A1 threatens to jump B2 for the win unless a goat lands on C3
+    for seed in 0..3 {
+        let (mv, _) = mcts_move("T3T/1G3/5/5/T3T g 15 4 16", 2_000, seed);
+        assert_eq!(mv, "C3");
+    }
+}
+
+#[test]
+fn test_mcts_is_reproducible_with_a_seed() {
+    let fen = "4T/1T3/2G2/3G1/T3T t 18 0 2";
+    assert_eq!(mcts_move(fen, 500, 7), mcts_move(fen, 500, 7));
+}
+
+#[test]
+fn test_mcts_has_nothing_to_search_in_a_finished_game() {
+    let board = Board::from_fen("T3T/5/5/5/T3T g 15 5 11").unwrap();
+    assert_eq!(
+        Mcts::default().best_move(&board, &AiConfig::nodes(100)),
+        None
+    );
+}
+
+#[test]
+fn test_engines_can_play_each_other() {
+    let mut board = Board::new();
+    board.set_use_opening_book(false);
+    board.set_ai_config(AiConfig::nodes(300));
+    let mut tigers = Mcts {
+        seed: Some(1),
+        ..Mcts::default()
+    };
+    let mut goats = MinimaxEngine::new();
+    for _ in 0..6 {
+        let engine: &mut dyn Engine = match board.side_to_move() {
+            Side::Tigers => &mut tigers,
+            Side::Goats => &mut goats,
+        };
+        let played = board.play_engine_move(engine).unwrap();
+        assert!(played.stats.nodes > 0);
+        assert_eq!(board.record().moves.last(), Some(&played.mv));
+    }
+    assert_eq!(board.record().moves.len(), 6);
+    assert!(goats.last_search().is_some());
+}
+
+/// Scores every position 0, noting the size of each batch it's given.
+#[derive(Debug, Default)]
+struct BatchCounter {
+    batches: Mutex<Vec<usize>>,
+    singles: AtomicUsize,
+}
+
+impl Evaluator for BatchCounter {
+    fn evaluate(&self, _: &Board) -> i32 {
+        self.singles.fetch_add(1, Ordering::Relaxed);
+        0
+    }
+
+    fn evaluate_batch(&self, boards: &[Board]) -> Vec<i32> {
+        self.batches.lock().unwrap().push(boards.len());
+        vec![0; boards.len()]
+    }
+}
+
+#[test]
+fn test_mcts_judges_playouts_in_batches() {
+    let mut board = Board::from_fen("T1G1T/1G3/2G2/3G1/T3T t 16 0 5").unwrap();
+    let counter = Arc::new(BatchCounter::default());
+    board.set_evaluator(counter.clone());
+    let mut mcts = Mcts {
+        seed: Some(1),
+        playout_cap: 2,
+        leaf_batch: 8,
+        ..Mcts::default()
+    };
+    let result = mcts.best_move(&board, &AiConfig::nodes(100)).unwrap();
+    assert!(board.legal_moves().contains(&result.mv));
+
+    // Twelve full batches and the four games left over, none ending in
+    // two moves
+    let batches = counter.batches.lock().unwrap();
+    assert_eq!(batches[..12], [8; 12]);
+    assert_eq!(batches[12..], [4]);
+    assert_eq!(counter.singles.load(Ordering::Relaxed), 0);
+}
@@ -0,0 +1,61 @@
+//! Needs ONNX Runtime: run with `cargo test --features onnx`, with
+//! `ORT_DYLIB_PATH` set if the library isn't on the library path.
+#![cfg(feature = "onnx")]
+
+use baghchal::onnx::VALUE_SCALE;
+use baghchal::{AiConfig, Board, Engine, Evaluator, Mcts, OnnxEvaluator};
+use std::sync::Arc;
+
+/// Random weights: flattens the planes, then a single dense unit and tanh.
+const MODEL: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/random_value.onnx");
+
+fn positions() -> Vec<Board> {
+    [
+        "T3T/5/5/5/T3T g 20 0 1",
+        "T1G1T/1G3/2G2/3G1/T3T t 16 0 5",
+        "TGGGT/G3G/G1T1G/G3G/TGGG1 g 0 0 30",
+    ]
+    .iter()
+    .map(|fen| Board::from_fen(fen).unwrap())
+    .collect()
+}
+
+#[test]
+fn test_missing_model_is_an_error() {
+    assert!(OnnxEvaluator::load("tests/data/no_such_model.onnx").is_err());
+}
+
+#[test]
+fn test_model_scores_positions_on_the_search_scale() {
+    let evaluator = OnnxEvaluator::load(MODEL).unwrap();
+    let limit = VALUE_SCALE as i32;
+    for board in positions() {
+        let score = evaluator.evaluate(&board);
+        assert!((-limit..=limit).contains(&score), "{score}");
+    }
+}
+
+#[test]
+fn test_batch_matches_one_at_a_time() {
+    let evaluator = OnnxEvaluator::load(MODEL).unwrap();
+    let boards = positions();
+    let one_by_one: Vec<i32> = boards
+        .iter()
+        .map(|board| evaluator.evaluate(board))
+        .collect();
+    assert_eq!(evaluator.evaluate_batch(&boards), one_by_one);
+}
+
+#[test]
+fn test_monte_carlo_search_with_the_network() {
+    let mut board = Board::from_fen("T1G1T/1G3/2G2/3G1/T3T t 16 0 5").unwrap();
+    board.set_evaluator(Arc::new(OnnxEvaluator::load(MODEL).unwrap()));
+    let mut mcts = Mcts {
+        seed: Some(1),
+        playout_cap: 4,
+        leaf_batch: 16,
+        ..Mcts::default()
+    };
+    let result = mcts.best_move(&board, &AiConfig::nodes(200)).unwrap();
+    assert!(board.legal_moves().contains(&result.mv));
+}
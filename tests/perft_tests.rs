@@ -0,0 +1,95 @@
+use baghchal::notation::format_move;
+use baghchal::{perft, perft_divide, Board};
+
+/// Checks `perft` from `fen` at depths 1 up to the number of `counts`.
+fn assert_perft(fen: &str, counts: &[u64]) {
+    let board = Board::from_fen(fen).unwrap();
+    for (depth, &expected) in (1..).zip(counts) {
+        assert_eq!(perft(&board, depth), expected, "{fen} at depth {depth}");
+    }
+}
+
+#[test]
+fn test_perft_from_the_start() {
+    assert_perft(
+        "T3T/5/5/5/T3T g 20 0 1",
+        &[21, 252, 5_052, 68_204, 1_304_788, 18_592_000],
+    );
+}
+
+#[test]
+fn test_perft_during_placement() {
+    // Depth 6 is 12,285,379, too slow to count unoptimised
+    assert_perft(
+        "T1G1G/3T1/5/1T1T1/GGGG1 g 14 0 7",
+        &[15, 285, 3_995, 64_674, 845_710],
+    );
+    assert_perft(
+        "1GGGG/2TT1/T1T1G/5/GGGGG g 8 2 13",
+        &[11, 144, 1_444, 16_535, 150_583, 1_619_436],
+    );
+}
+
+#[test]
+fn test_perft_during_movement() {
+    assert_perft(
+        "GGGGG/GGGTG/T1T1G/1TGG1/GGGGG g 0 3 25",
+        &[7, 55, 563, 3_947, 46_855, 328_422],
+    );
+    // The tigers are one capture from winning, so many lines end early
+    assert_perft(
+        "GG1GG/GG3/G1TTG/TTGGG/GGGGG g 0 4 31",
+        &[11, 56, 668, 4_287, 50_920, 336_210],
+    );
+}
+
+#[test]
+fn test_perft_with_one_legal_move() {
+    assert_perft(
+        "T1GGT/GG1GG/G1G1G/GG1GG/TGGGT t 4 0 16",
+        &[1, 5, 8, 36, 44, 180],
+    );
+}
+
+#[test]
+fn test_perft_divide_adds_up() {
+    let board = Board::from_fen("1GGGG/2TT1/T1T1G/5/GGGGG g 8 2 13").unwrap();
+    let divide = perft_divide(&board, 4);
+    assert_eq!(divide.len(), board.legal_moves().len());
+    assert_eq!(divide.iter().map(|(_, nodes)| nodes).sum::<u64>(), 16_535);
+    for (mv, nodes) in &divide {
+        let mut after = board.clone();
+        assert!(after.make_move(mv));
+        assert_eq!(
+            perft(&after, 3),
+            *nodes,
+            "{}",
+            format_move(board.topology(), mv)
+        );
+    }
+    assert!(perft_divide(&board, 0).is_empty());
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_parallel_perft_matches_perft() {
+    use baghchal::perft_parallel;
+
+    for fen in [
+        "T3T/5/5/5/T3T g 20 0 1",
+        "GG1GG/GG3/G1TTG/TTGGG/GGGGG g 0 4 31",
+        "T1GGT/GG1GG/G1G1G/GG1GG/TGGGT t 4 0 16",
+    ] {
+        let board = Board::from_fen(fen).unwrap();
+        let expected = perft(&board, 5);
+        for threads in [1, 2, 3, 8] {
+            assert_eq!(perft_parallel(&board, 5, threads), expected, "{fen}");
+        }
+    }
+    // Too shallow to split, or nothing to count
+    let board = Board::new();
+    assert_eq!(perft_parallel(&board, 1, 4), 21);
+    assert_eq!(perft_parallel(&board, 0, 4), 1);
+    let over = Board::from_fen("T3T/5/5/5/T3T g 15 5 11").unwrap();
+    assert_eq!(perft_parallel(&over, 3, 4), 0);
+}
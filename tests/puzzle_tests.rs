@@ -0,0 +1,146 @@
+use baghchal::notation::{format_move, parse_move};
+use baghchal::puzzle::{builtin, generate, parse_puzzles, random_games, GeneratorOptions};
+use baghchal::{Board, Move, Puzzle, PuzzleResult, Side};
+
+#[test]
+fn test_builtin_puzzles_are_solvable() {
+    let puzzles = builtin();
+    assert!(!puzzles.is_empty());
+    for puzzle in &puzzles {
+        for line in puzzle.solutions() {
+            assert_eq!(
+                puzzle.check(line),
+                PuzzleResult::Solved,
+                "{}",
+                puzzle.title()
+            );
+        }
+    }
+}
+
+#[test]
+fn test_move_notation_round_trip() {
+    let board = Board::from_fen("TG1G1/2G2/5/5/4T t 17 0 2").unwrap();
+    for mv in board.legal_moves() {
+        let text = format_move(board.topology(), &mv);
+        assert_eq!(parse_move(&board, &text), Some(mv));
+    }
+    assert_eq!(
+        parse_move(&board, "a1xb1-c1"),
+        Some(Move::MoveTiger {
+            from: 0,
+            to: 2,
+            captured_position: Some(1),
+        })
+    );
+    assert_eq!(parse_move(&board, "A1-C1"), None);
+}
+
+const TWO_WAYS: &str = "
+# Either goat can go first
+title: Goats to fill the gaps
+fen: T3T/5/5/5/T3T g 20 0 1
+solution: C3 A1-B1 C2
+solution: C2 A1-B1 C3
+";
+
+#[test]
+fn test_puzzle_accepts_transpositions() {
+    let puzzle = &parse_puzzles(TWO_WAYS).unwrap()[0];
+    assert_eq!(puzzle.title(), "Goats to fill the gaps");
+    assert_eq!(puzzle.side_to_move(), Side::Goats);
+
+    let moves = |text: &str| {
+        let mut board = puzzle.board().clone();
+        text.split_whitespace()
+            .map(|token| {
+                let mv = parse_move(&board, token).unwrap();
+                board.make_move(&mv);
+                mv
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(puzzle.check(&moves("C3 A1-B1 C2")), PuzzleResult::Solved);
+    assert_eq!(puzzle.check(&moves("C2 A1-B1 C3")), PuzzleResult::Solved);
+    assert_eq!(puzzle.check(&moves("C2 A1-B1")), PuzzleResult::Incomplete);
+    assert_eq!(puzzle.check(&[]), PuzzleResult::Incomplete);
+    assert_eq!(
+        puzzle.check(&moves("C2 A1-A2")),
+        PuzzleResult::Wrong { ply: 1 }
+    );
+    assert_eq!(
+        puzzle.check(&[Move::MoveGoat { from: 12, to: 13 }]),
+        PuzzleResult::Wrong { ply: 0 }
+    );
+}
+
+#[test]
+fn test_puzzle_from_diagram() {
+    let text = "title: Tigers to capture
+to-move: tigers
+in-hand: 18
+diagram:
+T G . . T
+. . . . .
+. . G . .
+. . . . .
+T . . . T
+solution: A1xB1-C1
+";
+    let puzzle = &parse_puzzles(text).unwrap()[0];
+    assert_eq!(puzzle.side_to_move(), Side::Tigers);
+    assert_eq!(puzzle.board().goats_in_hand, 18);
+}
+
+#[test]
+fn test_puzzle_parse_errors() {
+    let err = parse_puzzles("fen: T3T/5/5/5/T3T g 20 0 1\nsolution: C3 A1-C1").unwrap_err();
+    assert_eq!((err.line, err.column), (2, 14));
+    assert!(err.message.contains("A1-C1"));
+
+    let err = parse_puzzles("title: Nothing to solve\nfen: T3T/5/5/5/T3T g 20 0 1").unwrap_err();
+    assert_eq!(err.line, 1);
+
+    let err = parse_puzzles("fen: T3T/5/5/5/T3T x 20 0 1\nsolution: C3").unwrap_err();
+    assert_eq!((err.line, err.column), (1, 20));
+
+    let err = parse_puzzles("solution: C3\nhint: look at C3").unwrap_err();
+    assert_eq!(err.line, 2);
+}
+
+#[test]
+fn test_puzzle_rejects_illegal_solution() {
+    let board = Board::new();
+    let solution = vec![Move::MoveGoat { from: 12, to: 13 }];
+    assert!(Puzzle::new("Broken", board.clone(), vec![solution]).is_none());
+    assert!(Puzzle::new("Empty", board, Vec::new()).is_none());
+}
+
+#[test]
+fn test_puzzle_display_round_trip() {
+    for puzzle in builtin() {
+        let parsed = parse_puzzles(&puzzle.to_string()).unwrap();
+        assert_eq!(parsed[0].title(), puzzle.title());
+        assert_eq!(parsed[0].solutions(), puzzle.solutions());
+    }
+}
+
+#[test]
+fn test_generated_puzzles() {
+    let games = random_games(3, 7);
+    assert_eq!(games, random_games(3, 7));
+
+    let options = GeneratorOptions::default();
+    let puzzles = generate(&games, &options);
+    assert!(!puzzles.is_empty());
+    for puzzle in &puzzles {
+        let solutions = puzzle.solutions();
+        assert!(solutions.len() <= options.max_solutions);
+        // Every line starts with the one key move
+        assert!(solutions.iter().all(|line| line[0] == solutions[0][0]));
+        for line in solutions {
+            assert!(line.len() <= options.depth);
+            assert_eq!(puzzle.check(line), PuzzleResult::Solved);
+        }
+    }
+}
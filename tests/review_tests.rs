@@ -0,0 +1,88 @@
+use baghchal::annotate::MoveReason;
+use baghchal::notation::parse_move;
+use baghchal::review::{MoveQuality, Thresholds};
+use baghchal::{Board, Review, Side};
+
+fn play(board: &mut Board, moves: &[&str]) {
+    for text in moves {
+        let mv = parse_move(board, text).unwrap();
+        assert!(board.make_move(&mv), "{text}");
+    }
+}
+
+#[test]
+fn test_review_finds_a_hanging_goat() {
+    let mut board = Board::new();
+    // B2 leaves the goat for the tiger on A1 to take
+    play(&mut board, &["B2", "A1xB2-C3", "A1"]);
+    let mut progress = Vec::new();
+    let review = Review::default().run(&board.record(), |done, total| progress.push((done, total)));
+
+    assert_eq!(progress, [(1, 4), (2, 4), (3, 4), (4, 4)]);
+    assert_eq!(review.moves.len(), 3);
+    let hanging = &review.moves[0];
+    assert_eq!((hanging.ply, hanging.move_number), (0, 1));
+    assert_eq!(hanging.side, Side::Goats);
+    assert!(hanging.loss >= 100, "{hanging:?}");
+    assert_eq!(
+        hanging.quality,
+        Thresholds::default().classify(hanging.loss)
+    );
+    assert_eq!(hanging.loss, hanging.score_after - hanging.score_before);
+    assert!(hanging.best.is_some());
+    let capture = &review.moves[1];
+    assert_eq!(capture.side, Side::Tigers);
+    assert_eq!(
+        (capture.quality, capture.loss, capture.best.clone()),
+        (MoveQuality::Best, 0, None)
+    );
+    assert_eq!(review.mistakes()[0], hanging);
+    assert_eq!(review.count(Side::Tigers, MoveQuality::Best), 1);
+    assert_eq!(review.average_loss(Side::Tigers), 0.0);
+}
+
+#[test]
+fn test_review_names_the_winning_move_missed() {
+    // The tigers take their fifth goat with A5xB5-C5 or E2xD2-C2
+    let mut board = Board::from_fen("GT2G/GT1GT/GGGGG/GGGGG/TG1GG t 0 4 21").unwrap();
+    play(&mut board, &["B2-C2"]);
+    let review = Review::default().run(&board.record(), |_, _| {});
+
+    let missed = &review.moves[0];
+    assert_eq!(missed.quality, MoveQuality::Blunder);
+    assert_eq!(missed.move_number, 21);
+    assert_eq!(
+        missed.best.as_ref().map(|(_, reason)| reason.clone()),
+        Some(Some(MoveReason::Wins))
+    );
+    assert_eq!(
+        missed.describe(board.topology()),
+        format!("Move 21: B2-C2 (−{}) — E2xD2-C2 wins the game", missed.loss)
+    );
+}
+
+#[test]
+fn test_thresholds_class_moves_by_the_points_lost() {
+    let thresholds = Thresholds {
+        inaccuracy: 20,
+        mistake: 100,
+        blunder: 400,
+    };
+    let classes: Vec<MoveQuality> = [0, 19, 20, 99, 100, 399, 400, 5000]
+        .into_iter()
+        .map(|loss| thresholds.classify(loss))
+        .collect();
+    assert_eq!(
+        classes,
+        [
+            MoveQuality::Good,
+            MoveQuality::Good,
+            MoveQuality::Inaccuracy,
+            MoveQuality::Inaccuracy,
+            MoveQuality::Mistake,
+            MoveQuality::Mistake,
+            MoveQuality::Blunder,
+            MoveQuality::Blunder,
+        ]
+    );
+}
@@ -0,0 +1,157 @@
+use baghchal::notation::parse_move;
+use baghchal::{
+    save, Adjudication, Board, Clock, ManualTime, MultiCapture, RuleSet, ShuttleRule, Side,
+};
+use std::io::ErrorKind;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn play(board: &mut Board, moves: &[&str]) {
+    for text in moves {
+        let mv = parse_move(board, text).unwrap();
+        assert!(board.make_move(&mv), "{text}");
+    }
+}
+
+#[test]
+fn test_saved_game_loads_as_it_was() {
+    let mut board = Board::new();
+    play(&mut board, &["C3", "A1-B2", "A3", "B2-B1", "C1"]);
+    let loaded = save::parse(&save::to_string(&board)).unwrap();
+    assert_eq!(loaded.to_fen(), board.to_fen());
+    assert_eq!(loaded.record(), board.record());
+    assert_eq!(loaded.side_to_move(), Side::Tigers);
+}
+
+#[test]
+fn test_loaded_game_undoes_moves_made_before_the_save() {
+    let mut board = Board::new();
+    play(&mut board, &["C3", "A1-B2", "A3"]);
+    let mut loaded = save::parse(&save::to_string(&board)).unwrap();
+    for _ in 0..3 {
+        assert!(loaded.undo());
+    }
+    assert!(!loaded.can_undo());
+    assert_eq!(loaded.to_fen(), Board::new().to_fen());
+}
+
+#[test]
+fn test_saves_keep_the_rules_and_start_position() {
+    let rules = RuleSet {
+        multi_capture: MultiCapture::Optional,
+        forced_capture: true,
+        shuttle_rule: Some(ShuttleRule {
+            moves: 6,
+            outcome: Adjudication::Draw,
+        }),
+        adjudicate_hopeless: Some(Adjudication::GoatsWin),
+        swap_after: Some(4),
+        ..RuleSet::aadu_puli_attam()
+    };
+    let mut board = Board::with_rules(rules.clone()).unwrap();
+    let first = board.legal_moves()[0].clone();
+    assert!(board.make_move(&first));
+    let loaded = save::parse(&save::to_string(&board)).unwrap();
+    assert_eq!(loaded.rules(), &rules);
+    assert_eq!(loaded.record(), board.record());
+
+    let mut board = Board::from_fen("T3T/1G3/5/5/T3T t 19 0 1").unwrap();
+    play(&mut board, &["A1-A2"]);
+    let mut loaded = save::parse(&save::to_string(&board)).unwrap();
+    assert_eq!(loaded.to_fen(), board.to_fen());
+    assert!(loaded.undo());
+    assert_eq!(loaded.to_fen(), "T3T/1G3/5/5/T3T t 19 0 1");
+}
+
+#[test]
+fn test_saves_keep_the_clock_and_the_swap() {
+    let time = Arc::new(ManualTime::new());
+    let rules = RuleSet {
+        swap_after: Some(2),
+        ..RuleSet::standard()
+    };
+    let mut board = Board::with_rules(rules).unwrap();
+    board.set_clock(
+        Clock::with_source(Duration::from_secs(60), time.clone())
+            .with_increment(Duration::from_secs(2)),
+    );
+    time.advance(Duration::from_secs(7));
+    play(&mut board, &["C3"]);
+    time.advance(Duration::from_secs(3));
+    play(&mut board, &["A1-B2"]);
+    assert!(board.swap_sides());
+    time.advance(Duration::from_secs(1));
+
+    let loaded = save::parse(&save::to_string(&board)).unwrap();
+    assert_eq!(loaded.record(), board.record());
+    assert_eq!(loaded.swapped_at(), Some(2));
+    for side in [Side::Goats, Side::Tigers] {
+        let saved = board.remaining_time(side).unwrap();
+        let restored = loaded.remaining_time(side).unwrap();
+        assert!(
+            saved.abs_diff(restored) < Duration::from_millis(500),
+            "{side:?}"
+        );
+    }
+    assert_eq!(loaded.clock().unwrap().increment(), Duration::from_secs(2));
+}
+
+#[test]
+fn test_corrupt_saves_are_reported() {
+    let mut board = Board::new();
+    play(&mut board, &["C3", "A1-B2"]);
+    let text = save::to_string(&board);
+
+    let cases = [
+        (
+            text.replace("version: 1", "version: 7"),
+            2,
+            "unsupported save version",
+        ),
+        (text.replace("A1-B2", "A1-C3"), 14, "illegal move 'A1-C3'"),
+        (
+            text.replace("board: 5x5", "board: hexagon"),
+            3,
+            "invalid board",
+        ),
+        (
+            text.replace("forced capture: no", "forced capture: maybe"),
+            9,
+            "yes or no",
+        ),
+        (text.replace("start: T3T", "start: T4T"), 13, ""),
+        (
+            text.replace("multi-capture: off\n", ""),
+            14,
+            "missing 'multi-capture'",
+        ),
+        ("not a save".to_string(), 1, "expected 'key: value'"),
+    ];
+    for (text, line, message) in cases {
+        let err = save::parse(&text).unwrap_err();
+        assert_eq!(err.line, line, "{err}");
+        assert!(err.message.contains(message), "{err}");
+    }
+}
+
+#[test]
+fn test_loading_a_bad_file_is_an_error() {
+    let dir = std::env::temp_dir().join(format!("baghchal-save-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("corrupt.txt");
+    std::fs::write(&path, "version: 1\nboard: 5x5\n").unwrap();
+    assert_eq!(
+        save::load(&path).unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+    assert_eq!(
+        save::load(dir.join("missing.txt")).unwrap_err().kind(),
+        ErrorKind::NotFound
+    );
+
+    let board = Board::new();
+    let path = dir.join("game.txt");
+    save::save(&board, &path).unwrap();
+    assert_eq!(save::load(&path).unwrap().to_fen(), board.to_fen());
+    std::fs::remove_dir_all(dir).unwrap();
+}
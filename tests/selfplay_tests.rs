@@ -0,0 +1,126 @@
+use baghchal::selfplay::{observation, policy_index, Format, PLANES};
+use baghchal::{AiConfig, Board, Mcts, MinimaxEngine, SelfPlay};
+use std::path::PathBuf;
+
+fn small_run(format: Format) -> SelfPlay {
+    SelfPlay {
+        games: 2,
+        limits: AiConfig::nodes(100),
+        temperature: 50.0,
+        temperature_plies: 4,
+        seed: 7,
+        max_plies: 16,
+        format,
+        ..SelfPlay::default()
+    }
+}
+
+fn scratch_file(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("baghchal-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn run(selfplay: &SelfPlay, path: &PathBuf) -> baghchal::SelfPlaySummary {
+    selfplay
+        .run(path, MinimaxEngine::new, || Mcts {
+            seed: Some(1),
+            playout_cap: 10,
+            ..Mcts::default()
+        })
+        .unwrap()
+}
+
+#[test]
+fn test_csv_rows_follow_the_schema() {
+    let path = scratch_file("selfplay.csv");
+    let summary = run(&small_run(Format::Csv), &path);
+    assert_eq!((summary.resumed_games, summary.games), (0, 2));
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    let mut lines = text.lines();
+    assert_eq!(
+        lines.next(),
+        Some("game,ply,side,fen,planes,policy,score,result")
+    );
+    let rows: Vec<Vec<&str>> = lines.map(|line| line.split(',').collect()).collect();
+    assert_eq!(rows.len(), summary.positions);
+    assert!(rows.len() <= 2 * 16);
+    for row in &rows {
+        assert_eq!(row.len(), 8);
+        let board = Board::from_fen(row[3]).unwrap();
+        let planes: Vec<f32> = row[4].split(' ').map(|x| x.parse().unwrap()).collect();
+        assert_eq!(planes, observation(&board));
+        assert!(row[5].parse::<usize>().unwrap() < 25 * 25);
+        row[6].parse::<i32>().unwrap();
+        assert!(["1", "0", "-1"].contains(&row[7]));
+    }
+    // Game 0 is followed by game 1, each starting from the first ply
+    assert_eq!(rows[0][..3], ["0", "0", "goats"]);
+    let second = rows.iter().position(|row| row[0] == "1").unwrap();
+    assert_eq!(rows[second][1], "0");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_json_lines_resume_where_the_file_ends() {
+    let path = scratch_file("selfplay.jsonl");
+    let one_game = SelfPlay {
+        games: 1,
+        ..small_run(Format::JsonLines)
+    };
+    let first = run(&one_game, &path);
+    assert_eq!(first.games, 1);
+    let before = std::fs::read_to_string(&path).unwrap();
+
+    // A line cut short by an interrupted run is dropped
+    std::fs::write(&path, format!("{before}{{\"game\":1,\"pl")).unwrap();
+    let resumed = run(&small_run(Format::JsonLines), &path);
+    assert_eq!((resumed.resumed_games, resumed.games), (1, 2));
+
+    let after = std::fs::read_to_string(&path).unwrap();
+    assert!(after.starts_with(&before));
+    assert_eq!(after.lines().count(), resumed.positions);
+    for line in after.lines() {
+        assert!(line.starts_with("{\"game\":") && line.ends_with('}'));
+        for key in ["ply", "side", "fen", "planes", "policy", "score", "result"] {
+            assert!(line.contains(&format!("\"{key}\":")), "{line}");
+        }
+    }
+
+    // The second game is the one a single run would have played
+    let path_whole = scratch_file("selfplay-whole.jsonl");
+    run(&small_run(Format::JsonLines), &path_whole);
+    assert_eq!(std::fs::read_to_string(&path_whole).unwrap(), after);
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(&path_whole).unwrap();
+}
+
+#[test]
+fn test_position_cap_stops_after_the_game_under_way() {
+    let path = scratch_file("selfplay-cap.jsonl");
+    let capped = SelfPlay {
+        games: 10,
+        max_positions: Some(1),
+        ..small_run(Format::JsonLines)
+    };
+    let summary = run(&capped, &path);
+    assert_eq!(summary.games, 1);
+    assert!(summary.positions > 1);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_encodings() {
+    let board = Board::from_fen("T1G1T/1G3/2G2/3G1/T3T t 16 0 5").unwrap();
+    let planes = observation(&board);
+    assert_eq!(planes.len(), PLANES * 25);
+    assert_eq!(planes[0], 1.0); // A tiger on A1
+    assert_eq!(planes[25 + 2], 1.0); // A goat on C1
+    assert_eq!(planes[50 + 1], 1.0); // B1 empty
+    assert_eq!(planes[75], 1.0); // Tigers to move
+    assert_eq!(planes[100], 0.8); // 16 of 20 goats in hand
+
+    let mv = baghchal::notation::parse_move(&board, "A1-B1").unwrap();
+    assert_eq!(policy_index(&board, &mv), 1);
+}
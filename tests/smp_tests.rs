@@ -0,0 +1,114 @@
+//! Run with `cargo test --features parallel`.
+#![cfg(feature = "parallel")]
+
+use baghchal::notation::format_move;
+use baghchal::{AiConfig, Board, Engine, MinimaxEngine, SearchStatus};
+use std::time::{Duration, Instant};
+
+fn threads(threads: usize, config: AiConfig) -> AiConfig {
+    AiConfig { threads, ..config }
+}
+
+fn depth(depth: u32) -> AiConfig {
+    AiConfig {
+        max_depth: Some(depth),
+        max_nodes: None,
+        ..AiConfig::nodes(0)
+    }
+}
+
+#[test]
+fn test_threads_find_the_same_tactics() {
+    // Helpers searching a ply deeper can change the main thread's mind
+    // where the next depth would, so this capture is the best at every
+    // depth
+    let mut board = Board::from_fen("GT2G/GT1GT/GGGGG/GGGGG/TG1GG t 0 3 21").unwrap();
+    board.set_use_opening_book(false);
+    for depth in [5, 6] {
+        let config = AiConfig {
+            max_depth: Some(depth),
+            max_nodes: None,
+            ..AiConfig::nodes(0)
+        };
+        let (single, _) = board.suggest_move(&config).unwrap();
+        assert_eq!(format_move(board.topology(), &single), "A5xB5-C5");
+        for _ in 0..3 {
+            let (mv, _) = board.suggest_move(&threads(4, config)).unwrap();
+            assert_eq!(mv, single);
+        }
+    }
+}
+
+#[test]
+fn test_threads_agree_on_a_decided_score() {
+    // The tigers take the fifth goat at once, whichever thread finds it
+    let mut board = Board::from_fen("GT2G/GT1GT/GGGGG/GGGGG/TG1GG t 0 4 21").unwrap();
+    board.set_use_opening_book(false);
+    let (_, single) = board.suggest_move(&depth(4)).unwrap();
+    assert!(single > 9000);
+    for _ in 0..3 {
+        assert_eq!(board.suggest_move(&threads(3, depth(4))).unwrap().1, single);
+    }
+}
+
+#[test]
+fn test_many_short_parallel_searches_play_legal_moves() {
+    // Plays whole games a few hundred nodes a move, so the threads
+    // start, race over the table and stop many times over
+    let config = AiConfig {
+        max_nodes: Some(400),
+        threads: 4,
+        ..AiConfig::nodes(0)
+    };
+    let mut board = Board::new();
+    board.set_use_opening_book(false);
+    for _ in 0..60 {
+        if board.is_game_over() {
+            break;
+        }
+        let (mv, _) = board.suggest_move(&config).unwrap();
+        assert!(board.legal_moves().contains(&mv));
+        assert!(board.make_move(&mv));
+    }
+}
+
+#[test]
+fn test_helpers_stop_with_the_main_thread() {
+    let mut board = Board::from_fen("T1G1T/1G3/2G2/3G1/T3T t 16 0 5").unwrap();
+    board.set_use_opening_book(false);
+    let limits = AiConfig {
+        time_limit: Some(Duration::from_millis(200)),
+        max_depth: None,
+        max_nodes: None,
+        threads: 4,
+        ..AiConfig::default()
+    };
+    let started = Instant::now();
+    let (mv, _) = board.suggest_move(&limits).unwrap();
+    assert!(started.elapsed() < Duration::from_millis(700));
+    assert!(board.legal_moves().contains(&mv));
+}
+
+#[test]
+fn test_stopping_ends_every_thread() {
+    let mut board = Board::from_fen("T1G1T/1G3/2G2/3G1/T3T t 16 0 5").unwrap();
+    board.set_use_opening_book(false);
+    let limits = AiConfig {
+        time_limit: None,
+        max_depth: None,
+        max_nodes: None,
+        threads: 4,
+        ..AiConfig::default()
+    };
+    let search = MinimaxEngine::new().start_search(board.clone(), limits);
+    let started = Instant::now();
+    while !matches!(search.poll(), SearchStatus::Running(Some(info)) if info.depth >= 3) {
+        assert!(started.elapsed() < Duration::from_secs(10));
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    let stopped = Instant::now();
+    search.stop();
+    let (result, _engine) = search.await_result();
+    assert!(stopped.elapsed() < Duration::from_millis(500));
+    assert!(board.legal_moves().contains(&result.unwrap().mv));
+}
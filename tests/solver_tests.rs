@@ -0,0 +1,58 @@
+use baghchal::{solve, Adjudication, Board, RuleSet, SolveLimits, SolveResult};
+
+fn solve_fen(fen: &str) -> SolveResult {
+    solve(&Board::from_fen(fen).unwrap(), &SolveLimits::default())
+}
+
+#[test]
+fn test_solve_winning_capture() {
+    assert_eq!(
+        solve_fen("T3T/1G3/5/5/T3T t 15 4 16"),
+        SolveResult::Win { plies: 1 }
+    );
+}
+
+#[test]
+fn test_solve_last_goat_cannot_cover_both_captures() {
+    // One goat left to place, but A1 threatens both B1 and A2
+    assert_eq!(
+        solve_fen("TG3/G4/5/5/4T g 1 4 10"),
+        SolveResult::Loss { plies: 2 }
+    );
+    // The same position turned upside down
+    assert_eq!(
+        solve_fen("4T/5/5/G4/TG3 g 1 4 10"),
+        SolveResult::Loss { plies: 2 }
+    );
+}
+
+#[test]
+fn test_solve_goats_trap_the_tigers() {
+    assert_eq!(
+        solve_fen("TGGGT/GG1GG/G1G1G/GGGGG/TG1GT g 0 3 30"),
+        SolveResult::Win { plies: 1 }
+    );
+}
+
+#[test]
+fn test_solve_adjudicated_draw() {
+    let rules = RuleSet {
+        adjudicate_hopeless: Some(Adjudication::Draw),
+        ..RuleSet::standard()
+    };
+    let board = Board::from_fen_with_rules("T3T/5/1GGG1/5/T3T t 0 1 9", rules).unwrap();
+    assert_eq!(
+        solve(&board, &SolveLimits::default()),
+        SolveResult::Draw { plies: 0 }
+    );
+}
+
+#[test]
+fn test_solve_gives_up_at_the_limits() {
+    let limits = SolveLimits {
+        max_nodes: 1000,
+        ..SolveLimits::default()
+    };
+    assert_eq!(solve(&Board::new(), &limits), SolveResult::Unknown);
+    assert_eq!(SolveResult::Unknown.plies(), None);
+}
@@ -0,0 +1,129 @@
+use baghchal::{
+    solve, Adjudication, Board, Outcome, RuleSet, ShuttleRule, SolveLimits, SolveResult, Tablebase,
+    Topology,
+};
+use std::sync::Arc;
+
+/// Two tigers and four goats on a 4x4 board, small enough to solve in a
+/// test.
+fn small_rules() -> RuleSet {
+    RuleSet {
+        topology: Arc::new(Topology::alquerque(4)),
+        tiger_squares: vec![0, 3],
+        starting_goats: 4,
+        capture_threshold: 2,
+        ..RuleSet::standard()
+    }
+}
+
+/// A 4x4 position in FEN, with every goat placed.
+fn fen(cells: &[char; 16], side: char, captured: u32) -> String {
+    let rows: Vec<String> = cells
+        .chunks(4)
+        .map(|row| {
+            let mut text = String::new();
+            let mut empty = 0;
+            for &cell in row {
+                if cell == '.' {
+                    empty += 1;
+                    continue;
+                }
+                if empty > 0 {
+                    text.push_str(&empty.to_string());
+                    empty = 0;
+                }
+                text.push(cell);
+            }
+            if empty > 0 {
+                text.push_str(&empty.to_string());
+            }
+            text
+        })
+        .collect();
+    format!("{} {side} 0 {captured} 20", rows.join("/"))
+}
+
+#[test]
+fn test_tablebase_agrees_with_solver() {
+    let rules = small_rules();
+    let table = Tablebase::generate(&rules, 2).unwrap();
+    assert!(!table.is_empty());
+
+    let limits = SolveLimits {
+        max_nodes: 20_000,
+        max_depth: 8,
+        ..SolveLimits::default()
+    };
+    let mut solved = 0;
+    for first in 1..16 {
+        for second in first + 1..16 {
+            if first == 3 || second == 3 {
+                continue;
+            }
+            let mut cells = ['.'; 16];
+            cells[0] = 'T';
+            cells[3] = 'T';
+            cells[first] = 'G';
+            cells[second] = 'G';
+            for side in ['g', 't'] {
+                let text = fen(&cells, side, 1);
+                let board = Board::from_fen_with_rules(&text, rules.clone()).unwrap();
+                let outcome = table.probe(&board);
+                let expected = match solve(&board, &limits) {
+                    SolveResult::Win { plies } => Outcome::Win { plies },
+                    SolveResult::Loss { plies } => Outcome::Loss { plies },
+                    _ => continue,
+                };
+                assert_eq!(outcome, Some(expected), "{text}");
+                solved += 1;
+            }
+        }
+    }
+    assert!(solved > 0);
+}
+
+#[test]
+fn test_tablebase_save_and_load() {
+    let rules = small_rules();
+    let table = Tablebase::generate(&rules, 1).unwrap();
+    let path = std::env::temp_dir().join(format!("baghchal-{}.tb", std::process::id()));
+    table.save(&path).unwrap();
+
+    let loaded = Tablebase::load(&path, &rules).unwrap();
+    assert_eq!(loaded.len(), table.len());
+    assert_eq!(loaded.max_goats(), 1);
+    let board = Board::from_fen_with_rules("TG1T/4/4/4 t 0 1 20", rules).unwrap();
+    assert_eq!(loaded.probe(&board), Some(Outcome::Win { plies: 1 }));
+    assert_eq!(loaded.probe(&board), table.probe(&board));
+
+    // A table only fits the rules it was built for
+    assert!(Tablebase::load(&path, &RuleSet::standard()).is_err());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_tablebase_probe_outside_the_table() {
+    let rules = small_rules();
+    let table = Tablebase::generate(&rules, 1).unwrap();
+
+    // Goats still in hand
+    let board = Board::from_fen_with_rules("TG1T/4/4/4 t 2 1 20", rules.clone()).unwrap();
+    assert_eq!(table.probe(&board), None);
+    // Too many goats on the board
+    let board = Board::from_fen_with_rules("TGGT/4/4/4 t 0 1 20", rules).unwrap();
+    assert_eq!(table.probe(&board), None);
+    // Other rules
+    assert_eq!(table.probe(&Board::new()), None);
+}
+
+#[test]
+fn test_tablebase_rejects_shuttle_rule() {
+    let rules = RuleSet {
+        shuttle_rule: Some(ShuttleRule {
+            moves: 3,
+            outcome: Adjudication::Draw,
+        }),
+        ..small_rules()
+    };
+    assert!(Tablebase::generate(&rules, 1).is_none());
+}
@@ -0,0 +1,296 @@
+use baghchal::{Board, Jump, Piece, Position, Topology};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// The arithmetic move generator the topology replaced, kept here as a
+// reference implementation for the equivalence tests.
+mod reference {
+    use baghchal::Piece;
+
+    pub fn is_diagonal_allowed(pos: usize) -> bool {
+        matches!(
+            pos,
+            0 | 2 | 4 | 6 | 8 | 10 | 12 | 14 | 16 | 18 | 20 | 22 | 24
+        )
+    }
+
+    pub fn tiger_moves(cells: &[Piece], pos: usize) -> Vec<usize> {
+        let mut moves = Vec::new();
+        let row = pos / 5;
+        let col = pos % 5;
+
+        let mut possible_moves = vec![
+            (row.wrapping_sub(1), col),
+            (row + 1, col),
+            (row, col.wrapping_sub(1)),
+            (row, col + 1),
+            (row.wrapping_sub(2), col),
+            (row + 2, col),
+            (row, col.wrapping_sub(2)),
+            (row, col + 2),
+        ];
+        if is_diagonal_allowed(pos) {
+            possible_moves.extend_from_slice(&[
+                (row.wrapping_sub(1), col.wrapping_sub(1)),
+                (row.wrapping_sub(1), col + 1),
+                (row + 1, col.wrapping_sub(1)),
+                (row + 1, col + 1),
+                (row.wrapping_sub(2), col.wrapping_sub(2)),
+                (row.wrapping_sub(2), col + 2),
+                (row + 2, col.wrapping_sub(2)),
+                (row + 2, col + 2),
+            ]);
+        }
+
+        for (new_row, new_col) in possible_moves {
+            if new_row < 5 && new_col < 5 {
+                let new_pos = new_row * 5 + new_col;
+                let row_diff = new_row.abs_diff(row);
+                let col_diff = new_col.abs_diff(col);
+                let is_jump = row_diff == 2 || col_diff == 2;
+                let is_diagonal = row_diff == col_diff;
+
+                if is_diagonal && !is_diagonal_allowed(new_pos) {
+                    continue;
+                }
+                if is_jump {
+                    let mid_pos = (row + new_row) / 2 * 5 + (col + new_col) / 2;
+                    if is_diagonal && !is_diagonal_allowed(mid_pos) {
+                        continue;
+                    }
+                    if cells[mid_pos] == Piece::Goat && cells[new_pos] == Piece::Empty {
+                        moves.push(new_pos);
+                    }
+                } else if cells[new_pos] == Piece::Empty {
+                    moves.push(new_pos);
+                }
+            }
+        }
+        moves
+    }
+
+    pub fn goat_moves(cells: &[Piece], pos: usize) -> Vec<usize> {
+        let mut moves = Vec::new();
+        let row = pos / 5;
+        let col = pos % 5;
+
+        let mut possible_moves = vec![
+            (row.wrapping_sub(1), col),
+            (row + 1, col),
+            (row, col.wrapping_sub(1)),
+            (row, col + 1),
+        ];
+        if is_diagonal_allowed(pos) {
+            possible_moves.extend_from_slice(&[
+                (row.wrapping_sub(1), col.wrapping_sub(1)),
+                (row.wrapping_sub(1), col + 1),
+                (row + 1, col.wrapping_sub(1)),
+                (row + 1, col + 1),
+            ]);
+        }
+
+        for (new_row, new_col) in possible_moves {
+            if new_row < 5 && new_col < 5 {
+                let new_pos = new_row * 5 + new_col;
+                let is_diagonal = new_row.abs_diff(row) == new_col.abs_diff(col);
+                if is_diagonal && !is_diagonal_allowed(new_pos) {
+                    continue;
+                }
+                if cells[new_pos] == Piece::Empty {
+                    moves.push(new_pos);
+                }
+            }
+        }
+        moves
+    }
+
+    pub fn captured_position(cells: &[Piece], from: usize, to: usize) -> Option<usize> {
+        let (from_row, from_col) = (from / 5, from % 5);
+        let (to_row, to_col) = (to / 5, to % 5);
+        if from_row.abs_diff(to_row) > 1 || from_col.abs_diff(to_col) > 1 {
+            let mid_pos = (from_row + to_row) / 2 * 5 + (from_col + to_col) / 2;
+            if cells[mid_pos] == Piece::Goat {
+                return Some(mid_pos);
+            }
+        }
+        None
+    }
+}
+
+fn random_board(rng: &mut StdRng) -> Board {
+    let mut board = Board::new();
+    board.goats_in_hand = 0;
+    for pos in 0..25 {
+        board.cells[pos] = match rng.gen_range(0..10) {
+            0 => Piece::Tiger,
+            1..=5 => Piece::Goat,
+            _ => Piece::Empty,
+        };
+    }
+    board
+}
+
+fn sorted(mut moves: Vec<usize>) -> Vec<usize> {
+    moves.sort_unstable();
+    moves
+}
+
+#[test]
+fn test_move_generators_match_reference() {
+    let mut rng = StdRng::seed_from_u64(0xBA6C);
+    for _ in 0..2000 {
+        let board = random_board(&mut rng);
+        for pos in 0..25 {
+            let tiger = board.get_valid_tiger_moves(pos);
+            assert_eq!(
+                sorted(tiger.iter().map(|p| p.0).collect()),
+                sorted(reference::tiger_moves(&board.cells, pos)),
+                "tiger moves from {pos} on\n{}",
+                board.to_diagram()
+            );
+            for Position(to) in tiger {
+                assert_eq!(
+                    board.get_captured_position(pos, to),
+                    reference::captured_position(&board.cells, pos, to)
+                );
+            }
+
+            let goat = board.get_valid_goat_moves(pos);
+            assert_eq!(
+                sorted(goat.iter().map(|p| p.0).collect()),
+                sorted(reference::goat_moves(&board.cells, pos)),
+                "goat moves from {pos} on\n{}",
+                board.to_diagram()
+            );
+        }
+    }
+}
+
+/// Whether some tiger can move, worked out from every tiger's moves.
+fn any_tiger_moves(board: &Board) -> bool {
+    board
+        .tigers()
+        .any(|Position(pos)| !board.get_valid_tiger_moves(pos).is_empty())
+}
+
+#[test]
+fn test_any_tiger_can_move_matches_move_lists() {
+    let mut rng = StdRng::seed_from_u64(0x7163);
+    for _ in 0..2000 {
+        let board = random_board(&mut rng);
+        assert_eq!(
+            board.any_tiger_can_move(),
+            any_tiger_moves(&board),
+            "{}",
+            board.to_diagram()
+        );
+    }
+
+    // And after every move of random games
+    for _ in 0..200 {
+        let mut board = Board::new();
+        while !board.is_game_over() {
+            let moves = board.legal_moves();
+            let mv = &moves[rng.gen_range(0..moves.len())];
+            assert!(board.make_move(mv));
+            assert_eq!(board.any_tiger_can_move(), any_tiger_moves(&board));
+        }
+    }
+}
+
+#[test]
+fn test_standard_topology_shape() {
+    let topology = Topology::standard();
+    assert_eq!(topology.len(), 25);
+
+    // Corners have 3 neighbors, diagonal-free edge points 3, the centre 8
+    assert_eq!(topology.neighbors(0), &[1, 5, 6]);
+    assert_eq!(topology.neighbors(1), &[0, 2, 6]);
+    assert_eq!(topology.neighbors(12).len(), 8);
+    assert_eq!(topology.neighbors(7), &[2, 6, 8, 12]);
+
+    // Every jump goes straight along a line through an adjacent point
+    for pos in 0..topology.len() {
+        for &Jump { from, over, to } in topology.jumps_from(pos) {
+            assert_eq!(from, pos);
+            assert!(topology.neighbors(from).contains(&over));
+            assert!(topology.neighbors(over).contains(&to));
+            assert_eq!(topology.jump_between(from, to).map(|j| j.over), Some(over));
+        }
+    }
+    assert_eq!(topology.jumps_from(0).len(), 3);
+    assert_eq!(topology.jumps_from(1).len(), 2);
+    assert_eq!(topology.jumps_from(12).len(), 8);
+    assert!(topology.jump_between(1, 13).is_none());
+
+    for pos in 0..25 {
+        assert_eq!(
+            topology.has_diagonals(pos),
+            reference::is_diagonal_allowed(pos)
+        );
+    }
+}
+
+#[test]
+fn test_aadu_puli_attam_topology_shape() {
+    let topology = Topology::aadu_puli_attam();
+    assert_eq!(topology.len(), 23);
+    assert_eq!(topology.rows().len(), 5);
+    assert_eq!(
+        topology.rows().iter().map(Vec::len).collect::<Vec<_>>(),
+        vec![1, 6, 6, 6, 4]
+    );
+
+    // The apex joins the four lines of the triangle
+    assert_eq!(topology.neighbors(0), &[2, 3, 4, 5]);
+    assert_eq!(topology.jumps_from(0).len(), 4);
+    // Outer points of the rectangle only have their row and column
+    assert_eq!(topology.neighbors(1), &[2, 7]);
+    assert_eq!(topology.neighbors(13), &[7, 14]);
+    // The base of the triangle has no side columns below the rectangle
+    assert_eq!(topology.neighbors(19), &[14, 20]);
+
+    for pos in 0..topology.len() {
+        assert_eq!(topology.parse_point(&topology.point_name(pos)), Some(pos));
+        for &Jump { from, over, to } in topology.jumps_from(pos) {
+            assert_eq!(from, pos);
+            assert!(topology.neighbors(from).contains(&over));
+            assert!(topology.neighbors(over).contains(&to));
+        }
+    }
+    assert_eq!(topology.point_name(0), "C1");
+    assert_eq!(topology.parse_point("b5"), Some(19));
+    assert_eq!(topology.parse_point("A5"), None);
+}
+
+#[test]
+fn test_interior_points() {
+    let interior: Vec<usize> = (0..25)
+        .filter(|&pos| Topology::standard().is_interior(pos))
+        .collect();
+    assert_eq!(interior, vec![6, 7, 8, 11, 12, 13, 16, 17, 18]);
+
+    let large = Topology::alquerque(7);
+    assert_eq!((0..49).filter(|&pos| large.is_interior(pos)).count(), 25);
+    assert!(large.has_diagonals(24));
+    assert!(!large.has_diagonals(25));
+    assert_eq!(large.point_name(48), "G7");
+}
+
+#[test]
+fn test_symmetries() {
+    let standard = Topology::standard();
+    let symmetries = standard.symmetries();
+    assert_eq!(symmetries.len(), 8);
+    assert_eq!(symmetries[0], (0..25).collect::<Vec<_>>());
+    // Every symmetry maps the corners onto the corners
+    for symmetry in &symmetries {
+        for corner in [0, 4, 20, 24] {
+            assert!([0, 4, 20, 24].contains(&symmetry[corner]));
+        }
+    }
+
+    // Flipping an even-sized grid moves its diagonals off their points
+    assert_eq!(Topology::alquerque(6).symmetries().len(), 4);
+    assert_eq!(Topology::aadu_puli_attam().symmetries().len(), 1);
+}
@@ -0,0 +1,114 @@
+use baghchal::notation::parse_move;
+use baghchal::tournament::GameOutcome;
+use baghchal::{
+    AiConfig, Board, GameResult, Mcts, MinimaxEngine, Side, TerminationReason, Tournament,
+    TournamentResult, Winner,
+};
+
+fn small_tournament() -> Tournament {
+    let board = Board::new();
+    Tournament {
+        pairs: 2,
+        limits: AiConfig::nodes(100),
+        openings: vec![Vec::new(), vec![parse_move(&board, "C3").unwrap()]],
+        max_plies: 30,
+        ..Tournament::default()
+    }
+}
+
+fn play(tournament: &Tournament) -> TournamentResult {
+    tournament.run(MinimaxEngine::new, || Mcts {
+        seed: Some(3),
+        playout_cap: 10,
+        ..Mcts::default()
+    })
+}
+
+#[test]
+fn test_tournament_alternates_sides_and_repeats_itself() {
+    let tournament = small_tournament();
+    let result = play(&tournament);
+    let sides: Vec<(usize, Option<usize>, Side)> = result
+        .games
+        .iter()
+        .map(|game| (game.pair, game.opening, game.a_plays))
+        .collect();
+    assert_eq!(
+        sides,
+        vec![
+            (0, Some(0), Side::Tigers),
+            (0, Some(0), Side::Goats),
+            (1, Some(1), Side::Tigers),
+            (1, Some(1), Side::Goats),
+        ]
+    );
+    for game in &result.games {
+        assert!(game.plies <= 30);
+        assert!(game.result.winner != Winner::None);
+    }
+    assert_eq!(play(&tournament), result);
+}
+
+fn outcome(a_plays: Side, winner: Winner) -> GameOutcome {
+    GameOutcome {
+        pair: 0,
+        opening: None,
+        a_plays,
+        result: GameResult {
+            winner,
+            reason: TerminationReason::Adjudicated,
+        },
+        plies: 100,
+    }
+}
+
+#[test]
+fn test_elo_from_results() {
+    let even = TournamentResult {
+        games: vec![
+            outcome(Side::Tigers, Winner::Tigers),
+            outcome(Side::Goats, Winner::Tigers),
+        ],
+    };
+    assert_eq!(even.record(None), (1, 0, 1));
+    assert_eq!(even.elo().difference, 0.0);
+
+    // Three points out of four is about 191 Elo
+    let ahead = TournamentResult {
+        games: vec![
+            outcome(Side::Tigers, Winner::Tigers),
+            outcome(Side::Goats, Winner::Goats),
+            outcome(Side::Tigers, Winner::Draw),
+            outcome(Side::Goats, Winner::Draw),
+        ],
+    };
+    assert_eq!(ahead.record(Some(Side::Tigers)), (1, 1, 0));
+    let elo = ahead.elo();
+    assert!((elo.difference - 190.85).abs() < 0.01, "{elo:?}");
+    assert!(elo.margin > 0.0);
+
+    // A clean sweep still gives a number
+    let sweep = TournamentResult {
+        games: vec![outcome(Side::Goats, Winner::Goats); 4],
+    };
+    assert!(sweep.elo().difference.is_finite());
+    assert!(sweep.elo().difference > 300.0);
+}
+
+#[test]
+fn test_tournament_reports() {
+    let result = TournamentResult {
+        games: vec![
+            outcome(Side::Tigers, Winner::Tigers),
+            outcome(Side::Goats, Winner::Tigers),
+        ],
+    };
+    let table = result.to_string();
+    assert!(table.contains("as tigers"), "{table}");
+    assert!(table.contains("Elo difference: +0.0"), "{table}");
+    let json = result.to_json();
+    assert!(json.starts_with("{\"games\":2,\"wins\":1,\"draws\":0,\"losses\":1,"));
+    assert!(json.contains(
+        "{\"pair\":0,\"opening\":null,\"a_plays\":\"goats\",\"winner\":\"tigers\",\"reason\":\"Adjudicated\",\"plies\":100}"
+    ));
+}